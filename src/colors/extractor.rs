@@ -3,11 +3,14 @@
 //! This is a Rust port of the Swift ColorExtractor, which uses
 //! k-means++ initialization and iterative refinement.
 
+use std::ops::Range;
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use image::{DynamicImage, GenericImageView, imageops::FilterType};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tracing::warn;
 
 use super::scheme::{ColorScheme, Rgb};
 
@@ -20,11 +23,45 @@ pub struct ExtractionOptions {
   /// Force dark (Some(true)), light (Some(false)), or auto-detect (None)
   pub prefers_dark: Option<bool>,
 
-  /// WCAG-inspired contrast level (1.5 = low, 4.5 = AAA)
+  /// Minimum WCAG contrast ratio of colors 1-15 against the background (1.5 = low, 4.5 = AAA)
   pub contrast_ratio: f32,
 
   /// How much to adjust background (0.3 = subtle, 0.9 = intense)
   pub background_intensity: f32,
+
+  /// Cluster in CIELAB space instead of RGB. Off by default so existing palettes don't change.
+  pub perceptual: bool,
+
+  /// Seed for k-means++ initialization; the same seed and image always give the same
+  /// colors. None picks a random start each time.
+  pub seed: Option<u64>,
+
+  /// Only sample this part of the image. None samples all of it.
+  pub region: Option<Region>,
+}
+
+/// Part of an image to take colors from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Region {
+  /// Centered crop covering this percentage (1-100) of the width and height
+  Center(f32),
+}
+
+impl Region {
+  /// Pixel ranges this region covers in a `width` x `height` image
+  fn bounds(self, width: u32, height: u32) -> (Range<u32>, Range<u32>) {
+    match self {
+      Region::Center(percent) => {
+        let fraction = percent.clamp(1.0, 100.0) / 100.0;
+        let span = |size: u32| {
+          let len = ((size as f32 * fraction).round() as u32).clamp(1.min(size), size);
+          let start = (size - len) / 2;
+          start..start + len
+        };
+        (span(width), span(height))
+      }
+    }
+  }
 }
 
 impl Default for ExtractionOptions {
@@ -34,10 +71,30 @@ impl Default for ExtractionOptions {
       prefers_dark: None,
       contrast_ratio: 3.0,
       background_intensity: 0.6,
+      perceptual: false,
+      seed: None,
+      region: None,
     }
   }
 }
 
+impl ExtractionOptions {
+  /// Lowest and highest supported `contrast_ratio`
+  pub const CONTRAST_RANGE: (f32, f32) = (1.5, 4.5);
+
+  /// Pull out-of-range values back into range instead of failing on them
+  pub fn clamped(mut self) -> Self {
+    let (min, max) = Self::CONTRAST_RANGE;
+    if !(min..=max).contains(&self.contrast_ratio) {
+      let clamped = self.contrast_ratio.clamp(min, max);
+      warn!("contrast_ratio {} is outside {}-{}, using {}", self.contrast_ratio, min, max, clamped);
+      self.contrast_ratio = clamped;
+    }
+    self.color_count = self.color_count.max(1);
+    self
+  }
+}
+
 /// Extracts dominant colors from images using k-means clustering
 pub struct ColorExtractor {
   /// Maximum dimension for resized image (for performance)
@@ -66,6 +123,17 @@ impl ColorExtractor {
     }
   }
 
+  /// Create an extractor with custom sampling, trading speed for accuracy
+  ///
+  /// Larger `max_dimension` and smaller `sample_step` look at more pixels.
+  pub fn with_sampling(max_dimension: u32, sample_step: u32) -> Self {
+    Self {
+      max_dimension: max_dimension.max(1),
+      sample_step: sample_step.max(1),
+      ..Self::new()
+    }
+  }
+
   /// Extract a color scheme from an image file
   pub fn extract<P: AsRef<Path>>(&self, image_path: P, options: &ExtractionOptions) -> Result<ColorScheme> {
     let path = image_path.as_ref();
@@ -76,24 +144,99 @@ impl ColorExtractor {
 
   /// Extract a color scheme from a DynamicImage
   pub fn extract_from_image(&self, image: &DynamicImage, wallpaper_path: String, options: &ExtractionOptions) -> Result<ColorScheme> {
+    let colors = self.weighted_colors(image, options)?;
+    Ok(self.scheme_from_colors(wallpaper_path, &colors, options))
+  }
+
+  /// Generate a color scheme from clusters returned by [`weighted_colors`](Self::weighted_colors)
+  pub fn scheme_from_colors(&self, wallpaper_path: String, colors: &[(Rgb, usize)], options: &ExtractionOptions) -> ColorScheme {
+    self.generate_scheme(wallpaper_path, by_luminance(colors), options)
+  }
+
+  /// The wallpaper's accent: the dominant color that best combines saturation and
+  /// the share of the image it covers
+  ///
+  /// Near-gray, near-black and near-white clusters are only used when the image has
+  /// nothing more colorful.
+  #[allow(dead_code)]
+  pub fn extract_accent<P: AsRef<Path>>(&self, image_path: P, options: &ExtractionOptions) -> Result<Rgb> {
+    let img = image::open(image_path.as_ref()).context("Failed to open image")?;
+    let colors = self.weighted_colors(&img, options)?;
+    self.accent_from_colors(&colors).context("No colors found in image")
+  }
+
+  /// Like [`extract_accent`](Self::extract_accent), from clusters returned by
+  /// [`weighted_colors`](Self::weighted_colors)
+  pub fn accent_from_colors(&self, colors: &[(Rgb, usize)]) -> Option<Rgb> {
+    let total = colors.iter().map(|(_, count)| count).sum::<usize>().max(1) as f32;
+
+    let colorful = |color: &Rgb| color.saturation() >= 0.15 && (0.08..=0.95).contains(&color.luminance());
+    let score = |(color, count): &(Rgb, usize)| color.saturation() * (*count as f32 / total).sqrt();
+
+    colors
+      .iter()
+      .filter(|(color, _)| colorful(color))
+      .max_by(|a, b| score(a).total_cmp(&score(b)))
+      .or_else(|| colors.iter().max_by_key(|(_, count)| *count))
+      .map(|(color, _)| *color)
+  }
+
+  /// Extract a dark and a light scheme from the same image, returned as `(dark, light)`.
+  ///
+  /// Clustering runs once and both schemes are generated from the same dominant
+  /// colors. `options.prefers_dark` is ignored.
+  #[allow(dead_code)]
+  pub fn extract_both(&self, image: &DynamicImage, wallpaper_path: String, options: &ExtractionOptions) -> Result<(ColorScheme, ColorScheme)> {
+    let colors = self.weighted_colors(image, options)?;
+    Ok(self.both_from_colors(wallpaper_path, &colors, options))
+  }
+
+  /// Like [`extract_both`](Self::extract_both), from clusters returned by
+  /// [`weighted_colors`](Self::weighted_colors)
+  pub fn both_from_colors(&self, wallpaper_path: String, colors: &[(Rgb, usize)], options: &ExtractionOptions) -> (ColorScheme, ColorScheme) {
+    let centroids = by_luminance(colors);
+
+    let dark_options = ExtractionOptions {
+      prefers_dark: Some(true),
+      ..options.clone()
+    };
+    let light_options = ExtractionOptions {
+      prefers_dark: Some(false),
+      ..options.clone()
+    };
+
+    (
+      self.generate_scheme(wallpaper_path.clone(), centroids.clone(), &dark_options),
+      self.generate_scheme(wallpaper_path, centroids, &light_options),
+    )
+  }
+
+  /// Dominant colors of an image with the number of sampled pixels in each cluster
+  ///
+  /// Clustering is the expensive part of extraction. Callers that need more than
+  /// one result from the same image can cluster once and pass the colors to the
+  /// `*_from_colors` methods.
+  pub fn weighted_colors(&self, image: &DynamicImage, options: &ExtractionOptions) -> Result<Vec<(Rgb, usize)>> {
     // 1. Resize image for performance
     let resized = self.resize_image(image);
 
     // 2. Sample pixels
-    let pixels = self.sample_pixels(&resized);
+    let pixels = self.sample_pixels(&resized, options.region);
 
     if pixels.is_empty() {
       anyhow::bail!("No valid pixels found in image");
     }
 
     // 3. K-means clustering
-    let mut centroids = self.kmeans(&pixels, options.color_count);
-
-    // Sort by luminance (darkest first)
-    centroids.sort_by(|a, b| a.luminance().partial_cmp(&b.luminance()).unwrap());
-
-    // 4. Generate color scheme
-    Ok(self.generate_scheme(wallpaper_path, centroids, options))
+    let mut rng = match options.seed {
+      Some(seed) => StdRng::seed_from_u64(seed),
+      None => StdRng::from_entropy(),
+    };
+    Ok(if options.perceptual {
+      self.kmeans_lab(&pixels, options.color_count, &mut rng)
+    } else {
+      self.kmeans(&pixels, options.color_count, &mut rng)
+    })
   }
 
   /// Resize image to max_dimension while preserving aspect ratio
@@ -111,14 +254,18 @@ impl ColorExtractor {
     image.resize(new_width, new_height, FilterType::Triangle)
   }
 
-  /// Sample pixels from the image, filtering out transparent and extreme values
-  fn sample_pixels(&self, image: &DynamicImage) -> Vec<Rgb> {
+  /// Sample pixels from the image (or `region` of it), filtering out transparent and extreme values
+  fn sample_pixels(&self, image: &DynamicImage, region: Option<Region>) -> Vec<Rgb> {
     let (width, height) = image.dimensions();
+    let (xs, ys) = match region {
+      Some(region) => region.bounds(width, height),
+      None => (0..width, 0..height),
+    };
     let rgba = image.to_rgba8();
-    let mut pixels = Vec::with_capacity((width * height / 16) as usize);
+    let mut pixels = Vec::with_capacity((xs.len() * ys.len() / 16).max(1));
 
-    for y in (0..height).step_by(self.sample_step as usize) {
-      for x in (0..width).step_by(self.sample_step as usize) {
+    for y in ys.clone().step_by(self.sample_step as usize) {
+      for x in xs.clone().step_by(self.sample_step as usize) {
         let pixel = rgba.get_pixel(x, y);
         let [r, g, b, a] = pixel.0;
 
@@ -142,8 +289,8 @@ impl ColorExtractor {
     // If too filtered, sample without brightness filter
     if pixels.len() < 100 {
       pixels.clear();
-      for y in (0..height).step_by(self.sample_step as usize) {
-        for x in (0..width).step_by(self.sample_step as usize) {
+      for y in ys.step_by(self.sample_step as usize) {
+        for x in xs.clone().step_by(self.sample_step as usize) {
           let pixel = rgba.get_pixel(x, y);
           let [r, g, b, _] = pixel.0;
           pixels.push(Rgb::from_u8(r, g, b));
@@ -154,82 +301,106 @@ impl ColorExtractor {
     pixels
   }
 
-  /// K-means clustering with k-means++ initialization
-  fn kmeans(&self, pixels: &[Rgb], k: usize) -> Vec<Rgb> {
-    if pixels.len() <= k {
-      return pixels.to_vec();
+  /// K-means clustering with k-means++ initialization, in RGB space
+  fn kmeans(&self, pixels: &[Rgb], k: usize, rng: &mut impl Rng) -> Vec<(Rgb, usize)> {
+    let points: Vec<[f32; 3]> = pixels.iter().map(|p| [p.r, p.g, p.b]).collect();
+    self
+      .cluster(&points, k, rng)
+      .into_iter()
+      .map(|([r, g, b], count)| (Rgb::new(r, g, b), count))
+      .collect()
+  }
+
+  /// K-means clustering in CIELAB space, where distances track perceived difference
+  fn kmeans_lab(&self, pixels: &[Rgb], k: usize, rng: &mut impl Rng) -> Vec<(Rgb, usize)> {
+    let points: Vec<[f32; 3]> = pixels
+      .iter()
+      .map(|p| {
+        let (l, a, b) = p.to_lab();
+        [l, a, b]
+      })
+      .collect();
+    self
+      .cluster(&points, k, rng)
+      .into_iter()
+      .map(|([l, a, b], count)| (Rgb::from_lab(l, a, b), count))
+      .collect()
+  }
+
+  /// Cluster 3D points, returning the centroids and how many points each one has
+  fn cluster(&self, points: &[[f32; 3]], k: usize, rng: &mut impl Rng) -> Vec<([f32; 3], usize)> {
+    #[cfg(feature = "parallel")]
+    let assign = assign_parallel;
+    #[cfg(not(feature = "parallel"))]
+    let assign = assign_sequential;
+
+    self.cluster_with(points, k, rng, assign)
+  }
+
+  /// Cluster with the given nearest-centroid assignment step
+  fn cluster_with(&self, points: &[[f32; 3]], k: usize, rng: &mut impl Rng, assign: AssignFn) -> Vec<([f32; 3], usize)> {
+    if points.len() <= k {
+      return points.iter().map(|point| (*point, 1)).collect();
     }
 
     // Initialize centroids with k-means++
-    let mut centroids = self.kmeans_plus_plus_init(pixels, k);
-    let mut assignments = vec![0usize; pixels.len()];
+    let mut centroids = self.kmeans_plus_plus_init(points, k, rng);
+    let mut assignments = vec![0usize; points.len()];
 
     for _ in 0..self.max_iterations {
-      let mut changed = false;
-
-      // Assign each pixel to nearest centroid
-      for (i, pixel) in pixels.iter().enumerate() {
-        let mut min_dist = f32::MAX;
-        let mut min_idx = 0;
-
-        for (j, centroid) in centroids.iter().enumerate() {
-          let dist = pixel.distance_squared(centroid);
-          if dist < min_dist {
-            min_dist = dist;
-            min_idx = j;
-          }
-        }
-
-        if assignments[i] != min_idx {
-          assignments[i] = min_idx;
-          changed = true;
-        }
-      }
+      // Assign each point to nearest centroid
+      let nearest = assign(points, &centroids);
+      let changed = nearest != assignments;
+      assignments = nearest;
 
       if !changed {
         break;
       }
 
       // Update centroids
-      let mut sums = vec![(0.0f32, 0.0f32, 0.0f32); k];
+      let mut sums = vec![[0.0f32; 3]; k];
       let mut counts = vec![0usize; k];
 
-      for (i, pixel) in pixels.iter().enumerate() {
+      for (i, point) in points.iter().enumerate() {
         let c = assignments[i];
-        sums[c].0 += pixel.r;
-        sums[c].1 += pixel.g;
-        sums[c].2 += pixel.b;
+        for d in 0..3 {
+          sums[c][d] += point[d];
+        }
         counts[c] += 1;
       }
 
       for (c, centroid) in centroids.iter_mut().enumerate() {
         if counts[c] > 0 {
           let count = counts[c] as f32;
-          *centroid = Rgb::new(sums[c].0 / count, sums[c].1 / count, sums[c].2 / count);
+          *centroid = sums[c].map(|sum| sum / count);
         }
       }
     }
 
-    centroids
+    let mut counts = vec![0usize; k];
+    for &c in &assignments {
+      counts[c] += 1;
+    }
+
+    centroids.into_iter().zip(counts).collect()
   }
 
   /// K-means++ initialization for better starting centroids
-  fn kmeans_plus_plus_init(&self, pixels: &[Rgb], k: usize) -> Vec<Rgb> {
-    let mut rng = rand::thread_rng();
+  fn kmeans_plus_plus_init(&self, points: &[[f32; 3]], k: usize, rng: &mut impl Rng) -> Vec<[f32; 3]> {
     let mut centroids = Vec::with_capacity(k);
 
     // First centroid is random
-    let first_idx = rng.r#gen_range(0..pixels.len());
-    centroids.push(pixels[first_idx]);
+    let first_idx = rng.r#gen_range(0..points.len());
+    centroids.push(points[first_idx]);
 
-    let mut min_distances = vec![f32::MAX; pixels.len()];
+    let mut min_distances = vec![f32::MAX; points.len()];
 
     for _ in 1..k {
       let mut total_dist = 0.0f32;
 
       // Update min distances to nearest existing centroid
-      for (i, pixel) in pixels.iter().enumerate() {
-        let dist = pixel.distance_squared(centroids.last().unwrap());
+      for (i, point) in points.iter().enumerate() {
+        let dist = distance_squared(point, centroids.last().unwrap());
         if dist < min_distances[i] {
           min_distances[i] = dist;
         }
@@ -249,7 +420,7 @@ impl ColorExtractor {
         }
       }
 
-      centroids.push(pixels[selected_idx]);
+      centroids.push(points[selected_idx]);
     }
 
     centroids
@@ -307,6 +478,14 @@ impl ColorExtractor {
     // Cursor: first saturated color or foreground
     let cursor = dominant_colors.iter().find(|c| c.saturation() > 0.3).cloned().unwrap_or(foreground);
 
+    // Everything drawn on the background must stay readable. Color 0 is the background itself.
+    let target = options.contrast_ratio;
+    for color in colors.iter_mut().skip(1) {
+      *color = with_contrast(*color, background, target);
+    }
+    let foreground = with_contrast(foreground, background, target);
+    let cursor = with_contrast(cursor, background, target);
+
     ColorScheme::new(wallpaper, is_dark, background, foreground, cursor, colors)
   }
 
@@ -370,16 +549,135 @@ impl ColorExtractor {
   }
 }
 
+/// Lighten or darken `color` until its contrast ratio against `background` reaches `target`
+///
+/// The color moves towards whichever of white or black contrasts more with the
+/// background, so even mid-tone backgrounds can reach 4.5 (the upper end of
+/// `ExtractionOptions::CONTRAST_RANGE`).
+fn with_contrast(color: Rgb, background: Rgb, target: f32) -> Rgb {
+  if color.contrast_ratio(&background) >= target {
+    return color;
+  }
+
+  let white = Rgb::new(1.0, 1.0, 1.0);
+  let black = Rgb::new(0.0, 0.0, 0.0);
+  let lighten = white.contrast_ratio(&background) >= black.contrast_ratio(&background);
+
+  for step in 1..=20 {
+    let amount = step as f32 * 0.05;
+    let adjusted = if lighten { color.lightened(amount) } else { color.darkened(amount) };
+    if adjusted.contrast_ratio(&background) >= target {
+      return adjusted;
+    }
+  }
+
+  if lighten { white } else { black }
+}
+
+/// Assignment step: the index of the nearest centroid for each point
+type AssignFn = fn(&[[f32; 3]], &[[f32; 3]]) -> Vec<usize>;
+
+/// Index of the centroid closest to `point`
+fn nearest_centroid(point: &[f32; 3], centroids: &[[f32; 3]]) -> usize {
+  let mut min_dist = f32::MAX;
+  let mut min_idx = 0;
+
+  for (j, centroid) in centroids.iter().enumerate() {
+    let dist = distance_squared(point, centroid);
+    if dist < min_dist {
+      min_dist = dist;
+      min_idx = j;
+    }
+  }
+
+  min_idx
+}
+
+/// Nearest centroid of every point, on the current thread
+#[cfg_attr(feature = "parallel", allow(dead_code))]
+fn assign_sequential(points: &[[f32; 3]], centroids: &[[f32; 3]]) -> Vec<usize> {
+  points.iter().map(|point| nearest_centroid(point, centroids)).collect()
+}
+
+/// Nearest centroid of every point, spread over rayon's thread pool. Centroids are
+/// only read during assignment, so points can be handled independently.
+#[cfg(feature = "parallel")]
+fn assign_parallel(points: &[[f32; 3]], centroids: &[[f32; 3]]) -> Vec<usize> {
+  use rayon::prelude::*;
+  points.par_iter().map(|point| nearest_centroid(point, centroids)).collect()
+}
+
+/// Cluster colors sorted darkest first
+fn by_luminance(colors: &[(Rgb, usize)]) -> Vec<Rgb> {
+  let mut centroids: Vec<Rgb> = colors.iter().map(|(color, _)| *color).collect();
+  centroids.sort_by(|a, b| a.luminance().partial_cmp(&b.luminance()).unwrap());
+  centroids
+}
+
+/// Squared Euclidean distance between two points
+fn distance_squared(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+  a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_extraction_options_clamped() {
+    let opts = ExtractionOptions {
+      contrast_ratio: 9.0,
+      color_count: 0,
+      ..Default::default()
+    }
+    .clamped();
+    assert!((opts.contrast_ratio - 4.5).abs() < 0.001);
+    assert_eq!(opts.color_count, 1);
+
+    let opts = ExtractionOptions {
+      contrast_ratio: 0.5,
+      ..Default::default()
+    }
+    .clamped();
+    assert!((opts.contrast_ratio - 1.5).abs() < 0.001);
+  }
+
+  #[test]
+  fn test_region_bounds() {
+    assert_eq!(Region::Center(50.0).bounds(100, 40), (25..75, 10..30));
+    assert_eq!(Region::Center(100.0).bounds(100, 40), (0..100, 0..40));
+    // Out-of-range percentages are clamped, and at least one pixel is kept
+    assert_eq!(Region::Center(250.0).bounds(10, 10), (0..10, 0..10));
+    assert_eq!(Region::Center(0.0).bounds(10, 10), (4..5, 4..5));
+  }
+
+  #[test]
+  fn test_center_region_ignores_surroundings() {
+    let extractor = ColorExtractor::with_sampling(200, 1);
+    // Blue frame around a small red subject
+    let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(100, 100, |x, y| {
+      if (40..60).contains(&x) && (40..60).contains(&y) {
+        image::Rgb([200, 30, 30])
+      } else {
+        image::Rgb([30, 60, 200])
+      }
+    }));
+
+    let pixels = extractor.sample_pixels(&image, Some(Region::Center(20.0)));
+    assert_eq!(pixels.len(), 400);
+    assert!(pixels.iter().all(|p| p.r > p.b));
+
+    let pixels = extractor.sample_pixels(&image, None);
+    assert_eq!(pixels.len(), 10_000);
+  }
+
   #[test]
   fn test_extraction_options_default() {
     let opts = ExtractionOptions::default();
     assert_eq!(opts.color_count, 16);
     assert_eq!(opts.prefers_dark, None);
     assert!((opts.contrast_ratio - 3.0).abs() < 0.001);
+    assert!(!opts.perceptual);
   }
 
   #[test]
@@ -394,7 +692,171 @@ mod tests {
       Rgb::new(0.1, 0.0, 1.0),
     ];
 
-    let centroids = extractor.kmeans(&pixels, 3);
+    let centroids = extractor.kmeans(&pixels, 3, &mut rand::thread_rng());
     assert_eq!(centroids.len(), 3);
+    assert_eq!(centroids.iter().map(|(_, count)| count).sum::<usize>(), pixels.len());
+  }
+
+  #[test]
+  fn test_kmeans_lab_separates_hues() {
+    let extractor = ColorExtractor::new();
+    let pixels = vec![
+      Rgb::new(0.9, 0.1, 0.1),
+      Rgb::new(0.8, 0.1, 0.1),
+      Rgb::new(0.1, 0.8, 0.1),
+      Rgb::new(0.1, 0.7, 0.1),
+      Rgb::new(0.1, 0.1, 0.9),
+      Rgb::new(0.1, 0.1, 0.8),
+    ];
+
+    let mut centroids: Vec<Rgb> = extractor
+      .kmeans_lab(&pixels, 3, &mut rand::thread_rng())
+      .into_iter()
+      .map(|(color, _)| color)
+      .collect();
+    centroids.sort_by(|a, b| a.hue().partial_cmp(&b.hue()).unwrap());
+
+    assert!(centroids[0].r > 0.7);
+    assert!(centroids[1].g > 0.6);
+    assert!(centroids[2].b > 0.7);
+  }
+
+  #[test]
+  fn test_extract_accent() {
+    let extractor = ColorExtractor::new();
+    let options = ExtractionOptions {
+      color_count: 4,
+      ..Default::default()
+    };
+
+    // Mostly gray with a wide orange band: the gray covers more, but the orange wins
+    let tmp = tempfile::TempDir::new().unwrap();
+    let path = tmp.path().join("band.png");
+    image::RgbImage::from_fn(64, 64, |_, y| {
+      if y < 20 {
+        image::Rgb([230, 120, 20])
+      } else {
+        image::Rgb([120, 120, 120])
+      }
+    })
+    .save(&path)
+    .unwrap();
+    let accent = extractor.extract_accent(&path, &options).unwrap();
+    assert!(accent.r > 0.8 && accent.b < 0.2, "{}", accent.hex());
+    assert!(extractor.extract_accent(tmp.path().join("missing.png"), &options).is_err());
+
+    // With nothing colorful, the largest cluster is used
+    let gray = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(32, 32, image::Rgb([100, 100, 100])));
+    let colors = extractor.weighted_colors(&gray, &options).unwrap();
+    let accent = extractor.accent_from_colors(&colors).unwrap();
+    let gray = 100.0 / 255.0;
+    assert!([accent.r, accent.g, accent.b].iter().all(|c| (c - gray).abs() < 0.01), "{}", accent.hex());
+
+    assert_eq!(extractor.accent_from_colors(&[]), None);
+  }
+
+  #[cfg(feature = "parallel")]
+  #[test]
+  fn test_parallel_clustering_matches_sequential() {
+    let extractor = ColorExtractor::new();
+    let mut rng = StdRng::seed_from_u64(7);
+    let points: Vec<[f32; 3]> = (0..5000).map(|_| [rng.r#gen(), rng.r#gen(), rng.r#gen()]).collect();
+
+    let sequential = extractor.cluster_with(&points, 16, &mut StdRng::seed_from_u64(1), assign_sequential);
+    let parallel = extractor.cluster_with(&points, 16, &mut StdRng::seed_from_u64(1), assign_parallel);
+
+    assert_eq!(sequential.len(), parallel.len());
+    for ((a, a_count), (b, b_count)) in sequential.iter().zip(&parallel) {
+      assert_eq!(a_count, b_count);
+      assert!(distance_squared(a, b) < 1e-10);
+    }
+  }
+
+  #[test]
+  fn test_seeded_extraction_is_reproducible() {
+    let extractor = ColorExtractor::new();
+    let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 64, |x, y| {
+      image::Rgb([(x * 4) as u8, (y * 4) as u8, ((x * y) % 256) as u8])
+    }));
+    let options = ExtractionOptions {
+      seed: Some(42),
+      ..Default::default()
+    };
+
+    let first = extractor.extract_from_image(&image, "test.png".to_string(), &options).unwrap();
+    for _ in 0..3 {
+      let again = extractor.extract_from_image(&image, "test.png".to_string(), &options).unwrap();
+      assert_eq!(again.colors, first.colors);
+    }
+  }
+
+  #[test]
+  fn test_extract_both() {
+    let extractor = ColorExtractor::new();
+    let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(32, 32, |x, y| image::Rgb([(x * 8) as u8, (y * 8) as u8, 128])));
+
+    let (dark, light) = extractor
+      .extract_both(&image, "test.png".to_string(), &ExtractionOptions::default())
+      .unwrap();
+
+    assert!(dark.is_dark);
+    assert!(!light.is_dark);
+    assert!(dark.background.luminance() < light.background.luminance());
+  }
+
+  #[test]
+  fn test_schemes_from_one_clustering() {
+    let extractor = ColorExtractor::new();
+    let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(32, 32, |x, y| image::Rgb([(x * 8) as u8, (y * 8) as u8, 128])));
+    let options = ExtractionOptions {
+      prefers_dark: Some(true),
+      ..Default::default()
+    };
+
+    let colors = extractor.weighted_colors(&image, &options).unwrap();
+    let scheme = extractor.scheme_from_colors("test.png".to_string(), &colors, &options);
+    let (dark, light) = extractor.both_from_colors("test.png".to_string(), &colors, &options);
+
+    // Same clusters, so the scheme matches its dark variant exactly
+    assert_eq!(scheme.background, dark.background);
+    assert_eq!(scheme.colors, dark.colors);
+    assert!(!light.is_dark);
+  }
+
+  #[test]
+  fn test_scheme_colors_meet_contrast_target() {
+    let extractor = ColorExtractor::new();
+    // Muted grey-blues with barely any spread in brightness
+    let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 64, |x, y| {
+      image::Rgb([110 + (x % 8) as u8, 115 + (y % 8) as u8, 125 + ((x + y) % 6) as u8])
+    }));
+
+    for prefers_dark in [Some(true), Some(false), None] {
+      for contrast_ratio in [1.5, 3.0, 4.5] {
+        let options = ExtractionOptions {
+          prefers_dark,
+          contrast_ratio,
+          background_intensity: 0.3,
+          ..Default::default()
+        };
+        let scheme = extractor.extract_from_image(&image, "test.png".to_string(), &options).unwrap();
+
+        assert_eq!(scheme.colors.len(), 16);
+        assert_eq!(scheme.colors[0], scheme.background);
+        for (i, color) in scheme.colors.iter().enumerate().skip(1) {
+          let contrast = color.contrast_ratio(&scheme.background);
+          assert!(
+            contrast >= contrast_ratio,
+            "color{} {} has contrast {} < {}",
+            i,
+            color.hex(),
+            contrast,
+            contrast_ratio
+          );
+        }
+        assert!(scheme.foreground.contrast_ratio(&scheme.background) >= contrast_ratio);
+        assert!(scheme.cursor.contrast_ratio(&scheme.background) >= contrast_ratio);
+      }
+    }
   }
 }
@@ -6,6 +6,6 @@
 mod extractor;
 mod scheme;
 
-pub use extractor::{ColorExtractor, ExtractionOptions};
+pub use extractor::{ColorExtractor, ExtractionOptions, Region};
 #[allow(unused_imports)]
 pub use scheme::{ColorScheme, Rgb};
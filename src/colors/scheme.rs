@@ -30,6 +30,17 @@ impl Rgb {
     0.299 * self.r + 0.587 * self.g + 0.114 * self.b
   }
 
+  /// WCAG relative luminance, computed from linear RGB
+  pub fn relative_luminance(&self) -> f32 {
+    0.2126 * srgb_to_linear(self.r) + 0.7152 * srgb_to_linear(self.g) + 0.0722 * srgb_to_linear(self.b)
+  }
+
+  /// WCAG contrast ratio against another color, from 1 (identical) to 21 (black on white)
+  pub fn contrast_ratio(&self, other: &Rgb) -> f32 {
+    let (a, b) = (self.relative_luminance(), other.relative_luminance());
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+  }
+
   /// HSV saturation
   pub fn saturation(&self) -> f32 {
     let max_c = self.r.max(self.g).max(self.b);
@@ -80,6 +91,30 @@ impl Rgb {
     }
   }
 
+  /// Rotate the hue by the given number of degrees, keeping chroma and brightness
+  pub fn hue_rotated(&self, degrees: f32) -> Self {
+    let max_c = self.r.max(self.g).max(self.b);
+    let min_c = self.r.min(self.g).min(self.b);
+    let chroma = max_c - min_c;
+
+    if chroma <= 0.0 {
+      return *self;
+    }
+
+    let h = (self.hue() + degrees).rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+      0 => (chroma, x, 0.0),
+      1 => (x, chroma, 0.0),
+      2 => (0.0, chroma, x),
+      3 => (0.0, x, chroma),
+      4 => (x, 0.0, chroma),
+      _ => (chroma, 0.0, x),
+    };
+
+    Self::new(r + min_c, g + min_c, b + min_c)
+  }
+
   /// Increase saturation by a factor
   pub fn saturated(&self, factor: f32) -> Self {
     let gray = self.luminance();
@@ -90,6 +125,17 @@ impl Rgb {
     }
   }
 
+  /// Parse a hex string (`#FF5500` or `FF5500`)
+  pub fn from_hex(hex: &str) -> Option<Self> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.is_ascii() {
+      return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Self::from_u8(channel(0)?, channel(2)?, channel(4)?))
+  }
+
   /// Convert to hex string (e.g., "#FF5500")
   pub fn hex(&self) -> String {
     format!(
@@ -131,12 +177,68 @@ impl Rgb {
   }
 
   /// Squared Euclidean distance to another color
+  #[allow(dead_code)]
   pub fn distance_squared(&self, other: &Rgb) -> f32 {
     let dr = self.r - other.r;
     let dg = self.g - other.g;
     let db = self.b - other.b;
     dr * dr + dg * dg + db * db
   }
+
+  /// Convert to CIELAB (D65 white point) as `(L, a, b)`, with L in 0-100
+  pub fn to_lab(self) -> (f32, f32, f32) {
+    let r = srgb_to_linear(self.r);
+    let g = srgb_to_linear(self.g);
+    let b = srgb_to_linear(self.b);
+
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / D65_WHITE.0;
+    let y = (0.2126729 * r + 0.7151522 * g + 0.072175 * b) / D65_WHITE.1;
+    let z = (0.0193339 * r + 0.119192 * g + 0.9503041 * b) / D65_WHITE.2;
+
+    let (fx, fy, fz) = (lab_f(x), lab_f(y), lab_f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+  }
+
+  /// Create from CIELAB coordinates, clamping colors outside the sRGB gamut
+  pub fn from_lab(l: f32, a: f32, b: f32) -> Self {
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+
+    let x = lab_f_inv(fx) * D65_WHITE.0;
+    let y = lab_f_inv(fy) * D65_WHITE.1;
+    let z = lab_f_inv(fz) * D65_WHITE.2;
+
+    let r = 3.2404542 * x - 1.5371385 * y - 0.4985314 * z;
+    let g = -0.969266 * x + 1.8760108 * y + 0.041556 * z;
+    let b = 0.0556434 * x - 0.2040259 * y + 1.0572252 * z;
+
+    Self::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b))
+  }
+}
+
+/// Reference white (D65, Y normalized to 1.0)
+const D65_WHITE: (f32, f32, f32) = (0.95047, 1.0, 1.08883);
+
+const LAB_EPSILON: f32 = 216.0 / 24389.0;
+const LAB_KAPPA: f32 = 24389.0 / 27.0;
+
+fn srgb_to_linear(c: f32) -> f32 {
+  if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+  let c = c.clamp(0.0, 1.0);
+  if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+fn lab_f(t: f32) -> f32 {
+  if t > LAB_EPSILON { t.cbrt() } else { (LAB_KAPPA * t + 16.0) / 116.0 }
+}
+
+fn lab_f_inv(t: f32) -> f32 {
+  let t3 = t * t * t;
+  if t3 > LAB_EPSILON { t3 } else { (116.0 * t - 16.0) / LAB_KAPPA }
 }
 
 impl Default for Rgb {
@@ -226,6 +328,66 @@ impl ColorScheme {
     lines.push("}".to_string());
     lines.join("\n")
   }
+
+  /// Export as a kitty config snippet (`include` it from kitty.conf)
+  pub fn to_kitty_conf(&self) -> String {
+    let mut lines = Vec::new();
+
+    lines.push(format!("foreground {}", self.foreground.hex()));
+    lines.push(format!("background {}", self.background.hex()));
+    lines.push(format!("cursor {}", self.cursor.hex()));
+    lines.push(format!("selection_foreground {}", self.background.hex()));
+    lines.push(format!("selection_background {}", self.foreground.hex()));
+
+    for (i, color) in self.colors.iter().enumerate() {
+      lines.push(format!("color{} {}", i, color.hex()));
+    }
+
+    lines.join("\n")
+  }
+
+  /// Export as an Alacritty TOML color section (`import` it from alacritty.toml)
+  pub fn to_alacritty_toml(&self) -> String {
+    const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+    let mut lines = Vec::new();
+
+    lines.push("[colors.primary]".to_string());
+    lines.push(format!("background = \"{}\"", self.background.hex()));
+    lines.push(format!("foreground = \"{}\"", self.foreground.hex()));
+    lines.push(String::new());
+    lines.push("[colors.cursor]".to_string());
+    lines.push(format!("cursor = \"{}\"", self.cursor.hex()));
+    lines.push(format!("text = \"{}\"", self.background.hex()));
+
+    for (section, colors) in ["normal", "bright"].iter().zip(self.colors.chunks(NAMES.len())) {
+      lines.push(String::new());
+      lines.push(format!("[colors.{}]", section));
+      for (name, color) in NAMES.iter().zip(colors) {
+        lines.push(format!("{} = \"{}\"", name, color.hex()));
+      }
+    }
+
+    lines.join("\n")
+  }
+
+  /// Export as the OSC escape sequences pywal writes to open terminals
+  ///
+  /// Writing the result to a terminal (or its pty) recolors it on the spot.
+  /// OSC 708 sets the border color in urxvt and is ignored elsewhere.
+  pub fn to_sequences(&self) -> String {
+    let mut sequences = String::new();
+
+    for (i, color) in self.colors.iter().enumerate() {
+      sequences.push_str(&format!("\x1b]4;{};{}\x1b\\", i, color.hex()));
+    }
+    sequences.push_str(&format!("\x1b]10;{}\x1b\\", self.foreground.hex()));
+    sequences.push_str(&format!("\x1b]11;{}\x1b\\", self.background.hex()));
+    sequences.push_str(&format!("\x1b]12;{}\x1b\\", self.cursor.hex()));
+    sequences.push_str(&format!("\x1b]708;{}\x1b\\", self.background.hex()));
+
+    sequences
+  }
 }
 
 #[cfg(test)]
@@ -238,6 +400,23 @@ mod tests {
     assert_eq!(color.hex(), "#FF7F00");
   }
 
+  #[test]
+  fn test_rgb_from_hex() {
+    assert_eq!(Rgb::from_hex("#FF7F00").unwrap().hex(), "#FF7F00");
+    assert_eq!(Rgb::from_hex("1a2b3c").unwrap().hex(), "#1A2B3C");
+    assert!(Rgb::from_hex("#FFF").is_none());
+    assert!(Rgb::from_hex("#GG0000").is_none());
+  }
+
+  #[test]
+  fn test_rgb_hue_rotated() {
+    assert_eq!(Rgb::new(1.0, 0.0, 0.0).hue_rotated(120.0).hex(), "#00FF00");
+    assert_eq!(Rgb::new(1.0, 0.0, 0.0).hue_rotated(-120.0).hex(), "#0000FF");
+
+    let gray = Rgb::new(0.5, 0.5, 0.5);
+    assert_eq!(gray.hue_rotated(90.0), gray);
+  }
+
   #[test]
   fn test_rgb_luminance() {
     let white = Rgb::new(1.0, 1.0, 1.0);
@@ -246,6 +425,17 @@ mod tests {
     assert!(black.luminance().abs() < 0.001);
   }
 
+  #[test]
+  fn test_rgb_contrast_ratio() {
+    let white = Rgb::new(1.0, 1.0, 1.0);
+    let black = Rgb::new(0.0, 0.0, 0.0);
+    assert!((white.contrast_ratio(&black) - 21.0).abs() < 0.01);
+    assert!((black.contrast_ratio(&white) - 21.0).abs() < 0.01);
+    assert!((white.contrast_ratio(&white) - 1.0).abs() < 0.001);
+    // #777777 on white is the classic just-under-AA gray
+    assert!((Rgb::from_hex("#777777").unwrap().contrast_ratio(&white) - 4.48).abs() < 0.01);
+  }
+
   #[test]
   fn test_rgb_lightened() {
     let color = Rgb::new(0.5, 0.5, 0.5);
@@ -255,6 +445,71 @@ mod tests {
     assert!(lighter.b > color.b);
   }
 
+  #[test]
+  fn test_rgb_lab_round_trip() {
+    let (l, a, b) = Rgb::new(1.0, 1.0, 1.0).to_lab();
+    assert!((l - 100.0).abs() < 0.01);
+    assert!(a.abs() < 0.01 && b.abs() < 0.01);
+
+    for color in [Rgb::new(0.8, 0.2, 0.1), Rgb::new(0.1, 0.5, 0.9), Rgb::new(0.02, 0.02, 0.02)] {
+      let (l, a, b) = color.to_lab();
+      let back = Rgb::from_lab(l, a, b);
+      assert!(color.distance_squared(&back) < 1e-6, "{:?} -> {:?}", color, back);
+    }
+  }
+
+  /// Black background, white text, red cursor, colors going from #000000 to #F0F0F0
+  fn sample_scheme() -> ColorScheme {
+    ColorScheme::new(
+      "/path/to/wallpaper.jpg".to_string(),
+      true,
+      Rgb::from_hex("#000000").unwrap(),
+      Rgb::from_hex("#FFFFFF").unwrap(),
+      Rgb::from_hex("#FF0000").unwrap(),
+      (0..16u8).map(|i| Rgb::from_u8(i * 16, i * 16, i * 16)).collect(),
+    )
+  }
+
+  #[test]
+  fn test_to_kitty_conf() {
+    let conf = sample_scheme().to_kitty_conf();
+    let lines: Vec<&str> = conf.lines().collect();
+
+    assert_eq!(lines[0], "foreground #FFFFFF");
+    assert_eq!(lines[1], "background #000000");
+    assert_eq!(lines[2], "cursor #FF0000");
+    assert!(lines.contains(&"color0 #000000"));
+    assert!(lines.contains(&"color15 #F0F0F0"));
+    assert_eq!(lines.iter().filter(|line| line.starts_with("color")).count(), 16);
+  }
+
+  #[test]
+  fn test_to_alacritty_toml() {
+    let toml = sample_scheme().to_alacritty_toml();
+    let parsed: toml::Value = toml::from_str(&toml).unwrap();
+    let colors = &parsed["colors"];
+
+    assert_eq!(colors["primary"]["background"].as_str(), Some("#000000"));
+    assert_eq!(colors["primary"]["foreground"].as_str(), Some("#FFFFFF"));
+    assert_eq!(colors["cursor"]["cursor"].as_str(), Some("#FF0000"));
+    assert_eq!(colors["normal"]["black"].as_str(), Some("#000000"));
+    assert_eq!(colors["normal"]["white"].as_str(), Some("#707070"));
+    assert_eq!(colors["bright"]["black"].as_str(), Some("#808080"));
+    assert_eq!(colors["bright"]["white"].as_str(), Some("#F0F0F0"));
+  }
+
+  #[test]
+  fn test_to_sequences() {
+    let sequences = sample_scheme().to_sequences();
+
+    assert!(sequences.starts_with("\x1b]4;0;#000000\x1b\\\x1b]4;1;#101010\x1b\\"));
+    assert!(sequences.contains("\x1b]4;15;#F0F0F0\x1b\\"));
+    assert!(sequences.contains("\x1b]10;#FFFFFF\x1b\\"));
+    assert!(sequences.contains("\x1b]11;#000000\x1b\\"));
+    assert!(sequences.contains("\x1b]12;#FF0000\x1b\\"));
+    assert_eq!(sequences.matches("\x1b]").count(), 20);
+  }
+
   #[test]
   fn test_color_scheme_json() {
     let scheme = ColorScheme::new(
@@ -1,10 +1,16 @@
+pub mod schedule;
+pub mod validate;
+pub mod watch;
+
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+pub use schedule::ScheduleEntry;
+
 /// Main configuration structure
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
   pub paths: PathsConfig,
   pub transition: TransitionConfig,
@@ -17,41 +23,97 @@ pub struct Config {
   pub logging: LoggingConfig,
   #[serde(default)]
   pub advanced: AdvancedConfig,
+  #[serde(default)]
+  pub history: HistoryConfig,
+  #[serde(default)]
+  pub display: DisplayConfig,
+  /// Time-of-day ranges that override the default source
+  #[serde(default)]
+  pub schedule: Vec<ScheduleEntry>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PathsConfig {
   pub local: String,
   pub downloads: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TransitionConfig {
   #[serde(rename = "type")]
   pub transition_type: TransitionType,
   pub duration: u32,
   #[serde(default = "default_fps")]
   pub fps: u32,
+  /// Give each monitor its own wallpaper when rotating local wallpapers.
+  /// History and colors follow the primary monitor's wallpaper.
+  #[serde(default)]
+  pub per_monitor: bool,
+  /// How the image is scaled: fill, fit, stretch, center or tile
+  #[serde(default = "default_scaling")]
+  pub scaling: String,
+  /// Crop and resize a copy of the image to the display's exact resolution before it is set.
+  /// When one image goes on every monitor, only done if they all have the same resolution.
+  #[serde(default)]
+  pub fit_to_display: bool,
+  /// Stretch one image across all monitors, each showing its part of it
+  #[serde(default)]
+  pub span_monitors: bool,
+  /// Blur or darken the image before it is set
+  #[serde(default)]
+  pub post_process: PostProcessConfig,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Changes made to a copy of the wallpaper before it is set, e.g. to keep desktop
+/// icons readable. Colors are still extracted from the original.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PostProcessConfig {
+  /// Gaussian blur strength (sigma, in pixels)
+  #[serde(default)]
+  pub blur: Option<f32>,
+  /// How much darker to make the image, from 0.0 (unchanged) to 1.0 (black)
+  #[serde(default)]
+  pub dim: Option<f32>,
+}
+
+impl PostProcessConfig {
+  /// Whether there is anything to do; wallpapers are set untouched otherwise
+  pub fn is_enabled(&self) -> bool {
+    self.blur.is_some_and(|blur| blur > 0.0) || self.dim.is_some_and(|dim| dim > 0.0)
+  }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum TransitionType {
   Single(String),
   Multiple(Vec<String>),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TimerConfig {
   pub interval: u32,
   pub randomize: String,
   #[serde(default)]
   pub start_delay: Option<String>,
+  /// Skip one rotation when the wallpaper was changed by something other than the daemon
+  #[serde(default)]
+  pub respect_manual: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SourcesConfig {
   pub default: String,
+  /// Reject downloads smaller than this (e.g. "1920x1080")
+  #[serde(default)]
+  pub min_resolution: Option<String>,
+  /// Image orientation to download
+  #[serde(default)]
+  pub orientation: SourceOrientation,
+  /// Sources the daemon tries, in order, when the default one is unreachable or has
+  /// nothing to offer; local is the last resort after these
+  #[serde(default)]
+  pub fallback: Vec<String>,
   #[serde(default)]
   pub wallhaven: WallhavenConfig,
   #[serde(default)]
@@ -61,12 +123,22 @@ pub struct SourcesConfig {
   #[serde(default)]
   pub apod: ApodConfig,
   #[serde(default)]
+  pub bing: BingConfig,
+  #[serde(default)]
   pub unsplash: UnsplashConfig,
   #[serde(default)]
+  pub pexels: PexelsConfig,
+  #[serde(default)]
+  pub pixabay: PixabayConfig,
+  #[serde(default)]
+  pub flickr: FlickrConfig,
+  #[serde(default)]
+  pub url: UrlConfig,
+  #[serde(default)]
   pub reddit: RedditConfig,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct WallhavenConfig {
   #[serde(default = "default_wallhaven_url")]
   pub url: String,
@@ -82,17 +154,31 @@ pub struct WallhavenConfig {
   pub purity: Vec<String>, // sfw, sketchy, nsfw
   #[serde(default)]
   pub categories: Vec<String>, // general, anime, people (or search terms)
+  #[serde(default = "default_wallhaven_sorting")]
+  pub sorting: String, // date_added, relevance, random, views, favorites, toplist
+  #[serde(default)]
+  pub top_range: Option<String>, // 1d, 3d, 1w, 1M, 3M, 6M, 1y (toplist only)
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct PicsumConfig {
   #[serde(default)]
   pub width: Option<u32>, // Auto-detect if None
   #[serde(default)]
   pub height: Option<u32>, // Auto-detect if None
+  /// Ask for black-and-white photos
+  #[serde(default)]
+  pub grayscale: bool,
+  /// Blur the photos, from 1 (slight) to 10 (heavy)
+  #[serde(default)]
+  pub blur: Option<u8>,
+  /// Always fetch the photo for this seed instead of a random one.
+  /// `{date}` is replaced with today's date, for a photo that changes daily.
+  #[serde(default)]
+  pub seed: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ApodConfig {
   #[serde(default = "default_apod_api_url")]
   pub url: String,
@@ -100,48 +186,252 @@ pub struct ApodConfig {
   pub api_key: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BingConfig {
+  /// Market the daily photos come from (e.g. "de-DE", "ja-JP")
+  #[serde(default = "default_bing_market")]
+  pub market: String,
+  /// Image size to download: "UHD" or one of Bing's fixed sizes like "1920x1080"
+  #[serde(default = "default_bing_resolution")]
+  pub resolution: String,
+}
+
+impl Default for BingConfig {
+  fn default() -> Self {
+    Self {
+      market: default_bing_market(),
+      resolution: default_bing_resolution(),
+    }
+  }
+}
+
+fn default_bing_market() -> String {
+  "en-US".to_string()
+}
+
+fn default_bing_resolution() -> String {
+  "UHD".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct LocalConfig {
   #[serde(default = "default_true")]
   pub recursive: bool,
+  /// How many folders deep `recursive` goes below the wallpaper folder
+  #[serde(default = "default_max_depth")]
+  pub max_depth: usize,
   #[serde(default = "default_formats")]
   pub formats: Vec<String>,
+  /// Show every wallpaper once before any repeats (in collections of up to 100, larger ones skip the last 100)
+  #[serde(default)]
+  pub shuffle: bool,
+  /// Also collect videos (mp4, webm, gif), which are played with mpvpaper on Wayland
+  #[serde(default)]
+  pub include_video: bool,
+  /// Only pick wallpapers from this folder, relative to `paths.local` (e.g. "nature")
+  #[serde(default)]
+  pub subdir: Option<String>,
+}
+
+impl LocalConfig {
+  /// Folder levels to search below the wallpaper folder (0 when not recursive)
+  pub fn search_depth(&self) -> usize {
+    if self.recursive { self.max_depth } else { 0 }
+  }
+
+  /// File extensions to collect: the configured formats, plus videos when enabled
+  pub fn collected_formats(&self) -> Vec<String> {
+    let mut formats = self.formats.clone();
+    if self.include_video {
+      for video in crate::wallpaper::VIDEO_FORMATS {
+        if !formats.iter().any(|f| f.eq_ignore_ascii_case(video)) {
+          formats.push(video.to_string());
+        }
+      }
+    }
+    formats
+  }
+
+  /// Folder to pick wallpapers from: `subdir` under `local_root` when set, else `local_root`
+  pub fn directory(&self, local_root: &str) -> Result<PathBuf> {
+    let root = Path::new(local_root);
+    let Some(subdir) = self.subdir.as_deref().filter(|subdir| !subdir.is_empty()) else {
+      return Ok(root.to_path_buf());
+    };
+
+    let dir = root.join(subdir);
+    if !dir.is_dir() {
+      anyhow::bail!("sources.local.subdir '{}' does not exist: {} is not a directory", subdir, dir.display());
+    }
+    Ok(dir)
+  }
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct UnsplashConfig {
   /// Access Key from https://unsplash.com/developers (used as client_id)
   #[serde(default)]
   pub access_key: Option<String>,
+  /// Orientation of the photos to ask for; follows `sources.orientation` when unset
+  #[serde(default)]
+  pub orientation: Option<UnsplashOrientation>,
+  /// Only pick photos from these collection IDs (ignored when searching)
+  #[serde(default)]
+  pub collections: Vec<String>,
+  /// Width to download photos at (default 2560)
+  #[serde(default)]
+  pub width: Option<u32>,
+}
+
+/// Orientation of the images downloaded (`sources.orientation`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceOrientation {
+  /// Follow the display's rotation
+  #[default]
+  Auto,
+  Landscape,
+  Portrait,
+}
+
+/// Photo orientations the Unsplash API filters by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnsplashOrientation {
+  Landscape,
+  Portrait,
+  /// Whatever Unsplash considers close enough to square
+  Squarish,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+impl UnsplashOrientation {
+  /// Value of the API's `orientation` parameter
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Landscape => "landscape",
+      Self::Portrait => "portrait",
+      Self::Squarish => "squarish",
+    }
+  }
+}
+
+impl From<crate::display::Orientation> for UnsplashOrientation {
+  fn from(orientation: crate::display::Orientation) -> Self {
+    match orientation {
+      crate::display::Orientation::Landscape => Self::Landscape,
+      crate::display::Orientation::Portrait => Self::Portrait,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct PexelsConfig {
+  /// API key from https://www.pexels.com/api/
+  #[serde(default)]
+  pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct PixabayConfig {
+  /// API key from https://pixabay.com/api/docs/
+  #[serde(default)]
+  pub key: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct FlickrConfig {
+  /// API key from https://www.flickr.com/services/apps/create/
+  #[serde(default)]
+  pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct UrlConfig {
+  /// Image URL, or JSON endpoint that points to one
+  #[serde(default)]
+  pub endpoint: Option<String>,
+  /// Dotted path to the image URL in a JSON response (e.g. "data.images.0.url")
+  #[serde(default)]
+  pub json_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct RedditConfig {
   /// Default subreddit(s) to use (e.g., "wallpapers" or "wallpapers+earthporn")
   #[serde(default = "default_reddit_subreddit")]
   pub subreddit: String,
+  /// Listing to fetch: hot, new, top, rising or controversial (default: hot)
+  #[serde(default)]
+  pub sort: Option<String>,
+  /// Time window for top/controversial: hour, day, week, month, year or all
+  #[serde(default)]
+  pub time: Option<String>,
+  /// Keep posts marked NSFW (over_18)
+  #[serde(default)]
+  pub allow_nsfw: bool,
 }
 
 fn default_reddit_subreddit() -> String {
   "wallpapers".to_string()
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CleanupConfig {
   pub keep_count: u32,
   #[serde(default = "default_true")]
   pub auto_cleanup: bool,
+  /// Reuse an existing download instead of keeping an identical copy
+  #[serde(default)]
+  pub deduplicate: bool,
+  /// Write source and attribution details to a `<image>.json` file next to each download
+  #[serde(default)]
+  pub save_metadata: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct IntegrationConfig {
-  /// Send signals to apps (kitty, ghostty, etc.) to reload colors after template generation
+  /// Tell apps (kitty, ghostty, etc.) to reload colors after template generation
   #[serde(default)]
   pub reload_apps: bool,
+
+  /// Apps to push new colors to over their remote control, as `name[:address]`
+  /// (e.g. `kitty:unix:/tmp/kitty`, `nvim`)
+  #[serde(default)]
+  pub notify_apps: Vec<String>,
+
+  /// Switch between light and dark icon themes to match the wallpaper (GNOME/KDE)
+  #[serde(default)]
+  pub icon_theme_sync: bool,
+
+  /// Icon themes used by `icon_theme_sync`
+  #[serde(default)]
+  pub icon_themes: IconThemesConfig,
+
+  /// GNOME-specific integration
+  #[serde(default)]
+  pub gnome: GnomeIntegrationConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct GnomeIntegrationConfig {
+  /// Set GNOME's accent color (GNOME 47+) from the wallpaper's dominant color
+  #[serde(default)]
+  pub accent_sync: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct IconThemesConfig {
+  /// Icon theme for light wallpapers (e.g. "Papirus-Light")
+  #[serde(default)]
+  pub light: Option<String>,
+
+  /// Icon theme for dark wallpapers (e.g. "Papirus-Dark")
+  #[serde(default)]
+  pub dark: Option<String>,
 }
 
 /// Color extraction and theming configuration
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ColorsConfig {
   /// Enable color extraction when setting wallpapers
   #[serde(default = "default_true")]
@@ -162,6 +452,34 @@ pub struct ColorsConfig {
   /// Force dark/light mode: null = auto-detect, true = dark, false = light
   #[serde(default)]
   pub prefer_dark: Option<bool>,
+
+  /// Also write dark and light variants (`colors-dark.json`, `<template>-dark.<ext>`, ...)
+  #[serde(default)]
+  pub dark_and_light: bool,
+
+  /// Number of dominant colors to cluster the image into
+  #[serde(default = "default_color_count")]
+  pub color_count: usize,
+
+  /// Cluster in CIELAB space (closer to how colors are perceived) rather than RGB
+  #[serde(default)]
+  pub perceptual: bool,
+
+  /// Images are downscaled to at most this many pixels on the longest side before sampling
+  #[serde(default = "default_max_dimension")]
+  pub max_dimension: u32,
+
+  /// Sample every Nth pixel of the downscaled image
+  #[serde(default = "default_sample_step")]
+  pub sample_step: u32,
+
+  /// Fixed k-means seed, so a wallpaper always gets the same colors
+  #[serde(default)]
+  pub seed: Option<u64>,
+
+  /// Only take colors from a centered crop covering this percentage of the image
+  #[serde(default)]
+  pub center_percent: Option<f32>,
 }
 
 impl Default for ColorsConfig {
@@ -172,10 +490,71 @@ impl Default for ColorsConfig {
       contrast_ratio: 3.0,
       background_intensity: 0.6,
       prefer_dark: None,
+      dark_and_light: false,
+      color_count: default_color_count(),
+      perceptual: false,
+      max_dimension: default_max_dimension(),
+      sample_step: default_sample_step(),
+      seed: None,
+      center_percent: None,
+    }
+  }
+}
+
+impl ColorsConfig {
+  /// Extraction options for this config, with out-of-range values clamped.
+  /// `prefer_dark: null` falls back to the desktop's dark mode setting.
+  pub fn extraction_options(&self) -> crate::colors::ExtractionOptions {
+    crate::colors::ExtractionOptions {
+      color_count: self.color_count,
+      prefers_dark: self.prefer_dark.or_else(crate::platform::detect_dark_mode),
+      contrast_ratio: self.contrast_ratio,
+      background_intensity: self.background_intensity,
+      perceptual: self.perceptual,
+      seed: self.seed,
+      region: self.center_percent.map(crate::colors::Region::Center),
+    }
+    .clamped()
+  }
+
+  /// Color extractor using the configured sampling settings
+  pub fn extractor(&self) -> crate::colors::ColorExtractor {
+    crate::colors::ColorExtractor::with_sampling(self.max_dimension, self.sample_step)
+  }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DisplayConfig {
+  /// Request images at the logical (scaled) resolution instead of physical pixels.
+  /// Smaller downloads on HiDPI screens, at the cost of some sharpness.
+  #[serde(default)]
+  pub use_logical: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HistoryConfig {
+  /// Record every applied wallpaper in history.jsonl
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+
+  /// Oldest entries are dropped once the history grows past this many
+  #[serde(default = "default_history_max_entries")]
+  pub max_entries: usize,
+}
+
+impl Default for HistoryConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      max_entries: default_history_max_entries(),
     }
   }
 }
 
+fn default_history_max_entries() -> usize {
+  1000
+}
+
 fn default_colors_engine() -> String {
   "native".to_string()
 }
@@ -185,8 +564,17 @@ fn default_contrast_ratio() -> f32 {
 fn default_background_intensity() -> f32 {
   0.6
 }
+fn default_color_count() -> usize {
+  16
+}
+fn default_max_dimension() -> u32 {
+  200
+}
+fn default_sample_step() -> u32 {
+  4
+}
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LoggingConfig {
   #[serde(default = "default_true")]
   pub enabled: bool,
@@ -196,18 +584,64 @@ pub struct LoggingConfig {
   pub file: Option<String>,
   #[serde(default = "default_true")]
   pub timestamp: bool,
+  /// Output format: text, json or logfmt
+  #[serde(default = "default_log_format")]
+  pub format: String,
+  /// Start a new log file: never, daily or hourly
+  #[serde(default = "default_log_rotation")]
+  pub rotation: String,
+  /// Rotated log files to keep (0 keeps all)
+  #[serde(default = "default_log_max_files")]
+  pub max_files: usize,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+impl Default for LoggingConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      level: default_log_level(),
+      file: None,
+      timestamp: true,
+      format: default_log_format(),
+      rotation: default_log_rotation(),
+      max_files: default_log_max_files(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AdvancedConfig {
   #[serde(default = "default_parallel_downloads")]
   pub parallel_downloads: u32,
   #[serde(default = "default_retry_attempts")]
   pub retry_attempts: u32,
+  /// Delay before the first retry in milliseconds (doubles on each attempt)
+  #[serde(default = "default_retry_delay_ms")]
+  pub retry_delay_ms: u64,
   #[serde(default = "default_timeout")]
   pub timeout: u32,
   #[serde(default = "default_user_agent")]
   pub user_agent: String,
+  /// Reload the daemon automatically when the config file changes
+  #[serde(default = "default_true")]
+  pub watch_config: bool,
+  /// Reuse a random earlier download when a source can't be reached
+  #[serde(default)]
+  pub offline_fallback: bool,
+}
+
+impl Default for AdvancedConfig {
+  fn default() -> Self {
+    Self {
+      parallel_downloads: default_parallel_downloads(),
+      retry_attempts: default_retry_attempts(),
+      retry_delay_ms: default_retry_delay_ms(),
+      timeout: default_timeout(),
+      user_agent: default_user_agent(),
+      watch_config: default_true(),
+      offline_fallback: false,
+    }
+  }
 }
 
 // Default value functions (serde uses these for missing fields)
@@ -215,6 +649,10 @@ fn default_fps() -> u32 {
   30
 }
 
+fn default_scaling() -> String {
+  "fill".to_string()
+}
+
 fn default_wallhaven_url() -> String {
   "https://wallhaven.cc/api/v1/search".to_string()
 }
@@ -223,6 +661,10 @@ fn default_wallhaven_quality() -> String {
   "large".to_string()
 }
 
+fn default_wallhaven_sorting() -> String {
+  "random".to_string()
+}
+
 fn default_apod_api_url() -> String {
   "https://api.nasa.gov/planetary/apod".to_string()
 }
@@ -233,12 +675,27 @@ fn default_apod_api_key() -> String {
 fn default_true() -> bool {
   true
 }
+fn default_max_depth() -> usize {
+  16
+}
 fn default_formats() -> Vec<String> {
-  vec!["jpg".to_string(), "jpeg".to_string(), "png".to_string(), "webp".to_string()]
+  ["jpg", "jpeg", "png", "webp", "bmp", "tif", "tiff", "gif", "avif", "heic", "heif"]
+    .into_iter()
+    .map(String::from)
+    .collect()
 }
 fn default_log_level() -> String {
   "info".to_string()
 }
+fn default_log_format() -> String {
+  "text".to_string()
+}
+fn default_log_rotation() -> String {
+  "never".to_string()
+}
+fn default_log_max_files() -> usize {
+  7
+}
 fn default_user_agent() -> String {
   "Wallflow/1.0 (+https://github.com/MKSG-MugunthKumar/wallflow)".to_string()
 }
@@ -248,20 +705,146 @@ fn default_parallel_downloads() -> u32 {
 fn default_retry_attempts() -> u32 {
   3
 }
+fn default_retry_delay_ms() -> u64 {
+  500
+}
 fn default_timeout() -> u32 {
   30
 }
 
+/// Fields that can be overridden with environment variables. The variable name is
+/// `WALLFLOW_` plus the upper-cased key with dots as underscores, so `timer.interval`
+/// is set by `WALLFLOW_TIMER_INTERVAL`.
+pub const ENV_OVERRIDES: &[&str] = &[
+  "paths.local",
+  "paths.downloads",
+  "transition.type",
+  "transition.duration",
+  "transition.fps",
+  "timer.interval",
+  "timer.randomize",
+  "sources.default",
+  "sources.min_resolution",
+  "sources.orientation",
+  "sources.local.subdir",
+  "sources.wallhaven.api_key",
+  "sources.apod.api_key",
+  "sources.unsplash.access_key",
+  "sources.pexels.api_key",
+  "sources.pixabay.key",
+  "sources.flickr.api_key",
+  "sources.url.endpoint",
+  "cleanup.keep_count",
+  "cleanup.auto_cleanup",
+  "colors.enabled",
+  "colors.prefer_dark",
+  "integration.reload_apps",
+  "logging.enabled",
+  "logging.level",
+  "logging.format",
+  "display.use_logical",
+];
+
+/// Environment variable that overrides a config key
+pub fn env_var_name(key: &str) -> String {
+  format!("WALLFLOW_{}", key.replace('.', "_").to_uppercase())
+}
+
+/// Config file formats, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+  Yaml,
+  Toml,
+}
+
+impl ConfigFormat {
+  /// `.toml` files are TOML; everything else (`.yml`, `.yaml`, no extension) is YAML
+  fn from_path(path: &Path) -> Self {
+    match path.extension().and_then(|ext| ext.to_str()) {
+      Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+      _ => Self::Yaml,
+    }
+  }
+}
+
 impl Config {
   /// Load configuration from file - SO MUCH CLEANER than AWK! ✨
+  ///
+  /// The format follows the extension: `.toml` is parsed as TOML, anything else as YAML.
   pub fn load(path: &Path) -> Result<Self> {
     let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-    let config: Config = serde_yaml::from_str(&contents).with_context(|| format!("Failed to parse YAML config: {}", path.display()))?;
+    let config: Config = match ConfigFormat::from_path(path) {
+      ConfigFormat::Yaml => serde_yaml::from_str(&contents).with_context(|| format!("Failed to parse YAML config: {}", path.display()))?,
+      ConfigFormat::Toml => toml::from_str(&contents).with_context(|| format!("Failed to parse TOML config: {}", path.display()))?,
+    };
+
+    schedule::validate(&config.schedule).with_context(|| format!("Invalid schedule in {}", path.display()))?;
 
     Ok(config)
   }
 
+  /// Write configuration to file, as TOML for `.toml` paths and YAML otherwise
+  pub fn save(&self, path: &Path) -> Result<()> {
+    let contents = match ConfigFormat::from_path(path) {
+      ConfigFormat::Yaml => serde_yaml::to_string(self).context("Failed to serialize config as YAML")?,
+      ConfigFormat::Toml => toml::to_string_pretty(self).context("Failed to serialize config as TOML")?,
+    };
+
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, contents).with_context(|| format!("Failed to write config file: {}", path.display()))
+  }
+
+  /// Set a single field by dotted path (e.g. "timer.interval"), parsing `value` as YAML
+  ///
+  /// The key must already exist in the config, so typos are rejected instead of ignored.
+  /// Values that don't fit the field as YAML (an all-digit API key, say) are retried as plain strings.
+  pub fn set_field(&mut self, key: &str, value: &str) -> Result<()> {
+    let yaml = serde_yaml::from_str(value).unwrap_or_else(|_| serde_yaml::Value::String(value.to_string()));
+
+    let updated = self
+      .with_field(key, yaml)
+      .or_else(|e| self.with_field(key, serde_yaml::Value::String(value.to_string())).map_err(|_| e))?;
+    *self = updated;
+    Ok(())
+  }
+
+  /// A copy of this config with the field at `key` replaced
+  fn with_field(&self, key: &str, value: serde_yaml::Value) -> Result<Self> {
+    let mut root = serde_yaml::to_value(self).context("Failed to serialize config")?;
+
+    let mut node = &mut root;
+    for segment in key.split('.') {
+      node = node
+        .as_mapping_mut()
+        .and_then(|mapping| mapping.get_mut(segment))
+        .ok_or_else(|| anyhow::anyhow!("Unknown config key '{}'", key))?;
+    }
+    *node = value;
+
+    serde_yaml::from_value(root).with_context(|| format!("Invalid value for {}", key))
+  }
+
+  /// Override fields from `WALLFLOW_*` environment variables (see [`ENV_OVERRIDES`])
+  ///
+  /// Call after loading the file and before CLI flags, so precedence is file < env < CLI.
+  pub fn apply_env_overrides(&mut self) -> Result<()> {
+    self.apply_overrides_from(|name| std::env::var(name).ok())
+  }
+
+  fn apply_overrides_from(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+    for key in ENV_OVERRIDES {
+      let name = env_var_name(key);
+      if let Some(value) = lookup(&name) {
+        self.set_field(key, &value).with_context(|| format!("Invalid {}", name))?;
+        tracing::debug!("{} overridden by {}", key, name);
+      }
+    }
+    Ok(())
+  }
+
   /// Get default config file path (XDG compliant)
   pub fn default_path() -> PathBuf {
     dirs::config_dir()
@@ -271,14 +854,21 @@ impl Config {
       .join("config.yml")
   }
 
-  /// Load with fallback to defaults
-  pub fn load_or_default() -> Result<Self> {
+  /// The default config file that `load_or_default` would read, if any
+  pub fn existing_default_path() -> Option<PathBuf> {
     let path = Self::default_path();
+    let toml_path = path.with_extension("toml");
+    [path, toml_path].into_iter().find(|p| p.exists())
+  }
 
-    if path.exists() {
+  /// Load with fallback to defaults
+  ///
+  /// `config.toml` next to the default path is used when there is no `config.yml`.
+  pub fn load_or_default() -> Result<Self> {
+    if let Some(path) = Self::existing_default_path() {
       Self::load(&path)
     } else {
-      tracing::warn!("Config file not found at {}, using defaults", path.display());
+      tracing::warn!("Config file not found at {}, using defaults", Self::default_path().display());
       Ok(Self::default())
     }
   }
@@ -288,26 +878,84 @@ impl Config {
     self.paths.local = resolve_wallpaper_path(&self.paths.local);
     self.paths.downloads = resolve_wallpaper_path(&self.paths.downloads);
 
+    for entry in &mut self.schedule {
+      if let Some(path) = &entry.path {
+        entry.path = Some(resolve_wallpaper_path(path));
+      }
+    }
+
     Ok(())
   }
 
-  /// Get wallhaven resolution (from config or auto-detect)
+  /// Config with the schedule entry active at `time` applied, or `None` when no range matches
+  pub fn scheduled_for(&self, time: chrono::NaiveTime) -> Option<Config> {
+    let entry = schedule::active_entry(&self.schedule, time)?;
+    let mut config = self.clone();
+
+    if let Some(path) = &entry.path {
+      config.paths.local = path.clone();
+      config.sources.default = "local".to_string();
+    }
+    if let Some(source) = &entry.source {
+      config.sources.default = source.clone();
+    }
+
+    Some(config)
+  }
+
+  /// Get wallhaven resolution (from config, or detected for `monitor` / the primary display)
   #[allow(dead_code)]
-  pub fn get_wallhaven_resolution(&self) -> Result<crate::display::Resolution> {
+  pub fn get_wallhaven_resolution(&self, monitor: Option<&str>) -> Result<crate::display::Resolution> {
     match &self.sources.wallhaven.resolution {
       Some(res_str) => crate::display::Resolution::from_string(res_str),
-      None => crate::display::get_primary_display_resolution(),
+      None => self.detect_resolution(monitor),
+    }
+  }
+
+  /// Minimum resolution for downloads, if configured
+  pub fn get_min_resolution(&self) -> Result<Option<crate::display::Resolution>> {
+    self
+      .sources
+      .min_resolution
+      .as_deref()
+      .filter(|res| !res.is_empty())
+      .map(crate::display::Resolution::from_string)
+      .transpose()
+  }
+
+  /// Orientation downloads should have; "auto" follows `monitor` (or the primary display)
+  /// and falls back to landscape when the display can't be detected
+  pub fn get_orientation(&self, monitor: Option<&str>) -> crate::display::Orientation {
+    use crate::display::Orientation;
+
+    match self.sources.orientation {
+      SourceOrientation::Landscape => Orientation::Landscape,
+      SourceOrientation::Portrait => Orientation::Portrait,
+      SourceOrientation::Auto => self
+        .detect_resolution(monitor)
+        .map(|resolution| resolution.orientation())
+        .unwrap_or(Orientation::Landscape),
     }
   }
 
-  /// Get picsum resolution (from config or auto-detect)
+  /// Get picsum resolution (from config, or detected for `monitor` / the primary display)
   #[allow(dead_code)]
-  pub fn get_picsum_resolution(&self) -> Result<crate::display::Resolution> {
+  pub fn get_picsum_resolution(&self, monitor: Option<&str>) -> Result<crate::display::Resolution> {
     match (self.sources.picsum.width, self.sources.picsum.height) {
       (Some(w), Some(h)) => Ok(crate::display::Resolution::new(w, h)),
-      _ => crate::display::get_primary_display_resolution(),
+      _ => self.detect_resolution(monitor),
     }
   }
+
+  /// Detected resolution of `monitor` (or the primary display), logical if `display.use_logical` is set
+  fn detect_resolution(&self, monitor: Option<&str>) -> Result<crate::display::Resolution> {
+    let resolution = match monitor {
+      Some(output) => crate::display::get_display_resolution(output)?,
+      None => crate::display::get_primary_display_resolution()?,
+    };
+
+    Ok(if self.display.use_logical { resolution.logical() } else { resolution })
+  }
 }
 
 /// Create default configuration
@@ -326,46 +974,66 @@ impl Default for Config {
         transition_type: TransitionType::Single("random".to_string()),
         duration: 5,
         fps: 30,
+        per_monitor: false,
+        scaling: default_scaling(),
+        fit_to_display: false,
+        span_monitors: false,
+        post_process: PostProcessConfig::default(),
       },
       timer: TimerConfig {
         interval: 30,
         randomize: "5m".to_string(),
         start_delay: Some("1m".to_string()),
+        respect_manual: false,
       },
       sources: SourcesConfig {
         default: "local".to_string(),
+        min_resolution: None,
+        orientation: SourceOrientation::Auto,
+        fallback: Vec::new(),
         wallhaven: WallhavenConfig::default(),
         picsum: PicsumConfig::default(),
         local: LocalConfig::default(),
         apod: ApodConfig::default(),
+        bing: BingConfig::default(),
         unsplash: UnsplashConfig::default(),
+        pexels: PexelsConfig::default(),
+        pixabay: PixabayConfig::default(),
+        flickr: FlickrConfig::default(),
+        url: UrlConfig::default(),
         reddit: RedditConfig::default(),
       },
       cleanup: CleanupConfig {
         keep_count: 10,
         auto_cleanup: true,
+        deduplicate: false,
+        save_metadata: false,
       },
       integration: IntegrationConfig::default(),
       colors: ColorsConfig::default(),
       logging: LoggingConfig::default(),
       advanced: AdvancedConfig::default(),
+      history: HistoryConfig::default(),
+      display: DisplayConfig::default(),
+      schedule: Vec::new(),
     }
   }
 }
 
 fn resolve_wallpaper_path(path: &str) -> String {
-  let path_obj = Path::new(path);
+  // Expand ~ and env vars like $HOME first, they usually make the path absolute
+  let expanded = shellexpand::full(path).map(|s| s.into_owned()).unwrap_or_else(|_| path.to_string());
 
-  if path_obj.is_absolute() {
-    // Absolute path - expand any env vars like $HOME or ~
-    shellexpand::full(path).map(|s| s.into_owned()).unwrap_or_else(|_| path.to_string())
+  if Path::new(&expanded).is_absolute() {
+    expanded
   } else {
     // Relative path - resolve from XDG pictures dir
-    let base = dirs::picture_dir().unwrap_or_else(|| PathBuf::from("~/Pictures"));
-    let full_path = base.join(path_obj);
-    let full_path_str = full_path.to_string_lossy();
-    shellexpand::full(&full_path_str)
-      .map(|s| s.into_owned())
-      .unwrap_or_else(|_| full_path_str.into_owned())
+    let base = dirs::picture_dir()
+      .or_else(|| dirs::home_dir().map(|home| home.join("Pictures")))
+      .unwrap_or_else(|| PathBuf::from("Pictures"));
+    base.join(expanded).to_string_lossy().into_owned()
   }
 }
+
+#[cfg(test)]
+mod tests;
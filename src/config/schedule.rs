@@ -0,0 +1,163 @@
+//! Time-of-day wallpaper scheduling
+//!
+//! Each entry maps a daily time range to a source or a local folder:
+//!
+//! ```yaml
+//! schedule:
+//!   - { start: "06:00", end: "12:00", path: "morning" }
+//!   - { start: "12:00", end: "18:00", source: "bing" }
+//!   - { start: "22:00", end: "06:00", path: "night" } # wraps past midnight
+//! ```
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScheduleEntry {
+  /// Start of the range, "HH:MM" (inclusive)
+  pub start: String,
+  /// End of the range, "HH:MM" (exclusive). An end before the start wraps past midnight.
+  pub end: String,
+  /// Source to use in this range (e.g. "bing"). Defaults to "local" when only `path` is set.
+  #[serde(default)]
+  pub source: Option<String>,
+  /// Local folder to pick wallpapers from in this range
+  #[serde(default)]
+  pub path: Option<String>,
+}
+
+impl ScheduleEntry {
+  /// Start and end as minutes since midnight
+  fn minutes(&self) -> Result<(u32, u32)> {
+    Ok((parse_time(&self.start)?, parse_time(&self.end)?))
+  }
+
+  /// Whether `minute` (since midnight) falls inside this range
+  fn contains(&self, minute: u32) -> bool {
+    match self.minutes() {
+      Ok((start, end)) if start <= end => minute >= start && minute < end,
+      Ok((start, end)) => minute >= start || minute < end,
+      Err(_) => false,
+    }
+  }
+
+  /// The range split into non-wrapping `[start, end)` spans
+  fn spans(&self) -> Result<Vec<(u32, u32)>> {
+    let (start, end) = self.minutes()?;
+    Ok(if start <= end {
+      vec![(start, end)]
+    } else {
+      vec![(start, MINUTES_PER_DAY), (0, end)]
+    })
+  }
+
+  fn describe(&self) -> String {
+    format!("{}-{}", self.start, self.end)
+  }
+}
+
+fn parse_time(value: &str) -> Result<u32> {
+  let time = NaiveTime::parse_from_str(value.trim(), "%H:%M").with_context(|| format!("Invalid time '{}', expected HH:MM", value))?;
+  Ok(time.hour() * 60 + time.minute())
+}
+
+/// Check that every entry is well-formed and no two ranges overlap
+pub fn validate(entries: &[ScheduleEntry]) -> Result<()> {
+  for entry in entries {
+    let (start, end) = entry.minutes()?;
+    if start == end {
+      return Err(anyhow!("Schedule range {} is empty", entry.describe()));
+    }
+    if entry.source.is_none() && entry.path.is_none() {
+      return Err(anyhow!("Schedule range {} needs a source or a path", entry.describe()));
+    }
+  }
+
+  for (i, a) in entries.iter().enumerate() {
+    let a_spans = a.spans()?;
+    for b in &entries[i + 1..] {
+      let b_spans = b.spans()?;
+      let overlaps = a_spans
+        .iter()
+        .any(|(a_start, a_end)| b_spans.iter().any(|(b_start, b_end)| a_start < b_end && b_start < a_end));
+
+      if overlaps {
+        return Err(anyhow!("Schedule ranges {} and {} overlap", a.describe(), b.describe()));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// The entry whose range contains `time`, if any
+pub fn active_entry(entries: &[ScheduleEntry], time: NaiveTime) -> Option<&ScheduleEntry> {
+  let minute = time.hour() * 60 + time.minute();
+  entries.iter().find(|entry| entry.contains(minute))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(start: &str, end: &str, path: &str) -> ScheduleEntry {
+    ScheduleEntry {
+      start: start.to_string(),
+      end: end.to_string(),
+      source: None,
+      path: Some(path.to_string()),
+    }
+  }
+
+  fn at(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+  }
+
+  #[test]
+  fn test_active_entry() {
+    let entries = vec![
+      entry("06:00", "12:00", "morning"),
+      entry("12:00", "18:00", "day"),
+      entry("18:00", "22:00", "evening"),
+      entry("22:00", "06:00", "night"),
+    ];
+    assert!(validate(&entries).is_ok());
+
+    let path_at = |h, m| active_entry(&entries, at(h, m)).and_then(|e| e.path.clone());
+    assert_eq!(path_at(6, 0).as_deref(), Some("morning"));
+    assert_eq!(path_at(11, 59).as_deref(), Some("morning"));
+    assert_eq!(path_at(12, 0).as_deref(), Some("day"));
+    assert_eq!(path_at(21, 30).as_deref(), Some("evening"));
+    assert_eq!(path_at(23, 15).as_deref(), Some("night"));
+    assert_eq!(path_at(3, 0).as_deref(), Some("night"));
+  }
+
+  #[test]
+  fn test_gaps_have_no_entry() {
+    let entries = vec![entry("09:00", "17:00", "work")];
+    assert!(active_entry(&entries, at(8, 59)).is_none());
+    assert!(active_entry(&entries, at(17, 0)).is_none());
+  }
+
+  #[test]
+  fn test_validate_rejects_overlaps() {
+    assert!(validate(&[entry("06:00", "12:00", "a"), entry("11:00", "14:00", "b")]).is_err());
+    // Wrapping range overlapping the early morning
+    assert!(validate(&[entry("22:00", "07:00", "a"), entry("06:00", "12:00", "b")]).is_err());
+    // Touching ranges are fine
+    assert!(validate(&[entry("22:00", "06:00", "a"), entry("06:00", "22:00", "b")]).is_ok());
+  }
+
+  #[test]
+  fn test_validate_rejects_bad_entries() {
+    assert!(validate(&[entry("25:00", "06:00", "a")]).is_err());
+    assert!(validate(&[entry("06:00", "06:00", "a")]).is_err());
+
+    let mut no_target = entry("06:00", "07:00", "a");
+    no_target.path = None;
+    assert!(validate(&[no_target]).is_err());
+  }
+}
@@ -11,14 +11,15 @@ fn test_config_default() {
   assert_eq!(config.transition.duration, 5);
   assert_eq!(config.timer.interval, 30);
   assert_eq!(config.timer.randomize, "5m");
-  assert_eq!(config.sources.default, "wallhaven");
+  assert_eq!(config.sources.default, "local");
   assert_eq!(config.cleanup.keep_count, 10);
   assert!(config.cleanup.auto_cleanup);
 
-  // Check advanced config defaults (from Config::default implementation, not struct Default)
-  assert_eq!(config.advanced.parallel_downloads, 0); // Uses AdvancedConfig::default()
-  assert_eq!(config.advanced.retry_attempts, 0);
-  assert_eq!(config.advanced.timeout, 0);
+  // Check advanced config defaults, including hot reload being on
+  assert_eq!(config.advanced.parallel_downloads, 3); // Same as the serde defaults
+  assert_eq!(config.advanced.retry_attempts, 3);
+  assert_eq!(config.advanced.timeout, 30);
+  assert!(config.advanced.watch_config);
 
   // Test nested defaults (from Default trait, not serde defaults)
   assert!(!config.integration.reload_apps); // Default trait sets to false
@@ -90,6 +91,152 @@ logging:
   assert_eq!(config.logging.level, "debug");
 }
 
+#[test]
+fn test_config_load_from_toml_file() {
+  let temp_dir = tempdir().expect("Failed to create temp dir");
+  let config_path = temp_dir.path().join("test_config.toml");
+
+  let toml_content = r#"
+[paths]
+local = "/home/user/wallpapers"
+downloads = "/home/user/downloads"
+
+[transition]
+type = "fade"
+duration = 10
+fps = 60
+
+[timer]
+interval = 60
+randomize = "10m"
+
+[sources]
+default = "local"
+
+[cleanup]
+keep_count = 5
+auto_cleanup = false
+
+[integration]
+reload_apps = false
+
+[logging]
+enabled = false
+level = "debug"
+"#;
+
+  fs::write(&config_path, toml_content).expect("Failed to write test config");
+
+  let config = Config::load(&config_path).expect("Failed to load config");
+
+  assert_eq!(config.paths.local, "/home/user/wallpapers");
+  assert_eq!(config.paths.downloads, "/home/user/downloads");
+  assert_eq!(config.timer.interval, 60);
+  assert_eq!(config.sources.default, "local");
+  assert_eq!(config.cleanup.keep_count, 5);
+  assert!(!config.cleanup.auto_cleanup);
+  assert!(!config.logging.enabled);
+  assert_eq!(config.logging.level, "debug");
+}
+
+#[test]
+fn test_config_toml_round_trip() {
+  let temp_dir = tempdir().expect("Failed to create temp dir");
+  let config_path = temp_dir.path().join("config.toml");
+
+  let config = Config::default();
+  let toml = toml::to_string(&config).expect("Failed to serialize config");
+  fs::write(&config_path, toml).expect("Failed to write test config");
+
+  let loaded = Config::load(&config_path).expect("Failed to load config");
+
+  assert_eq!(loaded.timer.interval, config.timer.interval);
+  assert_eq!(loaded.sources.default, config.sources.default);
+  assert_eq!(loaded.cleanup.keep_count, config.cleanup.keep_count);
+  assert_eq!(loaded.paths.local, config.paths.local);
+}
+
+#[test]
+fn test_config_load_invalid_toml() {
+  let temp_dir = tempdir().expect("Failed to create temp dir");
+  let config_path = temp_dir.path().join("invalid_config.toml");
+
+  fs::write(&config_path, "[paths\nlocal = ").expect("Failed to write invalid config");
+
+  let result = Config::load(&config_path);
+  assert!(result.is_err());
+  assert!(result.unwrap_err().to_string().contains("Failed to parse TOML config"));
+}
+
+#[test]
+fn test_config_save_round_trip() {
+  let temp_dir = tempdir().expect("Failed to create temp dir");
+
+  for name in ["nested/config.yml", "nested/config.toml"] {
+    let config_path = temp_dir.path().join(name);
+    let mut config = Config::default();
+    config.timer.interval = 42;
+
+    config.save(&config_path).expect("Failed to save config");
+    let loaded = Config::load(&config_path).expect("Failed to load saved config");
+
+    assert_eq!(loaded.timer.interval, 42);
+    assert_eq!(loaded.sources.default, config.sources.default);
+  }
+}
+
+#[test]
+fn test_config_set_field() {
+  let mut config = Config::default();
+
+  config.set_field("timer.interval", "15").expect("Failed to set interval");
+  config.set_field("sources.default", "bing").expect("Failed to set source");
+  config.set_field("colors.prefer_dark", "true").expect("Failed to set prefer_dark");
+
+  assert_eq!(config.timer.interval, 15);
+  assert_eq!(config.sources.default, "bing");
+  assert_eq!(config.colors.prefer_dark, Some(true));
+
+  assert!(config.set_field("timer.intervall", "15").is_err());
+  assert!(config.set_field("timer.interval", "soon").is_err());
+  assert_eq!(config.timer.interval, 15);
+}
+
+#[test]
+fn test_config_env_overrides() {
+  let env: std::collections::HashMap<&str, &str> = [
+    ("WALLFLOW_SOURCES_DEFAULT", "bing"),
+    ("WALLFLOW_TIMER_INTERVAL", "45"),
+    ("WALLFLOW_SOURCES_PEXELS_API_KEY", "123456"),
+  ]
+  .into();
+
+  let mut config = Config::default();
+  config
+    .apply_overrides_from(|name| env.get(name).map(|v| v.to_string()))
+    .expect("Failed to apply overrides");
+
+  assert_eq!(config.sources.default, "bing");
+  assert_eq!(config.timer.interval, 45);
+  assert_eq!(config.sources.pexels.api_key.as_deref(), Some("123456"));
+
+  let err = config
+    .apply_overrides_from(|name| (name == "WALLFLOW_TIMER_INTERVAL").then(|| "often".to_string()))
+    .unwrap_err();
+  assert!(err.to_string().contains("WALLFLOW_TIMER_INTERVAL"));
+}
+
+#[test]
+fn test_env_override_keys_exist() {
+  for key in ENV_OVERRIDES {
+    let mut config = Config::default();
+    let current = serde_yaml::to_value(&config).unwrap();
+    let value = key.split('.').try_fold(&current, |node, segment| node.get(segment));
+    assert!(value.is_some(), "{} is not a config field", key);
+    assert!(config.set_field(key, &serde_yaml::to_string(value.unwrap()).unwrap()).is_ok(), "{}", key);
+  }
+}
+
 #[test]
 fn test_config_load_missing_file() {
   let path = PathBuf::from("/nonexistent/config.yml");
@@ -142,7 +289,6 @@ timer:
   randomize: "5m"
 sources:
   default: local
-  category: nature
 cleanup:
   keep_count: 10
 integration:
@@ -171,7 +317,6 @@ timer:
   randomize: "5m"
 sources:
   default: local
-  category: nature
 cleanup:
   keep_count: 10
 integration:
@@ -199,14 +344,15 @@ fn test_wallhaven_config_defaults() {
   let config = WallhavenConfig::default();
 
   assert!(config.resolution.is_none());
-  assert_eq!(config.q, ""); // Default trait sets to empty string
+  assert_eq!(config.quality, ""); // Default trait sets to empty string
 
   // Test that serde defaults work during deserialization
   let minimal_yaml = r#"{}"#;
   let config: WallhavenConfig = serde_yaml::from_str(minimal_yaml).expect("Failed to parse minimal wallhaven config");
 
   assert!(config.resolution.is_none());
-  assert_eq!(config.q, "large");
+  assert_eq!(config.q, "");
+  assert_eq!(config.quality, "large");
 }
 
 #[test]
@@ -230,17 +376,36 @@ fn test_local_config_defaults() {
   let config: LocalConfig = serde_yaml::from_str(minimal_yaml).expect("Failed to parse minimal local config");
 
   assert!(config.recursive);
-  assert_eq!(config.formats, vec!["jpg", "jpeg", "png", "webp"]);
+  assert_eq!(config.formats, default_formats());
+}
+
+#[test]
+fn test_local_subdir() {
+  let temp_dir = tempdir().expect("Failed to create temp dir");
+  fs::create_dir(temp_dir.path().join("nature")).unwrap();
+  let root = temp_dir.path().to_string_lossy().to_string();
+
+  let mut config = LocalConfig::default();
+  assert_eq!(config.directory(&root).unwrap(), temp_dir.path());
+
+  config.subdir = Some("nature".to_string());
+  assert_eq!(config.directory(&root).unwrap(), temp_dir.path().join("nature"));
+
+  config.subdir = Some("cities".to_string());
+  let err = config.directory(&root).unwrap_err().to_string();
+  assert!(err.contains("'cities' does not exist"), "{}", err);
 }
 
 #[test]
 fn test_advanced_config_defaults() {
-  // Test the struct default (derives Default)
+  // The struct default matches the serde defaults
   let config = AdvancedConfig::default();
 
-  assert_eq!(config.parallel_downloads, 0);
-  assert_eq!(config.retry_attempts, 0);
-  assert_eq!(config.timeout, 0);
+  assert_eq!(config.parallel_downloads, 3);
+  assert_eq!(config.retry_attempts, 3);
+  assert_eq!(config.timeout, 30);
+  assert!(config.watch_config);
+  assert!(!config.offline_fallback);
 
   // Test that serde defaults are used when deserializing minimal config
   let minimal_yaml = r#"{}"#;
@@ -249,17 +414,29 @@ fn test_advanced_config_defaults() {
   assert_eq!(config.parallel_downloads, 3);
   assert_eq!(config.retry_attempts, 3);
   assert_eq!(config.timeout, 30);
+  assert!(config.watch_config);
+}
+
+#[test]
+fn test_config_without_advanced_section() {
+  let mut yaml = serde_yaml::to_value(Config::default()).unwrap();
+  yaml.as_mapping_mut().unwrap().remove("advanced");
+
+  let config: Config = serde_yaml::from_value(yaml).expect("Failed to parse config without advanced");
+  assert!(config.advanced.watch_config);
+  assert_eq!(config.advanced.parallel_downloads, 3);
+  assert_eq!(config.advanced.user_agent, AdvancedConfig::default().user_agent);
 }
 
 #[test]
 fn test_logging_config_defaults() {
-  // Test the struct default (derives Default)
+  // The struct default matches the serde defaults
   let config = LoggingConfig::default();
 
-  assert!(!config.enabled); // Default trait sets to false
-  assert_eq!(config.level, ""); // Default trait sets to empty string
+  assert!(config.enabled);
+  assert_eq!(config.level, "info");
   assert!(config.file.is_none());
-  assert!(!config.timestamp); // Default trait sets to false
+  assert!(config.timestamp);
 
   // Test that serde defaults work during deserialization
   let minimal_yaml = r#"{}"#;
@@ -287,6 +464,18 @@ fn test_expand_paths() {
   assert!(!config.paths.downloads.contains('$'));
 }
 
+#[test]
+fn test_resolve_wallpaper_path() {
+  let home = dirs::home_dir().unwrap();
+  assert_eq!(resolve_wallpaper_path("~/walls"), home.join("walls").to_string_lossy());
+  assert_eq!(resolve_wallpaper_path("/srv/walls"), "/srv/walls");
+
+  // Relative paths are inside the pictures folder, and never keep a literal ~
+  let nested = resolve_wallpaper_path("walls/nature");
+  assert!(nested.ends_with("walls/nature"), "{}", nested);
+  assert!(Path::new(&nested).is_absolute() && !nested.contains('~'), "{}", nested);
+}
+
 #[test]
 fn test_expand_paths_invalid() {
   let mut config = Config::default();
@@ -316,7 +505,6 @@ timer:
   randomize: "5m"
 sources:
   default: local
-  category: nature
 cleanup:
   keep_count: 10
 integration:
@@ -349,7 +537,6 @@ timer:
   randomize: "5m"
 sources:
   default: local
-  category: nature
   local: {}
 cleanup:
   keep_count: 10
@@ -365,7 +552,7 @@ logging:
   // Optional fields should use defaults
   assert_eq!(config.transition.fps, 30); // default_fps
   assert!(config.sources.local.recursive); // default_true from serde
-  assert_eq!(config.sources.local.formats, vec!["jpg", "jpeg", "png", "webp"]); // default_formats from serde
+  assert_eq!(config.sources.local.formats, default_formats()); // default_formats from serde
   assert!(config.logging.enabled); // default_true from serde
   assert_eq!(config.logging.level, "info"); // default_log_level from serde
 }
@@ -406,3 +593,26 @@ mod property_tests {
     )
   }
 }
+
+#[test]
+fn test_source_orientation() {
+  let config: SourcesConfig = serde_yaml::from_str("default: local\norientation: portrait").unwrap();
+  assert_eq!(config.orientation, SourceOrientation::Portrait);
+  let config: SourcesConfig = serde_yaml::from_str("default: local").unwrap();
+  assert_eq!(config.orientation, SourceOrientation::Auto);
+  assert!(serde_yaml::from_str::<SourcesConfig>("default: local\norientation: sideways").is_err());
+
+  let mut config = Config::default();
+  config.set_field("sources.orientation", "landscape").unwrap();
+  assert_eq!(config.get_orientation(None), crate::display::Orientation::Landscape);
+  assert!(config.set_field("sources.orientation", "sideways").is_err());
+}
+
+#[test]
+fn test_unsplash_orientation() {
+  let config: UnsplashConfig = serde_yaml::from_str("orientation: squarish").unwrap();
+  assert_eq!(config.orientation, Some(UnsplashOrientation::Squarish));
+  assert!(serde_yaml::from_str::<UnsplashConfig>("orientation: square").is_err());
+
+  assert_eq!(UnsplashOrientation::from(crate::display::Orientation::Portrait).as_str(), "portrait");
+}
@@ -0,0 +1,350 @@
+//! Semantic checks for a loaded configuration
+//!
+//! Parsing only catches malformed files. These checks catch values that parse
+//! fine but fail later, such as unknown sources or a zero timer interval.
+
+use std::path::Path;
+
+use super::Config;
+
+/// How serious a problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+  Error,
+  Warning,
+}
+
+/// A single problem found in the configuration
+#[derive(Debug, Clone)]
+pub struct Issue {
+  pub severity: Severity,
+  /// Dotted path of the offending field (e.g. "sources.default")
+  pub field: String,
+  pub message: String,
+}
+
+impl Issue {
+  fn error(field: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      severity: Severity::Error,
+      field: field.into(),
+      message: message.into(),
+    }
+  }
+
+  fn warning(field: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      severity: Severity::Warning,
+      field: field.into(),
+      message: message.into(),
+    }
+  }
+}
+
+/// Run every check and collect the problems found
+pub fn validate(config: &Config, known_sources: &[String]) -> Vec<Issue> {
+  let mut issues = Vec::new();
+
+  check_dir(&mut issues, "paths.local", &config.paths.local);
+  check_dir(&mut issues, "paths.downloads", &config.paths.downloads);
+
+  if config.transition.duration == 0 {
+    issues.push(Issue::error("transition.duration", "must be greater than 0"));
+  }
+  if config.transition.fps == 0 {
+    issues.push(Issue::error("transition.fps", "must be greater than 0"));
+  }
+  if config.transition.span_monitors && config.transition.per_monitor {
+    issues.push(Issue::warning(
+      "transition.span_monitors",
+      "local wallpapers follow per_monitor instead, spanning only applies to other sources",
+    ));
+  }
+  if let Some(blur) = config.transition.post_process.blur
+    && !(blur >= 0.0 && blur.is_finite())
+  {
+    issues.push(Issue::error("transition.post_process.blur", "must be 0 or more"));
+  }
+  if let Some(dim) = config.transition.post_process.dim
+    && !(0.0..=1.0).contains(&dim)
+  {
+    issues.push(Issue::error(
+      "transition.post_process.dim",
+      format!("{} is outside 0.0-1.0 (0.3 darkens by 30%)", dim),
+    ));
+  }
+  if config.timer.interval == 0 {
+    issues.push(Issue::error("timer.interval", "must be greater than 0 (minutes)"));
+  }
+
+  let (min_contrast, max_contrast) = crate::colors::ExtractionOptions::CONTRAST_RANGE;
+  if !(min_contrast..=max_contrast).contains(&config.colors.contrast_ratio) {
+    issues.push(Issue::warning(
+      "colors.contrast_ratio",
+      format!(
+        "{} is outside {}-{} and will be clamped",
+        config.colors.contrast_ratio, min_contrast, max_contrast
+      ),
+    ));
+  }
+  if let Some(percent) = config.colors.center_percent
+    && !(1.0..=100.0).contains(&percent)
+  {
+    issues.push(Issue::warning(
+      "colors.center_percent",
+      format!("{} is outside 1-100 and will be clamped", percent),
+    ));
+  }
+  if config.colors.color_count == 0 {
+    issues.push(Issue::error("colors.color_count", "must be greater than 0"));
+  }
+
+  let is_known = |source: &str| matches!(source, "local" | "favorites") || known_sources.iter().any(|s| s == source);
+
+  let mut sources_in_use = vec![config.sources.default.as_str()];
+  if !is_known(&config.sources.default) {
+    issues.push(Issue::error(
+      "sources.default",
+      format!(
+        "unknown source '{}'. Known sources: local, favorites, {}",
+        config.sources.default,
+        known_sources.join(", ")
+      ),
+    ));
+  }
+
+  for source in &config.sources.fallback {
+    sources_in_use.push(source);
+    if !is_known(source) {
+      issues.push(Issue::error("sources.fallback", format!("unknown source '{}'", source)));
+    }
+  }
+
+  if let Err(e) = config.get_min_resolution() {
+    issues.push(Issue::error("sources.min_resolution", format!("{:#}", e)));
+  }
+  if let Some(blur) = config.sources.picsum.blur
+    && !(1..=10).contains(&blur)
+  {
+    issues.push(Issue::error("sources.picsum.blur", format!("{} is outside 1-10", blur)));
+  }
+
+  if config.sources.unsplash.width == Some(0) {
+    issues.push(Issue::error("sources.unsplash.width", "must be greater than 0"));
+  }
+
+  if crate::downloaders::bing::resolution_suffix(&config.sources.bing.resolution).is_none() {
+    issues.push(Issue::error(
+      "sources.bing.resolution",
+      format!(
+        "unknown resolution '{}'. Expected one of {}",
+        config.sources.bing.resolution,
+        crate::downloaders::bing::RESOLUTIONS.join(", ")
+      ),
+    ));
+  }
+  if !is_market_code(&config.sources.bing.market) {
+    issues.push(Issue::warning(
+      "sources.bing.market",
+      format!(
+        "'{}' doesn't look like a market code such as en-US or de-DE; Bing may fall back to its default",
+        config.sources.bing.market
+      ),
+    ));
+  }
+
+  if !matches!(config.logging.format.to_lowercase().as_str(), "text" | "json" | "logfmt") {
+    issues.push(Issue::error(
+      "logging.format",
+      format!("unknown format '{}'. Expected text, json or logfmt", config.logging.format),
+    ));
+  }
+
+  if !matches!(config.logging.rotation.to_lowercase().as_str(), "never" | "daily" | "hourly") {
+    issues.push(Issue::error(
+      "logging.rotation",
+      format!("unknown rotation '{}'. Expected never, daily or hourly", config.logging.rotation),
+    ));
+  }
+
+  if let Err(e) = super::schedule::validate(&config.schedule) {
+    issues.push(Issue::error("schedule", format!("{:#}", e)));
+  }
+  for entry in &config.schedule {
+    if let Some(source) = &entry.source {
+      sources_in_use.push(source);
+      if !is_known(source) {
+        issues.push(Issue::error(
+          "schedule.source",
+          format!("unknown source '{}' in {}-{}", source, entry.start, entry.end),
+        ));
+      }
+    }
+  }
+
+  for entry in &config.integration.notify_apps {
+    if crate::integration::NotifyTarget::parse(entry).is_none() {
+      issues.push(Issue::warning(
+        "integration.notify_apps",
+        format!("unknown app '{}' (supported: kitty, nvim)", entry),
+      ));
+    }
+  }
+
+  let missing_key = |key: &Option<String>| key.as_deref().is_none_or(str::is_empty);
+  for (source, field, missing) in [
+    (
+      "unsplash",
+      "sources.unsplash.access_key",
+      missing_key(&config.sources.unsplash.access_key),
+    ),
+    ("pexels", "sources.pexels.api_key", missing_key(&config.sources.pexels.api_key)),
+    ("pixabay", "sources.pixabay.key", missing_key(&config.sources.pixabay.key)),
+    ("flickr", "sources.flickr.api_key", missing_key(&config.sources.flickr.api_key)),
+  ] {
+    if missing && sources_in_use.contains(&source) {
+      issues.push(Issue::error(field, format!("{} is used but has no API key", source)));
+    }
+  }
+  if missing_key(&config.sources.url.endpoint) && sources_in_use.contains(&"url") {
+    issues.push(Issue::error("sources.url.endpoint", "url is used but has no endpoint"));
+  }
+
+  issues
+}
+
+/// Language and region, as in "en-US" or "ja-JP"
+fn is_market_code(market: &str) -> bool {
+  match market.split_once('-') {
+    Some((language, region)) => language.len() == 2 && region.len() == 2 && language.chars().chain(region.chars()).all(|c| c.is_ascii_alphabetic()),
+    None => false,
+  }
+}
+
+/// A directory must exist, or be creatable under an existing directory
+fn check_dir(issues: &mut Vec<Issue>, field: &str, value: &str) {
+  let path = Path::new(value);
+
+  if path.exists() {
+    if !path.is_dir() {
+      issues.push(Issue::error(field, format!("{} exists but is not a directory", value)));
+    }
+    return;
+  }
+
+  match path.ancestors().skip(1).find(|ancestor| ancestor.exists()) {
+    Some(ancestor) if ancestor.is_dir() => {
+      issues.push(Issue::warning(field, format!("{} does not exist yet and will be created", value)));
+    }
+    _ => issues.push(Issue::error(field, format!("{} cannot be created", value))),
+  }
+}
+
+/// 1-based line number where `field` is set in the config file, if it can be found
+///
+/// Walks the dotted path one key at a time, so `sources.default` matches the
+/// `default:` under `sources:` rather than the first `default:` in the file.
+/// TOML tables (`[sources]`) are matched the same way.
+pub fn locate(contents: &str, field: &str) -> Option<usize> {
+  let mut line_no = 0;
+
+  for key in field.split('.') {
+    let yaml_key = format!("{}:", key);
+    let toml_key = format!("{} =", key);
+    let toml_table = format!("[{}]", key);
+
+    let (offset, _) = contents.lines().enumerate().skip(line_no).find(|(_, line)| {
+      let line = line.trim_start().trim_start_matches("- ");
+      line.starts_with(&yaml_key) || line.starts_with(&toml_key) || line.trim_end().ends_with(&toml_table)
+    })?;
+    line_no = offset;
+  }
+
+  Some(line_no + 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sources() -> Vec<String> {
+    vec!["bing".to_string(), "pexels".to_string(), "wallhaven".to_string()]
+  }
+
+  fn config() -> Config {
+    let dir = std::env::temp_dir().to_string_lossy().to_string();
+    let mut config = Config::default();
+    config.paths.local = dir.clone();
+    config.paths.downloads = dir;
+    config
+  }
+
+  #[test]
+  fn test_default_config_is_valid() {
+    let issues = validate(&config(), &sources());
+    assert!(issues.is_empty(), "{:?}", issues);
+  }
+
+  #[test]
+  fn test_reports_bad_values() {
+    let mut config = config();
+    config.transition.post_process.dim = Some(30.0);
+    config.timer.interval = 0;
+    config.colors.contrast_ratio = 6.0;
+    config.sources.default = "pexels".to_string();
+    config.integration.notify_apps = vec!["kitty".to_string(), "emacs".to_string()];
+    config.schedule = vec![crate::config::ScheduleEntry {
+      start: "06:00".to_string(),
+      end: "12:00".to_string(),
+      source: Some("deviantart".to_string()),
+      path: None,
+    }];
+
+    let fields: Vec<String> = validate(&config, &sources()).into_iter().map(|issue| issue.field).collect();
+    assert_eq!(
+      fields,
+      vec![
+        "transition.post_process.dim",
+        "timer.interval",
+        "colors.contrast_ratio",
+        "schedule.source",
+        "integration.notify_apps",
+        "sources.pexels.api_key"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_bing_settings() {
+    let mut config = config();
+    config.sources.bing.resolution = "1920X1080".to_string();
+    config.sources.bing.market = "de-DE".to_string();
+    assert!(validate(&config, &sources()).iter().all(|issue| !issue.field.starts_with("sources.bing")));
+
+    config.sources.bing.resolution = "2560x1440".to_string();
+    config.sources.bing.market = "german".to_string();
+    let issues: Vec<(String, Severity)> = validate(&config, &sources())
+      .into_iter()
+      .filter(|issue| issue.field.starts_with("sources.bing"))
+      .map(|issue| (issue.field, issue.severity))
+      .collect();
+    assert_eq!(
+      issues,
+      vec![
+        ("sources.bing.resolution".to_string(), Severity::Error),
+        ("sources.bing.market".to_string(), Severity::Warning)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_locate() {
+    let yaml = "paths:\n  local: ~/a\ntimer:\n  interval: 0\nsources:\n  default: nope\n";
+    assert_eq!(locate(yaml, "timer.interval"), Some(4));
+    assert_eq!(locate(yaml, "sources.default"), Some(6));
+    assert_eq!(locate(yaml, "sources.pexels.api_key"), None);
+
+    let toml = "[timer]\ninterval = 0\n\n[sources]\ndefault = \"nope\"\n";
+    assert_eq!(locate(toml, "sources.default"), Some(5));
+  }
+}
@@ -0,0 +1,85 @@
+//! Config file watching
+//!
+//! Lets the daemon pick up edits without a SIGHUP. The parent directory is
+//! watched rather than the file itself, because editors usually save by
+//! writing a new file and renaming it over the old one.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep};
+use tracing::debug;
+
+/// Editors often write in several steps; wait this long for things to settle
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Reports changes to a single config file
+pub struct ConfigWatcher {
+  // Dropping the watcher stops the notifications
+  _watcher: RecommendedWatcher,
+  events: mpsc::UnboundedReceiver<()>,
+}
+
+impl ConfigWatcher {
+  /// Start watching `path`
+  pub fn new(path: &Path) -> Result<Self> {
+    let file_name = path.file_name().context("Config path has no file name")?.to_os_string();
+    let dir = match path.parent() {
+      Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+      _ => PathBuf::from("."),
+    };
+
+    let (tx, events) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+      let Ok(event) = event else { return };
+      if matches!(event.kind, EventKind::Access(_)) {
+        return;
+      }
+      if event.paths.iter().any(|p| p.file_name() == Some(file_name.as_os_str())) {
+        let _ = tx.send(());
+      }
+    })
+    .context("Failed to create config watcher")?;
+
+    watcher
+      .watch(&dir, RecursiveMode::NonRecursive)
+      .with_context(|| format!("Failed to watch {}", dir.display()))?;
+    debug!("Watching {} for config changes", path.display());
+
+    Ok(Self { _watcher: watcher, events })
+  }
+
+  /// Wait until the file has changed, coalescing bursts of events into one
+  pub async fn changed(&mut self) {
+    if self.events.recv().await.is_none() {
+      // Watcher is gone; never report a change again
+      return std::future::pending().await;
+    }
+
+    sleep(DEBOUNCE).await;
+    while self.events.try_recv().is_ok() {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  #[tokio::test]
+  async fn test_reports_changes_to_the_watched_file_only() {
+    let dir = TempDir::new().unwrap();
+    let config = dir.path().join("config.yml");
+    std::fs::write(&config, "a: 1").unwrap();
+
+    let mut watcher = ConfigWatcher::new(&config).unwrap();
+
+    std::fs::write(dir.path().join("other.yml"), "b: 2").unwrap();
+    assert!(tokio::time::timeout(Duration::from_millis(500), watcher.changed()).await.is_err());
+
+    std::fs::write(&config, "a: 2").unwrap();
+    assert!(tokio::time::timeout(Duration::from_secs(5), watcher.changed()).await.is_ok());
+  }
+}
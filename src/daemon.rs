@@ -1,27 +1,32 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use daemonize::Daemonize;
 use rand::Rng;
 use std::fs::File;
-use tokio::time::{Duration, interval, sleep};
+use std::path::PathBuf;
+use tokio::signal::unix::{Signal, SignalKind, signal};
+use tokio::time::{Duration, Instant, sleep, sleep_until};
 use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
+use crate::config::watch::ConfigWatcher;
+use crate::daemon_control::{ControlCommand, ControlRequest, ControlServer};
+use crate::daemon_status::DaemonStatusManager;
 use crate::downloaders::DownloadOptions;
-use crate::wallpaper;
+use crate::downloaders::error::DownloaderError;
+use crate::{daemon_status, history, paths, wallpaper};
 
 /// Run daemon in foreground with automatic wallpaper rotation
-pub async fn run_foreground(config: Config) -> Result<()> {
+///
+/// `config_path` is the file `config` came from; when set (and `advanced.watch_config`
+/// is on) edits to it are applied without restarting. SIGHUP reloads it on demand.
+/// SIGTERM and SIGINT stop the daemon and remove `daemon_status.json`.
+pub async fn run_foreground(config: Config, config_path: Option<PathBuf>) -> Result<()> {
+  let mut config = config;
   info!("🌊 wallflow daemon starting");
   info!("   Interval: {}m", config.timer.interval);
   info!("   Randomize: {}", config.timer.randomize);
   info!("   Source: {}", config.sources.default);
 
-  // Parse randomization duration
-  let randomize_secs = parse_duration(&config.timer.randomize).unwrap_or_else(|_| {
-    warn!("Invalid randomize format '{}', using 0", config.timer.randomize);
-    0
-  });
-
   // Initial delay if configured
   if let Some(start_delay) = &config.timer.start_delay
     && let Ok(delay_secs) = parse_duration(start_delay)
@@ -39,59 +44,456 @@ pub async fn run_foreground(config: Config) -> Result<()> {
     }
   }
 
+  let mut state = DaemonState {
+    paused: false,
+    timer: RotationTimer::start(rotation_interval(&config), randomize_secs(&config)),
+    rewind: Rewind::default(),
+    status: DaemonStatusManager::new()?,
+    applied: None,
+  };
+  if let Err(e) = state.status.initialize_daemon(&config).await {
+    warn!("Failed to write daemon status: {}", e);
+  }
+
   // Set initial wallpaper
   info!("Setting initial wallpaper...");
-  if let Err(e) = set_wallpaper_by_source(&config).await {
-    error!("Failed to set initial wallpaper: {}", e);
-    // Continue anyway - might work later
+  match set_wallpaper_by_source(&config).await {
+    Ok(_) => state.record_rotation().await,
+    Err(e) => {
+      error!("Failed to set initial wallpaper: {}", e);
+      // Continue anyway - might work later
+    }
   }
 
-  // Start rotation timer
-  let interval_secs = config.timer.interval as u64 * 60;
-  let mut timer = interval(Duration::from_secs(interval_secs));
+  // Listen for next/prev/pause/resume from the CLI
+  let control = match ControlServer::bind() {
+    Ok(server) => Some(server),
+    Err(e) => {
+      warn!("Control socket unavailable, daemon commands disabled: {}", e);
+      None
+    }
+  };
+
+  let mut config_watcher = match &config_path {
+    Some(path) if config.advanced.watch_config => match ConfigWatcher::new(path) {
+      Ok(watcher) => Some(watcher),
+      Err(e) => {
+        warn!("Config hot-reload disabled: {:#}", e);
+        None
+      }
+    },
+    _ => None,
+  };
+
+  let mut signals = Signals::install()?;
+  let mut loaded = LoadedFile::read(config_path.as_deref());
 
-  // Consume the first tick (tokio interval fires immediately on first tick)
-  timer.tick().await;
+  // Keeps daemon_status.json fresh between rotations so readers don't treat it as stale
+  let mut heartbeat = tokio::time::interval(Duration::from_secs(60));
 
   info!("✅ Daemon started, rotating every {}m", config.timer.interval);
 
   loop {
-    timer.tick().await;
+    tokio::select! {
+      _ = sleep_until(state.timer.next) => {
+        // The schedule keeps running while paused, so resuming doesn't trigger an immediate rotation
+        state.timer.advance();
+
+        if state.paused {
+          debug!("Timer tick while paused - skipping rotation");
+          continue;
+        }
+
+        if state.manually_changed(&config).await {
+          info!("Wallpaper was changed manually, skipping this rotation");
+          continue;
+        }
+
+        debug!("Timer tick - rotating wallpaper");
+
+        // Rotate wallpaper
+        match set_wallpaper_by_source(&config).await {
+          Ok(_) => {
+            info!("✨ Wallpaper rotated successfully");
+            state.rewind = Rewind::default();
+            state.record_rotation().await;
+          }
+          Err(e) => {
+            error!("❌ Failed to rotate wallpaper: {}", e);
+            // Continue running - temporary failures shouldn't stop the daemon
+          }
+        }
+      }
+      _ = heartbeat.tick() => {
+        if let Err(e) = state.status.heartbeat().await {
+          debug!("Failed to refresh daemon status: {}", e);
+        }
+      }
+      request = next_control_request(control.as_ref()) => {
+        let result = handle_control_command(request.command, &config, &mut state).await;
+        request.respond(result).await;
+      }
+      _ = next_config_change(config_watcher.as_mut()) => {
+        let Some(path) = &config_path else { continue };
+        if let Some(new_config) = try_reload_config(path, &mut loaded) {
+          apply_reloaded_config(new_config, &mut config, &mut state).await;
+        }
+      }
+      _ = signals.hangup.recv() => {
+        let Some(path) = &config_path else {
+          warn!("Received SIGHUP, but there is no config file to reload");
+          continue;
+        };
+        info!("Received SIGHUP");
+        if let Some(new_config) = try_reload_config(path, &mut loaded) {
+          apply_reloaded_config(new_config, &mut config, &mut state).await;
+        }
+      }
+      _ = signals.terminate.recv() => {
+        info!("Received SIGTERM, shutting down");
+        break;
+      }
+      _ = signals.interrupt.recv() => {
+        info!("Received SIGINT, shutting down");
+        break;
+      }
+    }
+  }
 
-    debug!("Timer tick - rotating wallpaper");
+  if let Err(e) = state.status.cleanup().await {
+    warn!("Failed to remove daemon status: {}", e);
+  }
+  info!("👋 wallflow daemon stopped");
+  Ok(())
+}
 
-    // Rotate wallpaper
-    match set_wallpaper_by_source(&config).await {
-      Ok(_) => info!("✨ Wallpaper rotated successfully"),
-      Err(e) => {
-        error!("❌ Failed to rotate wallpaper: {}", e);
-        // Continue running - temporary failures shouldn't stop the daemon
-      }
+/// Signals the daemon listens for while running
+struct Signals {
+  terminate: Signal,
+  interrupt: Signal,
+  hangup: Signal,
+}
+
+impl Signals {
+  fn install() -> Result<Self> {
+    Ok(Self {
+      terminate: signal(SignalKind::terminate()).context("Failed to install SIGTERM handler")?,
+      interrupt: signal(SignalKind::interrupt()).context("Failed to install SIGINT handler")?,
+      hangup: signal(SignalKind::hangup()).context("Failed to install SIGHUP handler")?,
+    })
+  }
+}
+
+/// Contents of the config file as of the last (re)load
+///
+/// Saving the file and then running `wallflow daemon reload` reaches the daemon twice,
+/// once through the watcher and once as SIGHUP. Comparing contents lets the second
+/// one see that there is nothing new.
+struct LoadedFile(Option<Vec<u8>>);
+
+impl LoadedFile {
+  fn read(path: Option<&std::path::Path>) -> Self {
+    Self(path.and_then(|path| std::fs::read(path).ok()))
+  }
+}
+
+/// Reload the config file, logging the outcome. `None` is returned when the file is
+/// unchanged since the last load, or when it fails to load, in which case the daemon
+/// keeps running with the old config.
+fn try_reload_config(path: &std::path::Path, loaded: &mut LoadedFile) -> Option<Config> {
+  let current = LoadedFile::read(Some(path));
+  if current.0.is_some() && current.0 == loaded.0 {
+    debug!("{} is unchanged since the last load, not reloading", path.display());
+    return None;
+  }
+
+  match reload_config(path) {
+    Ok(config) => {
+      info!("🔄 Reloaded config from {} (rotating every {}m)", path.display(), config.timer.interval);
+      *loaded = current;
+      Some(config)
+    }
+    Err(e) => {
+      error!("Config reload failed, keeping the previous config: {:#}", e);
+      None
+    }
+  }
+}
+
+/// Switch the running daemon over to a reloaded config
+async fn apply_reloaded_config(new_config: Config, config: &mut Config, state: &mut DaemonState) {
+  if state.timer.reschedule(rotation_interval(&new_config), randomize_secs(&new_config)) {
+    info!(
+      "Next rotation rescheduled for {}",
+      state.timer.next_utc().with_timezone(&chrono::Local).format("%H:%M:%S")
+    );
+  }
+  if let Err(e) = state.status.update_config(&new_config, state.timer.next_utc()).await {
+    warn!("Failed to update daemon status: {}", e);
+  }
+  *config = new_config;
+}
+
+/// Load the config file the same way `main` does at startup
+fn reload_config(path: &std::path::Path) -> Result<Config> {
+  let mut config = Config::load(path)?;
+  config.apply_env_overrides()?;
+  config.expand_paths()?;
+  Ok(config)
+}
+
+/// Time between rotations (`timer.interval`)
+fn rotation_interval(config: &Config) -> Duration {
+  Duration::from_secs(config.timer.interval as u64 * 60)
+}
+
+/// Upper bound of the random offset applied to each rotation
+fn randomize_secs(config: &Config) -> u64 {
+  parse_duration(&config.timer.randomize).unwrap_or_else(|_| {
+    warn!("Invalid randomize format '{}', using 0", config.timer.randomize);
+    0
+  })
+}
+
+/// Wait for the config file to change, or forever if it isn't being watched
+async fn next_config_change(watcher: Option<&mut ConfigWatcher>) {
+  match watcher {
+    Some(watcher) => watcher.changed().await,
+    None => std::future::pending().await,
+  }
+}
+
+/// Mutable state of the running daemon
+struct DaemonState {
+  /// Skip timer rotations while set
+  paused: bool,
+  timer: RotationTimer,
+  rewind: Rewind,
+  status: DaemonStatusManager,
+  /// Wallpaper the daemon applied last, to notice when someone else changes it
+  applied: Option<PathBuf>,
+}
+
+impl DaemonState {
+  /// Write the newly applied wallpaper to daemon_status.json
+  async fn record_rotation(&mut self) {
+    self.applied = wallpaper::last_applied_wallpaper();
+    let current = self.applied.as_ref().map(|path| path.to_string_lossy().into_owned());
+    if let Err(e) = self.status.update_rotation(current, self.timer.next_utc()).await {
+      warn!("Failed to update daemon status: {}", e);
     }
+  }
+
+  /// With `timer.respect_manual`, whether the wallpaper on screen isn't the one the
+  /// daemon applied. The new wallpaper is adopted, so only one rotation is skipped.
+  async fn manually_changed(&mut self, config: &Config) -> bool {
+    if !config.timer.respect_manual {
+      return false;
+    }
+    let Some(current) = wallpaper::get_current_wallpaper().await else {
+      return false;
+    };
+
+    let changed = self
+      .applied
+      .as_deref()
+      .is_some_and(|applied| !wallpaper::is_same_wallpaper(applied, &current));
+    self.applied = Some(current);
+    changed
+  }
 
-    // Add randomization delay
-    if randomize_secs > 0 {
-      let mut rng = rand::thread_rng();
-      let random_delay = rng.gen_range(0..=randomize_secs);
-      if random_delay > 0 {
-        debug!("Adding random delay: {}s", random_delay);
-        sleep(Duration::from_secs(random_delay)).await;
+  async fn set_paused(&mut self, paused: bool) {
+    self.paused = paused;
+    if let Err(e) = self.status.set_paused(paused).await {
+      warn!("Failed to update daemon status: {}", e);
+    }
+  }
+}
+
+/// When the next rotation is due
+///
+/// Rotations sit on a fixed grid `interval` apart and each one is moved by a random
+/// offset of up to `randomize_secs` either way. The offset isn't carried over to the
+/// following tick, so jitter doesn't add up and the average interval stays `interval`.
+/// A rotation never comes sooner than `MIN_GAP` after the previous one, however large
+/// `randomize_secs` is compared to `interval`.
+struct RotationTimer {
+  interval: Duration,
+  randomize_secs: u64,
+  /// Unjittered time of the next rotation
+  base: Instant,
+  /// `base` plus this tick's random offset
+  next: Instant,
+}
+
+impl RotationTimer {
+  const MIN_GAP: Duration = Duration::from_secs(60);
+
+  fn start(interval: Duration, randomize_secs: u64) -> Self {
+    let now = Instant::now();
+    let mut timer = Self {
+      interval,
+      randomize_secs,
+      base: now + interval,
+      next: now,
+    };
+    timer.jitter(now);
+    timer
+  }
+
+  /// Move on to the rotation after the one that just fired
+  fn advance(&mut self) {
+    let now = Instant::now();
+    self.base += self.interval;
+    // After a suspend the grid is behind; restart it
+    if self.base <= now {
+      self.base = now + self.interval;
+    }
+    self.jitter(now);
+  }
+
+  /// Apply a new interval and randomization to the pending rotation, counting from the
+  /// slot of the previous one. Returns false if neither changed.
+  fn reschedule(&mut self, interval: Duration, randomize_secs: u64) -> bool {
+    if interval == self.interval && randomize_secs == self.randomize_secs {
+      return false;
+    }
+
+    let now = Instant::now();
+    let previous = self.base.checked_sub(self.interval).unwrap_or(now);
+    self.interval = interval;
+    self.randomize_secs = randomize_secs;
+    // A shorter interval can put the rotation in the past; it's then due right away
+    self.base = (previous + interval).max(now);
+    self.jitter(now);
+    true
+  }
+
+  /// Move `next` off the grid, but not closer than `MIN_GAP` to `now` (unless `base` already is)
+  fn jitter(&mut self, now: Instant) {
+    self.next = jittered(self.base, self.randomize_secs).max(self.base.min(now + Self::MIN_GAP));
+  }
+
+  /// `next` as wall-clock time, for daemon_status.json
+  fn next_utc(&self) -> chrono::DateTime<chrono::Utc> {
+    let remaining = self.next.saturating_duration_since(Instant::now());
+    chrono::Utc::now() + chrono::Duration::from_std(remaining).unwrap_or_default()
+  }
+}
+
+/// `base` moved by a random offset in `-randomize_secs..=randomize_secs`
+fn jittered(base: Instant, randomize_secs: u64) -> Instant {
+  if randomize_secs == 0 {
+    return base;
+  }
+
+  let offset = rand::thread_rng().gen_range(-(randomize_secs as i64)..=randomize_secs as i64);
+  debug!("Jittering next rotation by {}s", offset);
+  if offset >= 0 {
+    base + Duration::from_secs(offset as u64)
+  } else {
+    base.checked_sub(Duration::from_secs(offset.unsigned_abs())).unwrap_or(base)
+  }
+}
+
+/// Wait for the next control command, or forever if the socket couldn't be bound
+async fn next_control_request(control: Option<&ControlServer>) -> ControlRequest {
+  match control {
+    Some(server) => server.accept().await,
+    None => std::future::pending().await,
+  }
+}
+
+/// Carry out a command received over the control socket
+async fn handle_control_command(command: ControlCommand, config: &Config, state: &mut DaemonState) -> Result<String> {
+  match command {
+    ControlCommand::Next => {
+      set_wallpaper_by_source(config).await?;
+      state.rewind = Rewind::default();
+      state.record_rotation().await;
+      info!("✨ Wallpaper rotated on request");
+      Ok("Rotated to a new wallpaper".to_string())
+    }
+    ControlCommand::Previous => {
+      let path = state.rewind.step_back(config).await?;
+      state.record_rotation().await;
+      info!("⏪ Restored previous wallpaper {}", path.display());
+      Ok(format!("Restored {}", path.display()))
+    }
+    ControlCommand::Pause => {
+      if state.paused {
+        return Ok("Rotation is already paused".to_string());
+      }
+      state.set_paused(true).await;
+      info!("⏸️  Rotation paused");
+      Ok("Rotation paused".to_string())
+    }
+    ControlCommand::Resume => {
+      if !state.paused {
+        return Ok("Rotation is not paused".to_string());
       }
+      state.set_paused(false).await;
+      info!("▶️  Rotation resumed");
+      Ok("Rotation resumed".to_string())
     }
   }
 }
 
+/// Tracks consecutive "previous" commands so each one steps further back in history.
+/// Re-applying an old wallpaper appends it to the history again, so the entries
+/// added by earlier steps are skipped when looking for the next one.
+#[derive(Default)]
+struct Rewind {
+  /// Entries appended to the history by previous steps
+  applied: usize,
+  /// Index of the wallpaper currently shown, in the history as it was before rewinding
+  position: usize,
+}
+
+impl Rewind {
+  async fn step_back(&mut self, config: &Config) -> Result<PathBuf> {
+    if !config.history.enabled {
+      return Err(anyhow!("History is disabled, so there is no previous wallpaper"));
+    }
+
+    let entries = history::recent(usize::MAX)?;
+    let original = entries.get(self.applied..).unwrap_or_default();
+    let current = original.get(self.position).map(|entry| &entry.path);
+
+    let (index, entry) = original
+      .iter()
+      .enumerate()
+      .skip(self.position + 1)
+      .find(|(_, entry)| Some(&entry.path) != current && entry.path.exists())
+      .ok_or_else(|| anyhow!("No previous wallpaper in history"))?;
+
+    let applied = wallpaper::apply_wallpaper_daemon(&entry.path, config).await?;
+
+    self.applied += 1;
+    self.position = index;
+    Ok(applied.path)
+  }
+}
+
+/// Send a command to the running daemon over its control socket
+pub fn control_daemon(command: ControlCommand) -> Result<()> {
+  let message = crate::daemon_control::send_command(command)?;
+  println!("✅ {}", message);
+  Ok(())
+}
+
 /// Run daemon in background (daemonize)
 /// Note: This function daemonizes first, then creates a new tokio runtime
-pub fn run_background(config: Config) -> Result<()> {
+pub fn run_background(config: Config, config_path: Option<PathBuf>) -> Result<()> {
   // Create PID and log directories
-  let home_dir = dirs::home_dir().context("Could not find home directory")?;
-  let runtime_dir = home_dir.join(".local/share/mksg/wallflow");
+  let runtime_dir = paths::runtime_dir()?;
+  let state_dir = paths::state_dir()?;
   std::fs::create_dir_all(&runtime_dir).context("Failed to create runtime directory")?;
+  std::fs::create_dir_all(&state_dir).context("Failed to create state directory")?;
 
-  let pid_file = runtime_dir.join("wallflow.pid");
-  let stdout_file = runtime_dir.join("wallflow.log");
-  let stderr_file = runtime_dir.join("wallflow_error.log");
+  let pid_file = get_pid_file()?;
+  let stdout_file = state_dir.join("wallflow.log");
+  let stderr_file = state_dir.join("wallflow_error.log");
 
   info!("Daemonizing wallflow...");
   info!("PID file: {}", pid_file.display());
@@ -101,7 +503,7 @@ pub fn run_background(config: Config) -> Result<()> {
   let daemonize = Daemonize::new()
     .pid_file(&pid_file)
     .chown_pid_file(true)
-    .working_directory(&runtime_dir)
+    .working_directory(&state_dir)
     .stdout(File::create(&stdout_file)?)
     .stderr(File::create(&stderr_file)?)
     .privileged_action(|| "Daemonizing wallflow");
@@ -120,7 +522,7 @@ pub fn run_background(config: Config) -> Result<()> {
       info!("✅ Daemon process started (PID: {})", std::process::id());
 
       // Run the daemon in the new runtime
-      rt.block_on(run_foreground(config))
+      rt.block_on(run_foreground(config, config_path))
     }
     Err(e) => {
       error!("Failed to daemonize: {}", e);
@@ -131,13 +533,22 @@ pub fn run_background(config: Config) -> Result<()> {
 
 /// Get the PID file path
 fn get_pid_file() -> Result<std::path::PathBuf> {
-  let home_dir = dirs::home_dir().context("Could not find home directory")?;
-  Ok(home_dir.join(".local/share/mksg/wallflow/wallflow.pid"))
+  Ok(paths::runtime_dir()?.join("wallflow.pid"))
+}
+
+/// The PID file of the running daemon, which is in the legacy directory when an
+/// earlier version started it
+fn find_pid_file() -> Result<std::path::PathBuf> {
+  let pid_file = get_pid_file()?;
+  if pid_file.exists() {
+    return Ok(pid_file);
+  }
+  Ok(paths::legacy_file("wallflow.pid").unwrap_or(pid_file))
 }
 
 /// Read the daemon PID from the PID file
 fn read_daemon_pid() -> Result<i32> {
-  let pid_file = get_pid_file()?;
+  let pid_file = find_pid_file()?;
   let pid_str = std::fs::read_to_string(&pid_file).with_context(|| format!("Could not read PID file: {}", pid_file.display()))?;
   let pid: i32 = pid_str.trim().parse().context("Invalid PID in file")?;
   Ok(pid)
@@ -153,7 +564,7 @@ pub fn stop_daemon() -> Result<()> {
   if output.status.success() {
     println!("✅ Daemon stopped (PID: {})", pid);
     // Clean up PID file
-    let pid_file = get_pid_file()?;
+    let pid_file = find_pid_file()?;
     let _ = std::fs::remove_file(pid_file);
     Ok(())
   } else {
@@ -201,8 +612,7 @@ pub fn status_daemon() -> Result<()> {
     println!("   📍 PID: {}", pid);
 
     // Try to read status from daemon_status.json
-    let home_dir = dirs::home_dir().context("Could not find home directory")?;
-    let status_file = home_dir.join(".local/share/mksg/wallflow/daemon_status.json");
+    let status_file = daemon_status::status_file_path()?;
     if status_file.exists()
       && let Ok(content) = std::fs::read_to_string(&status_file)
       && let Ok(status) = serde_json::from_str::<serde_json::Value>(&content)
@@ -210,12 +620,14 @@ pub fn status_daemon() -> Result<()> {
       if let Some(current) = status.get("current_wallpaper").and_then(|v| v.as_str()) {
         println!("   🖼️  Current: {}", current);
       }
-      if let Some(next) = status.get("next_rotation").and_then(|v| v.as_str()) {
+      if status.get("paused").and_then(|v| v.as_bool()).unwrap_or(false) {
+        println!("   ⏸️  Rotation: Paused (resume with 'wallflow daemon resume')");
+      } else if let Some(next) = status.get("next_rotation").and_then(|v| v.as_str()) {
         println!("   ⏰ Next rotation: {}", next);
       }
     }
 
-    let log_file = home_dir.join(".local/share/mksg/wallflow/wallflow.log");
+    let log_file = paths::state_dir()?.join("wallflow.log");
     println!("   📄 Log file: {}", log_file.display());
   } else {
     println!("   🔴 Status: Not running");
@@ -380,7 +792,7 @@ fn install_launchd_service(exe_path: &str) -> Result<()> {
   std::fs::create_dir_all(&launch_agents_dir).context("Failed to create LaunchAgents directory")?;
 
   let plist_file = launch_agents_dir.join("com.mksg.wallflow.plist");
-  let log_dir = home_dir.join(".local/share/mksg/wallflow");
+  let log_dir = paths::state_dir()?;
   std::fs::create_dir_all(&log_dir).context("Failed to create log directory")?;
 
   let plist_content = format!(
@@ -455,17 +867,59 @@ fn uninstall_launchd_service() -> Result<()> {
   Ok(())
 }
 
-/// Set wallpaper based on configured default source
+/// Set wallpaper based on configured default source, or the schedule entry for the current time
+/// If that source is unreachable or has no results, `sources.fallback` and then local are tried
 /// Uses fire-and-forget mode to avoid blocking during transitions
 async fn set_wallpaper_by_source(config: &Config) -> Result<()> {
-  let source = config.sources.default.as_str();
+  let scheduled = config.scheduled_for(chrono::Local::now().time());
+  if let Some(scheduled) = &scheduled {
+    debug!("Using scheduled source '{}' ({})", scheduled.sources.default, scheduled.paths.local);
+  }
+  let config = scheduled.as_ref().unwrap_or(config);
+
+  let primary = config.sources.default.as_str();
+  let mut error = match set_wallpaper_from(config, primary).await {
+    Ok(()) => return Ok(()),
+    Err(e) => e,
+  };
+
+  // Remote hiccups fall through to the next source; bad config or a failed apply won't get better
+  let fallbacks = config.sources.fallback.iter().map(String::as_str).chain(["local"]);
+  for source in fallbacks.filter(|&source| source != primary) {
+    if !is_transient(&error) {
+      return Err(error);
+    }
+    warn!("{:#}; trying {} instead", error, source);
+
+    match set_wallpaper_from(config, source).await {
+      Ok(()) => {
+        info!("Wallpaper set from fallback source {}", source);
+        return Ok(());
+      }
+      Err(e) => error = e,
+    }
+  }
+
+  Err(error)
+}
+
+/// Whether a source failed for reasons another source might not share
+fn is_transient(error: &anyhow::Error) -> bool {
+  error
+    .downcast_ref::<DownloaderError>()
+    .is_some_and(|e| e.is_retryable() || matches!(e, DownloaderError::NoResults(_)))
+}
+
+/// Set wallpaper from one named source
+async fn set_wallpaper_from(config: &Config, source: &str) -> Result<()> {
   // Daemon always sets wallpaper (no --no-set)
   let opts = DownloadOptions::default();
   match source {
     "local" => wallpaper::set_local_daemon(config).await,
+    "favorites" => wallpaper::set_favorites_daemon(config).await,
     // All remote sources use the generic set_from_source with empty query
     // (daemon uses config defaults, not CLI args)
-    "wallhaven" | "picsum" | "apod" | "bing" | "reddit" | "earthview" | "unsplash" => {
+    "wallhaven" | "picsum" | "apod" | "bing" | "reddit" | "earthview" | "unsplash" | "pexels" | "pixabay" | "flickr" | "url" | "wikimedia" => {
       wallpaper::set_from_source_daemon(config, source, &[], &opts).await
     }
     other => {
@@ -508,6 +962,72 @@ fn parse_duration(duration_str: &str) -> Result<u64> {
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_is_transient() {
+    assert!(is_transient(&DownloaderError::NoResults("No posts found in r/x".to_string()).into()));
+    assert!(is_transient(&DownloaderError::RateLimited("slow down".to_string()).into()));
+    assert!(!is_transient(&DownloaderError::MissingApiKey("key please".to_string()).into()));
+    assert!(!is_transient(&anyhow::anyhow!("Failed to set wallpaper")));
+  }
+
+  #[test]
+  fn test_rotation_timer_does_not_drift() {
+    let interval = Duration::from_secs(600);
+    let mut timer = RotationTimer::start(interval, 60);
+    let origin = timer.base;
+
+    for tick in 1..=20u32 {
+      timer.advance();
+      assert_eq!(timer.base, origin + interval * tick);
+      assert!(timer.next >= timer.base - Duration::from_secs(60));
+      assert!(timer.next <= timer.base + Duration::from_secs(60));
+    }
+  }
+
+  #[test]
+  fn test_rotation_timer_keeps_a_minimum_gap() {
+    // Jitter as large as the interval could otherwise make a rotation due immediately
+    let mut timer = RotationTimer::start(Duration::from_secs(120), 120);
+    for _ in 0..20 {
+      let now = Instant::now();
+      timer.advance();
+      assert!(timer.next >= now + RotationTimer::MIN_GAP);
+    }
+  }
+
+  #[test]
+  fn test_rotation_timer_reschedule() {
+    let mut timer = RotationTimer::start(Duration::from_secs(1800), 0);
+    let base = timer.base;
+    assert!(!timer.reschedule(Duration::from_secs(1800), 0));
+    assert_eq!(timer.base, base);
+
+    // The pending rotation moves with the interval instead of restarting the wait
+    assert!(timer.reschedule(Duration::from_secs(3600), 0));
+    assert_eq!(timer.base, base + Duration::from_secs(1800));
+    assert_eq!(timer.next, timer.base);
+
+    // Shrinking the interval below the time already waited makes the rotation due now
+    assert!(timer.reschedule(Duration::ZERO, 0));
+    assert!(timer.next <= Instant::now());
+  }
+
+  #[test]
+  fn test_try_reload_config_skips_unchanged_file() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.yml");
+    let mut config = Config::default();
+    std::fs::write(&path, serde_yaml::to_string(&config).unwrap()).unwrap();
+
+    let mut loaded = LoadedFile::read(Some(&path));
+    assert!(try_reload_config(&path, &mut loaded).is_none());
+
+    config.timer.interval = 20;
+    std::fs::write(&path, serde_yaml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(try_reload_config(&path, &mut loaded).unwrap().timer.interval, 20);
+    assert!(try_reload_config(&path, &mut loaded).is_none());
+  }
+
   #[test]
   fn test_parse_duration() {
     assert_eq!(parse_duration("30s").unwrap(), 30);
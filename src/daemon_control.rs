@@ -0,0 +1,211 @@
+//! Control socket for talking to a running daemon
+//!
+//! The daemon listens on a Unix domain socket named `control.sock` in the
+//! runtime directory (see [`crate::paths`]). Clients send a single line
+//! with a command (`next`, `prev`, `pause`, `resume`) and get one line back,
+//! either `ok: <message>` or `error: <message>`.
+
+use anyhow::{Context, Result, anyhow};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::debug;
+
+/// How long the daemon waits for a connected client to send its command
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Commands understood by the daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+  /// Rotate to a new wallpaper now
+  Next,
+  /// Re-apply the previous wallpaper from history
+  Previous,
+  /// Stop rotating until resumed
+  Pause,
+  /// Continue rotating
+  Resume,
+}
+
+impl ControlCommand {
+  /// Wire representation of the command
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Next => "next",
+      Self::Previous => "prev",
+      Self::Pause => "pause",
+      Self::Resume => "resume",
+    }
+  }
+
+  /// Parse a command line sent by a client
+  pub fn parse(line: &str) -> Option<Self> {
+    match line.trim() {
+      "next" => Some(Self::Next),
+      "prev" | "previous" => Some(Self::Previous),
+      "pause" => Some(Self::Pause),
+      "resume" => Some(Self::Resume),
+      _ => None,
+    }
+  }
+}
+
+/// Get the control socket path
+pub fn socket_path() -> Result<PathBuf> {
+  Ok(crate::paths::runtime_dir()?.join("control.sock"))
+}
+
+/// Listening side of the control socket (owned by the daemon)
+pub struct ControlServer {
+  listener: UnixListener,
+  path: PathBuf,
+}
+
+/// A command received from a client, waiting for the daemon's reply
+pub struct ControlRequest {
+  pub command: ControlCommand,
+  stream: UnixStream,
+}
+
+impl ControlServer {
+  /// Bind the control socket, replacing a stale socket file left by a previous run
+  pub fn bind() -> Result<Self> {
+    let path = socket_path()?;
+
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent).context("Failed to create runtime directory")?;
+    }
+    if path.exists() {
+      std::fs::remove_file(&path).context("Failed to remove stale control socket")?;
+    }
+
+    let listener = UnixListener::bind(&path).with_context(|| format!("Failed to bind control socket: {}", path.display()))?;
+    debug!("Control socket listening at {}", path.display());
+
+    Ok(Self { listener, path })
+  }
+
+  /// Wait for the next valid command. Malformed or silent clients get an error reply and are skipped.
+  pub async fn accept(&self) -> ControlRequest {
+    loop {
+      let mut stream = match self.listener.accept().await {
+        Ok((stream, _)) => stream,
+        Err(e) => {
+          debug!("Control socket accept failed: {}", e);
+          continue;
+        }
+      };
+
+      let mut line = String::new();
+      let read = tokio::time::timeout(READ_TIMEOUT, tokio::io::BufReader::new(&mut stream).read_line(&mut line)).await;
+
+      if !matches!(read, Ok(Ok(_))) {
+        debug!("Control client did not send a command");
+        continue;
+      }
+
+      match ControlCommand::parse(&line) {
+        Some(command) => {
+          debug!("Control command received: {}", command.as_str());
+          return ControlRequest { command, stream };
+        }
+        None => {
+          let _ = stream.write_all(format!("error: unknown command '{}'\n", line.trim()).as_bytes()).await;
+        }
+      }
+    }
+  }
+}
+
+impl Drop for ControlServer {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+impl ControlRequest {
+  /// Send the outcome of the command back to the client
+  pub async fn respond(mut self, result: Result<String>) {
+    let reply = match result {
+      Ok(message) => format!("ok: {}\n", message),
+      Err(e) => format!("error: {}\n", e),
+    };
+
+    if let Err(e) = self.stream.write_all(reply.as_bytes()).await {
+      debug!("Failed to reply to control client: {}", e);
+    }
+  }
+}
+
+/// Send a command to the running daemon and return its reply message
+pub fn send_command(command: ControlCommand) -> Result<String> {
+  let path = socket_path()?;
+
+  if !path.exists() {
+    return Err(anyhow!("Daemon is not running (no control socket at {})", path.display()));
+  }
+
+  let mut stream = std::os::unix::net::UnixStream::connect(&path).map_err(|e| match e.kind() {
+    std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::NotFound => anyhow!("Daemon is not running"),
+    _ => anyhow!("Failed to connect to daemon: {}", e),
+  })?;
+
+  writeln!(stream, "{}", command.as_str()).context("Failed to send command to daemon")?;
+
+  // Applying a wallpaper can take a while (downloads), so don't time out too eagerly
+  stream.set_read_timeout(Some(Duration::from_secs(120)))?;
+
+  let mut reply = String::new();
+  BufReader::new(stream).read_line(&mut reply).context("No reply from daemon")?;
+
+  parse_reply(&reply)
+}
+
+/// Split a reply line into its message, turning `error:` replies into errors
+fn parse_reply(reply: &str) -> Result<String> {
+  let reply = reply.trim();
+
+  if let Some(message) = reply.strip_prefix("ok:") {
+    Ok(message.trim().to_string())
+  } else if let Some(message) = reply.strip_prefix("error:") {
+    Err(anyhow!("{}", message.trim()))
+  } else {
+    Err(anyhow!("Unexpected reply from daemon: '{}'", reply))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_command() {
+    assert_eq!(ControlCommand::parse("next\n"), Some(ControlCommand::Next));
+    assert_eq!(ControlCommand::parse("prev"), Some(ControlCommand::Previous));
+    assert_eq!(ControlCommand::parse("previous"), Some(ControlCommand::Previous));
+    assert_eq!(ControlCommand::parse(" pause "), Some(ControlCommand::Pause));
+    assert_eq!(ControlCommand::parse("resume"), Some(ControlCommand::Resume));
+    assert_eq!(ControlCommand::parse("reboot"), None);
+
+    for command in [
+      ControlCommand::Next,
+      ControlCommand::Previous,
+      ControlCommand::Pause,
+      ControlCommand::Resume,
+    ] {
+      assert_eq!(ControlCommand::parse(command.as_str()), Some(command));
+    }
+  }
+
+  #[test]
+  fn test_parse_reply() {
+    assert_eq!(parse_reply("ok: Wallpaper rotated\n").unwrap(), "Wallpaper rotated");
+    assert_eq!(
+      parse_reply("error: No previous wallpaper").unwrap_err().to_string(),
+      "No previous wallpaper"
+    );
+    assert!(parse_reply("garbage").is_err());
+  }
+}
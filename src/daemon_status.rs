@@ -39,6 +39,10 @@ pub struct DaemonStatus {
   /// Current wallpaper file path
   pub current_wallpaper: Option<String>,
 
+  /// Whether rotation is paused (`wallflow daemon pause`)
+  #[serde(default)]
+  pub paused: bool,
+
   /// Daemon configuration snapshot
   pub config: DaemonConfig,
 
@@ -59,6 +63,16 @@ pub struct DaemonConfig {
   pub source: String,
 }
 
+impl From<&crate::config::Config> for DaemonConfig {
+  fn from(config: &crate::config::Config) -> Self {
+    Self {
+      interval_minutes: config.timer.interval,
+      randomize: config.timer.randomize.clone(),
+      source: config.sources.default.clone(),
+    }
+  }
+}
+
 #[allow(dead_code)]
 impl DaemonStatus {
   /// Create new daemon status
@@ -73,29 +87,18 @@ impl DaemonStatus {
       last_rotation: None,
       next_rotation,
       current_wallpaper: None,
-      config: DaemonConfig {
-        interval_minutes: config.timer.interval,
-        randomize: config.timer.randomize.clone(),
-        source: config.sources.default.clone(),
-      },
+      paused: false,
+      config: DaemonConfig::from(config),
       updated_at: now,
     }
   }
 
-  /// Update with new rotation information
-  pub fn update_rotation(&mut self, wallpaper_path: Option<String>) {
+  /// Update with new rotation information and the time the daemon scheduled next
+  pub fn update_rotation(&mut self, wallpaper_path: Option<String>, next_rotation: DateTime<Utc>) {
     let now = Utc::now();
     self.last_rotation = Some(now);
     self.current_wallpaper = wallpaper_path;
-
-    // Calculate next rotation time
-    let interval = Duration::from_secs(self.config.interval_minutes as u64 * 60);
-
-    // Add randomization if configured
-    let randomize_secs = parse_duration(&self.config.randomize).unwrap_or(0);
-    let total_interval = interval + Duration::from_secs(randomize_secs / 2); // Average randomization
-
-    self.next_rotation = now + chrono::Duration::from_std(total_interval).unwrap();
+    self.next_rotation = next_rotation;
     self.updated_at = now;
   }
 
@@ -145,7 +148,7 @@ pub struct DaemonStatusManager {
 impl DaemonStatusManager {
   /// Create new status manager
   pub fn new() -> Result<Self> {
-    let status_file = get_status_file_path()?;
+    let status_file = status_file_path()?;
     Ok(Self { status_file, status: None })
   }
 
@@ -158,15 +161,45 @@ impl DaemonStatusManager {
   }
 
   /// Update daemon status with new rotation
-  pub async fn update_rotation(&mut self, wallpaper_path: Option<String>) -> Result<()> {
+  pub async fn update_rotation(&mut self, wallpaper_path: Option<String>, next_rotation: DateTime<Utc>) -> Result<()> {
     if let Some(ref mut status) = self.status {
-      status.update_rotation(wallpaper_path);
+      status.update_rotation(wallpaper_path, next_rotation);
       self.save().await?;
       debug!("Updated daemon status with new rotation");
     }
     Ok(())
   }
 
+  /// Record a reloaded config and the rotation time it led to
+  pub async fn update_config(&mut self, config: &crate::config::Config, next_rotation: DateTime<Utc>) -> Result<()> {
+    if let Some(ref mut status) = self.status {
+      status.config = DaemonConfig::from(config);
+      status.next_rotation = next_rotation;
+      status.updated_at = Utc::now();
+      self.save().await?;
+    }
+    Ok(())
+  }
+
+  /// Record whether rotation is paused
+  pub async fn set_paused(&mut self, paused: bool) -> Result<()> {
+    if let Some(ref mut status) = self.status {
+      status.paused = paused;
+      status.updated_at = Utc::now();
+      self.save().await?;
+    }
+    Ok(())
+  }
+
+  /// Refresh the update timestamp so readers don't consider the status stale
+  pub async fn heartbeat(&mut self) -> Result<()> {
+    if let Some(ref mut status) = self.status {
+      status.updated_at = Utc::now();
+      self.save().await?;
+    }
+    Ok(())
+  }
+
   /// Load daemon status from file
   pub async fn load(&mut self) -> Result<()> {
     if self.status_file.exists() {
@@ -246,10 +279,8 @@ impl DaemonStatusManager {
 }
 
 /// Get the path for daemon status file
-fn get_status_file_path() -> Result<PathBuf> {
-  let home_dir = dirs::home_dir().context("Could not find home directory")?;
-  let runtime_dir = home_dir.join(".local/share/mksg/wallflow");
-  Ok(runtime_dir.join("daemon_status.json"))
+pub fn status_file_path() -> Result<PathBuf> {
+  Ok(crate::paths::runtime_dir()?.join("daemon_status.json"))
 }
 
 /// Parse duration string (same as daemon.rs)
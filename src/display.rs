@@ -1,4 +1,5 @@
 use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
 use std::process::Command;
 use tracing::{debug, warn};
 
@@ -6,20 +7,45 @@ use tracing::{debug, warn};
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct Resolution {
+  /// Physical pixels
   pub width: u32,
   pub height: u32,
+  /// Ratio of physical to logical pixels (2.0 on Retina), 1.0 when unknown
+  pub scale: f32,
 }
 
 #[allow(dead_code)]
 impl Resolution {
   pub fn new(width: u32, height: u32) -> Self {
-    Self { width, height }
+    Self { width, height, scale: 1.0 }
+  }
+
+  /// Attach the display's scale factor
+  pub fn with_scale(self, scale: f32) -> Self {
+    Self { scale, ..self }
+  }
+
+  /// Size in logical pixels, i.e. what the desktop lays out at
+  pub fn logical(&self) -> Self {
+    if self.scale <= 1.0 {
+      return self.clone();
+    }
+
+    Self::new(
+      (self.width as f32 / self.scale).round() as u32,
+      (self.height as f32 / self.scale).round() as u32,
+    )
   }
 
   pub fn as_string(&self) -> String {
     format!("{}x{}", self.width, self.height)
   }
 
+  /// Taller than wide is portrait; everything else is landscape
+  pub fn orientation(&self) -> Orientation {
+    Orientation::of(self.width, self.height)
+  }
+
   /// Parse resolution from string (e.g., "1920x1080")
   pub fn from_string(s: &str) -> Result<Self> {
     let parts: Vec<&str> = s.split('x').collect();
@@ -41,86 +67,277 @@ impl Default for Resolution {
   }
 }
 
-/// Get the resolution of the primary display
+/// Which way round a display or image is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+  Landscape,
+  Portrait,
+}
+
+impl Orientation {
+  /// How far past square an image of the other orientation may be and still be
+  /// accepted (1.1 = up to 10% taller than wide on a landscape display)
+  pub const TOLERANCE: f32 = 1.1;
+
+  pub fn of(width: u32, height: u32) -> Self {
+    if width < height { Self::Portrait } else { Self::Landscape }
+  }
+
+  /// Name used by the Unsplash, Pexels and Wallhaven APIs
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Landscape => "landscape",
+      Self::Portrait => "portrait",
+    }
+  }
+
+  /// Whether an image of this size suits the orientation, allowing near-square images
+  pub fn accepts(&self, width: u32, height: u32) -> bool {
+    let (width, height) = (width as f32, height as f32);
+    match self {
+      Self::Landscape => width * Self::TOLERANCE >= height,
+      Self::Portrait => height * Self::TOLERANCE >= width,
+    }
+  }
+}
+
+/// A connected monitor and its current mode
+#[derive(Debug, Clone)]
 #[allow(dead_code)]
-pub fn get_primary_display_resolution() -> Result<Resolution> {
-  // Try different detection methods based on available tools
+pub struct Display {
+  /// Output name (e.g. "DP-1"), when the detection tool reports one
+  pub name: Option<String>,
+  pub resolution: Resolution,
+  /// Whether the tool marked this output as the primary one
+  pub primary: bool,
+  /// Top-left corner in the desktop layout, when the tool reports it. Wayland
+  /// compositors use logical pixels here, X11 physical ones.
+  pub position: Option<(i32, i32)>,
+}
 
-  // Method 1: Try macOS system_profiler
-  #[cfg(target_os = "macos")]
-  if let Ok(resolution) = detect_resolution_macos() {
-    debug!("Detected resolution via macOS: {}x{}", resolution.width, resolution.height);
-    return Ok(resolution);
+impl Display {
+  fn new(name: Option<String>, resolution: Resolution) -> Self {
+    Self {
+      name,
+      resolution,
+      primary: false,
+      position: None,
+    }
   }
+}
+
+/// A function that lists displays using one particular tool
+#[allow(dead_code)]
+type DisplayDetector = fn() -> Result<Vec<Display>>;
+
+/// Detect every connected display, using the first tool that reports any
+#[allow(dead_code)]
+pub fn get_displays() -> Result<Vec<Display>> {
+  #[cfg(target_os = "macos")]
+  let detectors: [(&str, DisplayDetector); 1] = [("macOS", detect_displays_macos)];
 
-  // Method 2: Try xrandr (X11)
   #[cfg(target_os = "linux")]
-  if let Ok(resolution) = detect_resolution_xrandr() {
-    debug!("Detected resolution via xrandr: {}x{}", resolution.width, resolution.height);
-    return Ok(resolution);
+  let detectors: [(&str, DisplayDetector); 4] = [
+    ("xrandr", detect_displays_xrandr),
+    ("sway", detect_displays_sway),
+    ("wlr-randr", detect_displays_wlr_randr),
+    ("kscreen-doctor", detect_displays_kscreen),
+  ];
+
+  #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+  let detectors: [(&str, DisplayDetector); 0] = [];
+
+  for (tool, detect) in detectors {
+    if let Ok(displays) = detect()
+      && !displays.is_empty()
+    {
+      for output in &displays {
+        debug!(
+          "Detected display via {}: {} {}",
+          tool,
+          output.name.as_deref().unwrap_or("(unnamed)"),
+          output.resolution.as_string()
+        );
+      }
+      return Ok(displays);
+    }
   }
 
-  // Method 3: Try swaymsg (Sway/wlroots)
-  #[cfg(target_os = "linux")]
-  if let Ok(resolution) = detect_resolution_sway() {
-    debug!("Detected resolution via sway: {}x{}", resolution.width, resolution.height);
-    return Ok(resolution);
+  Err(anyhow!("Could not detect any displays"))
+}
+
+/// Get the resolution of every connected display
+#[allow(dead_code)]
+pub fn get_all_display_resolutions() -> Result<Vec<Resolution>> {
+  Ok(get_displays()?.into_iter().map(|display| display.resolution).collect())
+}
+
+/// Get the resolution of the primary display
+///
+/// Uses the output marked as primary, or the largest one when no tool says.
+#[allow(dead_code)]
+pub fn get_primary_display_resolution() -> Result<Resolution> {
+  match get_displays() {
+    Ok(displays) => Ok(primary_display(displays).map(|display| display.resolution).unwrap_or_default()),
+    Err(_) => {
+      warn!("Could not detect display resolution, using default");
+      Ok(Resolution::default())
+    }
   }
+}
 
-  // Method 4: Try wlr-randr (wlroots)
-  #[cfg(target_os = "linux")]
-  if let Ok(resolution) = detect_resolution_wlr_randr() {
-    debug!("Detected resolution via wlr-randr: {}x{}", resolution.width, resolution.height);
-    return Ok(resolution);
+/// Get the resolution of a named output, falling back to the primary display
+#[allow(dead_code)]
+pub fn get_display_resolution(output: &str) -> Result<Resolution> {
+  let displays = get_displays().unwrap_or_default();
+
+  if let Some(display) = displays.iter().find(|display| display.name.as_deref() == Some(output)) {
+    return Ok(display.resolution.clone());
   }
 
-  // Method 5: Try KDE's kscreen-doctor
+  warn!("No resolution found for output '{}', using the primary display", output);
+  Ok(primary_display(displays).map(|display| display.resolution).unwrap_or_default())
+}
+
+/// The display marked primary, otherwise the one with the most pixels
+fn primary_display(displays: Vec<Display>) -> Option<Display> {
+  if let Some(primary) = displays.iter().position(|display| display.primary) {
+    return displays.into_iter().nth(primary);
+  }
+
+  displays
+    .into_iter()
+    .max_by_key(|display| u64::from(display.resolution.width) * u64::from(display.resolution.height))
+}
+
+/// A function that lists outputs using one particular tool
+#[allow(dead_code)]
+type OutputDetector = fn() -> Result<Vec<String>>;
+
+/// List the names of connected outputs (e.g. "DP-1", "HDMI-A-1").
+/// When the tool reports a primary output, it comes first.
+#[allow(dead_code)]
+pub fn list_outputs() -> Result<Vec<String>> {
   #[cfg(target_os = "linux")]
-  if let Ok(resolution) = detect_resolution_kscreen() {
-    debug!("Detected resolution via kscreen-doctor: {}x{}", resolution.width, resolution.height);
-    return Ok(resolution);
+  {
+    let detectors: [(&str, OutputDetector); 4] = [
+      ("hyprctl", list_outputs_hyprctl),
+      ("swaymsg", list_outputs_sway),
+      ("wlr-randr", list_outputs_wlr_randr),
+      ("xrandr", list_outputs_xrandr),
+    ];
+
+    for (tool, detect) in detectors {
+      if let Ok(outputs) = detect()
+        && !outputs.is_empty()
+      {
+        debug!("Detected outputs via {}: {:?}", tool, outputs);
+        return Ok(outputs);
+      }
+    }
   }
 
-  warn!("Could not detect display resolution, using default");
-  Ok(Resolution::default())
+  Err(anyhow!("Could not detect display outputs"))
 }
 
-/// Detect resolution on macOS using system_profiler
-#[cfg(target_os = "macos")]
+/// Run a command and return its stdout, failing on a non-zero exit
 #[allow(dead_code)]
-fn detect_resolution_macos() -> Result<Resolution> {
-  // Use system_profiler to get display information
-  let output = Command::new("system_profiler")
-    .args(["SPDisplaysDataType", "-json"])
+fn command_stdout(program: &str, args: &[&str]) -> Result<String> {
+  let output = Command::new(program)
+    .args(args)
     .output()
-    .context("Failed to execute system_profiler")?;
+    .with_context(|| format!("Failed to execute {}", program))?;
 
   if !output.status.success() {
-    return Err(anyhow!("system_profiler command failed"));
+    return Err(anyhow!("{} command failed", program));
   }
 
-  let stdout = String::from_utf8(output.stdout).context("Invalid UTF-8 from system_profiler")?;
+  String::from_utf8(output.stdout).with_context(|| format!("Invalid UTF-8 from {}", program))
+}
+
+/// List outputs using hyprctl (Hyprland)
+#[allow(dead_code)]
+fn list_outputs_hyprctl() -> Result<Vec<String>> {
+  parse_json_output_names(&command_stdout("hyprctl", &["monitors", "-j"])?)
+}
+
+/// List outputs using swaymsg (Sway)
+#[allow(dead_code)]
+fn list_outputs_sway() -> Result<Vec<String>> {
+  parse_json_output_names(&command_stdout("swaymsg", &["-t", "get_outputs"])?)
+}
+
+/// List outputs using wlr-randr (wlroots)
+#[allow(dead_code)]
+fn list_outputs_wlr_randr() -> Result<Vec<String>> {
+  Ok(parse_wlr_randr_outputs(&command_stdout("wlr-randr", &[])?))
+}
+
+/// List outputs using xrandr (X11)
+#[allow(dead_code)]
+fn list_outputs_xrandr() -> Result<Vec<String>> {
+  Ok(parse_xrandr_outputs(&command_stdout("xrandr", &["--current"])?))
+}
+
+/// Extract output names from a JSON array of objects with a "name" field
+/// (the format used by both `hyprctl monitors -j` and `swaymsg -t get_outputs`)
+#[allow(dead_code)]
+fn parse_json_output_names(json: &str) -> Result<Vec<String>> {
+  let outputs: Vec<serde_json::Value> = serde_json::from_str(json).context("Invalid output list JSON")?;
+
+  Ok(
+    outputs
+      .iter()
+      .filter_map(|output| output.get("name").and_then(|name| name.as_str()))
+      .map(str::to_string)
+      .collect(),
+  )
+}
+
+/// Output names are the unindented lines, e.g. `DP-1 "Dell Inc. U2720Q"`
+#[allow(dead_code)]
+fn parse_wlr_randr_outputs(stdout: &str) -> Vec<String> {
+  stdout
+    .lines()
+    .filter(|line| !line.is_empty() && !line.starts_with(char::is_whitespace))
+    .filter_map(|line| line.split_whitespace().next())
+    .map(str::to_string)
+    .collect()
+}
+
+/// Connected outputs from lines like `DP-1 connected primary 2560x1440+0+0 ...`
+#[allow(dead_code)]
+fn parse_xrandr_outputs(stdout: &str) -> Vec<String> {
+  let mut outputs = Vec::new();
 
-  // Parse JSON to find resolution
-  // Look for "_spdisplays_resolution" or "Resolution" field
-  // Format is typically "2560 x 1440" or similar
-  let re_pattern = regex::Regex::new(r"(\d{3,5})\s*x\s*(\d{3,5})").ok();
   for line in stdout.lines() {
-    if line.contains("_spdisplays_resolution") || line.contains("Resolution") {
-      // Extract resolution pattern like "2560 x 1440"
-      if let Some(ref re) = re_pattern
-        && let Some(caps) = re.captures(line)
-      {
-        let width: u32 = caps.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
-        let height: u32 = caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
-        if width > 0 && height > 0 {
-          return Ok(Resolution::new(width, height));
-        }
-      }
+    let mut parts = line.split_whitespace();
+    let (Some(name), Some("connected")) = (parts.next(), parts.next()) else {
+      continue;
+    };
+
+    if parts.next() == Some("primary") {
+      outputs.insert(0, name.to_string());
+    } else {
+      outputs.push(name.to_string());
     }
   }
 
+  outputs
+}
+
+/// Detect displays on macOS using system_profiler
+#[cfg(target_os = "macos")]
+#[allow(dead_code)]
+fn detect_displays_macos() -> Result<Vec<Display>> {
+  let stdout = command_stdout("system_profiler", &["SPDisplaysDataType", "-json"])?;
+  let displays = parse_system_profiler_displays(&stdout)?;
+  if !displays.is_empty() {
+    return Ok(displays);
+  }
+
   // Fallback: try screenresolution tool if available
+  let mut displays = Vec::new();
   if let Ok(output) = Command::new("screenresolution").arg("get").output()
     && output.status.success()
   {
@@ -135,7 +352,7 @@ fn detect_resolution_macos() -> Result<Resolution> {
           if let Some(end_pos) = rest.find(|c: char| !c.is_ascii_digit()) {
             let height_str = &rest[..end_pos];
             if let (Ok(width), Ok(height)) = (width_str.parse::<u32>(), height_str.parse::<u32>()) {
-              return Ok(Resolution::new(width, height));
+              displays.push(Display::new(None, Resolution::new(width, height)));
             }
           }
         }
@@ -143,144 +360,295 @@ fn detect_resolution_macos() -> Result<Resolution> {
     }
   }
 
-  Err(anyhow!("No resolution found via macOS methods"))
+  if displays.is_empty() {
+    return Err(anyhow!("No resolution found via macOS methods"));
+  }
+  displays[0].primary = true;
+  Ok(displays)
 }
 
-/// Detect resolution using xrandr (X11)
-#[allow(dead_code)]
-fn detect_resolution_xrandr() -> Result<Resolution> {
-  let output = Command::new("xrandr").arg("--current").output().context("Failed to execute xrandr")?;
+/// Top level of `system_profiler SPDisplaysDataType -json`
+#[derive(Deserialize)]
+struct SystemProfiler {
+  #[serde(rename = "SPDisplaysDataType", default)]
+  gpus: Vec<SystemProfilerGpu>,
+}
 
-  if !output.status.success() {
-    return Err(anyhow!("xrandr command failed"));
-  }
+#[derive(Deserialize)]
+struct SystemProfilerGpu {
+  #[serde(rename = "spdisplays_ndrvs", default)]
+  displays: Vec<SystemProfilerDisplay>,
+}
 
-  let stdout = String::from_utf8(output.stdout).context("Invalid UTF-8 from xrandr")?;
+#[derive(Deserialize)]
+struct SystemProfilerDisplay {
+  #[serde(rename = "_name")]
+  name: Option<String>,
+  /// Native panel size, e.g. "3024 x 1964"
+  #[serde(rename = "_spdisplays_pixels")]
+  pixels: Option<String>,
+  /// UI ("looks like") size, e.g. "1512 x 982 @ 120.00Hz"
+  #[serde(rename = "_spdisplays_resolution")]
+  resolution: Option<String>,
+  /// e.g. "spdisplays_3024x1964Retina" on older macOS releases
+  #[serde(rename = "spdisplays_pixelresolution")]
+  pixel_resolution: Option<String>,
+  #[serde(rename = "spdisplays_main")]
+  main: Option<String>,
+}
 
-  // Look for primary display or first connected display
-  for line in stdout.lines() {
-    if line.contains(" connected") && (line.contains("primary") || !line.contains("disconnected")) {
-      // Parse line like: "DP-1 connected primary 2560x1440+0+0 (normal left inverted right x axis y axis) 597mm x 336mm"
-      let parts: Vec<&str> = line.split_whitespace().collect();
-      for part in parts {
-        if part.contains('x') && part.chars().next().unwrap_or('a').is_ascii_digit() {
-          // Extract resolution part (before any + or other chars)
-          let resolution_part = part.split('+').next().unwrap_or(part);
-          if let Ok(resolution) = Resolution::from_string(resolution_part) {
-            return Ok(resolution);
-          }
+/// Physical resolution and scale of each display reported by system_profiler
+///
+/// The scale is the native pixel width over the UI width. When only a
+/// "Retina" marker is available, 2x is assumed.
+#[allow(dead_code)]
+fn parse_system_profiler_displays(json: &str) -> Result<Vec<Display>> {
+  let profile: SystemProfiler = serde_json::from_str(json).context("Invalid system_profiler JSON")?;
+
+  let mut displays: Vec<Display> = profile
+    .gpus
+    .into_iter()
+    .flat_map(|gpu| gpu.displays)
+    .filter_map(|entry| {
+      let native = entry.pixels.as_deref().and_then(parse_dimensions);
+      let ui = entry.resolution.as_deref().and_then(parse_dimensions);
+      let retina = entry.pixel_resolution.as_deref().is_some_and(|res| res.contains("Retina"));
+
+      let resolution = match (native, ui) {
+        (Some(native), Some(ui)) => {
+          let scale = native.width as f32 / ui.width as f32;
+          native.with_scale(scale)
         }
-      }
-    }
+        (Some(native), None) if retina => native.with_scale(2.0),
+        (Some(native), None) => native,
+        (None, Some(ui)) => ui,
+        (None, None) => return None,
+      };
+
+      let mut display = Display::new(entry.name, resolution);
+      display.primary = entry.main.as_deref() == Some("spdisplays_yes");
+      Some(display)
+    })
+    .collect();
+
+  if !displays.is_empty() && !displays.iter().any(|display| display.primary) {
+    displays[0].primary = true;
   }
 
-  Err(anyhow!("No resolution found in xrandr output"))
+  Ok(displays)
 }
 
-/// Detect resolution using swaymsg (Sway)
-#[allow(dead_code)]
-fn detect_resolution_sway() -> Result<Resolution> {
-  let output = Command::new("swaymsg")
-    .args(["-t", "get_outputs"])
-    .output()
-    .context("Failed to execute swaymsg")?;
+/// First "WIDTH x HEIGHT" pair in a system_profiler value
+fn parse_dimensions(value: &str) -> Option<Resolution> {
+  let mut numbers = value
+    .split(|c: char| !c.is_ascii_digit())
+    .filter(|part| !part.is_empty())
+    .map(str::parse::<u32>);
 
-  if !output.status.success() {
-    return Err(anyhow!("swaymsg command failed"));
+  match (numbers.next(), numbers.next()) {
+    (Some(Ok(width)), Some(Ok(height))) if width > 0 && height > 0 => Some(Resolution::new(width, height)),
+    _ => None,
   }
+}
+
+/// Detect displays using xrandr (X11)
+#[allow(dead_code)]
+fn detect_displays_xrandr() -> Result<Vec<Display>> {
+  Ok(parse_xrandr_displays(&command_stdout("xrandr", &["--current"])?))
+}
 
-  let stdout = String::from_utf8(output.stdout).context("Invalid UTF-8 from swaymsg")?;
+/// Connected outputs with their geometry, from lines like
+/// `DP-1 connected primary 2560x1440+0+0 (normal left inverted right x axis y axis) 597mm x 336mm`
+#[allow(dead_code)]
+fn parse_xrandr_displays(stdout: &str) -> Vec<Display> {
+  let mut displays = Vec::new();
 
-  // Parse JSON output (simplified - could use serde_json for robustness)
-  // Look for "current_mode":{"width":2560,"height":1440,"refresh":59999}
   for line in stdout.lines() {
-    if line.contains("current_mode") && line.contains("width") {
-      // Extract width and height from JSON
-      if let (Some(width_start), Some(height_start)) = (line.find("\"width\":").map(|i| i + 8), line.find("\"height\":").map(|i| i + 9)) {
-        let width_end = line[width_start..].find(',').map(|i| i + width_start).unwrap_or(line.len());
-        let height_end = line[height_start..].find(',').map(|i| i + height_start).unwrap_or(line.len());
-
-        if let (Ok(width), Ok(height)) = (line[width_start..width_end].parse::<u32>(), line[height_start..height_end].parse::<u32>()) {
-          return Ok(Resolution::new(width, height));
-        }
-      }
+    let mut parts = line.split_whitespace();
+    let (Some(name), Some("connected")) = (parts.next(), parts.next()) else {
+      continue;
+    };
+
+    let rest: Vec<&str> = parts.collect();
+    // Connected but disabled outputs have no geometry
+    let Some((resolution, geometry)) = rest
+      .iter()
+      .filter(|part| part.starts_with(|c: char| c.is_ascii_digit()) && part.contains('x'))
+      .find_map(|part| Some((Resolution::from_string(part.split('+').next().unwrap_or(part)).ok()?, *part)))
+    else {
+      continue;
+    };
+
+    let mut display = Display::new(Some(name.to_string()), resolution);
+    display.primary = rest.first() == Some(&"primary");
+    // `2560x1440+1920+0`
+    let mut offsets = geometry.split('+').skip(1).map(str::parse::<i32>);
+    if let (Some(Ok(x)), Some(Ok(y))) = (offsets.next(), offsets.next()) {
+      display.position = Some((x, y));
     }
+    displays.push(display);
   }
 
-  Err(anyhow!("No resolution found in swaymsg output"))
+  displays
 }
 
-/// Detect resolution using wlr-randr (wlroots)
+/// Detect displays using swaymsg (Sway)
 #[allow(dead_code)]
-fn detect_resolution_wlr_randr() -> Result<Resolution> {
-  let output = Command::new("wlr-randr").output().context("Failed to execute wlr-randr")?;
+fn detect_displays_sway() -> Result<Vec<Display>> {
+  parse_sway_displays(&command_stdout("swaymsg", &["-t", "get_outputs"])?)
+}
 
-  if !output.status.success() {
-    return Err(anyhow!("wlr-randr command failed"));
-  }
+/// An entry of `swaymsg -t get_outputs`
+#[derive(Deserialize)]
+struct SwayOutput {
+  name: Option<String>,
+  #[serde(default)]
+  primary: bool,
+  scale: Option<f32>,
+  /// Missing for disabled outputs
+  current_mode: Option<Size>,
+  /// Position and logical size in the layout
+  rect: Option<Position>,
+}
+
+/// Width and height as reported in sway's `current_mode` and kscreen's `size`
+#[derive(Deserialize)]
+struct Size {
+  width: u32,
+  height: u32,
+}
+
+/// An output's place in the layout: sway's `rect` and kscreen's `pos`
+#[derive(Deserialize)]
+struct Position {
+  x: i32,
+  y: i32,
+}
+
+/// Active outputs from `swaymsg -t get_outputs`, using each one's `current_mode`
+#[allow(dead_code)]
+fn parse_sway_displays(json: &str) -> Result<Vec<Display>> {
+  let outputs: Vec<SwayOutput> = serde_json::from_str(json).context("Invalid swaymsg output JSON")?;
+
+  Ok(
+    outputs
+      .into_iter()
+      .filter_map(|output| {
+        let mode = output.current_mode?;
+        let resolution = Resolution::new(mode.width, mode.height).with_scale(output.scale.unwrap_or(1.0));
+        let mut display = Display::new(output.name, resolution);
+        display.primary = output.primary;
+        display.position = output.rect.map(|rect| (rect.x, rect.y));
+        Some(display)
+      })
+      .collect(),
+  )
+}
+
+/// Detect displays using wlr-randr (wlroots)
+#[allow(dead_code)]
+fn detect_displays_wlr_randr() -> Result<Vec<Display>> {
+  Ok(parse_wlr_randr_displays(&command_stdout("wlr-randr", &[])?))
+}
 
-  let stdout = String::from_utf8(output.stdout).context("Invalid UTF-8 from wlr-randr")?;
+/// Each output's current mode, e.g. `    2560x1440 px, 59.996 Hz (preferred, current)`,
+/// attributed to the unindented output line above it, and its `  Position: 2560,0`
+#[allow(dead_code)]
+fn parse_wlr_randr_displays(stdout: &str) -> Vec<Display> {
+  let mut displays = Vec::new();
+  let mut output = None;
 
-  // Look for lines like "  2560x1440 @ 59.996 Hz (current)"
   for line in stdout.lines() {
-    if line.contains("(current)") {
-      let trimmed = line.trim();
-      if let Some(resolution_end) = trimmed.find(' ') {
-        let resolution_str = &trimmed[..resolution_end];
-        if let Ok(resolution) = Resolution::from_string(resolution_str) {
-          return Ok(resolution);
-        }
-      }
+    if !line.is_empty() && !line.starts_with(char::is_whitespace) {
+      output = line.split_whitespace().next().map(str::to_string);
+    } else if is_current_mode(line)
+      && let Some(mode) = line.split_whitespace().next()
+      && let Ok(resolution) = Resolution::from_string(mode)
+    {
+      displays.push(Display::new(output.clone(), resolution));
+    } else if let Some((x, y)) = line.trim().strip_prefix("Position:").and_then(|value| value.trim().split_once(','))
+      && let (Ok(x), Ok(y)) = (x.trim().parse(), y.trim().parse())
+      && let Some(display) = displays.last_mut().filter(|display| display.name == output)
+    {
+      display.position = Some((x, y));
     }
   }
 
-  Err(anyhow!("No current resolution found in wlr-randr output"))
+  displays
 }
 
-/// Detect resolution using kscreen-doctor (KDE)
-#[allow(dead_code)]
-fn detect_resolution_kscreen() -> Result<Resolution> {
-  let output = Command::new("kscreen-doctor")
-    .arg("-j")
-    .output()
-    .context("Failed to execute kscreen-doctor")?;
+/// Whether a wlr-randr mode line is flagged `(current)` or e.g. `(preferred, current)`
+fn is_current_mode(line: &str) -> bool {
+  line
+    .rsplit_once('(')
+    .is_some_and(|(_, flags)| flags.trim_end_matches(')').split(',').any(|flag| flag.trim() == "current"))
+}
 
-  if !output.status.success() {
-    return Err(anyhow!("kscreen-doctor command failed"));
-  }
+/// Top level of `kscreen-doctor -j`
+#[derive(Deserialize)]
+struct KscreenConfig {
+  outputs: Vec<KscreenOutput>,
+}
 
-  let stdout = String::from_utf8(output.stdout).context("Invalid UTF-8 from kscreen-doctor")?;
+#[derive(Deserialize)]
+struct KscreenOutput {
+  name: Option<String>,
+  #[serde(default)]
+  enabled: bool,
+  /// Plasma 5 flags the primary output directly
+  #[serde(default)]
+  primary: bool,
+  /// Plasma 6 orders outputs instead; priority 1 is the primary one
+  priority: Option<u32>,
+  scale: Option<f32>,
+  /// Current mode size; absent for disabled outputs
+  size: Option<Size>,
+  pos: Option<Position>,
+}
 
-  // Parse JSON output (simplified)
-  // Look for enabled outputs with current mode
-  if stdout.contains("\"enabled\": true") {
-    // This is a simplified parser - in practice, you'd use serde_json
-    // Look for resolution patterns in the JSON
-    for line in stdout.lines() {
-      if line.contains("\"size\"") && line.contains("width") && line.contains("height") {
-        // Extract from something like: "size": {"width": 2560, "height": 1440}
-        if let (Some(width_start), Some(height_start)) = (line.find("\"width\": ").map(|i| i + 9), line.find("\"height\": ").map(|i| i + 10)) {
-          let width_end = line[width_start..].find(',').map(|i| i + width_start).unwrap_or(line.len());
-          let height_end = line[height_start..].find('}').map(|i| i + height_start).unwrap_or(line.len());
-
-          if let (Ok(width), Ok(height)) = (
-            line[width_start..width_end].trim().parse::<u32>(),
-            line[height_start..height_end].trim().parse::<u32>(),
-          ) {
-            return Ok(Resolution::new(width, height));
-          }
-        }
-      }
-    }
-  }
+/// Detect displays using kscreen-doctor (KDE)
+#[allow(dead_code)]
+fn detect_displays_kscreen() -> Result<Vec<Display>> {
+  parse_kscreen_displays(&command_stdout("kscreen-doctor", &["-j"])?)
+}
 
-  Err(anyhow!("No enabled display found in kscreen-doctor output"))
+/// Enabled outputs from `kscreen-doctor -j`, using each one's `size`
+#[allow(dead_code)]
+fn parse_kscreen_displays(json: &str) -> Result<Vec<Display>> {
+  let config: KscreenConfig = serde_json::from_str(json).context("Invalid kscreen-doctor JSON")?;
+
+  Ok(
+    config
+      .outputs
+      .into_iter()
+      .filter(|output| output.enabled)
+      .filter_map(|output| {
+        let size = output.size?;
+        let resolution = Resolution::new(size.width, size.height).with_scale(output.scale.unwrap_or(1.0));
+        let mut display = Display::new(output.name, resolution);
+        display.primary = output.primary || output.priority == Some(1);
+        display.position = output.pos.map(|pos| (pos.x, pos.y));
+        Some(display)
+      })
+      .collect(),
+  )
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_orientation() {
+    assert_eq!(Resolution::new(1920, 1080).orientation(), Orientation::Landscape);
+    assert_eq!(Resolution::new(1080, 1920).orientation(), Orientation::Portrait);
+
+    assert!(Orientation::Landscape.accepts(1000, 1050));
+    assert!(!Orientation::Landscape.accepts(1000, 1200));
+    assert!(Orientation::Portrait.accepts(1080, 1920));
+    assert!(!Orientation::Portrait.accepts(1920, 1080));
+  }
+
   #[test]
   fn test_resolution_from_string() {
     assert!(Resolution::from_string("1920x1080").is_ok());
@@ -297,4 +665,271 @@ mod tests {
     assert!(Resolution::from_string("1920").is_err());
     assert!(Resolution::from_string("1920x").is_err());
   }
+
+  #[test]
+  fn test_resolution_logical() {
+    let retina = Resolution::new(3024, 1964).with_scale(2.0);
+    assert_eq!(retina.logical().as_string(), "1512x982");
+
+    let fractional = Resolution::new(3840, 2160).with_scale(1.5);
+    assert_eq!(fractional.logical().as_string(), "2560x1440");
+
+    assert_eq!(Resolution::new(1920, 1080).logical().as_string(), "1920x1080");
+  }
+
+  #[test]
+  fn test_parse_system_profiler_displays() {
+    let json = r#"{
+  "SPDisplaysDataType" : [
+    {
+      "_name" : "Apple M1 Pro",
+      "spdisplays_ndrvs" : [
+        {
+          "_name" : "Color LCD",
+          "_spdisplays_display-product-id" : "a050",
+          "_spdisplays_pixels" : "3024 x 1964",
+          "_spdisplays_resolution" : "1512 x 982 @ 120.00Hz",
+          "spdisplays_main" : "spdisplays_yes",
+          "spdisplays_mirror" : "spdisplays_off",
+          "spdisplays_online" : "spdisplays_yes",
+          "spdisplays_pixelresolution" : "spdisplays_3024x1964Retina"
+        },
+        {
+          "_name" : "DELL U2720Q",
+          "_spdisplays_pixels" : "3840 x 2160",
+          "_spdisplays_resolution" : "3840 x 2160 @ 60.00Hz"
+        }
+      ]
+    }
+  ]
+}"#;
+
+    let displays = parse_system_profiler_displays(json).unwrap();
+    assert_eq!(displays.len(), 2);
+
+    assert_eq!(displays[0].name.as_deref(), Some("Color LCD"));
+    assert!(displays[0].primary);
+    assert_eq!(displays[0].resolution.as_string(), "3024x1964");
+    assert!((displays[0].resolution.scale - 2.0).abs() < 0.001);
+    assert_eq!(displays[0].resolution.logical().as_string(), "1512x982");
+
+    assert!(!displays[1].primary);
+    assert!((displays[1].resolution.scale - 1.0).abs() < 0.001);
+  }
+
+  #[test]
+  fn test_parse_xrandr_outputs() {
+    let stdout = "Screen 0: minimum 8 x 8, current 4480 x 1440, maximum 32767 x 32767
+HDMI-1 connected 1920x1080+2560+0 (normal left inverted right x axis y axis) 527mm x 296mm
+   1920x1080     60.00*+
+DP-1 connected primary 2560x1440+0+0 (normal left inverted right x axis y axis) 597mm x 336mm
+   2560x1440     59.95*+
+DP-2 disconnected (normal left inverted right x axis y axis)
+";
+
+    assert_eq!(parse_xrandr_outputs(stdout), vec!["DP-1", "HDMI-1"]);
+  }
+
+  #[test]
+  fn test_parse_wlr_randr_outputs() {
+    let stdout = "DP-1 \"Dell Inc. DELL U2720Q\"
+  Enabled: yes
+  Modes:
+    3840x2160 px, 59.997002 Hz (preferred, current)
+HDMI-A-1 \"LG Electronics LG HDR 4K\"
+  Enabled: yes
+";
+
+    assert_eq!(parse_wlr_randr_outputs(stdout), vec!["DP-1", "HDMI-A-1"]);
+  }
+
+  #[test]
+  fn test_parse_xrandr_displays() {
+    let stdout = "Screen 0: minimum 8 x 8, current 4480 x 1440, maximum 32767 x 32767
+HDMI-1 connected 1920x1080+2560+0 (normal left inverted right x axis y axis) 527mm x 296mm
+   1920x1080     60.00*+
+DP-1 connected primary 3840x2160+0+0 (normal left inverted right x axis y axis) 597mm x 336mm
+   3840x2160     59.95*+
+DP-2 connected (normal left inverted right x axis y axis)
+DP-3 disconnected (normal left inverted right x axis y axis)
+";
+
+    let displays = parse_xrandr_displays(stdout);
+    let summary: Vec<(Option<&str>, String, bool)> = displays
+      .iter()
+      .map(|d| (d.name.as_deref(), d.resolution.as_string(), d.primary))
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        (Some("HDMI-1"), "1920x1080".to_string(), false),
+        (Some("DP-1"), "3840x2160".to_string(), true),
+      ]
+    );
+
+    assert_eq!(displays[0].position, Some((2560, 0)));
+    assert_eq!(displays[1].position, Some((0, 0)));
+
+    assert_eq!(primary_display(displays).unwrap().name.as_deref(), Some("DP-1"));
+  }
+
+  #[test]
+  fn test_parse_wlr_randr_displays() {
+    let stdout = "DP-1 \"Dell Inc. DELL U2720Q\"
+  Enabled: yes
+  Modes:
+    1920x1080 px, 60.000000 Hz
+    3840x2160 px, 59.997002 Hz (preferred, current)
+  Position: 0,0
+  Transform: normal
+  Scale: 1.500000
+HDMI-A-1 \"LG Electronics LG HDR 4K\"
+  Enabled: yes
+  Modes:
+    1920x1080 px, 60.000000 Hz (current)
+  Position: 2560,0
+";
+
+    let displays = parse_wlr_randr_displays(stdout);
+    assert_eq!(displays.len(), 2);
+    assert_eq!(displays[0].name.as_deref(), Some("DP-1"));
+    assert_eq!(displays[0].resolution.as_string(), "3840x2160");
+    assert_eq!(displays[1].name.as_deref(), Some("HDMI-A-1"));
+    assert_eq!(displays[1].resolution.as_string(), "1920x1080");
+    assert_eq!(displays[1].position, Some((2560, 0)));
+
+    // Nothing is marked primary, so the largest display wins
+    assert_eq!(primary_display(displays).unwrap().name.as_deref(), Some("DP-1"));
+  }
+
+  #[test]
+  fn test_parse_sway_displays() {
+    let json = r#"[
+      {"name": "eDP-1", "active": true, "primary": false, "current_mode": {"width": 1920, "height": 1200, "refresh": 60000}},
+      {"name": "DP-3", "active": false, "primary": false},
+      {"name": "DP-4", "active": true, "primary": false, "current_mode": {"width": 3840, "height": 2160, "refresh": 59997}}
+    ]"#;
+
+    let displays = parse_sway_displays(json).unwrap();
+    let names: Vec<_> = displays.iter().map(|d| d.name.as_deref().unwrap()).collect();
+    assert_eq!(names, vec!["eDP-1", "DP-4"]);
+    assert_eq!(displays[1].resolution.as_string(), "3840x2160");
+    assert!(parse_sway_displays("not json").is_err());
+  }
+
+  #[test]
+  fn test_parse_sway_displays_recorded() {
+    // Trimmed from a real `swaymsg -t get_outputs`, pretty-printed across many lines
+    let json = r#"[
+  {
+    "id": 4,
+    "type": "output",
+    "name": "eDP-1",
+    "active": true,
+    "dpms": true,
+    "power": true,
+    "primary": false,
+    "make": "BOE",
+    "model": "0x095F",
+    "serial": "0x00000000",
+    "scale": 1.5,
+    "scale_filter": "linear",
+    "transform": "normal",
+    "adaptive_sync_status": "disabled",
+    "current_workspace": "1",
+    "modes": [
+      {
+        "width": 2256,
+        "height": 1504,
+        "refresh": 59999,
+        "picture_aspect_ratio": "none"
+      }
+    ],
+    "current_mode": {
+      "width": 2256,
+      "height": 1504,
+      "refresh": 59999,
+      "picture_aspect_ratio": "none"
+    },
+    "rect": {
+      "x": 0,
+      "y": 0,
+      "width": 1504,
+      "height": 1003
+    }
+  }
+]"#;
+
+    let displays = parse_sway_displays(json).unwrap();
+    assert_eq!(displays.len(), 1);
+    assert_eq!(displays[0].name.as_deref(), Some("eDP-1"));
+    assert_eq!(displays[0].resolution.as_string(), "2256x1504");
+    assert_eq!(displays[0].resolution.logical().as_string(), "1504x1003");
+    assert_eq!(displays[0].position, Some((0, 0)));
+  }
+
+  #[test]
+  fn test_parse_kscreen_displays() {
+    // Trimmed from a real `kscreen-doctor -j` on Plasma 6
+    let json = r#"{
+  "features": 31,
+  "outputs": [
+    {
+      "connected": true,
+      "currentModeId": "1",
+      "enabled": true,
+      "id": 1,
+      "name": "DP-1",
+      "pos": { "x": 0, "y": 0 },
+      "priority": 2,
+      "scale": 1,
+      "size": { "height": 1080, "width": 1920 },
+      "type": 14
+    },
+    {
+      "connected": true,
+      "currentModeId": "3",
+      "enabled": true,
+      "id": 2,
+      "name": "DP-2",
+      "pos": { "x": 1920, "y": 0 },
+      "priority": 1,
+      "scale": 1.5,
+      "size": { "height": 2160, "width": 3840 },
+      "type": 14
+    },
+    {
+      "connected": true,
+      "enabled": false,
+      "id": 3,
+      "name": "HDMI-A-1",
+      "priority": 0,
+      "type": 11
+    }
+  ],
+  "screen": { "currentSize": { "height": 2160, "width": 5760 }, "id": 0 }
+}"#;
+
+    let displays = parse_kscreen_displays(json).unwrap();
+    let summary: Vec<(Option<&str>, String, bool)> = displays
+      .iter()
+      .map(|d| (d.name.as_deref(), d.resolution.as_string(), d.primary))
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        (Some("DP-1"), "1920x1080".to_string(), false),
+        (Some("DP-2"), "3840x2160".to_string(), true),
+      ]
+    );
+    assert_eq!(displays[1].position, Some((1920, 0)));
+    assert!(parse_kscreen_displays("[]").is_err());
+  }
+
+  #[test]
+  fn test_parse_json_output_names() {
+    let json = r#"[{"id": 0, "name": "eDP-1", "width": 2880}, {"id": 1, "name": "DP-3", "width": 3840}]"#;
+    assert_eq!(parse_json_output_names(json).unwrap(), vec!["eDP-1", "DP-3"]);
+    assert!(parse_json_output_names("not json").is_err());
+  }
 }
@@ -0,0 +1,405 @@
+//! `wallflow doctor`: checks everything wallflow needs to set wallpapers
+//!
+//! Each check prints one line with ✅, ⚠️ or ❌, plus a hint on how to fix it.
+//! Failures (❌) are the problems that stop wallpapers from being applied; the
+//! command exits non-zero when there is at least one, so it can be scripted.
+
+use crate::config::Config;
+use crate::config::validate::{self, Severity};
+use crate::platform::{self, PlatformStatus};
+use crate::wallpaper::backends::BackendRegistry;
+use crate::{downloaders, templates, wallpaper};
+use anyhow::{Result, bail};
+use std::path::Path;
+use std::time::Duration;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+  Ok,
+  Warning,
+  Failed,
+}
+
+#[derive(Debug)]
+struct Check {
+  status: Status,
+  summary: String,
+  hint: Option<String>,
+}
+
+impl Check {
+  fn ok(summary: impl Into<String>) -> Self {
+    Self {
+      status: Status::Ok,
+      summary: summary.into(),
+      hint: None,
+    }
+  }
+
+  fn warning(summary: impl Into<String>, hint: impl Into<String>) -> Self {
+    Self {
+      status: Status::Warning,
+      summary: summary.into(),
+      hint: Some(hint.into()),
+    }
+  }
+
+  fn failed(summary: impl Into<String>, hint: impl Into<String>) -> Self {
+    Self {
+      status: Status::Failed,
+      summary: summary.into(),
+      hint: Some(hint.into()),
+    }
+  }
+}
+
+/// Run every check and print the results. `config_path` is the `--config` flag.
+pub async fn run(config_path: Option<&Path>) -> Result<()> {
+  println!("🩺 wallflow doctor");
+
+  let mut checks = Vec::new();
+
+  let section = check_platform();
+  print_section("Platform", &section);
+  checks.extend(section);
+
+  let (section, config) = check_config(config_path);
+  print_section("Configuration", &section);
+  checks.extend(section);
+
+  let section = check_directories(&config);
+  print_section("Directories", &section);
+  checks.extend(section);
+
+  let section = check_network(&config).await;
+  print_section("Network", &section);
+  checks.extend(section);
+
+  let section = check_tools(&config);
+  print_section("Optional tools", &section);
+  checks.extend(section);
+
+  println!();
+  let failed = checks.iter().filter(|check| check.status == Status::Failed).count();
+  let warnings = checks.iter().filter(|check| check.status == Status::Warning).count();
+  if failed > 0 {
+    bail!("{} check(s) failed, {} warning(s)", failed, warnings);
+  }
+  if warnings > 0 {
+    println!("✅ wallflow should work ({} warning(s) above)", warnings);
+  } else {
+    println!("✅ Everything looks good");
+  }
+  Ok(())
+}
+
+fn print_section(title: &str, checks: &[Check]) {
+  println!();
+  println!("{}", title);
+  for check in checks {
+    let icon = match check.status {
+      Status::Ok => "✅",
+      Status::Warning => "⚠️ ",
+      Status::Failed => "❌",
+    };
+    println!("  {} {}", icon, check.summary);
+    if let Some(hint) = &check.hint {
+      println!("     💡 {}", hint);
+    }
+  }
+}
+
+fn check_platform() -> Vec<Check> {
+  let mut checks = Vec::new();
+
+  match platform::detect_platform() {
+    Ok(detected) => checks.push(Check::ok(format!("Platform: {}", detected))),
+    Err(e) => {
+      checks.push(Check::failed(
+        format!("Platform: {:#}", e),
+        "Run wallflow from your desktop session so WAYLAND_DISPLAY or DISPLAY is set",
+      ));
+      return checks;
+    }
+  }
+
+  if let PlatformStatus::MissingDependency(_) = platform::check_platform_dependencies() {
+    checks.push(Check::failed(
+      "No wallpaper setter installed",
+      "Install one: awww or swww (Wayland), swaybg (Sway), hyprpaper (Hyprland), feh or nitrogen (X11)",
+    ));
+    return checks;
+  }
+
+  let registry = BackendRegistry::new();
+  let available = registry.available_backend_names();
+  if !available.is_empty() {
+    checks.push(Check::ok(format!("Backends available: {}", available.join(", "))));
+  }
+  match registry.get_best_backend() {
+    Ok(backend) => checks.push(Check::ok(format!("Wallpapers will be set with {}", backend.name()))),
+    Err(e) => checks.push(Check::failed(
+      format!("No working backend: {:#}", e),
+      "Check `wallflow list-backends`; awww/swww also need their daemon running (awww-daemon, swww-daemon)",
+    )),
+  }
+
+  checks
+}
+
+/// Load and validate the config; the defaults stand in when it can't be loaded
+fn check_config(config_path: Option<&Path>) -> (Vec<Check>, Config) {
+  let mut checks = Vec::new();
+
+  let path = config_path.map(Path::to_path_buf).or_else(Config::existing_default_path);
+  let mut config = match &path {
+    Some(path) => match Config::load(path) {
+      Ok(config) => {
+        checks.push(Check::ok(format!("Config file: {}", path.display())));
+        config
+      }
+      Err(e) => {
+        checks.push(Check::failed(
+          format!("Config file {} can't be loaded: {:#}", path.display(), e),
+          "Fix the file, or run `wallflow config validate` for details",
+        ));
+        Config::default()
+      }
+    },
+    None => {
+      checks.push(Check::warning(
+        format!("No config file at {}, using defaults", Config::default_path().display()),
+        "Run `wallflow config init` to write one you can edit",
+      ));
+      Config::default()
+    }
+  };
+
+  if let Err(e) = config.apply_env_overrides() {
+    checks.push(Check::failed(format!("{:#}", e), "Fix or unset the WALLFLOW_* environment variable"));
+  }
+  if let Err(e) = config.expand_paths() {
+    checks.push(Check::failed(
+      format!("Paths can't be expanded: {:#}", e),
+      "Check paths.local and paths.downloads",
+    ));
+  }
+
+  // Directories get their own section
+  let issues = validate::validate(&config, &downloaders::list_sources());
+  for issue in issues.iter().filter(|issue| !issue.field.starts_with("paths.")) {
+    let summary = format!("{}: {}", issue.field, issue.message);
+    let hint = format!("Change it with `wallflow config set {} <value>`", issue.field);
+    checks.push(match issue.severity {
+      Severity::Error => Check::failed(summary, hint),
+      Severity::Warning => Check::warning(summary, hint),
+    });
+  }
+
+  (checks, config)
+}
+
+fn check_directories(config: &Config) -> Vec<Check> {
+  let mut checks = Vec::new();
+
+  let downloads = Path::new(&config.paths.downloads);
+  checks.push(match check_writable(downloads) {
+    Ok(()) => Check::ok(format!("Downloads go to {}", downloads.display())),
+    Err(e) => Check::failed(
+      format!("Can't write to {}: {}", downloads.display(), e),
+      "Point paths.downloads at a folder you own",
+    ),
+  });
+
+  // Local wallpapers are the daemon's last resort, so an empty collection only fails when it's the main source
+  let local_is_default = config.sources.default == "local";
+  let empty = |summary: String| {
+    let hint = "Add images there, or change paths.local / sources.local.subdir";
+    if local_is_default {
+      Check::failed(summary, hint)
+    } else {
+      Check::warning(summary, hint)
+    }
+  };
+  let local = config.sources.local.directory(&config.paths.local);
+  checks.push(match local {
+    Ok(dir) if dir.is_dir() => {
+      match wallpaper::collect_wallpapers(&dir, &config.sources.local.collected_formats(), config.sources.local.search_depth()) {
+        Ok(found) if found.is_empty() => empty(format!("No wallpapers found in {}", dir.display())),
+        Ok(found) => Check::ok(format!("{} local wallpapers in {}", found.len(), dir.display())),
+        Err(e) => empty(format!("{:#}", e)),
+      }
+    }
+    Ok(dir) => empty(format!("Local wallpaper folder {} doesn't exist", dir.display())),
+    Err(e) => empty(format!("{:#}", e)),
+  });
+
+  checks
+}
+
+/// Create `dir` if needed and prove a file can be written there
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+  std::fs::create_dir_all(dir)?;
+  let probe = dir.join(format!(".wallflow-doctor-{}", std::process::id()));
+  std::fs::write(&probe, b"")?;
+  std::fs::remove_file(&probe)
+}
+
+async fn check_network(config: &Config) -> Vec<Check> {
+  let mut sources: Vec<&str> = vec![config.sources.default.as_str()];
+  sources.extend(config.sources.fallback.iter().map(String::as_str));
+  sources.extend(config.schedule.iter().filter_map(|entry| entry.source.as_deref()));
+  let mut seen = std::collections::HashSet::new();
+  sources.retain(|source| seen.insert(*source));
+
+  let probes: Vec<(&str, String)> = sources
+    .into_iter()
+    .filter_map(|source| probe_url(source, config).map(|url| (source, url)))
+    .collect();
+  if probes.is_empty() {
+    return vec![Check::ok("No online sources in use")];
+  }
+
+  let client = match reqwest::Client::builder()
+    .user_agent(config.advanced.user_agent.clone())
+    .timeout(Duration::from_secs(config.advanced.timeout.clamp(1, 15).into()))
+    .build()
+  {
+    Ok(client) => client,
+    Err(e) => {
+      return vec![Check::failed(
+        format!("Can't create an HTTP client: {}", e),
+        "This is a bug, please report it",
+      )];
+    }
+  };
+
+  let mut checks = Vec::new();
+  for (source, url) in probes {
+    // Any answer, even an error status, means the server is reachable
+    let result = client.get(&url).send().await;
+    let host = reqwest::Url::parse(&url)
+      .ok()
+      .and_then(|url| url.host_str().map(String::from))
+      .unwrap_or(url);
+    checks.push(match result {
+      Ok(_) => Check::ok(format!("{} is reachable ({})", source, host)),
+      Err(e) => {
+        let summary = format!("{} can't be reached ({}): {}", source, host, e);
+        let hint = "Check your connection, proxy or firewall; advanced.offline_fallback reuses earlier downloads meanwhile";
+        if source == config.sources.default {
+          Check::failed(summary, hint)
+        } else {
+          Check::warning(summary, hint)
+        }
+      }
+    });
+  }
+  checks
+}
+
+/// Address that shows whether a source's servers can be reached, `None` for offline sources
+fn probe_url(source: &str, config: &Config) -> Option<String> {
+  let url = match source {
+    "local" | "favorites" => return None,
+    "apod" => config.sources.apod.url.as_str(),
+    "url" => config.sources.url.endpoint.as_deref().filter(|endpoint| !endpoint.is_empty())?,
+    "wallhaven" => "https://wallhaven.cc",
+    "picsum" => "https://picsum.photos",
+    "bing" => "https://www.bing.com",
+    "reddit" => "https://www.reddit.com",
+    "earthview" => "https://earthview.withgoogle.com",
+    "unsplash" => "https://api.unsplash.com",
+    "pexels" => "https://api.pexels.com",
+    "pixabay" => "https://pixabay.com",
+    "flickr" => "https://api.flickr.com",
+    "wikimedia" => "https://commons.wikimedia.org",
+    _ => return None,
+  };
+  Some(url.to_string())
+}
+
+fn check_tools(config: &Config) -> Vec<Check> {
+  let mut tools: Vec<(String, String)> = Vec::new();
+  let mut need = |tool: &str, why: String| {
+    if !tools.iter().any(|(name, _)| name == tool) {
+      tools.push((tool.to_string(), why));
+    }
+  };
+
+  if config.sources.local.include_video {
+    need("mpvpaper", "plays video wallpapers (sources.local.include_video)".to_string());
+  }
+  for target in &config.integration.notify_apps {
+    let app = target.split(':').next().unwrap_or(target);
+    need(app, format!("live recoloring (integration.notify_apps: {})", target));
+  }
+  if config.integration.icon_theme_sync || config.integration.gnome.accent_sync {
+    need("gsettings", "icon theme and accent color sync on GNOME".to_string());
+  }
+
+  let bundles = templates::TemplateEngine::list_bundles(templates::templates_dir()).unwrap_or_default();
+  for (_, manifest) in &bundles {
+    let Some(reload) = &manifest.reload else { continue };
+    if let Some(program) = reload.command.first() {
+      need(program, format!("reloads {} after its template is rendered", manifest.name));
+    } else if !reload.process_name.is_empty() && !reload.signal.is_empty() {
+      need("pkill", format!("signals {} to reload its colors", manifest.name));
+    }
+  }
+
+  if cfg!(target_os = "linux") {
+    need("notify-send", "desktop notifications from your own hooks and scripts".to_string());
+    need(
+      "wal",
+      "pywal, only if your scripts still call it (wallflow extracts colors itself)".to_string(),
+    );
+  }
+
+  tools
+    .into_iter()
+    .map(|(tool, why)| {
+      if which::which(&tool).is_ok() {
+        Check::ok(format!("{}: {}", tool, why))
+      } else {
+        Check::warning(format!("{} not found: {}", tool, why), format!("Install {} if you need it", tool))
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_probe_url() {
+    let mut config = Config::default();
+    assert_eq!(probe_url("local", &config), None);
+    assert_eq!(probe_url("favorites", &config), None);
+    assert_eq!(probe_url("bing", &config).as_deref(), Some("https://www.bing.com"));
+
+    // Configurable endpoints are probed where they point
+    assert_eq!(probe_url("url", &config), None);
+    config.sources.url.endpoint = Some("https://walls.example.com/api/random".to_string());
+    assert_eq!(probe_url("url", &config).as_deref(), Some("https://walls.example.com/api/random"));
+
+    // Every built-in online source has something to probe
+    for source in downloaders::list_sources().iter().filter(|source| *source != "url") {
+      assert!(probe_url(source, &config).is_some(), "{}", source);
+    }
+  }
+
+  #[test]
+  fn test_check_writable() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let nested = tmp.path().join("a/b");
+    assert!(check_writable(&nested).is_ok());
+    assert_eq!(std::fs::read_dir(&nested).unwrap().count(), 0);
+
+    let file = tmp.path().join("file");
+    std::fs::write(&file, b"").unwrap();
+    assert!(check_writable(&file).is_err());
+  }
+}
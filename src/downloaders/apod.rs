@@ -7,17 +7,17 @@
 //! - Metadata extraction and preservation
 
 use super::DownloadOptions;
+use super::error::DownloaderError;
 use super::traits::WallpaperDownloader;
 use crate::config::Config;
 use crate::downloaders::client::WallflowClient as Client;
 use crate::downloaders::filesystem::FilesystemHelper;
-use crate::downloaders::traits::Wallpaper;
+use crate::downloaders::traits::{Metadata, Wallpaper};
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use serde::Deserialize;
-use std::path::Path;
-use tracing::debug;
+use tracing::{debug, info};
 
 /// NASA APOD API response structure
 #[derive(Debug, Deserialize)]
@@ -41,10 +41,23 @@ struct ApodResponse {
   /// Media type (usually "image" for wallpapers)
   media_type: String,
 
+  /// Preview image of a video entry (only sent when requested with `thumbs=true`)
+  thumbnail_url: Option<String>,
+
   /// Copyright information (when applicable)
   copyright: Option<String>,
 }
 
+impl ApodResponse {
+  /// The picture to use: the HD image, or the thumbnail of a video entry
+  fn image_url(&self) -> Option<&str> {
+    match self.media_type.as_str() {
+      "image" => Some(self.hdurl.as_deref().unwrap_or(&self.url)),
+      _ => self.thumbnail_url.as_deref(),
+    }
+  }
+}
+
 /// NASA APOD downloader implementation
 ///
 /// Educational aspects:
@@ -57,71 +70,73 @@ pub struct ApodDownloader;
 #[async_trait]
 impl WallpaperDownloader for ApodDownloader {
   /// Fetch APOD data from NASA API
-  /// Note: APOD ignores query parameters as it always returns the picture of the day
-  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper> {
-    debug!("Fetching APOD data from NASA API");
-    let client = Client::from(&config.advanced);
-    let url = config.sources.apod.url.clone();
-    let api_key: String = config.sources.apod.api_key.clone();
+  /// The query may hold a date (YYYY-MM-DD) to fetch that day's picture instead of today's.
+  /// APOD has no search, so anything that isn't a date (like the TUI's search terms) is ignored.
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let date = match query.first() {
+      Some(date) if looks_like_date(date) => Some(parse_apod_date(date, chrono::Local::now().date_naive())?),
+      Some(_) => {
+        debug!("APOD can't be searched, ignoring '{}'", query.join(" "));
+        None
+      }
+      None => None,
+    };
 
-    let response = client
-      .get(&url)
-      .query(&[("api_key", &api_key)])
-      .send()
-      .await
-      .context("Failed to send request to NASA APOD API")?;
+    let client = Client::from(&config.advanced);
+    let mut apod_data = fetch_apod(&client, config, date).await?;
+    let first_date = apod_data.date.clone();
 
-    if !response.status().is_success() {
-      let status = response.status();
-      let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-      return Err(anyhow!("NASA API request failed with status {}: {}", status, error_text));
+    // Video days have no picture; use the video's thumbnail, or else an earlier day's image.
+    // A date asked for explicitly is that day's picture or nothing.
+    for _ in 0..DAYS_BACK {
+      if apod_data.image_url().is_some() || date.is_some() {
+        break;
+      }
+      let Some(previous) = NaiveDate::parse_from_str(&apod_data.date, "%Y-%m-%d")
+        .ok()
+        .and_then(|day| day.pred_opt())
+        .filter(|day| *day >= FIRST_APOD)
+      else {
+        break;
+      };
+      debug!(
+        "APOD for {} is a {} without a thumbnail, trying {}",
+        apod_data.date, apod_data.media_type, previous
+      );
+      apod_data = fetch_apod(&client, config, Some(previous)).await?;
     }
 
-    let apod_data: ApodResponse = response.json().await.context("Failed to parse NASA APOD API response as JSON")?;
-
-    debug!("Successfully fetched APOD: {}", apod_data.title);
-
-    // Validate that this is actually an image we can use as wallpaper
-    if apod_data.media_type != "image" {
-      return Err(anyhow!(
-        "Today's APOD is not an image (type: {}), cannot use as wallpaper",
-        apod_data.media_type
-      ));
+    let Some(image_url) = apod_data.image_url() else {
+      return Err(DownloaderError::NoResults(match date {
+        Some(date) => format!("The APOD for {} is a {} with no image to use as wallpaper", date, apod_data.media_type),
+        None => format!(
+          "Neither today's APOD nor the {} days before it have an image to use as wallpaper",
+          DAYS_BACK
+        ),
+      }));
+    };
+    if date.is_none() && apod_data.date != first_date {
+      info!("Today's APOD ({}) has no image, using the one from {}", first_date, apod_data.date);
     }
-    let image_url = apod_data.hdurl.as_ref().unwrap_or(&apod_data.url);
+    let image_url = image_url.to_string();
     debug!("Image URL: {}", image_url);
-    let response = client.get(image_url).send().await.context("Failed to download image")?;
-
-    if !response.status().is_success() {
-      return Err(anyhow!("Image download failed with status: {}", response.status()));
-    }
 
-    let _content_length = response.content_length();
-    let bytes = response.bytes().await.context("Failed to read image data")?;
-
-    let filename = format!("{}_{}", self.source_name(), FilesystemHelper::make_file_suffix());
-    let file_extension = image_url
-      .rsplit('.')
-      .next()
-      .and_then(|ext| {
-        let ext = ext.split('?').next().unwrap_or(ext);
-        if ext.len() <= 5 { Some(ext) } else { None }
-      })
-      .unwrap_or("jpg");
-    let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
-    let file_path = download_dir.join(&filename).with_extension(file_extension);
-
-    // Ensure the parent directory exists
-    if let Some(parent) = file_path.parent() {
-      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
-    }
-
-    tokio::fs::write(&file_path, &bytes).await.context("Failed to save image to file")?;
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &image_url, config, opts)
+      .await
+      .context("Failed to download image")?;
 
     let wallpaper = Wallpaper {
       file_path,
       downloaded_at: Utc::now(),
       source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url),
+        page_url: apod_page_url(&apod_data.date),
+        title: Some(apod_data.title.clone()),
+        attribution: apod_data.copyright.as_ref().map(|c| c.trim().to_string()),
+        date: Some(apod_data.date.clone()),
+        ..Default::default()
+      }),
     };
 
     debug!("✅ Downloaded wallpaper: {:?}", wallpaper);
@@ -132,3 +147,124 @@ impl WallpaperDownloader for ApodDownloader {
     "apod"
   }
 }
+
+/// How many earlier days to try when a day has no usable image
+const DAYS_BACK: usize = 3;
+
+/// Fetch the APOD entry for `date`, or today's when `None`
+async fn fetch_apod(client: &Client, config: &Config, date: Option<NaiveDate>) -> Result<ApodResponse, DownloaderError> {
+  debug!("Fetching APOD data from NASA API");
+  let mut params = vec![("api_key", config.sources.apod.api_key.clone()), ("thumbs", "true".to_string())];
+  if let Some(date) = date {
+    params.push(("date", date.format("%Y-%m-%d").to_string()));
+  }
+
+  let request = client.get(&config.sources.apod.url).query(&params);
+  let response = client.send_with_retry(request).await.context("Failed to send request to NASA APOD API")?;
+
+  if !response.status().is_success() {
+    let status = response.status();
+    let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+    return Err(DownloaderError::Http {
+      status: status.as_u16(),
+      message: format!("NASA API request failed with status {}: {}", status, error_text),
+    });
+  }
+
+  let apod_data: ApodResponse = response.json().await.context("Failed to parse NASA APOD API response as JSON")?;
+  debug!("Successfully fetched APOD: {}", apod_data.title);
+  Ok(apod_data)
+}
+
+/// The first Astronomy Picture of the Day
+const FIRST_APOD: NaiveDate = NaiveDate::from_ymd_opt(1995, 6, 16).unwrap();
+
+/// Whether a query is meant as a date (digits and separators) rather than search terms
+fn looks_like_date(query: &str) -> bool {
+  let query = query.trim();
+  query.starts_with(|c: char| c.is_ascii_digit()) && query.chars().all(|c| c.is_ascii_digit() || matches!(c, '-' | '/' | '.'))
+}
+
+/// Check a date given on the command line, so typos get a clear message instead of an API error
+fn parse_apod_date(date: &str, today: NaiveDate) -> Result<NaiveDate, DownloaderError> {
+  let parsed = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+    .map_err(|_| DownloaderError::Other(anyhow!("Invalid APOD date '{}', expected YYYY-MM-DD (e.g. 2024-01-15)", date)))?;
+
+  if parsed > today {
+    return Err(DownloaderError::Other(anyhow!("APOD date {} is in the future", parsed)));
+  }
+  if parsed < FIRST_APOD {
+    return Err(DownloaderError::Other(anyhow!(
+      "APOD started on {}, there is no picture for {}",
+      FIRST_APOD,
+      parsed
+    )));
+  }
+  Ok(parsed)
+}
+
+/// APOD page for a date, e.g. "2025-01-15" -> https://apod.nasa.gov/apod/ap250115.html
+fn apod_page_url(date: &str) -> Option<String> {
+  let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+  Some(format!("https://apod.nasa.gov/apod/ap{}.html", date.format("%y%m%d")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_apod_date() {
+    let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+    assert_eq!(
+      parse_apod_date("2024-01-15", today).unwrap(),
+      NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+    );
+    assert_eq!(parse_apod_date("2025-03-01", today).unwrap(), today);
+    assert_eq!(parse_apod_date("1995-06-16", today).unwrap(), FIRST_APOD);
+
+    for bad in ["15-01-2024", "2024-02-30", "yesterday", ""] {
+      let err = parse_apod_date(bad, today).unwrap_err().to_string();
+      assert!(err.contains("expected YYYY-MM-DD"), "{}: {}", bad, err);
+    }
+    assert!(parse_apod_date("2025-03-02", today).unwrap_err().to_string().contains("in the future"));
+    assert!(parse_apod_date("1995-06-15", today).is_err());
+  }
+
+  #[test]
+  fn test_looks_like_date() {
+    for date in ["2024-01-15", " 2024-1-5 ", "15-01-2024", "2024/01/15", "20240115"] {
+      assert!(looks_like_date(date), "{}", date);
+    }
+    for terms in ["galaxy", "andromeda 2024", "", "-1"] {
+      assert!(!looks_like_date(terms), "{}", terms);
+    }
+  }
+
+  #[test]
+  fn test_image_url() {
+    let entry = |json: &str| serde_json::from_str::<ApodResponse>(json).unwrap();
+
+    let image = entry(
+      r#"{"date": "2024-01-15", "title": "M31", "explanation": "", "url": "https://apod.nasa.gov/m31.jpg",
+          "hdurl": "https://apod.nasa.gov/m31_hd.jpg", "media_type": "image"}"#,
+    );
+    assert_eq!(image.image_url(), Some("https://apod.nasa.gov/m31_hd.jpg"));
+
+    let video = entry(
+      r#"{"date": "2024-01-16", "title": "Eclipse", "explanation": "", "url": "https://www.youtube.com/embed/abc",
+          "media_type": "video", "thumbnail_url": "https://img.youtube.com/vi/abc/0.jpg"}"#,
+    );
+    assert_eq!(video.image_url(), Some("https://img.youtube.com/vi/abc/0.jpg"));
+
+    let bare_video = entry(r#"{"date": "2024-01-17", "title": "Flyby", "explanation": "", "url": "https://vimeo.com/1", "media_type": "video"}"#);
+    assert_eq!(bare_video.image_url(), None);
+  }
+
+  #[test]
+  fn test_apod_page_url() {
+    assert_eq!(apod_page_url("2025-01-15").as_deref(), Some("https://apod.nasa.gov/apod/ap250115.html"));
+    assert_eq!(apod_page_url("not a date"), None);
+  }
+}
@@ -5,19 +5,44 @@
 
 use super::DownloadOptions;
 use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
 use super::filesystem::FilesystemHelper;
-use super::traits::{Wallpaper, WallpaperDownloader};
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
 use crate::config::Config;
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
 use rand::seq::SliceRandom;
 use serde::Deserialize;
-use std::path::Path;
 use tracing::debug;
 
 /// Bing API endpoint for daily images
-const BING_API_URL: &str = "https://www.bing.com/HPImageArchive.aspx?format=js&idx=0&n=8&mkt=en-US";
+const BING_API_URL: &str = "https://www.bing.com/HPImageArchive.aspx";
+
+/// Sizes Bing serves every daily image in, as used in the `<urlbase>_<size>.jpg` file name
+pub const RESOLUTIONS: &[&str] = &[
+  "UHD",
+  "1920x1200",
+  "1920x1080",
+  "1366x768",
+  "1280x768",
+  "1024x768",
+  "800x600",
+  "800x480",
+  "640x480",
+  "400x240",
+  "320x240",
+  "1080x1920",
+  "768x1280",
+  "720x1280",
+  "480x800",
+  "240x320",
+];
+
+/// Bing's spelling of a configured resolution, matched case-insensitively ("uhd" -> "UHD")
+pub fn resolution_suffix(resolution: &str) -> Option<&'static str> {
+  RESOLUTIONS.iter().copied().find(|known| known.eq_ignore_ascii_case(resolution.trim()))
+}
 
 /// Bing API response structure
 #[derive(Debug, Deserialize)]
@@ -32,6 +57,9 @@ struct BingImage {
   urlbase: String,
   /// Copyright/description text
   copyright: String,
+  /// Short title of the photo
+  #[serde(default)]
+  title: Option<String>,
   /// Whether this is marked as a wallpaper
   #[serde(default)]
   wp: bool,
@@ -44,22 +72,37 @@ pub struct BingDownloader;
 impl WallpaperDownloader for BingDownloader {
   /// Download Bing Photo of the Day
   /// Note: Bing ignores query parameters as it returns daily curated images
-  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper> {
-    debug!("Fetching Bing Photo of the Day");
+  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let bing = &config.sources.bing;
+    let resolution = resolution_suffix(&bing.resolution).ok_or_else(|| {
+      DownloaderError::Other(anyhow!(
+        "Unknown Bing resolution '{}'. Expected one of {}",
+        bing.resolution,
+        RESOLUTIONS.join(", ")
+      ))
+    })?;
+
+    debug!("Fetching Bing Photo of the Day for {}", bing.market);
 
     let client = Client::from(&config.advanced);
-    let response = client.get(BING_API_URL).send().await.context("Failed to send request to Bing API")?;
+    let request = client
+      .get(BING_API_URL)
+      .query(&[("format", "js"), ("idx", "0"), ("n", "8"), ("mkt", bing.market.as_str())]);
+    let response = client.send_with_retry(request).await.context("Failed to send request to Bing API")?;
 
     if !response.status().is_success() {
       let status = response.status();
       let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-      return Err(anyhow!("Bing API request failed with status {}: {}", status, error_text));
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Bing API request failed with status {}: {}", status, error_text),
+      });
     }
 
     let bing_data: BingResponse = response.json().await.context("Failed to parse Bing API response")?;
 
     if bing_data.images.is_empty() {
-      return Err(anyhow!("No images returned from Bing API"));
+      return Err(DownloaderError::NoResults("No images returned from Bing API".to_string()));
     }
 
     // Filter to only wallpaper-marked images, or use all if none are marked
@@ -73,38 +116,27 @@ impl WallpaperDownloader for BingDownloader {
     // Pick a random image
     let image = images
       .choose(&mut rand::thread_rng())
-      .ok_or_else(|| anyhow!("Failed to select random Bing image"))?;
+      .ok_or_else(|| DownloaderError::NoResults("Failed to select random Bing image".to_string()))?;
 
     debug!("Selected Bing image: {}", image.copyright);
 
-    // Build UHD image URL
-    let image_url = format!("https://www.bing.com{}_UHD.jpg", image.urlbase);
+    let image_url = image_url(&image.urlbase, resolution);
     debug!("Image URL: {}", image_url);
 
-    // Download the image
-    let image_response = client.get(&image_url).send().await.context("Failed to download Bing image")?;
-
-    if !image_response.status().is_success() {
-      return Err(anyhow!("Bing image download failed with status: {}", image_response.status()));
-    }
-
-    let bytes = image_response.bytes().await.context("Failed to read Bing image data")?;
-
-    let filename = format!("{}_{}", self.source_name(), FilesystemHelper::make_file_suffix());
-    let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
-    let file_path = download_dir.join(&filename).with_extension("jpg");
-
-    // Ensure download directory exists
-    if let Some(parent) = file_path.parent() {
-      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
-    }
-
-    tokio::fs::write(&file_path, &bytes).await.context("Failed to save Bing image")?;
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &image_url, config, opts)
+      .await
+      .context("Failed to download Bing image")?;
 
     let wallpaper = Wallpaper {
       file_path,
       downloaded_at: Utc::now(),
       source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url.clone()),
+        title: image.title.clone(),
+        attribution: Some(image.copyright.clone()),
+        ..Default::default()
+      }),
     };
 
     debug!("Downloaded Bing wallpaper: {:?}", wallpaper);
@@ -115,3 +147,31 @@ impl WallpaperDownloader for BingDownloader {
     "bing"
   }
 }
+
+/// Full image URL for an archive entry at one of Bing's sizes
+fn image_url(urlbase: &str, resolution: &str) -> String {
+  format!("https://www.bing.com{}_{}.jpg", urlbase, resolution)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_resolution_suffix() {
+    assert_eq!(resolution_suffix("UHD"), Some("UHD"));
+    assert_eq!(resolution_suffix("uhd"), Some("UHD"));
+    assert_eq!(resolution_suffix(" 1366x768 "), Some("1366x768"));
+    assert_eq!(resolution_suffix("1920X1080"), Some("1920x1080"));
+    assert_eq!(resolution_suffix("2560x1440"), None);
+    assert_eq!(resolution_suffix(""), None);
+  }
+
+  #[test]
+  fn test_image_url() {
+    assert_eq!(
+      image_url("/th?id=OHR.Puffins_DE-DE123", "1920x1080"),
+      "https://www.bing.com/th?id=OHR.Puffins_DE-DE123_1920x1080.jpg"
+    );
+  }
+}
@@ -1,7 +1,15 @@
 use crate::config::AdvancedConfig;
+use anyhow::Result;
+use reqwest::StatusCode;
+use std::time::Duration;
+use tracing::debug;
 
 pub struct WallflowClient {
   client: reqwest::Client,
+  /// How many times a failed request is retried (0 = no retries)
+  retry_attempts: u32,
+  /// Delay before the first retry, doubled on each subsequent attempt
+  retry_delay: Duration,
 }
 
 impl WallflowClient {
@@ -12,10 +20,79 @@ impl WallflowClient {
         .user_agent(config.user_agent.clone())
         .build()
         .expect("Failed to build HTTP client"),
+      retry_attempts: config.retry_attempts,
+      retry_delay: Duration::from_millis(config.retry_delay_ms),
     }
   }
 
   pub fn get(&self, url: &str) -> reqwest::RequestBuilder {
     self.client.get(url)
   }
+
+  /// Send a request, retrying connection errors, timeouts, 5xx and 429 responses
+  /// with exponential backoff (e.g. 500ms, 1s, 2s).
+  ///
+  /// If every attempt gets a retryable status, the last response is returned so
+  /// the caller can report the status as usual.
+  pub async fn send_with_retry(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+    let mut attempt = 0;
+
+    loop {
+      // Requests with streaming bodies can't be cloned, so they only get one shot
+      let Some(this_request) = request.try_clone() else {
+        return Ok(request.send().await?);
+      };
+
+      let result = this_request.send().await;
+
+      let retryable = match &result {
+        Ok(response) => Self::is_retryable_status(response.status()),
+        Err(e) => e.is_connect() || e.is_timeout(),
+      };
+
+      if !retryable || attempt >= self.retry_attempts {
+        return Ok(result?);
+      }
+
+      let delay = self.retry_delay * 2u32.saturating_pow(attempt);
+      attempt += 1;
+
+      match &result {
+        Ok(response) => debug!(
+          "Request returned {}, retrying in {:?} (attempt {}/{})",
+          response.status(),
+          delay,
+          attempt,
+          self.retry_attempts
+        ),
+        Err(e) => debug!(
+          "Request failed ({}), retrying in {:?} (attempt {}/{})",
+          e, delay, attempt, self.retry_attempts
+        ),
+      }
+
+      tokio::time::sleep(delay).await;
+    }
+  }
+
+  /// Server errors and rate limiting are worth another try; other statuses are final
+  fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_retryable_status() {
+    assert!(WallflowClient::is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+    assert!(WallflowClient::is_retryable_status(StatusCode::BAD_GATEWAY));
+    assert!(WallflowClient::is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+
+    assert!(!WallflowClient::is_retryable_status(StatusCode::OK));
+    assert!(!WallflowClient::is_retryable_status(StatusCode::NOT_FOUND));
+    assert!(!WallflowClient::is_retryable_status(StatusCode::UNAUTHORIZED));
+  }
 }
@@ -5,10 +5,11 @@
 
 use super::DownloadOptions;
 use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
 use super::filesystem::FilesystemHelper;
-use super::traits::{Wallpaper, WallpaperDownloader};
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
 use crate::config::Config;
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
 use rand::seq::SliceRandom;
@@ -43,10 +44,8 @@ struct EarthViewPhoto {
   /// Region within country (may be "-" if unknown)
   region: String,
   /// Location name
-  #[allow(dead_code)]
   name: Option<String>,
   /// Attribution text
-  #[allow(dead_code)]
   attribution: Option<String>,
 }
 
@@ -57,43 +56,51 @@ pub struct EarthViewDownloader;
 impl WallpaperDownloader for EarthViewDownloader {
   /// Download a wallpaper from Google Earth View
   /// Note: Earth View ignores query parameters as it returns curated satellite imagery
-  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper> {
+  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
     debug!("Fetching Earth View photo list");
 
     let client = Client::from(&config.advanced);
 
     // Step 1: Fetch the list of available photos
     let list_response = client
-      .get(EARTHVIEW_LIST_URL)
-      .send()
+      .send_with_retry(client.get(EARTHVIEW_LIST_URL))
       .await
       .context("Failed to fetch Earth View photo list")?;
 
     if !list_response.status().is_success() {
       let status = list_response.status();
-      return Err(anyhow!("Earth View list request failed with status: {}", status));
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Earth View list request failed with status: {}", status),
+      });
     }
 
     let photo_list: Vec<EarthViewListItem> = list_response.json().await.context("Failed to parse Earth View photo list")?;
 
     if photo_list.is_empty() {
-      return Err(anyhow!("No photos available from Earth View"));
+      return Err(DownloaderError::NoResults("No photos available from Earth View".to_string()));
     }
 
     // Pick a random photo from the list
     let selected = photo_list
       .choose(&mut rand::thread_rng())
-      .ok_or_else(|| anyhow!("Failed to select random Earth View photo"))?;
+      .ok_or_else(|| DownloaderError::NoResults("Failed to select random Earth View photo".to_string()))?;
 
     debug!("Selected Earth View slug: {}", selected.slug);
 
     // Step 2: Fetch individual photo details
     let photo_url = format!("{}{}.json", EARTHVIEW_API_BASE, selected.slug);
-    let photo_response = client.get(&photo_url).send().await.context("Failed to fetch Earth View photo details")?;
+    let photo_response = client
+      .send_with_retry(client.get(&photo_url))
+      .await
+      .context("Failed to fetch Earth View photo details")?;
 
     if !photo_response.status().is_success() {
       let status = photo_response.status();
-      return Err(anyhow!("Earth View photo details request failed with status: {}", status));
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Earth View photo details request failed with status: {}", status),
+      });
     }
 
     let photo: EarthViewPhoto = photo_response.json().await.context("Failed to parse Earth View photo details")?;
@@ -107,15 +114,6 @@ impl WallpaperDownloader for EarthViewDownloader {
 
     debug!("Downloading Earth View image: {} - {}", photo.country, image_url);
 
-    // Download the image
-    let image_response = client.get(&image_url).send().await.context("Failed to download Earth View image")?;
-
-    if !image_response.status().is_success() {
-      return Err(anyhow!("Earth View image download failed with status: {}", image_response.status()));
-    }
-
-    let bytes = image_response.bytes().await.context("Failed to read Earth View image data")?;
-
     // Build descriptive filename
     let location = if photo.region != "-" && !photo.region.is_empty() {
       format!("{}, {}", photo.region, photo.country)
@@ -132,17 +130,21 @@ impl WallpaperDownloader for EarthViewDownloader {
     let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
     let file_path = download_dir.join(&filename);
 
-    // Ensure download directory exists
-    if let Some(parent) = file_path.parent() {
-      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
-    }
-
-    tokio::fs::write(&file_path, &bytes).await.context("Failed to save Earth View image")?;
+    FilesystemHelper::download_with_progress(&client, &image_url, &file_path, opts.show_progress)
+      .await
+      .context("Failed to download Earth View image")?;
 
     let wallpaper = Wallpaper {
       file_path,
       downloaded_at: Utc::now(),
       source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url.clone()),
+        title: photo.name.clone(),
+        attribution: photo.attribution.clone(),
+        location: Some(location.clone()),
+        ..Default::default()
+      }),
     };
 
     debug!("Downloaded Earth View wallpaper: {:?}", wallpaper);
@@ -0,0 +1,94 @@
+//! Typed errors for downloads, so library users can tell failures apart
+
+use thiserror::Error;
+
+/// Why a download failed
+///
+/// Converts into `anyhow::Error` like any other error. Anything without a more
+/// specific variant ends up in `Network` (the request itself failed) or `Other`.
+#[derive(Debug, Error)]
+pub enum DownloaderError {
+  #[error("Unknown wallpaper source: {0}")]
+  UnknownSource(String),
+
+  /// The source needs an API key that isn't configured
+  #[error("{0}")]
+  MissingApiKey(String),
+
+  /// The source rejected the configured API key
+  #[error("{0}")]
+  InvalidApiKey(String),
+
+  #[error("{0}")]
+  RateLimited(String),
+
+  /// The source answered but had nothing usable (empty search, video of the day, ...)
+  #[error("{0}")]
+  NoResults(String),
+
+  /// The downloaded file isn't an image we recognize (an HTML error page, a truncated blob, ...)
+  #[error("{0}")]
+  NotAnImage(String),
+
+  /// The source's API returned an unsuccessful status
+  #[error("{message}")]
+  Http { status: u16, message: String },
+
+  /// The request couldn't be sent or its response couldn't be read
+  #[error(transparent)]
+  Network(anyhow::Error),
+
+  #[error(transparent)]
+  Other(anyhow::Error),
+}
+
+impl DownloaderError {
+  /// Whether trying again later (or right away) could succeed
+  pub fn is_retryable(&self) -> bool {
+    match self {
+      Self::RateLimited(_) | Self::Network(_) | Self::NotAnImage(_) => true,
+      Self::Http { status, .. } => *status == 429 || *status >= 500,
+      _ => false,
+    }
+  }
+}
+
+impl From<anyhow::Error> for DownloaderError {
+  fn from(error: anyhow::Error) -> Self {
+    let error = match error.downcast::<DownloaderError>() {
+      Ok(error) => return error,
+      Err(error) => error,
+    };
+
+    // Unparseable responses come back as reqwest errors too, but aren't network trouble
+    let network = error
+      .chain()
+      .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+      .any(|cause| !cause.is_decode());
+
+    if network { Self::Network(error) } else { Self::Other(error) }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use anyhow::{Context, anyhow};
+
+  #[test]
+  fn test_from_anyhow() {
+    let typed: DownloaderError = anyhow::Error::from(DownloaderError::NoResults("empty".to_string())).into();
+    assert!(matches!(typed, DownloaderError::NoResults(_)));
+
+    let other: DownloaderError = Err::<(), _>(anyhow!("disk full")).context("Failed to save image").unwrap_err().into();
+    assert!(matches!(other, DownloaderError::Other(_)));
+    assert_eq!(other.to_string(), "Failed to save image");
+    assert!(!other.is_retryable());
+
+    let server_error = DownloaderError::Http {
+      status: 503,
+      message: "Bing API request failed with status 503".to_string(),
+    };
+    assert!(server_error.is_retryable());
+  }
+}
@@ -1,9 +1,717 @@
+use super::DownloadOptions;
+use super::client::WallflowClient;
+use super::error::DownloaderError;
+use super::traits::{Metadata, Wallpaper};
+use crate::config::Config;
+use anyhow::{Context, Result, anyhow};
 use chrono::Local;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info};
+
+/// Serializes access to the download index between concurrent downloads
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
 
 pub struct FilesystemHelper;
 
+/// Bytes kept from the start of a streamed download to identify its format
+const SNIFF_LEN: usize = 256;
+
+/// Contents of a `<image>.json` metadata sidecar
+#[derive(Debug, Serialize)]
+struct Sidecar<'a> {
+  source: &'a str,
+  downloaded_at: DateTime<Utc>,
+  #[serde(flatten)]
+  metadata: &'a Metadata,
+}
+
+/// Content hashes of downloaded files, persisted between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DownloadIndex {
+  files: HashMap<String, PathBuf>,
+}
+
 impl FilesystemHelper {
+  /// Timestamp suffix for downloaded files.
+  /// Includes milliseconds so concurrent downloads from the same source don't collide.
   pub fn make_file_suffix() -> String {
-    Local::now().format("%Y%m%d_%H%M%S").to_string()
+    Local::now().format("%Y%m%d_%H%M%S_%3f").to_string()
+  }
+
+  /// File extension for an image URL, ignoring any `?query` or `#fragment`.
+  /// Falls back to "jpg" when the path has no extension or the suffix is too long
+  /// to be one (e.g. `photo.1a2b3c4d5e`).
+  pub fn url_extension(url: &str) -> String {
+    let path = match reqwest::Url::parse(url) {
+      Ok(parsed) => parsed.path().to_string(),
+      Err(_) => url.split(['?', '#']).next().unwrap_or_default().to_string(),
+    };
+
+    Path::new(&path)
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .filter(|ext| !ext.is_empty() && ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+      .map(|ext| ext.to_lowercase())
+      .unwrap_or_else(|| "jpg".to_string())
+  }
+
+  /// Where a download is saved: `<output dir or paths.downloads>/<source>_<timestamp>.<ext>`
+  pub fn download_path(source_name: &str, extension: &str, config: &Config, opts: &DownloadOptions) -> PathBuf {
+    let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
+    download_dir
+      .join(format!("{}_{}", source_name, Self::make_file_suffix()))
+      .with_extension(extension)
+  }
+
+  /// Save image bytes fetched from `url` to the download directory and return the path.
+  /// The extension comes from the bytes themselves when they're a known format,
+  /// otherwise from the URL. Bytes that don't look like an image are not saved.
+  pub async fn save_image(source_name: &str, bytes: &[u8], url: &str, config: &Config, opts: &DownloadOptions) -> Result<PathBuf> {
+    Self::ensure_image(bytes, url)?;
+    let extension = image::guess_format(bytes)
+      .ok()
+      .and_then(|format| format.extensions_str().first())
+      .map(|ext| ext.to_string())
+      .unwrap_or_else(|| Self::url_extension(url));
+    let path = Self::download_path(source_name, &extension, config, opts);
+
+    Self::write_atomically(&path, |mut file| async move {
+      file.write_all(bytes).await?;
+      file.flush().await?;
+      Ok(())
+    })
+    .await
+    .with_context(|| format!("Failed to save {}", path.display()))?;
+
+    Ok(path)
+  }
+
+  /// Check the magic bytes at the start of a download, so an HTML error page served
+  /// in place of an image fails here rather than in the wallpaper backend
+  pub fn ensure_image(head: &[u8], url: &str) -> Result<(), DownloaderError> {
+    if image::guess_format(head).is_ok() {
+      return Ok(());
+    }
+
+    let kind = match String::from_utf8_lossy(&head[..head.len().min(256)]).trim_start().to_ascii_lowercase() {
+      text if text.starts_with("<!doctype html") || text.starts_with("<html") => "an HTML page",
+      text if text.starts_with('{') || text.starts_with('[') => "JSON",
+      _ if head.is_empty() => "an empty file",
+      _ => "not a recognized image format",
+    };
+    Err(DownloaderError::NotAnImage(format!("Download from {} is {}", url, kind)))
+  }
+
+  /// Where a download is written until it is complete: `<path>.part`
+  pub fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+  }
+
+  /// Hand `write` a fresh file at `<path>.part` and rename it to `path` once `write`
+  /// succeeds, so `path` never holds a half-written image. On failure the partial
+  /// file is removed.
+  async fn write_atomically<F, Fut>(path: &Path, write: F) -> Result<()>
+  where
+    F: FnOnce(tokio::fs::File) -> Fut,
+    Fut: Future<Output = Result<()>>,
+  {
+    if let Some(parent) = path.parent() {
+      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
+    }
+
+    let partial = Self::partial_path(path);
+    let file = tokio::fs::File::create(&partial)
+      .await
+      .with_context(|| format!("Failed to create {}", partial.display()))?;
+
+    let result = match write(file).await {
+      Ok(()) => tokio::fs::rename(&partial, path)
+        .await
+        .with_context(|| format!("Failed to move {} into place", partial.display())),
+      Err(e) => Err(e),
+    };
+    if result.is_err() {
+      let _ = tokio::fs::remove_file(&partial).await;
+    }
+    result
+  }
+
+  /// Download the image at `url` into the download directory and return the path
+  pub async fn download_image(client: &WallflowClient, source_name: &str, url: &str, config: &Config, opts: &DownloadOptions) -> Result<PathBuf> {
+    let path = Self::download_path(source_name, &Self::url_extension(url), config, opts);
+    Self::download_with_progress(client, url, &path, opts.show_progress).await?;
+    Ok(path)
+  }
+
+  /// Download `url` straight into `path`, creating its directory first.
+  /// With `show_progress` set and stderr a terminal, a progress bar is drawn while
+  /// the body streams in.
+  pub async fn download_with_progress(client: &WallflowClient, url: &str, path: &Path, show_progress: bool) -> Result<()> {
+    let response = client.send_with_retry(client.get(url)).await.context("Failed to send image request")?;
+
+    if !response.status().is_success() {
+      return Err(anyhow!("Image download failed with status: {}", response.status()));
+    }
+
+    Self::save_response(response, url, path, show_progress).await
+  }
+
+  /// Stream a successful response body from `url` into `path`. The body goes to
+  /// `<path>.part` first and only replaces `path` once it has fully arrived and
+  /// starts like an image.
+  pub async fn save_response(mut response: reqwest::Response, url: &str, path: &Path, show_progress: bool) -> Result<()> {
+    let mut progress = (show_progress && std::io::stderr().is_terminal()).then(|| DownloadProgress::new(response.content_length()));
+
+    let mut bar = progress.as_mut();
+    let result = Self::write_atomically(path, |mut file| async move {
+      let mut head = Vec::new();
+      while let Some(chunk) = response.chunk().await.context("Failed to read image data")? {
+        if head.len() < SNIFF_LEN {
+          head.extend_from_slice(&chunk[..chunk.len().min(SNIFF_LEN - head.len())]);
+        }
+        file.write_all(&chunk).await.context("Failed to save image")?;
+        if let Some(progress) = bar.as_mut() {
+          progress.advance(chunk.len() as u64);
+        }
+      }
+      Self::ensure_image(&head, url)?;
+      file.flush().await.context("Failed to save image")
+    })
+    .await;
+
+    if let Some(progress) = progress {
+      progress.finish();
+    }
+    result
+  }
+
+  /// SHA-256 of the contents of a file as hex (used to spot identical downloads)
+  pub async fn content_hash(path: &Path) -> Result<String> {
+    let bytes = tokio::fs::read(path)
+      .await
+      .with_context(|| format!("Failed to read {} for hashing", path.display()))?;
+
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+  }
+
+  /// Sidecar path for an image: `bing_20250101.jpg` -> `bing_20250101.jpg.json`
+  pub fn sidecar_path(image: &Path) -> PathBuf {
+    let mut path = image.as_os_str().to_owned();
+    path.push(".json");
+    PathBuf::from(path)
+  }
+
+  /// Write the wallpaper's source metadata next to the image
+  pub async fn write_sidecar(wallpaper: &Wallpaper) -> Result<()> {
+    let Some(metadata) = &wallpaper.metadata else {
+      return Ok(());
+    };
+
+    let sidecar = Sidecar {
+      source: &wallpaper.source,
+      downloaded_at: wallpaper.downloaded_at,
+      metadata,
+    };
+    let json = serde_json::to_string_pretty(&sidecar).context("Failed to serialize wallpaper metadata")?;
+    let path = Self::sidecar_path(&wallpaper.file_path);
+
+    tokio::fs::write(&path, json)
+      .await
+      .with_context(|| format!("Failed to write metadata sidecar: {}", path.display()))
+  }
+
+  /// Read the metadata sidecar for an image, if one was saved
+  pub fn read_sidecar(image: &Path) -> Option<Metadata> {
+    let json = std::fs::read_to_string(Self::sidecar_path(image)).ok()?;
+    serde_json::from_str(&json).ok()
+  }
+
+  /// If an identical image was downloaded before and still exists, delete `path`
+  /// and return the earlier file instead. Otherwise remember `path` and return it.
+  pub async fn deduplicate(path: &Path) -> Result<PathBuf> {
+    let hash = Self::content_hash(path).await?;
+    let index_file = download_index_file()?;
+
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    dedupe_with_index(&index_file, path, &hash)
+  }
+
+  /// Width and height of the image at `path`.
+  /// Only the header is read, so this is cheap even for large files.
+  pub fn image_size(path: &Path) -> Result<(u32, u32)> {
+    let size = imagesize::size(path).with_context(|| format!("Failed to read image dimensions: {}", path.display()))?;
+    Ok((size.width as u32, size.height as u32))
+  }
+}
+
+/// Single-line progress bar on stderr, redrawn at most every 100ms
+struct DownloadProgress {
+  /// Body size from `Content-Length`, if the server sent one
+  total: Option<u64>,
+  received: u64,
+  last_draw: Option<Instant>,
+}
+
+impl DownloadProgress {
+  const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+  fn new(total: Option<u64>) -> Self {
+    Self {
+      total: total.filter(|&total| total > 0),
+      received: 0,
+      last_draw: None,
+    }
+  }
+
+  fn advance(&mut self, bytes: u64) {
+    self.received += bytes;
+    if self.last_draw.is_none_or(|last| last.elapsed() >= Self::REDRAW_INTERVAL) {
+      self.last_draw = Some(Instant::now());
+      eprint!("\r{}", progress_line(self.received, self.total));
+      let _ = std::io::stderr().flush();
+    }
+  }
+
+  /// Clear the bar so later output starts on a clean line
+  fn finish(self) {
+    if self.last_draw.is_some() {
+      eprint!("\r\x1b[2K");
+      let _ = std::io::stderr().flush();
+    }
+  }
+}
+
+/// "Downloading [########------------]  40%  2.0/5.0 MB", or just the amount
+/// received when the size is unknown
+fn progress_line(received: u64, total: Option<u64>) -> String {
+  const WIDTH: usize = 20;
+  let megabytes = |bytes: u64| bytes as f64 / 1_000_000.0;
+
+  match total {
+    Some(total) => {
+      let fraction = (received as f64 / total as f64).min(1.0);
+      let filled = (fraction * WIDTH as f64).round() as usize;
+      format!(
+        "Downloading [{}{}] {:>3}%  {:.1}/{:.1} MB",
+        "#".repeat(filled),
+        "-".repeat(WIDTH - filled),
+        (fraction * 100.0).round() as u32,
+        megabytes(received),
+        megabytes(total)
+      )
+    }
+    None => format!("Downloading {:.1} MB", megabytes(received)),
+  }
+}
+
+fn download_index_file() -> Result<PathBuf> {
+  Ok(crate::paths::state_dir()?.join("download_index.json"))
+}
+
+fn dedupe_with_index(index_file: &Path, path: &Path, hash: &str) -> Result<PathBuf> {
+  let mut index: DownloadIndex = std::fs::read_to_string(index_file)
+    .ok()
+    .and_then(|json| serde_json::from_str(&json).ok())
+    .unwrap_or_default();
+
+  // Forget files removed by cleanup (or by hand)
+  index.files.retain(|_, file| file.exists());
+
+  let result = match index.files.get(hash) {
+    Some(existing) if existing != path => {
+      std::fs::remove_file(path).with_context(|| format!("Failed to remove duplicate download: {}", path.display()))?;
+      info!("Deduplicated download: {} is identical to {}", path.display(), existing.display());
+
+      // Count the reused file as fresh so cleanup keeps it
+      if let Ok(file) = std::fs::File::options().write(true).open(existing) {
+        let _ = file.set_modified(SystemTime::now());
+      }
+      existing.clone()
+    }
+    _ => {
+      index.files.insert(hash.to_string(), path.to_path_buf());
+      path.to_path_buf()
+    }
+  };
+
+  if let Some(parent) = index_file.parent() {
+    std::fs::create_dir_all(parent).context("Failed to create download index directory")?;
+  }
+  let json = serde_json::to_string(&index).context("Failed to serialize download index")?;
+  std::fs::write(index_file, json).with_context(|| format!("Failed to write download index: {}", index_file.display()))?;
+
+  Ok(result)
+}
+
+/// Remove old downloads, keeping the newest `cleanup.keep_count` files.
+/// The currently applied wallpaper is never removed. Partial downloads left behind
+/// by a killed process are removed once nothing has written to them for
+/// `advanced.timeout` seconds. Returns the number of files deleted.
+pub fn cleanup_downloads(config: &Config) -> Result<usize> {
+  if !config.cleanup.auto_cleanup {
+    return Ok(0);
+  }
+
+  let dir = Path::new(&config.paths.downloads);
+  let current = crate::wallpaper::last_applied_wallpaper();
+  let stale = remove_stale_partials(dir, Duration::from_secs(config.advanced.timeout.into()));
+  Ok(stale + cleanup_dir(dir, config.cleanup.keep_count as usize, current.as_deref())?)
+}
+
+/// Delete `.part` files in `dir` that haven't been written to for `stale_after`
+fn remove_stale_partials(dir: &Path, stale_after: Duration) -> usize {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return 0;
+  };
+
+  let mut removed = 0;
+  for path in entries.flatten().map(|entry| entry.path()) {
+    if path.extension().is_none_or(|ext| ext != "part") {
+      continue;
+    }
+    let idle = std::fs::metadata(&path)
+      .and_then(|metadata| metadata.modified())
+      .ok()
+      .and_then(|modified| modified.elapsed().ok());
+    if idle.is_some_and(|idle| idle > stale_after) {
+      match std::fs::remove_file(&path) {
+        Ok(()) => {
+          debug!("Removed abandoned partial download: {}", path.display());
+          removed += 1;
+        }
+        Err(e) => debug!("Failed to remove {}: {}", path.display(), e),
+      }
+    }
+  }
+  removed
+}
+
+/// Delete all but the newest `keep_count` files in `dir`, skipping `protected`.
+/// Metadata sidecars don't count towards the limit and are removed with their image.
+fn cleanup_dir(dir: &Path, keep_count: usize, protected: Option<&Path>) -> Result<usize> {
+  if !dir.is_dir() {
+    return Ok(0);
+  }
+
+  let entries = std::fs::read_dir(dir).with_context(|| format!("Failed to read downloads directory: {}", dir.display()))?;
+
+  let mut files: Vec<(SystemTime, std::path::PathBuf)> = entries
+    .flatten()
+    .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+    .filter(|entry| !is_sidecar(&entry.path()))
+    // Another download may still be writing these
+    .filter(|entry| entry.path().extension().is_none_or(|ext| ext != "part"))
+    .map(|entry| {
+      let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+      (modified, entry.path())
+    })
+    .collect();
+
+  // Newest first
+  files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+  let protected = protected.map(|p| p.canonicalize().unwrap_or_else(|_| p.to_path_buf()));
+  let mut removed = 0;
+
+  for (_, path) in files.into_iter().skip(keep_count) {
+    if let Some(protected) = &protected
+      && path.canonicalize().unwrap_or_else(|_| path.clone()) == *protected
+    {
+      debug!("Keeping current wallpaper: {}", path.display());
+      continue;
+    }
+
+    std::fs::remove_file(&path).with_context(|| format!("Failed to remove old download: {}", path.display()))?;
+    let _ = std::fs::remove_file(FilesystemHelper::sidecar_path(&path));
+    debug!("Removed old download: {}", path.display());
+    removed += 1;
+  }
+
+  Ok(removed)
+}
+
+/// Whether `path` is a metadata sidecar (`<image>.<ext>.json`)
+fn is_sidecar(path: &Path) -> bool {
+  path.extension().is_some_and(|ext| ext == "json") && path.file_stem().is_some_and(|stem| Path::new(stem).extension().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs::File;
+  use std::time::Duration;
+  use tempfile::TempDir;
+
+  /// Create `count` files named `0.jpg`.. where a higher index means a newer file
+  fn create_files(dir: &Path, count: u64) {
+    for i in 0..count {
+      let file = File::create(dir.join(format!("{}.jpg", i))).unwrap();
+      file
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + i * 60))
+        .unwrap();
+    }
+  }
+
+  fn remaining(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+      .unwrap()
+      .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+      .collect();
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn test_cleanup_keeps_newest() {
+    let tmp = TempDir::new().unwrap();
+    create_files(tmp.path(), 5);
+
+    let removed = cleanup_dir(tmp.path(), 2, None).unwrap();
+
+    assert_eq!(removed, 3);
+    assert_eq!(remaining(tmp.path()), vec!["3.jpg", "4.jpg"]);
+  }
+
+  #[test]
+  fn test_cleanup_never_removes_current_wallpaper() {
+    let tmp = TempDir::new().unwrap();
+    create_files(tmp.path(), 5);
+
+    let current = tmp.path().join("0.jpg");
+    let removed = cleanup_dir(tmp.path(), 2, Some(&current)).unwrap();
+
+    assert_eq!(removed, 2);
+    assert_eq!(remaining(tmp.path()), vec!["0.jpg", "3.jpg", "4.jpg"]);
+  }
+
+  #[test]
+  fn test_cleanup_removes_sidecars_with_images() {
+    let tmp = TempDir::new().unwrap();
+    create_files(tmp.path(), 3);
+    for i in 0..3 {
+      std::fs::write(tmp.path().join(format!("{}.jpg.json", i)), "{}").unwrap();
+    }
+
+    let removed = cleanup_dir(tmp.path(), 2, None).unwrap();
+
+    assert_eq!(removed, 1);
+    assert_eq!(remaining(tmp.path()), vec!["1.jpg", "1.jpg.json", "2.jpg", "2.jpg.json"]);
+  }
+
+  #[test]
+  fn test_cleanup_disabled() {
+    let tmp = TempDir::new().unwrap();
+    create_files(tmp.path(), 5);
+
+    let mut config = Config::default();
+    config.paths.downloads = tmp.path().to_string_lossy().into_owned();
+    config.cleanup.keep_count = 1;
+    config.cleanup.auto_cleanup = false;
+
+    assert_eq!(cleanup_downloads(&config).unwrap(), 0);
+    assert_eq!(remaining(tmp.path()).len(), 5);
+  }
+
+  #[test]
+  fn test_url_extension() {
+    assert_eq!(FilesystemHelper::url_extension("https://w.wallhaven.cc/full/ab/wallhaven-abc.png"), "png");
+    assert_eq!(
+      FilesystemHelper::url_extension("https://images.pexels.com/photos/1/a.JPEG?auto=compress&w=2560"),
+      "jpeg"
+    );
+    assert_eq!(FilesystemHelper::url_extension("https://i.redd.it/x.webp#top"), "webp");
+    // No extension, or something that only looks like one
+    assert_eq!(FilesystemHelper::url_extension("https://images.unsplash.com/photo-123?fm=png"), "jpg");
+    assert_eq!(FilesystemHelper::url_extension("https://example.com"), "jpg");
+    assert_eq!(FilesystemHelper::url_extension("https://example.com/photo.1a2b3c4d5e"), "jpg");
+    assert_eq!(FilesystemHelper::url_extension("not a url/a.gif?x=1"), "gif");
+  }
+
+  #[tokio::test]
+  async fn test_save_image() {
+    let tmp = TempDir::new().unwrap();
+    let opts = DownloadOptions {
+      output_dir: Some(tmp.path().join("out")),
+      ..Default::default()
+    };
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::RgbImage::new(4, 4).write_to(&mut png, image::ImageFormat::Png).unwrap();
+
+    // The bytes win over a misleading URL
+    let path = FilesystemHelper::save_image("url", png.get_ref(), "https://example.com/random.php", &Config::default(), &opts)
+      .await
+      .unwrap();
+
+    assert_eq!(path.parent(), Some(tmp.path().join("out").as_path()));
+    assert!(path.file_name().unwrap().to_str().unwrap().starts_with("url_"));
+    assert_eq!(path.extension().unwrap(), "png");
+    assert_eq!(std::fs::read(&path).unwrap(), *png.get_ref());
+  }
+
+  #[tokio::test]
+  async fn test_save_image_rejects_non_images() {
+    let tmp = TempDir::new().unwrap();
+    let opts = DownloadOptions {
+      output_dir: Some(tmp.path().to_path_buf()),
+      ..Default::default()
+    };
+
+    let page = b"<!DOCTYPE html><html><body>Removed</body></html>";
+    let error = FilesystemHelper::save_image("reddit", page, "https://i.imgur.com/gone.jpg", &Config::default(), &opts)
+      .await
+      .unwrap_err();
+
+    let error = DownloaderError::from(error);
+    assert!(matches!(error, DownloaderError::NotAnImage(_)));
+    assert!(error.to_string().contains("HTML page"));
+    assert!(error.is_retryable());
+    assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 0);
+  }
+
+  #[test]
+  fn test_ensure_image() {
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::RgbImage::new(1, 1).write_to(&mut png, image::ImageFormat::Png).unwrap();
+    assert!(FilesystemHelper::ensure_image(&png.get_ref()[..SNIFF_LEN.min(png.get_ref().len())], "a.png").is_ok());
+
+    assert!(FilesystemHelper::ensure_image(b"", "a.jpg").is_err());
+    assert!(FilesystemHelper::ensure_image(b"{\"error\": \"not found\"}", "a.jpg").is_err());
+    assert!(FilesystemHelper::ensure_image(b"\x00\x01garbage", "a.jpg").is_err());
+  }
+
+  #[tokio::test]
+  async fn test_interrupted_write_leaves_no_file() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join("bing_1.jpg");
+
+    let result = FilesystemHelper::write_atomically(&path, |mut file| async move {
+      file.write_all(b"half an ima").await?;
+      Err(anyhow!("connection reset"))
+    })
+    .await;
+
+    assert!(result.is_err());
+    assert!(!path.exists());
+    assert!(!FilesystemHelper::partial_path(&path).exists());
+
+    // An existing file is only replaced by a complete write
+    std::fs::write(&path, b"old").unwrap();
+    let _ = FilesystemHelper::write_atomically(&path, |_| async { Err(anyhow!("killed")) }).await;
+    assert_eq!(std::fs::read(&path).unwrap(), b"old");
+  }
+
+  #[test]
+  fn test_cleanup_skips_partial_downloads() {
+    let tmp = TempDir::new().unwrap();
+    create_files(tmp.path(), 2);
+    std::fs::write(tmp.path().join("9.jpg.part"), b"").unwrap();
+
+    assert_eq!(cleanup_dir(tmp.path(), 1, None).unwrap(), 1);
+    assert!(tmp.path().join("9.jpg.part").exists());
+  }
+
+  #[test]
+  fn test_remove_stale_partials() {
+    let tmp = TempDir::new().unwrap();
+    create_files(tmp.path(), 1);
+    let abandoned = File::create(tmp.path().join("1.jpg.part")).unwrap();
+    abandoned.set_modified(SystemTime::now() - Duration::from_secs(120)).unwrap();
+    std::fs::write(tmp.path().join("2.jpg.part"), b"").unwrap();
+
+    assert_eq!(remove_stale_partials(tmp.path(), Duration::from_secs(30)), 1);
+    assert_eq!(remaining(tmp.path()), vec!["0.jpg", "2.jpg.part"]);
+  }
+
+  #[test]
+  fn test_progress_line() {
+    assert_eq!(
+      progress_line(2_000_000, Some(5_000_000)),
+      "Downloading [########------------]  40%  2.0/5.0 MB"
+    );
+    assert_eq!(
+      progress_line(6_000_000, Some(5_000_000)),
+      "Downloading [####################] 100%  6.0/5.0 MB"
+    );
+    assert_eq!(progress_line(1_500_000, None), "Downloading 1.5 MB");
+  }
+
+  #[test]
+  fn test_image_size() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join("small.png");
+    image::RgbImage::new(64, 32).save(&path).unwrap();
+
+    assert_eq!(FilesystemHelper::image_size(&path).unwrap(), (64, 32));
+    assert!(FilesystemHelper::image_size(&tmp.path().join("missing.png")).is_err());
+  }
+
+  #[test]
+  fn test_dedupe_reuses_existing_file() {
+    let tmp = TempDir::new().unwrap();
+    let index = tmp.path().join("index.json");
+    let first = tmp.path().join("bing_1.jpg");
+    let second = tmp.path().join("bing_2.jpg");
+    std::fs::write(&first, b"same").unwrap();
+    std::fs::write(&second, b"same").unwrap();
+
+    assert_eq!(dedupe_with_index(&index, &first, "abc").unwrap(), first);
+    assert_eq!(dedupe_with_index(&index, &second, "abc").unwrap(), first);
+    assert!(!second.exists());
+
+    // Different content is kept as a new file
+    std::fs::write(&second, b"other").unwrap();
+    assert_eq!(dedupe_with_index(&index, &second, "def").unwrap(), second);
+  }
+
+  #[test]
+  fn test_dedupe_forgets_deleted_files() {
+    let tmp = TempDir::new().unwrap();
+    let index = tmp.path().join("index.json");
+    let first = tmp.path().join("apod_1.jpg");
+    let second = tmp.path().join("apod_2.jpg");
+    std::fs::write(&first, b"same").unwrap();
+    dedupe_with_index(&index, &first, "abc").unwrap();
+
+    std::fs::remove_file(&first).unwrap();
+    std::fs::write(&second, b"same").unwrap();
+    assert_eq!(dedupe_with_index(&index, &second, "abc").unwrap(), second);
+    assert!(second.exists());
+  }
+
+  #[tokio::test]
+  async fn test_sidecar_round_trip() {
+    let tmp = TempDir::new().unwrap();
+    let image = tmp.path().join("unsplash_1.jpg");
+    std::fs::write(&image, b"image").unwrap();
+
+    let wallpaper = Wallpaper {
+      file_path: image.clone(),
+      source: "unsplash".to_string(),
+      downloaded_at: chrono::Utc::now(),
+      metadata: Some(Metadata {
+        title: Some("Misty forest".to_string()),
+        attribution: Some("Photo by Jane Doe on Unsplash".to_string()),
+        ..Default::default()
+      }),
+    };
+    FilesystemHelper::write_sidecar(&wallpaper).await.unwrap();
+
+    let sidecar = FilesystemHelper::sidecar_path(&image);
+    assert_eq!(sidecar, tmp.path().join("unsplash_1.jpg.json"));
+    assert!(is_sidecar(&sidecar));
+    assert!(!is_sidecar(&image));
+
+    let metadata = FilesystemHelper::read_sidecar(&image).unwrap();
+    assert_eq!(metadata.credit().as_deref(), Some("Misty forest — Photo by Jane Doe on Unsplash"));
   }
 }
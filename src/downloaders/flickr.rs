@@ -0,0 +1,256 @@
+//! Flickr photo downloader
+//!
+//! Searches Flickr by tag for large originals matching the display's orientation.
+//! Requires an API key (get one at https://www.flickr.com/services/apps/create/).
+
+use super::DownloadOptions;
+use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
+use super::filesystem::FilesystemHelper;
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
+use crate::config::Config;
+use crate::display::Orientation;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Deserializer};
+use tracing::debug;
+
+/// Flickr REST endpoint
+const FLICKR_API_URL: &str = "https://api.flickr.com/services/rest/";
+
+/// Tags searched when none are given (Flickr rejects searches without criteria)
+const DEFAULT_TAGS: &str = "wallpaper";
+
+/// Flickr API response envelope
+#[derive(Debug, Deserialize)]
+struct FlickrResponse {
+  /// "ok" or "fail"
+  stat: String,
+  /// Error message when `stat` is "fail"
+  message: Option<String>,
+  /// Error code when `stat` is "fail" (100 = invalid API key)
+  code: Option<u32>,
+  photos: Option<FlickrPhotos>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FlickrPhotos {
+  photo: Vec<FlickrPhoto>,
+}
+
+/// Individual search result
+#[derive(Debug, Deserialize)]
+struct FlickrPhoto {
+  id: String,
+  owner: String,
+  secret: String,
+  server: String,
+  farm: u32,
+  #[serde(default)]
+  title: String,
+  /// Photographer name (`extras=owner_name`)
+  #[serde(default)]
+  ownername: String,
+  /// Original image, only present when the owner allows downloads (`extras=url_o`)
+  url_o: Option<String>,
+  #[serde(default, deserialize_with = "number_or_string")]
+  width_o: Option<u32>,
+  #[serde(default, deserialize_with = "number_or_string")]
+  height_o: Option<u32>,
+}
+
+impl FlickrPhoto {
+  /// An original in the wanted orientation that is at least Full HD
+  fn is_large_original(&self, orientation: Orientation) -> bool {
+    match (&self.url_o, self.width_o, self.height_o) {
+      (Some(_), Some(width), Some(height)) => Orientation::of(width, height) == orientation && width.max(height) >= 1920,
+      _ => false,
+    }
+  }
+
+  /// The original if there is one, otherwise the 1024px size built from the photo's fields
+  fn image_url(&self) -> String {
+    self.url_o.clone().unwrap_or_else(|| {
+      format!(
+        "https://farm{}.staticflickr.com/{}/{}_{}_b.jpg",
+        self.farm, self.server, self.id, self.secret
+      )
+    })
+  }
+
+  fn page_url(&self) -> String {
+    format!("https://www.flickr.com/photos/{}/{}", self.owner, self.id)
+  }
+}
+
+/// Flickr sends some numbers as strings, depending on the endpoint
+fn number_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum NumberOrString {
+    Number(u32),
+    String(String),
+  }
+
+  Ok(match Option::<NumberOrString>::deserialize(deserializer)? {
+    Some(NumberOrString::Number(n)) => Some(n),
+    Some(NumberOrString::String(s)) => s.parse().ok(),
+    None => None,
+  })
+}
+
+/// Flickr downloader
+pub struct FlickrDownloader;
+
+#[async_trait]
+impl WallpaperDownloader for FlickrDownloader {
+  /// Download a photo from Flickr
+  /// Query parameters are used as search tags (e.g., "wallflow flickr mountains lake")
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let flickr_config = &config.sources.flickr;
+
+    let api_key = flickr_config.api_key.as_ref().ok_or_else(|| {
+      DownloaderError::MissingApiKey(
+        "Flickr requires an API key. Get one at https://www.flickr.com/services/apps/create/ and add it to config:\n\
+         sources:\n  flickr:\n    api_key: \"your-api-key\""
+          .to_string(),
+      )
+    })?;
+
+    if api_key.is_empty() {
+      return Err(DownloaderError::MissingApiKey("Flickr api_key is empty".to_string()));
+    }
+
+    let tags = if query.is_empty() { DEFAULT_TAGS.to_string() } else { query.join(",") };
+    debug!("Searching Flickr for tags '{}'", tags);
+
+    let client = Client::from(&config.advanced);
+    let orientation = opts.orientation(config);
+
+    let request = client.get(FLICKR_API_URL).query(&[
+      ("method", "flickr.photos.search"),
+      ("api_key", api_key.as_str()),
+      ("tags", tags.as_str()),
+      ("tag_mode", "all"),
+      ("extras", "url_o,owner_name"),
+      ("media", "photos"),
+      ("content_type", "1"),
+      ("sort", "interestingness-desc"),
+      ("per_page", "100"),
+      ("format", "json"),
+      ("nojsoncallback", "1"),
+    ]);
+
+    let response = client.send_with_retry(request).await.context("Failed to send request to Flickr API")?;
+
+    if !response.status().is_success() {
+      let status = response.status();
+      let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+
+      if status.as_u16() == 429 {
+        return Err(DownloaderError::RateLimited("Flickr rate limit exceeded. Try again later.".to_string()));
+      }
+
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Flickr API request failed with status {}: {}", status, error_text),
+      });
+    }
+
+    let search: FlickrResponse = response.json().await.context("Failed to parse Flickr API response")?;
+
+    // Flickr reports errors with a 200 status and stat "fail"
+    if search.stat != "ok" {
+      if search.code == Some(100) {
+        return Err(DownloaderError::InvalidApiKey(
+          "Flickr api_key is invalid. Check your configuration.".to_string(),
+        ));
+      }
+      return Err(DownloaderError::Other(anyhow!(
+        "Flickr API request failed: {}",
+        search.message.unwrap_or_else(|| "Unknown error".to_string())
+      )));
+    }
+
+    let photos = search.photos.map(|p| p.photo).unwrap_or_default();
+    if photos.is_empty() {
+      return Err(DownloaderError::NoResults(format!("No photos found on Flickr for tags '{}'", tags)));
+    }
+
+    // Filter to large originals in the display's orientation
+    let suitable_photos: Vec<&FlickrPhoto> = photos.iter().filter(|p| p.is_large_original(orientation)).collect();
+
+    let photos_to_use = if suitable_photos.is_empty() {
+      photos.iter().collect()
+    } else {
+      suitable_photos
+    };
+
+    let photo = photos_to_use
+      .choose(&mut rand::thread_rng())
+      .ok_or_else(|| DownloaderError::NoResults("Failed to select random Flickr photo".to_string()))?;
+
+    let image_url = photo.image_url();
+    debug!("Selected Flickr photo: {:?}x{:?}", photo.width_o, photo.height_o);
+    debug!("Image URL: {}", image_url);
+
+    // Originals keep their uploaded format
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &image_url, config, opts)
+      .await
+      .context("Failed to download Flickr image")?;
+
+    let attribution = if photo.ownername.is_empty() {
+      "Photo on Flickr".to_string()
+    } else {
+      format!("Photo by {} on Flickr", photo.ownername)
+    };
+
+    let wallpaper = Wallpaper {
+      file_path,
+      downloaded_at: Utc::now(),
+      source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url.clone()),
+        page_url: Some(photo.page_url()),
+        title: Some(photo.title.clone()).filter(|title| !title.is_empty()),
+        attribution: Some(attribution),
+        ..Default::default()
+      }),
+    };
+
+    debug!("Downloaded Flickr wallpaper: {:?}", wallpaper);
+    Ok(wallpaper)
+  }
+
+  fn source_name(&self) -> &'static str {
+    "flickr"
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_search_response() {
+    let json = r#"{"photos": {"page": 1, "photo": [
+      {"id": "5301", "owner": "12@N01", "secret": "ab12", "server": "65535", "farm": 66, "title": "Lake",
+       "ownername": "Ana", "url_o": "https://live.staticflickr.com/65535/5301_cd34_o.png", "width_o": 4000, "height_o": "2667"},
+      {"id": "5302", "owner": "12@N01", "secret": "ef56", "server": "65535", "farm": 66, "title": ""}
+    ]}, "stat": "ok"}"#;
+
+    let response: FlickrResponse = serde_json::from_str(json).unwrap();
+    let photos = response.photos.unwrap().photo;
+
+    assert!(photos[0].is_large_original(Orientation::Landscape));
+    assert!(!photos[0].is_large_original(Orientation::Portrait));
+    assert_eq!(photos[0].height_o, Some(2667));
+    assert_eq!(photos[0].image_url(), "https://live.staticflickr.com/65535/5301_cd34_o.png");
+
+    assert!(!photos[1].is_large_original(Orientation::Landscape));
+    assert_eq!(photos[1].image_url(), "https://farm66.staticflickr.com/65535/5302_ef56_b.jpg");
+    assert_eq!(photos[1].page_url(), "https://www.flickr.com/photos/12@N01/5302");
+  }
+}
@@ -4,17 +4,29 @@ pub mod apod;
 pub mod bing;
 pub mod client;
 pub mod earthview;
+pub mod error;
 pub mod filesystem;
+pub mod flickr;
+pub mod pexels;
 pub mod picsum;
+pub mod pixabay;
 pub mod reddit;
 pub mod registry;
 pub mod traits;
 pub mod unsplash;
+pub mod url;
 pub mod wallhaven;
+pub mod wikimedia;
 
 use crate::config::Config;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use error::DownloaderError;
+use filesystem::FilesystemHelper;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
 
 /// Options for downloading wallpapers
 #[derive(Debug, Clone, Default)]
@@ -23,15 +35,274 @@ pub struct DownloadOptions {
   pub output_dir: Option<PathBuf>,
   /// Don't set as wallpaper after download
   pub no_set: bool,
+  /// Pick and download the wallpaper, then only print which backend would set it
+  pub dry_run: bool,
+  /// Output the wallpaper is meant for (e.g. "DP-1"); sources that size their
+  /// images fetch them at this monitor's resolution
+  pub monitor: Option<String>,
+  /// Draw a progress bar on stderr while the image downloads (interactive CLI only)
+  pub show_progress: bool,
+  /// Orientation already worked out for this download, so the display isn't detected again
+  pub orientation: Option<crate::display::Orientation>,
+}
+
+impl DownloadOptions {
+  /// Orientation downloads should have (`sources.orientation`, see `Config::get_orientation`)
+  pub fn orientation(&self, config: &Config) -> crate::display::Orientation {
+    self.orientation.unwrap_or_else(|| config.get_orientation(self.monitor.as_deref()))
+  }
 }
 
 /// Download wallpaper from specified source by name
 /// The `query` parameter contains additional CLI arguments (e.g., search terms, subreddit names)
-pub async fn download_from_source(source: &str, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<traits::Wallpaper> {
-  let registry = registry::DownloaderRegistry::new();
-  let downloader = registry.get_downloader(source)?;
+pub async fn download_from_source(
+  source: &str,
+  config: &Config,
+  query: &[String],
+  opts: &DownloadOptions,
+) -> Result<traits::Wallpaper, DownloaderError> {
+  registry::DownloaderRegistry::new()
+    .download_from_source(source, config, query, opts)
+    .await
+}
+
+/// Run a downloader and apply the post-download checks shared by all sources.
+///
+/// With `cleanup.deduplicate` set, a download identical to an earlier one is replaced
+/// by the earlier file. With `cleanup.save_metadata` set, the source's attribution is
+/// written to a `<image>.json` sidecar.
+async fn download_checked(
+  downloader: &(dyn traits::WallpaperDownloader + Send + Sync),
+  config: &Config,
+  query: &[String],
+  opts: &DownloadOptions,
+) -> Result<traits::Wallpaper, DownloaderError> {
+  let mut wallpaper = download_suitable(downloader, config, query, opts).await?;
+
+  if config.cleanup.deduplicate {
+    match FilesystemHelper::deduplicate(&wallpaper.file_path).await {
+      Ok(path) => wallpaper.file_path = path,
+      Err(e) => warn!("Could not deduplicate {}: {}", wallpaper.file_path.display(), e),
+    }
+  }
+
+  if config.cleanup.save_metadata
+    && let Err(e) = FilesystemHelper::write_sidecar(&wallpaper).await
+  {
+    warn!("Could not save metadata for {}: {}", wallpaper.file_path.display(), e);
+  }
+
+  Ok(wallpaper)
+}
+
+/// Images smaller than `sources.min_resolution` or the wrong way round for the display
+/// (`sources.orientation`) are deleted and the source is asked again, up to
+/// `advanced.retry_attempts` more times. An image that is only the wrong way round is
+/// kept when nothing better turns up, so sources with one picture a day still work.
+async fn download_suitable(
+  downloader: &(dyn traits::WallpaperDownloader + Send + Sync),
+  config: &Config,
+  query: &[String],
+  opts: &DownloadOptions,
+) -> Result<traits::Wallpaper, DownloaderError> {
+  let min_resolution = config.get_min_resolution()?;
+  let orientation = opts.orientation(config);
+  let opts = &DownloadOptions {
+    orientation: Some(orientation),
+    ..opts.clone()
+  };
+
+  let source = downloader.source_name();
+  let mut attempt = 0;
+  let mut fallback: Option<traits::Wallpaper> = None;
+  let mut rejected = String::new();
+
+  loop {
+    let wallpaper = match downloader.download(config, query, opts).await {
+      Ok(wallpaper) => wallpaper,
+      Err(DownloaderError::NotAnImage(reason)) if attempt < config.advanced.retry_attempts => {
+        debug!("{}, trying {} again", reason, source);
+        rejected = reason;
+        attempt += 1;
+        continue;
+      }
+      Err(e) => return Err(e),
+    };
+
+    match FilesystemHelper::image_size(&wallpaper.file_path) {
+      Ok((width, height)) => {
+        let big_enough = min_resolution.as_ref().is_none_or(|min| width >= min.width && height >= min.height);
+
+        if big_enough && orientation.accepts(width, height) {
+          if let Some(previous) = fallback {
+            let _ = tokio::fs::remove_file(&previous.file_path).await;
+          }
+          return Ok(wallpaper);
+        }
+
+        if big_enough {
+          debug!(
+            "{} download is {}x{}, not {}: {}",
+            source,
+            width,
+            height,
+            orientation.as_str(),
+            wallpaper.file_path.display()
+          );
+
+          // Asking again won't help if the source handed back the same image
+          let url = |wallpaper: &traits::Wallpaper| wallpaper.metadata.as_ref().and_then(|metadata| metadata.url.clone());
+          if let Some(previous) = &fallback
+            && url(previous).is_some()
+            && url(previous) == url(&wallpaper)
+          {
+            let _ = tokio::fs::remove_file(&wallpaper.file_path).await;
+            break;
+          }
+
+          if let Some(previous) = fallback.replace(wallpaper) {
+            let _ = tokio::fs::remove_file(&previous.file_path).await;
+          }
+        } else if let Some(min_resolution) = &min_resolution {
+          debug!(
+            "Discarding {} download below {}: {}",
+            source,
+            min_resolution.as_string(),
+            wallpaper.file_path.display()
+          );
+          rejected = format!("{}x{} is below the minimum resolution of {}", width, height, min_resolution.as_string());
+          let _ = tokio::fs::remove_file(&wallpaper.file_path).await;
+        }
+      }
+      // Without a minimum size there is nothing to enforce on an image we can't measure
+      Err(_) if min_resolution.is_none() => return Ok(wallpaper),
+      Err(e) => {
+        debug!("Discarding unreadable {} download: {}", source, e);
+        rejected = e.to_string();
+        let _ = tokio::fs::remove_file(&wallpaper.file_path).await;
+      }
+    }
+
+    if attempt >= config.advanced.retry_attempts {
+      break;
+    }
+    attempt += 1;
+  }
+
+  if let Some(wallpaper) = fallback {
+    warn!(
+      "No {} image from {} suited the display, using {}",
+      orientation.as_str(),
+      source,
+      wallpaper.file_path.display()
+    );
+    return Ok(wallpaper);
+  }
+
+  Err(DownloaderError::NoResults(format!(
+    "No suitable image from {} after {} attempts (last one: {})",
+    source,
+    attempt + 1,
+    rejected
+  )))
+}
+
+/// Download up to `count` wallpapers from a source concurrently
+///
+/// At most `advanced.parallel_downloads` downloads run at once. Identical images
+/// (same file contents) are only kept once. Failed downloads are logged and skipped,
+/// so the result may contain fewer than `count` wallpapers; an error is only
+/// returned when nothing could be downloaded.
+#[allow(dead_code)]
+pub async fn download_batch(source: &str, config: &Config, query: &[String], opts: &DownloadOptions, count: usize) -> Result<Vec<traits::Wallpaper>> {
+  registry::DownloaderRegistry::new()
+    .download_batch(source, config, query, opts, count)
+    .await
+}
+
+async fn download_batch_with(
+  downloader: Arc<dyn traits::WallpaperDownloader + Send + Sync>,
+  config: &Config,
+  query: &[String],
+  opts: &DownloadOptions,
+  count: usize,
+) -> Result<Vec<traits::Wallpaper>> {
+  let source = downloader.source_name();
+  let concurrency = (config.advanced.parallel_downloads as usize).max(1);
+  let config = Arc::new(config.clone());
+  let query: Arc<[String]> = Arc::from(query);
+  // Several bars redrawing the same terminal line would only garble each other
+  let opts = Arc::new(DownloadOptions {
+    show_progress: false,
+    orientation: Some(opts.orientation(&config)),
+    ..opts.clone()
+  });
+
+  debug!("Downloading batch of {} from {} ({} at a time)", count, source, concurrency);
+
+  let mut tasks = JoinSet::new();
+  let mut launched = 0;
+  let mut failures = 0;
+  let mut seen_hashes = HashMap::new();
+  let mut wallpapers = Vec::new();
+
+  loop {
+    // Keep the pool topped up to the concurrency limit
+    while launched < count && tasks.len() < concurrency {
+      let downloader = downloader.clone();
+      let config = config.clone();
+      let query = query.clone();
+      let opts = opts.clone();
+      tasks.spawn(async move { download_checked(downloader.as_ref(), &config, &query, &opts).await });
+      launched += 1;
+    }
+
+    let Some(joined) = tasks.join_next().await else {
+      break;
+    };
+
+    let wallpaper = match joined {
+      Ok(Ok(wallpaper)) => wallpaper,
+      Ok(Err(e)) => {
+        warn!("Batch download from {} failed: {}", source, e);
+        failures += 1;
+        continue;
+      }
+      Err(e) => {
+        warn!("Batch download task for {} did not complete: {}", source, e);
+        failures += 1;
+        continue;
+      }
+    };
+
+    match FilesystemHelper::content_hash(&wallpaper.file_path).await {
+      Ok(hash) if seen_hashes.contains_key(&hash) => {
+        debug!("Discarding duplicate download: {}", wallpaper.file_path.display());
+        // With deduplication on, the duplicate may already point at the kept file
+        if seen_hashes[&hash] != wallpaper.file_path {
+          let _ = tokio::fs::remove_file(&wallpaper.file_path).await;
+        }
+      }
+      Ok(hash) => {
+        seen_hashes.insert(hash, wallpaper.file_path.clone());
+        wallpapers.push(wallpaper);
+      }
+      Err(e) => {
+        debug!("Could not hash {}: {}", wallpaper.file_path.display(), e);
+        wallpapers.push(wallpaper);
+      }
+    }
+  }
+
+  if wallpapers.is_empty() && failures > 0 {
+    return Err(anyhow!("All {} downloads from {} failed", failures, source));
+  }
+
+  if failures > 0 {
+    warn!("{} of {} downloads from {} failed", failures, count, source);
+  }
 
-  downloader.download(config, query, opts).await
+  Ok(wallpapers)
 }
 
 /// List all available downloader sources
@@ -0,0 +1,156 @@
+//! Pexels free stock photo downloader
+//!
+//! Searches Pexels.com for landscape photos.
+//! Requires an API key (get one at https://www.pexels.com/api/).
+
+use super::DownloadOptions;
+use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
+use super::filesystem::FilesystemHelper;
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
+use crate::config::Config;
+use crate::display::Orientation;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use tracing::debug;
+
+/// Pexels photo search endpoint
+const PEXELS_API_URL: &str = "https://api.pexels.com/v1/search";
+
+/// Search used when no query is given (the search endpoint requires one)
+const DEFAULT_QUERY: &str = "wallpaper";
+
+/// Pexels search response
+#[derive(Debug, Deserialize)]
+struct PexelsSearchResponse {
+  photos: Vec<PexelsPhoto>,
+}
+
+/// Pexels photo
+#[derive(Debug, Deserialize)]
+struct PexelsPhoto {
+  /// Photo ID
+  #[allow(dead_code)]
+  id: u64,
+  /// Image dimensions
+  width: u32,
+  height: u32,
+  /// URLs for different sizes
+  src: PexelsSources,
+  /// Photo page on pexels.com
+  url: String,
+  /// Photographer name
+  photographer: String,
+  /// Photo description
+  #[serde(default)]
+  alt: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PexelsSources {
+  /// Original upload at full resolution
+  original: String,
+}
+
+/// Pexels downloader
+pub struct PexelsDownloader;
+
+#[async_trait]
+impl WallpaperDownloader for PexelsDownloader {
+  /// Download a photo from Pexels
+  /// Query parameters are used as search terms (e.g., "wallflow pexels forest")
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let pexels_config = &config.sources.pexels;
+
+    let api_key = pexels_config.api_key.as_ref().ok_or_else(|| {
+      DownloaderError::MissingApiKey(
+        "Pexels requires an API key. Get one at https://www.pexels.com/api/ and add it to config:\n\
+         sources:\n  pexels:\n    api_key: \"your-api-key\""
+          .to_string(),
+      )
+    })?;
+
+    if api_key.is_empty() {
+      return Err(DownloaderError::MissingApiKey("Pexels api_key is empty".to_string()));
+    }
+
+    let search_query = query.join(" ");
+    let search_query = if search_query.is_empty() { DEFAULT_QUERY } else { search_query.as_str() };
+
+    debug!("Searching Pexels for '{}'", search_query);
+
+    let client = Client::from(&config.advanced);
+    let orientation = opts.orientation(config);
+
+    let request = client.get(PEXELS_API_URL).header("Authorization", api_key.as_str()).query(&[
+      ("query", search_query),
+      ("orientation", orientation.as_str()),
+      ("per_page", "40"),
+    ]);
+
+    let response = client.send_with_retry(request).await.context("Failed to send request to Pexels API")?;
+
+    if !response.status().is_success() {
+      let status = response.status();
+      let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+
+      if status.as_u16() == 401 {
+        return Err(DownloaderError::InvalidApiKey(
+          "Pexels api_key is invalid. Check your configuration.".to_string(),
+        ));
+      }
+      if status.as_u16() == 429 {
+        return Err(DownloaderError::RateLimited("Pexels rate limit exceeded. Try again later.".to_string()));
+      }
+
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Pexels API request failed with status {}: {}", status, error_text),
+      });
+    }
+
+    let search: PexelsSearchResponse = response.json().await.context("Failed to parse Pexels API response")?;
+
+    // Images of the right orientation with adequate resolution only
+    let suitable_photos: Vec<&PexelsPhoto> = search
+      .photos
+      .iter()
+      .filter(|p| Orientation::of(p.width, p.height) == orientation && p.width.max(p.height) >= 1920)
+      .collect();
+
+    let photo = suitable_photos
+      .choose(&mut rand::thread_rng())
+      .ok_or_else(|| DownloaderError::NoResults(format!("No suitable photos found on Pexels for '{}'", search_query)))?;
+
+    debug!("Selected Pexels photo: {}x{}", photo.width, photo.height);
+    debug!("Image URL: {}", photo.src.original);
+
+    // Originals keep their upload format (usually jpeg, sometimes png)
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &photo.src.original, config, opts)
+      .await
+      .context("Failed to download Pexels image")?;
+
+    let wallpaper = Wallpaper {
+      file_path,
+      downloaded_at: Utc::now(),
+      source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(photo.src.original.clone()),
+        page_url: Some(photo.url.clone()),
+        title: photo.alt.clone().filter(|alt| !alt.is_empty()),
+        attribution: Some(format!("Photo by {} on Pexels", photo.photographer)),
+        ..Default::default()
+      }),
+    };
+
+    debug!("Downloaded Pexels wallpaper: {:?}", wallpaper);
+    Ok(wallpaper)
+  }
+
+  fn source_name(&self) -> &'static str {
+    "pexels"
+  }
+}
@@ -5,13 +5,14 @@
 
 use super::DownloadOptions;
 use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
 use super::filesystem::FilesystemHelper;
-use super::traits::{Wallpaper, WallpaperDownloader};
-use crate::config::Config;
-use anyhow::{Context, Result, anyhow};
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
+use crate::config::{Config, PicsumConfig};
+use crate::display::Resolution;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
-use chrono::Utc;
-use std::path::Path;
+use chrono::{NaiveDate, Utc};
 use tracing::debug;
 
 /// Picsum random photo downloader
@@ -20,37 +21,43 @@ pub struct PicsumDownloader;
 #[async_trait]
 impl WallpaperDownloader for PicsumDownloader {
   /// Download a random image from Picsum
-  /// Note: Picsum ignores query parameters as it always returns a random image
-  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper> {
-    let resolution = config.get_picsum_resolution()?;
-    let url = format!("https://picsum.photos/{}/{}", resolution.width, resolution.height);
+  /// Note: Picsum ignores query parameters; the image is random unless `sources.picsum.seed` is set
+  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let resolution = config.get_picsum_resolution(opts.monitor.as_deref())?;
+    let url = picsum_url(&resolution, &config.sources.picsum, chrono::Local::now().date_naive());
 
     debug!("Fetching random image from Picsum: {}", url);
 
     let client = Client::from(&config.advanced);
-    let response = client.get(&url).send().await.context("Failed to send request to Picsum")?;
+    let response = client
+      .send_with_retry(client.get(&url))
+      .await
+      .context("Failed to send request to Picsum")?;
 
     if !response.status().is_success() {
-      return Err(anyhow!("Picsum request failed with status: {}", response.status()));
+      return Err(DownloaderError::Http {
+        status: response.status().as_u16(),
+        message: format!("Picsum request failed with status: {}", response.status()),
+      });
     }
 
-    let bytes = response.bytes().await.context("Failed to read image data from Picsum")?;
+    // Picsum redirects to the actual photo, which identifies it
+    let image_url = response.url().to_string();
 
-    let filename = format!("{}_{}", self.source_name(), FilesystemHelper::make_file_suffix());
-    let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
-    let file_path = download_dir.join(&filename).with_extension("jpg");
+    let file_path = FilesystemHelper::download_path(self.source_name(), &FilesystemHelper::url_extension(&image_url), config, opts);
 
-    // Ensure download directory exists
-    if let Some(parent) = file_path.parent() {
-      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
-    }
-
-    tokio::fs::write(&file_path, &bytes).await.context("Failed to save Picsum image")?;
+    FilesystemHelper::save_response(response, &image_url, &file_path, opts.show_progress)
+      .await
+      .context("Failed to download Picsum image")?;
 
     let wallpaper = Wallpaper {
       file_path,
       downloaded_at: Utc::now(),
       source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url),
+        ..Default::default()
+      }),
     };
 
     debug!("Downloaded Picsum wallpaper: {:?}", wallpaper);
@@ -61,3 +68,81 @@ impl WallpaperDownloader for PicsumDownloader {
     "picsum"
   }
 }
+
+/// Picsum URL for a photo of `resolution` with the configured effects: a random one,
+/// or the one for `sources.picsum.seed` (with `{date}` filled in from `today`)
+fn picsum_url(resolution: &Resolution, picsum: &PicsumConfig, today: NaiveDate) -> String {
+  let mut url = reqwest::Url::parse("https://picsum.photos").expect("valid base URL");
+  {
+    let mut path = url.path_segments_mut().expect("base URL has a path");
+    path.clear();
+    if let Some(seed) = picsum.seed.as_deref().map(str::trim).filter(|seed| !seed.is_empty()) {
+      path.extend(["seed", &seed.replace("{date}", &today.format("%Y-%m-%d").to_string())]);
+    }
+    path.extend([resolution.width.to_string(), resolution.height.to_string()]);
+  }
+
+  let mut effects = Vec::new();
+  if picsum.grayscale {
+    effects.push("grayscale".to_string());
+  }
+  if let Some(blur) = picsum.blur {
+    effects.push(format!("blur={}", blur.clamp(1, 10)));
+  }
+
+  if !effects.is_empty() {
+    url.set_query(Some(&effects.join("&")));
+  }
+  url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_picsum_url() {
+    let resolution = Resolution::new(2560, 1440);
+    let today = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+    let mut picsum = PicsumConfig::default();
+    assert_eq!(picsum_url(&resolution, &picsum, today), "https://picsum.photos/2560/1440");
+
+    picsum.grayscale = true;
+    assert_eq!(picsum_url(&resolution, &picsum, today), "https://picsum.photos/2560/1440?grayscale");
+
+    picsum.blur = Some(3);
+    assert_eq!(
+      picsum_url(&resolution, &picsum, today),
+      "https://picsum.photos/2560/1440?grayscale&blur=3"
+    );
+
+    picsum.grayscale = false;
+    picsum.blur = Some(40);
+    assert_eq!(picsum_url(&resolution, &picsum, today), "https://picsum.photos/2560/1440?blur=10");
+  }
+
+  #[test]
+  fn test_picsum_url_seed() {
+    let resolution = Resolution::new(1920, 1080);
+    let today = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+    let mut picsum = PicsumConfig {
+      seed: Some("mountains".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(picsum_url(&resolution, &picsum, today), "https://picsum.photos/seed/mountains/1920/1080");
+
+    picsum.seed = Some("daily-{date}".to_string());
+    picsum.grayscale = true;
+    assert_eq!(
+      picsum_url(&resolution, &picsum, today),
+      "https://picsum.photos/seed/daily-2025-01-31/1920/1080?grayscale"
+    );
+
+    // Anything that isn't allowed in a path is escaped, blank seeds are ignored
+    picsum.seed = Some("a/b c".to_string());
+    picsum.grayscale = false;
+    assert_eq!(picsum_url(&resolution, &picsum, today), "https://picsum.photos/seed/a%2Fb%20c/1920/1080");
+    picsum.seed = Some("  ".to_string());
+    assert_eq!(picsum_url(&resolution, &picsum, today), "https://picsum.photos/1920/1080");
+  }
+}
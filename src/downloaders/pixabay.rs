@@ -0,0 +1,174 @@
+//! Pixabay free image downloader
+//!
+//! Searches Pixabay.com for horizontal photos.
+//! Requires an API key (get one at https://pixabay.com/api/docs/).
+
+use super::DownloadOptions;
+use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
+use super::filesystem::FilesystemHelper;
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
+use crate::config::Config;
+use crate::display::Orientation;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use tracing::debug;
+
+/// Pixabay image search endpoint
+const PIXABAY_API_URL: &str = "https://pixabay.com/api/";
+
+/// Pixabay search response
+#[derive(Debug, Deserialize)]
+struct PixabayResponse {
+  hits: Vec<PixabayHit>,
+}
+
+/// Individual search result
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PixabayHit {
+  /// Image ID
+  #[allow(dead_code)]
+  id: u64,
+  /// Scaled image with a maximum width/height of 1280px
+  #[serde(rename = "largeImageURL")]
+  large_image_url: String,
+  /// Full image dimensions
+  image_width: u32,
+  image_height: u32,
+  /// Image page on pixabay.com
+  #[serde(rename = "pageURL")]
+  page_url: String,
+  /// Comma-separated tags
+  #[serde(default)]
+  tags: String,
+  /// Uploader name
+  user: String,
+}
+
+/// Pixabay downloader
+pub struct PixabayDownloader;
+
+#[async_trait]
+impl WallpaperDownloader for PixabayDownloader {
+  /// Download an image from Pixabay
+  /// Query parameters are used as search terms (e.g., "wallflow pixabay mountains")
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let pixabay_config = &config.sources.pixabay;
+
+    let key = pixabay_config.key.as_ref().filter(|key| !key.is_empty()).ok_or_else(|| {
+      DownloaderError::MissingApiKey(
+        "Pixabay requires an API key. Sign up and find your key at https://pixabay.com/api/docs/, then add it to config:\n\
+         sources:\n  pixabay:\n    key: \"your-api-key\""
+          .to_string(),
+      )
+    })?;
+
+    let search_query = query.join(" ");
+    debug!("Searching Pixabay for '{}'", search_query);
+
+    let client = Client::from(&config.advanced);
+    let orientation = match opts.orientation(config) {
+      Orientation::Landscape => "horizontal",
+      Orientation::Portrait => "vertical",
+    };
+
+    let request = client.get(PIXABAY_API_URL).query(&[
+      ("key", key.as_str()),
+      ("q", search_query.as_str()),
+      ("image_type", "photo"),
+      ("orientation", orientation),
+      ("min_width", "1920"),
+    ]);
+
+    let response = client.send_with_retry(request).await.context("Failed to send request to Pixabay API")?;
+
+    if !response.status().is_success() {
+      let status = response.status();
+      let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+
+      // Pixabay reports a bad key as 400 "[ERROR 400] Invalid or missing API key"
+      if status.as_u16() == 401 || error_text.contains("API key") {
+        return Err(DownloaderError::InvalidApiKey(
+          "Pixabay key is invalid. Check your configuration.".to_string(),
+        ));
+      }
+      if status.as_u16() == 429 {
+        return Err(DownloaderError::RateLimited("Pixabay rate limit exceeded. Try again later.".to_string()));
+      }
+
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Pixabay API request failed with status {}: {}", status, error_text),
+      });
+    }
+
+    let search: PixabayResponse = response.json().await.context("Failed to parse Pixabay API response")?;
+
+    let hit = search
+      .hits
+      .choose(&mut rand::thread_rng())
+      .ok_or_else(|| DownloaderError::NoResults(format!("No images found on Pixabay for '{}'", search_query)))?;
+
+    debug!("Selected Pixabay image: {}x{}", hit.image_width, hit.image_height);
+    debug!("Image URL: {}", hit.large_image_url);
+
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &hit.large_image_url, config, opts)
+      .await
+      .context("Failed to download Pixabay image")?;
+
+    let wallpaper = Wallpaper {
+      file_path,
+      downloaded_at: Utc::now(),
+      source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(hit.large_image_url.clone()),
+        page_url: Some(hit.page_url.clone()),
+        title: Some(hit.tags.clone()).filter(|tags| !tags.is_empty()),
+        attribution: Some(format!("Image by {} on Pixabay", hit.user)),
+        ..Default::default()
+      }),
+    };
+
+    debug!("Downloaded Pixabay wallpaper: {:?}", wallpaper);
+    Ok(wallpaper)
+  }
+
+  fn source_name(&self) -> &'static str {
+    "pixabay"
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_search_response() {
+    // Trimmed from a real response of https://pixabay.com/api/?q=yellow+flowers&image_type=photo
+    let json = r#"{"total": 4692, "totalHits": 500, "hits": [{
+      "id": 195893,
+      "pageURL": "https://pixabay.com/en/blossom-bloom-flower-195893/",
+      "type": "photo",
+      "tags": "blossom, bloom, flower",
+      "previewURL": "https://cdn.pixabay.com/photo/2013/10/15/09/12/flower-195893_150.jpg",
+      "previewWidth": 150, "previewHeight": 84,
+      "webformatURL": "https://pixabay.com/get/35bbf209e13e39d2_640.jpg",
+      "webformatWidth": 640, "webformatHeight": 360,
+      "largeImageURL": "https://pixabay.com/get/ed6a99fd0a76647_1280.jpg",
+      "imageWidth": 4000, "imageHeight": 2250, "imageSize": 4731420,
+      "views": 7671, "downloads": 6439, "likes": 5, "comments": 2,
+      "user_id": 48777, "user": "Josch13",
+      "userImageURL": "https://cdn.pixabay.com/user/2013/11/05/02-10-23-764_250x250.jpg"
+    }]}"#;
+
+    let response: PixabayResponse = serde_json::from_str(json).unwrap();
+    let hit = &response.hits[0];
+
+    assert_eq!(hit.large_image_url, "https://pixabay.com/get/ed6a99fd0a76647_1280.jpg");
+    assert_eq!((hit.image_width, hit.image_height), (4000, 2250));
+  }
+}
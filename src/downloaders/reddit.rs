@@ -5,20 +5,32 @@
 
 use super::DownloadOptions;
 use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
 use super::filesystem::FilesystemHelper;
-use super::traits::{Wallpaper, WallpaperDownloader};
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
 use crate::config::Config;
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
 use rand::seq::SliceRandom;
 use serde::Deserialize;
-use std::path::Path;
 use tracing::debug;
 
 /// Default subreddit if none specified
 const DEFAULT_SUBREDDIT: &str = "wallpapers";
 
+/// Listings that can be fetched for a subreddit
+const SORTS: &[&str] = &["hot", "new", "top", "rising", "controversial"];
+
+/// Time windows accepted by the top and controversial listings
+const TIME_FILTERS: &[&str] = &["hour", "day", "week", "month", "year", "all"];
+
+/// Imgur album endpoint that works without an API client ID
+const IMGUR_ALBUM_URL: &str = "https://imgur.com/ajaxalbums/getimages";
+
+/// How many posts to try before giving up when links don't resolve to an image
+const MAX_RESOLVE_ATTEMPTS: usize = 5;
+
 /// Reddit API response structure
 #[derive(Debug, Deserialize)]
 struct RedditResponse {
@@ -43,33 +55,137 @@ struct RedditPost {
   /// Post permalink for source attribution
   permalink: String,
   /// Title of the post
-  #[allow(dead_code)]
   title: String,
   /// Whether the post is marked as NSFW
   over_18: bool,
 }
 
+/// Imgur album listing
+#[derive(Debug, Deserialize)]
+struct ImgurAlbumResponse {
+  data: ImgurAlbumData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImgurAlbumData {
+  #[serde(default)]
+  images: Vec<ImgurImage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImgurImage {
+  /// Image ID
+  hash: String,
+  /// Extension including the dot (e.g. ".jpg")
+  ext: String,
+}
+
+/// Where a post's link points once imgur URLs are normalized
+#[derive(Debug, PartialEq, Eq)]
+enum ImageLink {
+  /// A direct image URL
+  Direct(String),
+  /// An imgur album or gallery that needs to be expanded
+  ImgurAlbum(String),
+  /// Anything else (videos, reddit galleries, web pages)
+  Unsupported,
+}
+
 /// Reddit wallpaper downloader
 pub struct RedditDownloader;
 
 impl RedditDownloader {
+  /// Build the listing URL, e.g. `/r/wallpapers/top.json?limit=100&t=week`
+  fn listing_url(subreddit: &str, sort: Option<&str>, time: Option<&str>) -> Result<String> {
+    let sort = sort.unwrap_or("hot");
+    if !SORTS.contains(&sort) {
+      return Err(anyhow!("Invalid Reddit sort '{}'. Valid options: {}", sort, SORTS.join(", ")));
+    }
+
+    let mut url = format!("https://www.reddit.com/r/{}/{}.json?limit=100", subreddit, sort);
+
+    // Only top and controversial take a time window
+    if let Some(time) = time
+      && matches!(sort, "top" | "controversial")
+    {
+      if !TIME_FILTERS.contains(&time) {
+        return Err(anyhow!(
+          "Invalid Reddit time filter '{}'. Valid options: {}",
+          time,
+          TIME_FILTERS.join(", ")
+        ));
+      }
+      url.push_str("&t=");
+      url.push_str(time);
+    }
+
+    Ok(url)
+  }
+
   /// Check if URL points to a supported image format
   fn is_image_url(url: &str) -> bool {
     let lower = url.to_lowercase();
     lower.ends_with(".jpg") || lower.ends_with(".jpeg") || lower.ends_with(".png") || lower.ends_with(".webp")
   }
 
-  /// Convert imgur.com URLs to direct image URLs
-  fn normalize_imgur_url(url: &str) -> String {
-    // Convert imgur.com/abc123 to i.imgur.com/abc123.jpg
-    if url.starts_with("http://imgur.com/") || url.starts_with("https://imgur.com/") {
-      let normalized = url.replace("://imgur.com/", "://i.imgur.com/");
-      if !Self::is_image_url(&normalized) {
-        return format!("{}.jpg", normalized);
+  /// Classify a post URL, converting imgur.com links to direct image URLs
+  fn normalize_imgur_url(url: &str) -> ImageLink {
+    let imgur_path = ["://imgur.com/", "://www.imgur.com/", "://m.imgur.com/"]
+      .iter()
+      .find_map(|host| url.split_once(host).map(|(_, path)| path));
+
+    let url = match imgur_path {
+      Some(path) => {
+        let path = path.split(['?', '#']).next().unwrap_or(path).trim_end_matches('/');
+
+        // imgur.com/a/abc123 and imgur.com/gallery/abc123 are albums
+        let mut segments = path.split('/');
+        if let Some("a" | "gallery") = segments.next() {
+          return match segments.next() {
+            Some(id) if !id.is_empty() => ImageLink::ImgurAlbum(id.to_string()),
+            _ => ImageLink::Unsupported,
+          };
+        }
+
+        // imgur.com/abc123 -> i.imgur.com/abc123.jpg, keeping any existing extension
+        if path.contains('.') {
+          format!("https://i.imgur.com/{}", path)
+        } else {
+          format!("https://i.imgur.com/{}.jpg", path)
+        }
       }
-      return normalized;
+      None => url.to_string(),
+    };
+
+    if Self::is_image_url(&url) {
+      ImageLink::Direct(url)
+    } else {
+      ImageLink::Unsupported
     }
-    url.to_string()
+  }
+
+  /// Pick a random static image from an imgur album, if it has any
+  async fn resolve_imgur_album(client: &Client, album_id: &str) -> Option<String> {
+    let album_url = format!("{}/{}/hit.json", IMGUR_ALBUM_URL, album_id);
+    debug!("Expanding imgur album: {}", album_url);
+
+    let response = client.send_with_retry(client.get(&album_url)).await.ok()?;
+    if !response.status().is_success() {
+      debug!("Imgur album {} returned {}", album_id, response.status());
+      return None;
+    }
+
+    // Missing albums come back with `"data": []`, which fails to parse and is skipped
+    let album: ImgurAlbumResponse = response.json().await.ok()?;
+    let images: Vec<String> = album
+      .data
+      .images
+      .iter()
+      .map(|image| format!("https://i.imgur.com/{}{}", image.hash, image.ext))
+      .filter(|url| Self::is_image_url(url))
+      .collect();
+
+    images.choose(&mut rand::thread_rng()).cloned()
   }
 }
 
@@ -77,87 +193,84 @@ impl RedditDownloader {
 impl WallpaperDownloader for RedditDownloader {
   /// Download a wallpaper from Reddit
   /// Query parameters specify subreddit(s) (e.g., "wallflow reddit earthporn" or "wallflow reddit wallpapers+earthporn")
-  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper> {
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
     // Use first query param as subreddit, or default
     let subreddit = query.first().map(|s| s.as_str()).unwrap_or(DEFAULT_SUBREDDIT);
 
     // Build Reddit JSON URL
-    let reddit_url = format!("https://www.reddit.com/r/{}.json?limit=100", subreddit);
+    let reddit_config = &config.sources.reddit;
+    let reddit_url = Self::listing_url(subreddit, reddit_config.sort.as_deref(), reddit_config.time.as_deref())?;
     debug!("Fetching from Reddit: {}", reddit_url);
 
     let client = Client::from(&config.advanced);
-    let response = client.get(&reddit_url).send().await.context("Failed to send request to Reddit")?;
+    let response = client
+      .send_with_retry(client.get(&reddit_url))
+      .await
+      .context("Failed to send request to Reddit")?;
 
     if !response.status().is_success() {
       let status = response.status();
       let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-      return Err(anyhow!("Reddit API request failed with status {}: {}", status, error_text));
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Reddit API request failed with status {}: {}", status, error_text),
+      });
     }
 
     let reddit_data: RedditResponse = response.json().await.context("Failed to parse Reddit API response")?;
 
     if reddit_data.data.children.is_empty() {
-      return Err(anyhow!("No posts found in r/{}", subreddit));
+      return Err(DownloaderError::NoResults(format!("No posts found in r/{}", subreddit)));
     }
 
-    // Filter to image posts only, excluding NSFW
-    let image_posts: Vec<&RedditPost> = reddit_data
+    // Filter to image and album posts only, excluding NSFW unless allowed
+    let mut image_posts: Vec<(&RedditPost, ImageLink)> = reddit_data
       .data
       .children
       .iter()
       .map(|child| &child.data)
-      .filter(|post| {
-        let url = Self::normalize_imgur_url(&post.url);
-        Self::is_image_url(&url) && !post.over_18
-      })
+      .filter(|post| reddit_config.allow_nsfw || !post.over_18)
+      .map(|post| (post, Self::normalize_imgur_url(&post.url)))
+      .filter(|(_, link)| *link != ImageLink::Unsupported)
       .collect();
 
     if image_posts.is_empty() {
-      return Err(anyhow!("No suitable images found in r/{}", subreddit));
+      return Err(DownloaderError::NoResults(format!("No suitable images found in r/{}", subreddit)));
     }
 
-    // Pick a random image
-    let post = image_posts
-      .choose(&mut rand::thread_rng())
-      .ok_or_else(|| anyhow!("Failed to select random Reddit image"))?;
-
-    let image_url = Self::normalize_imgur_url(&post.url);
-    debug!("Selected Reddit image: {}", image_url);
-
-    // Download the image
-    let image_response = client.get(&image_url).send().await.context("Failed to download Reddit image")?;
-
-    if !image_response.status().is_success() {
-      return Err(anyhow!("Reddit image download failed with status: {}", image_response.status()));
+    // Try random posts until one resolves to an image (albums may be empty or video-only)
+    image_posts.shuffle(&mut rand::thread_rng());
+    let mut selected = None;
+    for (post, link) in image_posts.into_iter().take(MAX_RESOLVE_ATTEMPTS) {
+      let image_url = match link {
+        ImageLink::Direct(url) => Some(url),
+        ImageLink::ImgurAlbum(id) => Self::resolve_imgur_album(&client, &id).await,
+        ImageLink::Unsupported => None,
+      };
+      if let Some(image_url) = image_url {
+        selected = Some((post, image_url));
+        break;
+      }
     }
 
-    let bytes = image_response.bytes().await.context("Failed to read Reddit image data")?;
-
-    // Extract file extension from URL
-    let file_extension = image_url
-      .rsplit('.')
-      .next()
-      .and_then(|ext| {
-        let ext = ext.split('?').next().unwrap_or(ext);
-        if ext.len() <= 5 { Some(ext) } else { None }
-      })
-      .unwrap_or("jpg");
-
-    let filename = format!("{}_{}", self.source_name(), FilesystemHelper::make_file_suffix());
-    let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
-    let file_path = download_dir.join(&filename).with_extension(file_extension);
-
-    // Ensure download directory exists
-    if let Some(parent) = file_path.parent() {
-      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
-    }
+    let (post, image_url) = selected.ok_or_else(|| DownloaderError::NoResults(format!("Could not resolve an image from r/{}", subreddit)))?;
+    debug!("Selected Reddit image: {}", image_url);
 
-    tokio::fs::write(&file_path, &bytes).await.context("Failed to save Reddit image")?;
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &image_url, config, opts)
+      .await
+      .context("Failed to download Reddit image")?;
 
     let wallpaper = Wallpaper {
       file_path,
       downloaded_at: Utc::now(),
       source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url.clone()),
+        page_url: Some(format!("https://www.reddit.com{}", post.permalink)),
+        title: Some(post.title.clone()),
+        attribution: Some(format!("r/{}", subreddit)),
+        ..Default::default()
+      }),
     };
 
     debug!("Downloaded Reddit wallpaper: {:?}", wallpaper);
@@ -168,3 +281,74 @@ impl WallpaperDownloader for RedditDownloader {
     "reddit"
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_normalize_imgur_url() {
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("https://i.redd.it/abc123.png"),
+      ImageLink::Direct("https://i.redd.it/abc123.png".to_string())
+    );
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("https://imgur.com/abc123"),
+      ImageLink::Direct("https://i.imgur.com/abc123.jpg".to_string())
+    );
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("http://imgur.com/abc123.png"),
+      ImageLink::Direct("https://i.imgur.com/abc123.png".to_string())
+    );
+  }
+
+  #[test]
+  fn test_normalize_imgur_album_urls() {
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("https://imgur.com/a/XyZ12"),
+      ImageLink::ImgurAlbum("XyZ12".to_string())
+    );
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("https://imgur.com/gallery/XyZ12/"),
+      ImageLink::ImgurAlbum("XyZ12".to_string())
+    );
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("https://www.imgur.com/a/XyZ12?foo=bar"),
+      ImageLink::ImgurAlbum("XyZ12".to_string())
+    );
+    assert_eq!(RedditDownloader::normalize_imgur_url("https://imgur.com/a/"), ImageLink::Unsupported);
+  }
+
+  #[test]
+  fn test_unsupported_links_skipped() {
+    assert_eq!(RedditDownloader::normalize_imgur_url("https://v.redd.it/abc123"), ImageLink::Unsupported);
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("https://www.reddit.com/gallery/abc123"),
+      ImageLink::Unsupported
+    );
+    assert_eq!(
+      RedditDownloader::normalize_imgur_url("https://imgur.com/abc123.gifv"),
+      ImageLink::Unsupported
+    );
+  }
+
+  #[test]
+  fn test_listing_url() {
+    assert_eq!(
+      RedditDownloader::listing_url("wallpapers", None, None).unwrap(),
+      "https://www.reddit.com/r/wallpapers/hot.json?limit=100"
+    );
+    assert_eq!(
+      RedditDownloader::listing_url("earthporn", Some("top"), Some("week")).unwrap(),
+      "https://www.reddit.com/r/earthporn/top.json?limit=100&t=week"
+    );
+    // Time filter is ignored for listings that don't support it
+    assert_eq!(
+      RedditDownloader::listing_url("wallpapers", Some("new"), Some("week")).unwrap(),
+      "https://www.reddit.com/r/wallpapers/new.json?limit=100"
+    );
+
+    assert!(RedditDownloader::listing_url("wallpapers", Some("best"), None).is_err());
+    assert!(RedditDownloader::listing_url("wallpapers", Some("top"), Some("decade")).is_err());
+  }
+}
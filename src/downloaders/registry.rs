@@ -1,16 +1,29 @@
 use super::apod::ApodDownloader;
 use super::bing::BingDownloader;
 use super::earthview::EarthViewDownloader;
+use super::error::DownloaderError;
+use super::flickr::FlickrDownloader;
+use super::pexels::PexelsDownloader;
 use super::picsum::PicsumDownloader;
+use super::pixabay::PixabayDownloader;
 use super::reddit::RedditDownloader;
 use super::traits::WallpaperDownloader;
 use super::unsplash::UnsplashDownloader;
+use super::url::UrlDownloader;
 use super::wallhaven::WallhavenDownloader;
-use anyhow::{Result, anyhow};
+use super::wikimedia::WikimediaDownloader;
+use super::{DownloadOptions, traits::Wallpaper};
+use crate::config::Config;
+use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Registry for managing wallpaper downloaders
+///
+/// `new()` comes with the built-in sources. Crates using wallflow as a library can
+/// add their own with [`register`](Self::register) and download through
+/// [`download_from_source`](Self::download_from_source), which applies the same
+/// checks (minimum resolution, orientation, deduplication) as the built-ins get.
 pub struct DownloaderRegistry {
   downloaders: HashMap<String, Arc<dyn WallpaperDownloader + Send + Sync>>,
 }
@@ -25,15 +38,30 @@ impl DownloaderRegistry {
     registry
   }
 
+  /// Create a registry with no downloaders at all
+  pub fn empty() -> Self {
+    Self { downloaders: HashMap::new() }
+  }
+
   /// Register all built-in downloaders
   fn register_builtin_downloaders(&mut self) {
     self.register_downloader(Arc::new(ApodDownloader));
     self.register_downloader(Arc::new(BingDownloader));
     self.register_downloader(Arc::new(EarthViewDownloader));
+    self.register_downloader(Arc::new(FlickrDownloader));
+    self.register_downloader(Arc::new(PexelsDownloader));
     self.register_downloader(Arc::new(PicsumDownloader));
+    self.register_downloader(Arc::new(PixabayDownloader));
     self.register_downloader(Arc::new(RedditDownloader));
     self.register_downloader(Arc::new(UnsplashDownloader));
+    self.register_downloader(Arc::new(UrlDownloader));
     self.register_downloader(Arc::new(WallhavenDownloader));
+    self.register_downloader(Arc::new(WikimediaDownloader));
+  }
+
+  /// Add a downloader, replacing any existing one with the same source name
+  pub fn register(&mut self, downloader: Box<dyn WallpaperDownloader + Send + Sync>) {
+    self.register_downloader(Arc::from(downloader));
   }
 
   /// Register a new downloader
@@ -43,12 +71,12 @@ impl DownloaderRegistry {
   }
 
   /// Get a downloader by source name
-  pub fn get_downloader(&self, source: &str) -> Result<Arc<dyn WallpaperDownloader + Send + Sync>> {
+  pub fn get_downloader(&self, source: &str) -> Result<Arc<dyn WallpaperDownloader + Send + Sync>, DownloaderError> {
     self
       .downloaders
       .get(source)
       .cloned()
-      .ok_or_else(|| anyhow!("Unknown wallpaper source: {}", source))
+      .ok_or_else(|| DownloaderError::UnknownSource(source.to_string()))
   }
 
   /// List all available source names
@@ -62,6 +90,32 @@ impl DownloaderRegistry {
   pub fn has_source(&self, source: &str) -> bool {
     self.downloaders.contains_key(source)
   }
+
+  /// Download from one of this registry's sources
+  pub async fn download_from_source(
+    &self,
+    source: &str,
+    config: &Config,
+    query: &[String],
+    opts: &DownloadOptions,
+  ) -> Result<Wallpaper, DownloaderError> {
+    let downloader = self.get_downloader(source)?;
+    super::download_checked(downloader.as_ref(), config, query, opts).await
+  }
+
+  /// Download up to `count` wallpapers from one of this registry's sources, see [`super::download_batch`]
+  #[allow(dead_code)]
+  pub async fn download_batch(
+    &self,
+    source: &str,
+    config: &Config,
+    query: &[String],
+    opts: &DownloadOptions,
+    count: usize,
+  ) -> Result<Vec<Wallpaper>> {
+    let downloader = self.get_downloader(source)?;
+    super::download_batch_with(downloader, config, query, opts, count).await
+  }
 }
 
 impl Default for DownloaderRegistry {
@@ -69,3 +123,145 @@ impl Default for DownloaderRegistry {
     Self::new()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use async_trait::async_trait;
+  use chrono::Utc;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::time::Duration;
+  use tempfile::TempDir;
+
+  /// Writes a tiny PNG instead of fetching anything
+  struct SolidDownloader;
+
+  #[async_trait]
+  impl WallpaperDownloader for SolidDownloader {
+    async fn download(&self, _config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+      let file_path = opts.output_dir.clone().unwrap().join("solid.png");
+      image::RgbImage::new(32, 16).save(&file_path).map_err(anyhow::Error::from)?;
+      Ok(Wallpaper {
+        file_path,
+        source: self.source_name().to_string(),
+        downloaded_at: Utc::now(),
+        metadata: None,
+      })
+    }
+
+    fn source_name(&self) -> &'static str {
+      "solid"
+    }
+  }
+
+  /// Fails every third call and returns the same image every other third, slowly
+  /// enough that the batch has to run calls side by side
+  #[derive(Default)]
+  struct FlakyDownloader {
+    calls: AtomicUsize,
+    running: AtomicUsize,
+    most_running: AtomicUsize,
+  }
+
+  #[async_trait]
+  impl WallpaperDownloader for FlakyDownloader {
+    async fn download(&self, _config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+      let call = self.calls.fetch_add(1, Ordering::SeqCst);
+      let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+      self.most_running.fetch_max(running, Ordering::SeqCst);
+      tokio::time::sleep(Duration::from_millis(20)).await;
+      self.running.fetch_sub(1, Ordering::SeqCst);
+
+      let shade = match call % 3 {
+        0 => return Err(DownloaderError::NoResults(format!("call {} failed", call))),
+        1 => 0,
+        _ => call as u8,
+      };
+      let file_path = opts.output_dir.clone().unwrap().join(format!("flaky-{}.png", call));
+      image::RgbImage::from_pixel(32, 16, image::Rgb([shade, shade, shade]))
+        .save(&file_path)
+        .map_err(anyhow::Error::from)?;
+      Ok(Wallpaper {
+        file_path,
+        source: self.source_name().to_string(),
+        downloaded_at: Utc::now(),
+        metadata: None,
+      })
+    }
+
+    fn source_name(&self) -> &'static str {
+      "flaky"
+    }
+  }
+
+  fn batch_config(parallel_downloads: u32) -> Config {
+    let mut config = Config::default();
+    config.sources.orientation = crate::config::SourceOrientation::Landscape;
+    config.advanced.parallel_downloads = parallel_downloads;
+    config
+  }
+
+  #[tokio::test]
+  async fn test_download_batch() {
+    let tmp = TempDir::new().unwrap();
+    let flaky = Arc::new(FlakyDownloader::default());
+    let mut registry = DownloaderRegistry::empty();
+    registry.register_downloader(flaky.clone());
+    let opts = DownloadOptions {
+      output_dir: Some(tmp.path().to_path_buf()),
+      ..Default::default()
+    };
+
+    let wallpapers = registry.download_batch("flaky", &batch_config(2), &[], &opts, 9).await.unwrap();
+
+    assert_eq!(flaky.calls.load(Ordering::SeqCst), 9);
+    // Never more than parallel_downloads at once, but really in parallel
+    assert_eq!(flaky.most_running.load(Ordering::SeqCst), 2);
+    // 3 failed, and of the 3 identical images only one is kept
+    assert_eq!(wallpapers.len(), 4);
+    let files = std::fs::read_dir(tmp.path()).unwrap().count();
+    assert_eq!(files, 4);
+  }
+
+  #[tokio::test]
+  async fn test_download_batch_fails_when_nothing_downloads() {
+    let tmp = TempDir::new().unwrap();
+    let mut registry = DownloaderRegistry::empty();
+    registry.register(Box::new(FlakyDownloader::default()));
+    let opts = DownloadOptions {
+      output_dir: Some(tmp.path().to_path_buf()),
+      ..Default::default()
+    };
+
+    // The only call is the failing first one
+    let err = registry.download_batch("flaky", &batch_config(4), &[], &opts, 1).await.unwrap_err();
+    assert!(err.to_string().contains("All 1 downloads from flaky failed"), "{}", err);
+  }
+
+  #[tokio::test]
+  async fn test_custom_downloader() {
+    let tmp = TempDir::new().unwrap();
+    let mut registry = DownloaderRegistry::new();
+    registry.register(Box::new(SolidDownloader));
+
+    assert!(registry.list_sources().contains(&"solid".to_string()));
+    assert!(registry.has_source("bing"));
+    assert!(!DownloaderRegistry::empty().has_source("bing"));
+
+    let mut config = Config::default();
+    config.sources.orientation = crate::config::SourceOrientation::Landscape;
+    let opts = DownloadOptions {
+      output_dir: Some(tmp.path().to_path_buf()),
+      ..Default::default()
+    };
+
+    assert!(matches!(
+      registry.download_from_source("nope", &config, &[], &opts).await,
+      Err(DownloaderError::UnknownSource(_))
+    ));
+
+    let wallpaper = registry.download_from_source("solid", &config, &[], &opts).await.unwrap();
+    assert_eq!(wallpaper.source, "solid");
+    assert!(wallpaper.file_path.exists());
+  }
+}
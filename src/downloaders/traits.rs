@@ -1,9 +1,11 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 use super::DownloadOptions;
+use super::error::DownloaderError;
 use crate::config::Config;
 
 /// Result of a successful wallpaper download
@@ -13,6 +15,42 @@ pub struct Wallpaper {
   pub file_path: PathBuf,
   pub source: String,
   pub downloaded_at: DateTime<Utc>,
+  /// Attribution and details reported by the source, when it has any
+  pub metadata: Option<Metadata>,
+}
+
+/// Where a downloaded wallpaper came from and who made it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Metadata {
+  /// Original image URL
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub url: Option<String>,
+  /// Page the image was found on (e.g. Reddit permalink, Unsplash photo page)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub page_url: Option<String>,
+  /// Title or description
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub title: Option<String>,
+  /// Photographer, author or copyright line
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub attribution: Option<String>,
+  /// Where the photo was taken
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub location: Option<String>,
+  /// Day the picture was published for, for daily sources (YYYY-MM-DD)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub date: Option<String>,
+}
+
+impl Metadata {
+  /// One-line credit for display, e.g. "Misty forest — Photo by Jane Doe on Unsplash"
+  pub fn credit(&self) -> Option<String> {
+    match (&self.title, &self.attribution) {
+      (Some(title), Some(attribution)) => Some(format!("{} — {}", title, attribution)),
+      (Some(text), None) | (None, Some(text)) => Some(text.clone()),
+      (None, None) => None,
+    }
+  }
 }
 
 /// Trait for wallpaper downloader implementations
@@ -20,7 +58,7 @@ pub struct Wallpaper {
 pub trait WallpaperDownloader {
   /// Download a wallpaper based on the request
   /// The `query` parameter contains additional CLI arguments (e.g., search terms, subreddit names)
-  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper>;
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError>;
 
   /// Get the source name for this downloader
   fn source_name(&self) -> &'static str;
@@ -5,20 +5,24 @@
 
 use super::DownloadOptions;
 use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
 use super::filesystem::FilesystemHelper;
-use super::traits::{Wallpaper, WallpaperDownloader};
-use crate::config::Config;
-use anyhow::{Context, Result, anyhow};
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
+use crate::config::{Config, UnsplashOrientation};
+use crate::display::Orientation;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
 use rand::seq::SliceRandom;
 use serde::Deserialize;
-use std::path::Path;
 use tracing::debug;
 
 /// Unsplash API endpoint for random photos
 const UNSPLASH_API_URL: &str = "https://api.unsplash.com/photos/random";
 
+/// Download width when `sources.unsplash.width` isn't set
+const DEFAULT_WIDTH: u32 = 2560;
+
 /// Unsplash photo response
 #[derive(Debug, Deserialize)]
 struct UnsplashPhoto {
@@ -31,11 +35,11 @@ struct UnsplashPhoto {
   /// URLs for different sizes
   urls: UnsplashUrls,
   /// User/photographer info
-  #[allow(dead_code)]
   user: UnsplashUser,
   /// Description
-  #[allow(dead_code)]
   description: Option<String>,
+  /// Links to the photo on unsplash.com
+  links: UnsplashLinks,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,10 +54,15 @@ struct UnsplashUrls {
 #[derive(Debug, Deserialize)]
 struct UnsplashUser {
   /// Photographer name
-  #[allow(dead_code)]
   name: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct UnsplashLinks {
+  /// Photo page
+  html: String,
+}
+
 /// Unsplash downloader
 pub struct UnsplashDownloader;
 
@@ -61,60 +70,82 @@ pub struct UnsplashDownloader;
 impl WallpaperDownloader for UnsplashDownloader {
   /// Download a photo from Unsplash
   /// Query parameters are used as search topics (e.g., "wallflow unsplash nature")
-  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper> {
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
     let unsplash_config = &config.sources.unsplash;
 
     // Access Key is required for Unsplash (used as client_id)
     let access_key = unsplash_config.access_key.as_ref().ok_or_else(|| {
-      anyhow!(
+      DownloaderError::MissingApiKey(
         "Unsplash requires an Access Key. Get one at https://unsplash.com/developers and add it to config:\n\
          sources:\n  unsplash:\n    access_key: \"your-access-key\""
+          .to_string(),
       )
     })?;
 
     if access_key.is_empty() {
-      return Err(anyhow!("Unsplash access_key is empty"));
+      return Err(DownloaderError::MissingApiKey("Unsplash access_key is empty".to_string()));
     }
 
     debug!("Fetching random photo from Unsplash");
 
     let client = Client::from(&config.advanced);
+    let orientation = match unsplash_config.orientation {
+      Some(orientation) => orientation,
+      None => opts.orientation(config).into(),
+    };
 
     // Build request with query parameters (access_key is used as client_id)
-    let mut request = client
-      .get(UNSPLASH_API_URL)
-      .query(&[("client_id", access_key.as_str()), ("count", "10"), ("orientation", "landscape")]);
+    let mut request =
+      client
+        .get(UNSPLASH_API_URL)
+        .query(&[("client_id", access_key.as_str()), ("count", "10"), ("orientation", orientation.as_str())]);
 
-    // Add search query if provided
+    // Add search query if provided. Unsplash doesn't combine a search with collections.
     let search_query = query.join(" ");
+    let collections = collection_ids(&unsplash_config.collections);
     if !search_query.is_empty() {
       request = request.query(&[("query", search_query.as_str())]);
+      if !collections.is_empty() {
+        debug!("Searching all of Unsplash, sources.unsplash.collections is ignored with search terms");
+      }
+    } else if !collections.is_empty() {
+      request = request.query(&[("collections", collections.as_str())]);
     }
 
-    let response = request.send().await.context("Failed to send request to Unsplash API")?;
+    let response = client.send_with_retry(request).await.context("Failed to send request to Unsplash API")?;
 
     if !response.status().is_success() {
       let status = response.status();
       let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
 
       if status.as_u16() == 401 {
-        return Err(anyhow!("Unsplash access_key is invalid. Check your configuration."));
+        return Err(DownloaderError::InvalidApiKey(
+          "Unsplash access_key is invalid. Check your configuration.".to_string(),
+        ));
       }
       if status.as_u16() == 403 {
-        return Err(anyhow!("Unsplash rate limit exceeded. Try again later."));
+        return Err(DownloaderError::RateLimited("Unsplash rate limit exceeded. Try again later.".to_string()));
       }
 
-      return Err(anyhow!("Unsplash API request failed with status {}: {}", status, error_text));
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Unsplash API request failed with status {}: {}", status, error_text),
+      });
     }
 
     let photos: Vec<UnsplashPhoto> = response.json().await.context("Failed to parse Unsplash API response")?;
 
     if photos.is_empty() {
-      return Err(anyhow!("No photos returned from Unsplash"));
+      return Err(DownloaderError::NoResults("No photos returned from Unsplash".to_string()));
     }
 
-    // Filter to landscape images with adequate resolution
-    let suitable_photos: Vec<&UnsplashPhoto> = photos.iter().filter(|p| p.width > p.height && p.width >= 1920).collect();
+    // Filter to images of the right orientation with adequate resolution
+    // (squarish photos are whatever Unsplash considers close enough to square)
+    let suitable_photos: Vec<&UnsplashPhoto> = photos
+      .iter()
+      .filter(|p| orientation == UnsplashOrientation::Squarish || UnsplashOrientation::from(Orientation::of(p.width, p.height)) == orientation)
+      .filter(|p| p.width.max(p.height) >= 1920)
+      .collect();
 
     let photos_to_use = if suitable_photos.is_empty() {
       photos.iter().collect()
@@ -125,38 +156,30 @@ impl WallpaperDownloader for UnsplashDownloader {
     // Pick a random photo
     let photo = photos_to_use
       .choose(&mut rand::thread_rng())
-      .ok_or_else(|| anyhow!("Failed to select random Unsplash photo"))?;
+      .ok_or_else(|| DownloaderError::NoResults("Failed to select random Unsplash photo".to_string()))?;
 
     debug!("Selected Unsplash photo: {}x{}", photo.width, photo.height);
 
     // Use full URL with width parameter for optimal resolution
-    let image_url = format!("{}&w=2560&q=85", photo.urls.full);
+    let image_url = format!("{}&w={}&q=85", photo.urls.full, unsplash_config.width.unwrap_or(DEFAULT_WIDTH));
     debug!("Image URL: {}", image_url);
 
     // Download the image
-    let image_response = client.get(&image_url).send().await.context("Failed to download Unsplash image")?;
-
-    if !image_response.status().is_success() {
-      return Err(anyhow!("Unsplash image download failed with status: {}", image_response.status()));
-    }
-
-    let bytes = image_response.bytes().await.context("Failed to read Unsplash image data")?;
-
-    let filename = format!("{}_{}", self.source_name(), FilesystemHelper::make_file_suffix());
-    let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
-    let file_path = download_dir.join(&filename).with_extension("jpg");
-
-    // Ensure download directory exists
-    if let Some(parent) = file_path.parent() {
-      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
-    }
-
-    tokio::fs::write(&file_path, &bytes).await.context("Failed to save Unsplash image")?;
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &image_url, config, opts)
+      .await
+      .context("Failed to download Unsplash image")?;
 
     let wallpaper = Wallpaper {
       file_path,
       downloaded_at: Utc::now(),
       source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url.clone()),
+        page_url: Some(photo.links.html.clone()),
+        title: photo.description.clone(),
+        attribution: Some(format!("Photo by {} on Unsplash", photo.user.name)),
+        ..Default::default()
+      }),
     };
 
     debug!("Downloaded Unsplash wallpaper: {:?}", wallpaper);
@@ -167,3 +190,27 @@ impl WallpaperDownloader for UnsplashDownloader {
     "unsplash"
   }
 }
+
+/// Collection IDs as Unsplash expects them: comma-separated, without blanks
+fn collection_ids(collections: &[String]) -> String {
+  collections
+    .iter()
+    .map(|id| id.trim())
+    .filter(|id| !id.is_empty())
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_collection_ids() {
+    assert_eq!(collection_ids(&[]), "");
+    assert_eq!(
+      collection_ids(&["1065976".to_string(), " 3330445 ".to_string(), "".to_string()]),
+      "1065976,3330445"
+    );
+  }
+}
@@ -0,0 +1,192 @@
+//! Generic URL downloader
+//!
+//! Fetches `sources.url.endpoint` (or a URL given on the command line). An image
+//! response is saved as is; a JSON response is searched for the image URL at
+//! `sources.url.json_path`, which is then downloaded. This covers direct image
+//! links as well as self-hosted "random wallpaper" APIs.
+
+use super::DownloadOptions;
+use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
+use super::filesystem::FilesystemHelper;
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
+use crate::config::Config;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::header::CONTENT_TYPE;
+use tracing::debug;
+
+/// What an HTTP response turned out to be
+enum Body {
+  Image(Vec<u8>),
+  Json(serde_json::Value),
+}
+
+/// Generic URL downloader
+pub struct UrlDownloader;
+
+impl UrlDownloader {
+  /// Fetch a URL and sort its body into image or JSON by content type
+  async fn fetch(client: &Client, url: &str) -> Result<Body> {
+    let response = client
+      .send_with_retry(client.get(url))
+      .await
+      .with_context(|| format!("Failed to fetch {}", url))?;
+
+    if !response.status().is_success() {
+      return Err(anyhow!("{} returned status {}", url, response.status()));
+    }
+
+    let content_type = response
+      .headers()
+      .get(CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .unwrap_or("")
+      .to_lowercase();
+    let bytes = response.bytes().await.with_context(|| format!("Failed to read response from {}", url))?;
+
+    if is_json(&content_type) {
+      let json = serde_json::from_slice(&bytes).with_context(|| format!("{} returned invalid JSON", url))?;
+      return Ok(Body::Json(json));
+    }
+
+    // Servers often send images as octet-stream, so the bytes have the final say
+    match image_extension(&content_type, &bytes) {
+      Some(_) => Ok(Body::Image(bytes.to_vec())),
+      None => Err(anyhow!(
+        "{} returned '{}', which is neither an image nor JSON",
+        url,
+        if content_type.is_empty() { "no content type" } else { &content_type }
+      )),
+    }
+  }
+}
+
+#[async_trait]
+impl WallpaperDownloader for UrlDownloader {
+  /// Download from the configured endpoint
+  /// The first query parameter, if any, replaces the endpoint (e.g., "wallflow url https://example.com/a.jpg")
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let url_config = &config.sources.url;
+
+    let endpoint = query
+      .first()
+      .or(url_config.endpoint.as_ref())
+      .filter(|endpoint| !endpoint.is_empty())
+      .ok_or_else(|| {
+        anyhow!(
+          "The url source needs an endpoint. Pass one (wallflow url <url>) or add it to config:\n\
+           sources:\n  url:\n    endpoint: \"https://example.com/random\"\n    json_path: \"data.url\" # if the endpoint returns JSON"
+        )
+      })?;
+
+    debug!("Fetching {}", endpoint);
+
+    let client = Client::from(&config.advanced);
+
+    let (image_url, bytes) = match Self::fetch(&client, endpoint).await? {
+      Body::Image(bytes) => (endpoint.clone(), bytes),
+      Body::Json(json) => {
+        let json_path = url_config.json_path.as_deref().filter(|path| !path.is_empty()).ok_or_else(|| {
+          anyhow!(
+            "{} returned JSON. Set sources.url.json_path to the field holding the image URL.",
+            endpoint
+          )
+        })?;
+
+        let found = json_lookup(&json, json_path).ok_or_else(|| anyhow!("No string at '{}' in the JSON from {}", json_path, endpoint))?;
+
+        // Relative URLs are relative to the endpoint
+        let image_url = reqwest::Url::parse(endpoint)
+          .and_then(|base| base.join(found))
+          .with_context(|| format!("Invalid image URL '{}' in the JSON from {}", found, endpoint))?
+          .to_string();
+        debug!("Image URL: {}", image_url);
+
+        match Self::fetch(&client, &image_url).await? {
+          Body::Image(bytes) => (image_url, bytes),
+          Body::Json(_) => return Err(anyhow!("{} returned JSON instead of an image", image_url).into()),
+        }
+      }
+    };
+
+    let file_path = FilesystemHelper::save_image(self.source_name(), &bytes, &image_url, config, opts).await?;
+
+    let wallpaper = Wallpaper {
+      file_path,
+      downloaded_at: Utc::now(),
+      source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url),
+        page_url: Some(endpoint.clone()),
+        ..Default::default()
+      }),
+    };
+
+    debug!("Downloaded URL wallpaper: {:?}", wallpaper);
+    Ok(wallpaper)
+  }
+
+  fn source_name(&self) -> &'static str {
+    "url"
+  }
+}
+
+/// `application/json` and any `+json` type (e.g. `application/ld+json`)
+fn is_json(content_type: &str) -> bool {
+  let mime = content_type.split(';').next().unwrap_or("").trim();
+  mime == "application/json" || mime.ends_with("+json")
+}
+
+/// File extension for image bytes, or `None` if they aren't an image we can read.
+/// Anything other than `image/*` or a generic binary type is rejected up front.
+fn image_extension(content_type: &str, bytes: &[u8]) -> Option<&'static str> {
+  let mime = content_type.split(';').next().unwrap_or("").trim();
+  if !(mime.is_empty() || mime.starts_with("image/") || mime == "application/octet-stream") {
+    return None;
+  }
+
+  let format = image::guess_format(bytes).ok()?;
+  format.extensions_str().first().copied()
+}
+
+/// Follow a dotted path (`data.images.0.url`) to a string; numbers index arrays
+fn json_lookup<'a>(json: &'a serde_json::Value, path: &str) -> Option<&'a str> {
+  path
+    .split('.')
+    .try_fold(json, |value, key| match value {
+      serde_json::Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+      _ => value.get(key),
+    })?
+    .as_str()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_json_lookup() {
+    let json: serde_json::Value = serde_json::from_str(r#"{"data": {"images": [{"url": "/a.jpg"}, {"url": "/b.png"}]}, "count": 2}"#).unwrap();
+
+    assert_eq!(json_lookup(&json, "data.images.1.url"), Some("/b.png"));
+    assert_eq!(json_lookup(&json, "data.images.5.url"), None);
+    assert_eq!(json_lookup(&json, "count"), None);
+    assert_eq!(json_lookup(&json, "data.missing"), None);
+  }
+
+  #[test]
+  fn test_content_types() {
+    let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    assert!(is_json("application/json; charset=utf-8"));
+    assert!(is_json("application/ld+json"));
+    assert!(!is_json("text/html"));
+
+    assert_eq!(image_extension("image/png", png), Some("png"));
+    assert_eq!(image_extension("application/octet-stream", png), Some("png"));
+    assert_eq!(image_extension("text/html", png), None);
+    assert_eq!(image_extension("image/png", b"<html>"), None);
+  }
+}
@@ -5,17 +5,23 @@
 
 use super::DownloadOptions;
 use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
 use super::filesystem::FilesystemHelper;
-use super::traits::{Wallpaper, WallpaperDownloader};
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
 use crate::config::Config;
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
 use rand::seq::SliceRandom;
 use serde::Deserialize;
-use std::path::Path;
 use tracing::debug;
 
+/// Sort orders accepted by the search API
+const SORTINGS: &[&str] = &["date_added", "relevance", "random", "views", "favorites", "toplist"];
+
+/// Time ranges accepted for `sorting=toplist`
+const TOP_RANGES: &[&str] = &["1d", "3d", "1w", "1M", "3M", "6M", "1y"];
+
 /// Wallhaven API search response
 #[derive(Debug, Deserialize)]
 struct WallhavenResponse {
@@ -25,6 +31,9 @@ struct WallhavenResponse {
 /// Individual wallpaper from Wallhaven
 #[derive(Debug, Deserialize)]
 struct WallhavenImage {
+  /// Wallhaven page for the wallpaper
+  #[serde(default)]
+  url: Option<String>,
   /// Direct URL to the full image
   path: String,
   /// Image resolution (e.g., "1920x1080")
@@ -66,6 +75,32 @@ impl WallhavenDownloader {
     format!("{}{}{}", general as u8, anime as u8, people as u8)
   }
 
+  /// Sorting parameters for the search request (`sorting`, plus `topRange` for toplists)
+  fn sorting_params(sorting: &str, top_range: Option<&str>) -> Result<Vec<(&'static str, String)>> {
+    // An empty value comes from configs created before sorting existed
+    let sorting = if sorting.is_empty() { "random" } else { sorting };
+    if !SORTINGS.contains(&sorting) {
+      return Err(anyhow!("Invalid Wallhaven sorting '{}'. Valid options: {}", sorting, SORTINGS.join(", ")));
+    }
+
+    let mut params = vec![("sorting", sorting.to_string())];
+
+    if sorting == "toplist"
+      && let Some(top_range) = top_range
+    {
+      if !TOP_RANGES.contains(&top_range) {
+        return Err(anyhow!(
+          "Invalid Wallhaven top_range '{}'. Valid options: {}",
+          top_range,
+          TOP_RANGES.join(", ")
+        ));
+      }
+      params.push(("topRange", top_range.to_string()));
+    }
+
+    Ok(params)
+  }
+
   /// Build search query from categories (non-bitmask terms become search tags)
   fn build_search_query(config_q: &str, categories: &[String]) -> String {
     let mut terms: Vec<String> = Vec::new();
@@ -91,9 +126,9 @@ impl WallhavenDownloader {
 impl WallpaperDownloader for WallhavenDownloader {
   /// Download a wallpaper from Wallhaven
   /// Query parameters are used as search terms (e.g., "wallflow wallhaven nature mountains")
-  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper> {
+  async fn download(&self, config: &Config, query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
     let wallhaven_config = &config.sources.wallhaven;
-    let resolution = config.get_wallhaven_resolution()?;
+    let resolution = config.get_wallhaven_resolution(opts.monitor.as_deref())?;
 
     // Build query parameters
     let purity = Self::purity_to_bitmask(&wallhaven_config.purity);
@@ -104,6 +139,8 @@ impl WallpaperDownloader for WallhavenDownloader {
     let base_query = if cli_query.is_empty() { &wallhaven_config.q } else { &cli_query };
     let search_query = Self::build_search_query(base_query, &wallhaven_config.categories);
     let resolution_str = format!("{}x{}", resolution.width, resolution.height);
+    let sorting = Self::sorting_params(&wallhaven_config.sorting, wallhaven_config.top_range.as_deref())?;
+    let orientation = opts.orientation(config);
 
     debug!(
       "Searching Wallhaven: q='{}', resolution={}, purity={}, categories={}",
@@ -113,12 +150,15 @@ impl WallpaperDownloader for WallhavenDownloader {
     let client = Client::from(&config.advanced);
 
     // Build request with query parameters
-    let mut request = client.get(&wallhaven_config.url).query(&[
-      ("purity", purity.as_str()),
-      ("categories", categories.as_str()),
-      ("atleast", resolution_str.as_str()),
-      ("sorting", "random"),
-    ]);
+    let mut request = client
+      .get(&wallhaven_config.url)
+      .query(&[
+        ("purity", purity.as_str()),
+        ("categories", categories.as_str()),
+        ("atleast", resolution_str.as_str()),
+        ("ratios", orientation.as_str()),
+      ])
+      .query(&sorting);
 
     // Add search query if not empty
     if !search_query.is_empty() {
@@ -132,68 +172,47 @@ impl WallpaperDownloader for WallhavenDownloader {
       request = request.query(&[("apikey", api_key.as_str())]);
     }
 
-    let response = request.send().await.context("Failed to send request to Wallhaven API")?;
+    let response = client.send_with_retry(request).await.context("Failed to send request to Wallhaven API")?;
 
     if !response.status().is_success() {
       let status = response.status();
       let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-      return Err(anyhow!("Wallhaven API request failed with status {}: {}", status, error_text));
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Wallhaven API request failed with status {}: {}", status, error_text),
+      });
     }
 
     let wallhaven_data: WallhavenResponse = response.json().await.context("Failed to parse Wallhaven API response")?;
 
     if wallhaven_data.data.is_empty() {
-      return Err(anyhow!(
+      return Err(DownloaderError::NoResults(format!(
         "No wallpapers found matching criteria (resolution: {}, purity: {}, categories: {})",
-        resolution_str,
-        purity,
-        categories
-      ));
+        resolution_str, purity, categories
+      )));
     }
 
     // Pick a random wallpaper from results
     let image = wallhaven_data
       .data
       .choose(&mut rand::thread_rng())
-      .ok_or_else(|| anyhow!("Failed to select random wallpaper"))?;
+      .ok_or_else(|| DownloaderError::NoResults("Failed to select random wallpaper".to_string()))?;
 
     debug!("Selected wallpaper: {}", image.path);
 
-    // Download the actual image
-    let image_response = client.get(&image.path).send().await.context("Failed to download wallpaper image")?;
-
-    if !image_response.status().is_success() {
-      return Err(anyhow!("Image download failed with status: {}", image_response.status()));
-    }
-
-    let bytes = image_response.bytes().await.context("Failed to read image data")?;
-
-    // Extract file extension from URL
-    let file_extension = image
-      .path
-      .rsplit('.')
-      .next()
-      .and_then(|ext| {
-        let ext = ext.split('?').next().unwrap_or(ext);
-        if ext.len() <= 5 { Some(ext) } else { None }
-      })
-      .unwrap_or("jpg");
-
-    let filename = format!("{}_{}", self.source_name(), FilesystemHelper::make_file_suffix());
-    let download_dir = opts.output_dir.as_deref().unwrap_or(Path::new(&config.paths.downloads));
-    let file_path = download_dir.join(&filename).with_extension(file_extension);
-
-    // Ensure download directory exists
-    if let Some(parent) = file_path.parent() {
-      tokio::fs::create_dir_all(parent).await.context("Failed to create download directory")?;
-    }
-
-    tokio::fs::write(&file_path, &bytes).await.context("Failed to save wallpaper image")?;
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &image.path, config, opts)
+      .await
+      .context("Failed to download wallpaper image")?;
 
     let wallpaper = Wallpaper {
       file_path,
       downloaded_at: Utc::now(),
       source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image.path.clone()),
+        page_url: image.url.clone(),
+        ..Default::default()
+      }),
     };
 
     debug!("Downloaded Wallhaven wallpaper: {:?}", wallpaper);
@@ -204,3 +223,31 @@ impl WallpaperDownloader for WallhavenDownloader {
     "wallhaven"
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sorting_params() {
+    assert_eq!(
+      WallhavenDownloader::sorting_params("random", Some("1w")).unwrap(),
+      vec![("sorting", "random".to_string())]
+    );
+    assert_eq!(
+      WallhavenDownloader::sorting_params("", None).unwrap(),
+      vec![("sorting", "random".to_string())]
+    );
+    assert_eq!(
+      WallhavenDownloader::sorting_params("toplist", Some("1M")).unwrap(),
+      vec![("sorting", "toplist".to_string()), ("topRange", "1M".to_string())]
+    );
+    assert_eq!(
+      WallhavenDownloader::sorting_params("toplist", None).unwrap(),
+      vec![("sorting", "toplist".to_string())]
+    );
+
+    assert!(WallhavenDownloader::sorting_params("popular", None).is_err());
+    assert!(WallhavenDownloader::sorting_params("toplist", Some("2w")).is_err());
+  }
+}
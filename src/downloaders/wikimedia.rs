@@ -0,0 +1,213 @@
+//! Wikimedia Commons Picture of the Day downloader
+//!
+//! Fetches today's featured image from the Wikimedia feed API.
+//! No API key required; images are freely licensed.
+
+use super::DownloadOptions;
+use super::client::WallflowClient as Client;
+use super::error::DownloaderError;
+use super::filesystem::FilesystemHelper;
+use super::traits::{Metadata, Wallpaper, WallpaperDownloader};
+use crate::config::Config;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Deserialize;
+use std::path::Path;
+use tracing::debug;
+
+/// Wikimedia featured content feed; the date is appended as YYYY/MM/DD
+const WIKIMEDIA_FEED_URL: &str = "https://api.wikimedia.org/feed/v1/wikipedia/en/featured";
+
+/// Originals can be huge (50+ megapixels); anything wider is fetched as a 4K thumbnail
+const MAX_WIDTH: u32 = 3840;
+
+/// Featured feed response (only the picture of the day is used)
+#[derive(Debug, Deserialize)]
+struct FeedResponse {
+  image: Option<FeaturedImage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeaturedImage {
+  /// File name on Commons (e.g. "File:Lake.jpg")
+  title: String,
+  thumbnail: ImageSource,
+  image: ImageSource,
+  /// Commons file page
+  file_page: String,
+  artist: Option<TextField>,
+  description: Option<TextField>,
+  license: Option<License>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageSource {
+  source: String,
+  width: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextField {
+  text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct License {
+  /// Short license name (e.g. "CC BY-SA 4.0")
+  #[serde(rename = "type")]
+  kind: String,
+}
+
+impl FeaturedImage {
+  /// The original when it's a reasonable size and format, otherwise a thumbnail
+  /// scaled to `max_width`. Thumbnail URLs end in `<width>px-<name>`, so the
+  /// feed's small thumbnail is rewritten to the wanted width.
+  fn image_url(&self, max_width: u32) -> String {
+    let original = &self.image;
+    let web_format = Path::new(&original.source)
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "jpg" | "jpeg" | "png"));
+
+    if original.width <= max_width && web_format {
+      return original.source.clone();
+    }
+
+    let width = max_width.min(original.width);
+    let thumb_marker = format!("{}px-", self.thumbnail.width);
+    match self.thumbnail.source.rfind(&thumb_marker) {
+      Some(index) => format!(
+        "{}{}px-{}",
+        &self.thumbnail.source[..index],
+        width,
+        &self.thumbnail.source[index + thumb_marker.len()..]
+      ),
+      None => original.source.clone(),
+    }
+  }
+
+  /// "Jane Doe, CC BY-SA 4.0, via Wikimedia Commons"
+  fn attribution(&self) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    if let Some(artist) = &self.artist {
+      parts.push(artist.text.trim());
+    }
+    if let Some(license) = &self.license {
+      parts.push(&license.kind);
+    }
+    parts.push("via Wikimedia Commons");
+    parts.retain(|part| !part.is_empty());
+    parts.join(", ")
+  }
+}
+
+/// Wikimedia Commons Picture of the Day downloader
+pub struct WikimediaDownloader;
+
+#[async_trait]
+impl WallpaperDownloader for WikimediaDownloader {
+  /// Download the Wikimedia Commons Picture of the Day
+  /// Note: query parameters are ignored as there is one picture per day
+  async fn download(&self, config: &Config, _query: &[String], opts: &DownloadOptions) -> Result<Wallpaper, DownloaderError> {
+    let feed_url = format!("{}/{}", WIKIMEDIA_FEED_URL, Utc::now().format("%Y/%m/%d"));
+    debug!("Fetching Wikimedia Picture of the Day from {}", feed_url);
+
+    let client = Client::from(&config.advanced);
+    let response = client
+      .send_with_retry(client.get(&feed_url))
+      .await
+      .context("Failed to send request to Wikimedia API")?;
+
+    if !response.status().is_success() {
+      let status = response.status();
+      let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+      return Err(DownloaderError::Http {
+        status: status.as_u16(),
+        message: format!("Wikimedia API request failed with status {}: {}", status, error_text),
+      });
+    }
+
+    let feed: FeedResponse = response.json().await.context("Failed to parse Wikimedia API response")?;
+    let featured = feed
+      .image
+      .ok_or_else(|| DownloaderError::NoResults("Wikimedia has no Picture of the Day for today yet".to_string()))?;
+
+    debug!("Selected Wikimedia image: {}", featured.title);
+
+    let image_url = featured.image_url(MAX_WIDTH);
+    debug!("Image URL: {}", image_url);
+
+    // Thumbnails of TIFFs and SVGs are served as .jpg/.png, which the URL ends with
+    let file_path = FilesystemHelper::download_image(&client, self.source_name(), &image_url, config, opts)
+      .await
+      .context("Failed to download Wikimedia image")?;
+
+    let title = featured
+      .description
+      .as_ref()
+      .map(|description| description.text.trim().to_string())
+      .filter(|text| !text.is_empty())
+      .unwrap_or_else(|| featured.title.trim_start_matches("File:").to_string());
+
+    let wallpaper = Wallpaper {
+      file_path,
+      downloaded_at: Utc::now(),
+      source: self.source_name().to_string(),
+      metadata: Some(Metadata {
+        url: Some(image_url),
+        page_url: Some(featured.file_page.clone()),
+        title: Some(title),
+        attribution: Some(featured.attribution()),
+        ..Default::default()
+      }),
+    };
+
+    debug!("Downloaded Wikimedia wallpaper: {:?}", wallpaper);
+    Ok(wallpaper)
+  }
+
+  fn source_name(&self) -> &'static str {
+    "wikimedia"
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const FEED: &str = r#"{"image": {
+    "title": "File:Lake Bled.tif",
+    "thumbnail": {"source": "https://upload.wikimedia.org/wikipedia/commons/thumb/a/ab/Lake_Bled.tif/lossy-page1-640px-Lake_Bled.tif.jpg", "width": 640, "height": 427},
+    "image": {"source": "https://upload.wikimedia.org/wikipedia/commons/a/ab/Lake_Bled.tif", "width": 7360, "height": 4912},
+    "file_page": "https://commons.wikimedia.org/wiki/File:Lake_Bled.tif",
+    "artist": {"html": "<a>Jane Doe</a>", "text": "Jane Doe"},
+    "license": {"type": "CC BY-SA 4.0", "code": "cc-by-sa-4.0"},
+    "description": {"html": "<p>Lake Bled</p>", "text": "Lake Bled at dawn", "lang": "en"}
+  }}"#;
+
+  #[test]
+  fn test_large_original_uses_scaled_thumbnail() {
+    let feed: FeedResponse = serde_json::from_str(FEED).unwrap();
+    let image = feed.image.unwrap();
+
+    assert_eq!(
+      image.image_url(MAX_WIDTH),
+      "https://upload.wikimedia.org/wikipedia/commons/thumb/a/ab/Lake_Bled.tif/lossy-page1-3840px-Lake_Bled.tif.jpg"
+    );
+    assert_eq!(image.attribution(), "Jane Doe, CC BY-SA 4.0, via Wikimedia Commons");
+  }
+
+  #[test]
+  fn test_small_jpeg_uses_original() {
+    let mut image = serde_json::from_str::<FeedResponse>(FEED).unwrap().image.unwrap();
+    image.image = ImageSource {
+      source: "https://upload.wikimedia.org/wikipedia/commons/a/ab/Lake.jpg".to_string(),
+      width: 3000,
+    };
+    image.artist = None;
+
+    assert_eq!(image.image_url(MAX_WIDTH), "https://upload.wikimedia.org/wikipedia/commons/a/ab/Lake.jpg");
+    assert_eq!(image.attribution(), "CC BY-SA 4.0, via Wikimedia Commons");
+  }
+}
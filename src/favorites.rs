@@ -0,0 +1,80 @@
+//! Favorite wallpapers
+//!
+//! Starred wallpapers are kept as a plain JSON array of paths in `favorites.json`
+//! in the state directory (see [`crate::paths`]), so the list can be edited by hand.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Get the favorites file path
+pub fn favorites_file() -> Result<PathBuf> {
+  Ok(crate::paths::state_dir()?.join("favorites.json"))
+}
+
+/// Load the starred wallpapers. A missing file means no favorites yet.
+pub fn load() -> Result<BTreeSet<PathBuf>> {
+  load_from(&favorites_file()?)
+}
+
+/// Overwrite the favorites file with `favorites`
+pub fn save(favorites: &BTreeSet<PathBuf>) -> Result<()> {
+  save_to(&favorites_file()?, favorites)
+}
+
+/// Starred wallpapers that still exist on disk
+pub fn existing() -> Result<Vec<PathBuf>> {
+  Ok(load()?.into_iter().filter(|path| path.is_file()).collect())
+}
+
+fn load_from(file: &Path) -> Result<BTreeSet<PathBuf>> {
+  if !file.exists() {
+    return Ok(BTreeSet::new());
+  }
+
+  let contents = fs::read_to_string(file).with_context(|| format!("Failed to read favorites file: {}", file.display()))?;
+  if contents.trim().is_empty() {
+    return Ok(BTreeSet::new());
+  }
+
+  serde_json::from_str(&contents).with_context(|| format!("Failed to parse favorites file: {}", file.display()))
+}
+
+fn save_to(file: &Path, favorites: &BTreeSet<PathBuf>) -> Result<()> {
+  if let Some(parent) = file.parent() {
+    fs::create_dir_all(parent).context("Failed to create favorites directory")?;
+  }
+
+  let mut json = serde_json::to_string_pretty(favorites).context("Failed to serialize favorites")?;
+  json.push('\n');
+  fs::write(file, json).with_context(|| format!("Failed to write favorites file: {}", file.display()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  #[test]
+  fn test_round_trip() {
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("nested/favorites.json");
+
+    let favorites: BTreeSet<PathBuf> = [PathBuf::from("/walls/b.jpg"), PathBuf::from("/walls/a.jpg")].into();
+    save_to(&file, &favorites).unwrap();
+
+    assert_eq!(load_from(&file).unwrap(), favorites);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "[\n  \"/walls/a.jpg\",\n  \"/walls/b.jpg\"\n]\n");
+  }
+
+  #[test]
+  fn test_missing_or_empty_file() {
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("favorites.json");
+    assert!(load_from(&file).unwrap().is_empty());
+
+    fs::write(&file, "\n").unwrap();
+    assert!(load_from(&file).unwrap().is_empty());
+  }
+}
@@ -0,0 +1,167 @@
+//! Wallpaper history
+//!
+//! Every successfully applied wallpaper is appended as one JSON object per line to
+//! `history.jsonl` in the state directory (see [`crate::paths`]). The file only ever grows at the end;
+//! once it exceeds the configured limit the oldest lines are dropped.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// A single applied wallpaper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+  /// Path of the wallpaper image
+  pub path: PathBuf,
+
+  /// Where the wallpaper came from (e.g. "local", "wallhaven")
+  pub source: String,
+
+  /// When it was applied
+  pub timestamp: DateTime<Utc>,
+}
+
+impl HistoryEntry {
+  pub fn new(path: &Path, source: &str) -> Self {
+    Self {
+      path: path.to_path_buf(),
+      source: source.to_string(),
+      timestamp: Utc::now(),
+    }
+  }
+}
+
+/// Get the history file path
+pub fn history_file() -> Result<PathBuf> {
+  Ok(crate::paths::state_dir()?.join("history.jsonl"))
+}
+
+/// Append an entry to the history file, keeping at most `max_entries` lines
+pub fn record(entry: &HistoryEntry, max_entries: usize) -> Result<()> {
+  append_entry(&history_file()?, entry, max_entries)
+}
+
+/// Most recent entries, newest first
+pub fn recent(limit: usize) -> Result<Vec<HistoryEntry>> {
+  let mut entries = read_entries(&history_file()?)?;
+  entries.reverse();
+  entries.truncate(limit);
+  Ok(entries)
+}
+
+/// Remove all history entries
+pub fn clear() -> Result<()> {
+  let file = history_file()?;
+  if file.exists() {
+    fs::write(&file, "").with_context(|| format!("Failed to clear history: {}", file.display()))?;
+  }
+  Ok(())
+}
+
+fn append_entry(file: &Path, entry: &HistoryEntry, max_entries: usize) -> Result<()> {
+  if let Some(parent) = file.parent() {
+    fs::create_dir_all(parent).context("Failed to create history directory")?;
+  }
+
+  let line = serde_json::to_string(entry).context("Failed to serialize history entry")?;
+
+  let mut handle = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(file)
+    .with_context(|| format!("Failed to open history file: {}", file.display()))?;
+  writeln!(handle, "{}", line).context("Failed to write history entry")?;
+  drop(handle);
+
+  trim(file, max_entries)
+}
+
+/// Drop the oldest lines once the file grows past `max_entries`
+fn trim(file: &Path, max_entries: usize) -> Result<()> {
+  let contents = fs::read_to_string(file).with_context(|| format!("Failed to read history file: {}", file.display()))?;
+  let lines: Vec<&str> = contents.lines().filter(|line| !line.trim().is_empty()).collect();
+
+  if lines.len() <= max_entries {
+    return Ok(());
+  }
+
+  debug!("Trimming history from {} to {} entries", lines.len(), max_entries);
+  let mut kept = lines[lines.len() - max_entries..].join("\n");
+  if !kept.is_empty() {
+    kept.push('\n');
+  }
+  fs::write(file, kept).with_context(|| format!("Failed to trim history file: {}", file.display()))
+}
+
+/// Read all entries, oldest first. Lines that fail to parse are skipped.
+fn read_entries(file: &Path) -> Result<Vec<HistoryEntry>> {
+  if !file.exists() {
+    return Ok(Vec::new());
+  }
+
+  let contents = fs::read_to_string(file).with_context(|| format!("Failed to read history file: {}", file.display()))?;
+
+  Ok(
+    contents
+      .lines()
+      .filter_map(|line| match serde_json::from_str(line) {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+          if !line.trim().is_empty() {
+            debug!("Skipping malformed history line: {}", e);
+          }
+          None
+        }
+      })
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  #[test]
+  fn test_append_and_read() {
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("history.jsonl");
+
+    append_entry(&file, &HistoryEntry::new(Path::new("/walls/a.jpg"), "local"), 10).unwrap();
+    append_entry(&file, &HistoryEntry::new(Path::new("/walls/b.jpg"), "wallhaven"), 10).unwrap();
+
+    let entries = read_entries(&file).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].path, PathBuf::from("/walls/a.jpg"));
+    assert_eq!(entries[1].source, "wallhaven");
+  }
+
+  #[test]
+  fn test_history_is_capped() {
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("history.jsonl");
+
+    for i in 0..5 {
+      append_entry(&file, &HistoryEntry::new(Path::new(&format!("/walls/{}.jpg", i)), "local"), 3).unwrap();
+    }
+
+    let entries = read_entries(&file).unwrap();
+    let paths: Vec<_> = entries.iter().map(|e| e.path.to_string_lossy().into_owned()).collect();
+    assert_eq!(paths, vec!["/walls/2.jpg", "/walls/3.jpg", "/walls/4.jpg"]);
+  }
+
+  #[test]
+  fn test_malformed_lines_skipped() {
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("history.jsonl");
+    fs::write(&file, "not json\n").unwrap();
+
+    append_entry(&file, &HistoryEntry::new(Path::new("/walls/a.jpg"), "local"), 10).unwrap();
+
+    assert_eq!(read_entries(&file).unwrap().len(), 1);
+  }
+}
@@ -0,0 +1,73 @@
+//! Hue buckets shared by the accent color integrations
+//!
+//! Desktops only offer a handful of named accent colors. The macOS and GNOME
+//! integrations both sort the wallpaper's dominant color into these buckets and
+//! then map the bucket onto their own palette, so they agree on any wallpaper.
+
+/// Coarse color family of an RGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HueBucket {
+  /// Gray or too desaturated to have a meaningful hue
+  Neutral,
+  Red,
+  Orange,
+  Yellow,
+  Green,
+  Teal,
+  Blue,
+  Purple,
+  Pink,
+}
+
+impl HueBucket {
+  /// Bucket an 8-bit RGB color by its hue
+  pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+    let max = r.max(g).max(b) as f32;
+    let min = r.min(g).min(b) as f32;
+
+    if max - min < 30.0 {
+      return Self::Neutral;
+    }
+
+    let r = r as f32;
+    let g = g as f32;
+    let b = b as f32;
+
+    // Approximate hue (0-360)
+    let hue = if max == r {
+      60.0 * (((g - b) / (max - min)) % 6.0)
+    } else if max == g {
+      60.0 * (((b - r) / (max - min)) + 2.0)
+    } else {
+      60.0 * (((r - g) / (max - min)) + 4.0)
+    };
+    let hue = if hue < 0.0 { hue + 360.0 } else { hue };
+
+    match hue as u16 {
+      0..=15 | 346..=360 => Self::Red,
+      16..=45 => Self::Orange,
+      46..=70 => Self::Yellow,
+      71..=165 => Self::Green,
+      166..=195 => Self::Teal,
+      196..=260 => Self::Blue,
+      261..=290 => Self::Purple,
+      291..=345 => Self::Pink,
+      _ => Self::Blue,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_hue_buckets() {
+    assert_eq!(HueBucket::from_rgb(255, 50, 50), HueBucket::Red);
+    assert_eq!(HueBucket::from_rgb(255, 140, 0), HueBucket::Orange);
+    assert_eq!(HueBucket::from_rgb(50, 200, 50), HueBucket::Green);
+    assert_eq!(HueBucket::from_rgb(0, 180, 180), HueBucket::Teal);
+    assert_eq!(HueBucket::from_rgb(50, 100, 255), HueBucket::Blue);
+    assert_eq!(HueBucket::from_rgb(128, 128, 128), HueBucket::Neutral);
+  }
+}
@@ -0,0 +1,243 @@
+//! Live color updates for running terminal apps
+//!
+//! Targets come from `integration.notify_apps`, one entry per app with an
+//! optional address after a colon:
+//!
+//! - `kitty` or `kitty:unix:/tmp/kitty` — `kitty @ set-colors` with the rendered
+//!   `colors-kitty.conf` (needs `allow_remote_control` in kitty.conf)
+//! - `nvim` or `nvim:/run/user/1000/nvim.1234.0` — writes `colors-nvim.lua` and
+//!   sources it in each running Neovim via `nvim --server <addr> --remote-send`
+//!
+//! Without an address, kitty uses `$KITTY_LISTEN_ON` and every Neovim server
+//! socket in the runtime directory is notified. Apps that aren't running are
+//! skipped quietly.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+use tracing::{debug, warn};
+
+use crate::colors::ColorScheme;
+
+/// Rendered by the kitty template
+const KITTY_CONF: &str = "colors-kitty.conf";
+/// Written here for Neovim to source
+const NVIM_LUA: &str = "colors-nvim.lua";
+
+/// An app to notify, with its remote control address if one was given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifyTarget {
+  Kitty { socket: Option<String> },
+  Neovim { server: Option<String> },
+}
+
+impl NotifyTarget {
+  /// Parse a `name[:address]` entry; `None` for apps we don't know
+  pub fn parse(entry: &str) -> Option<Self> {
+    let (name, address) = match entry.split_once(':') {
+      Some((name, address)) => (name, Some(address.trim().to_string()).filter(|a| !a.is_empty())),
+      None => (entry, None),
+    };
+
+    match name.trim().to_lowercase().as_str() {
+      "kitty" => Some(Self::Kitty { socket: address }),
+      "nvim" | "neovim" => Some(Self::Neovim { server: address }),
+      _ => None,
+    }
+  }
+
+  /// The app's process name, as used in template manifests' reload section
+  pub fn process_name(&self) -> &'static str {
+    match self {
+      Self::Kitty { .. } => "kitty",
+      Self::Neovim { .. } => "nvim",
+    }
+  }
+}
+
+/// Whether `process_name` gets its colors from `notify_color_change` with these entries
+pub fn is_notified(entries: &[String], process_name: &str) -> bool {
+  entries
+    .iter()
+    .filter_map(|entry| NotifyTarget::parse(entry))
+    .any(|target| target.process_name() == process_name)
+}
+
+/// Push a new color scheme to the configured apps
+pub fn notify_color_change(entries: &[String], scheme: &ColorScheme, output_dir: &Path) {
+  for entry in entries {
+    let result = match NotifyTarget::parse(entry) {
+      Some(NotifyTarget::Kitty { socket }) => notify_kitty(socket.as_deref(), &output_dir.join(KITTY_CONF)),
+      Some(NotifyTarget::Neovim { server }) => notify_neovim(server.as_deref(), scheme, &output_dir.join(NVIM_LUA)),
+      None => {
+        warn!("Unknown app '{}' in integration.notify_apps", entry);
+        continue;
+      }
+    };
+
+    if let Err(e) = result {
+      debug!("Could not notify {}: {:#}", entry, e);
+    }
+  }
+}
+
+fn notify_kitty(socket: Option<&str>, colors: &Path) -> Result<()> {
+  if !colors.exists() {
+    bail!("{} has not been rendered", colors.display());
+  }
+  if which::which("kitty").is_err() {
+    bail!("kitty is not installed");
+  }
+
+  let mut cmd = Command::new("kitty");
+  cmd.arg("@");
+  if let Some(socket) = socket {
+    cmd.args(["--to", socket]);
+  }
+  cmd.args(["set-colors", "--all"]).arg(colors);
+  run(cmd)?;
+
+  debug!("kitty colors updated");
+  Ok(())
+}
+
+fn notify_neovim(server: Option<&str>, scheme: &ColorScheme, lua_path: &Path) -> Result<()> {
+  std::fs::write(lua_path, neovim_lua(scheme)).with_context(|| format!("Failed to write {}", lua_path.display()))?;
+
+  let servers = match server {
+    Some(server) => vec![PathBuf::from(server)],
+    None => find_nvim_servers(),
+  };
+  if servers.is_empty() {
+    debug!("No running Neovim found");
+    return Ok(());
+  }
+  if which::which("nvim").is_err() {
+    bail!("nvim is not installed");
+  }
+
+  let keys = neovim_keys(lua_path);
+  for server in servers {
+    let mut cmd = Command::new("nvim");
+    cmd.arg("--server").arg(&server).args(["--remote-send", &keys]);
+    match run(cmd) {
+      Ok(()) => debug!("Neovim at {} updated", server.display()),
+      // Stale sockets are left behind by crashed instances
+      Err(e) => debug!("Neovim at {} did not respond: {:#}", server.display(), e),
+    }
+  }
+  Ok(())
+}
+
+/// Lua that sets the terminal palette and lets user config react through
+/// `User WallflowColors`. Nothing else is touched, so colorschemes stay in charge.
+fn neovim_lua(scheme: &ColorScheme) -> String {
+  let quote = |hex: String| format!("\"{}\"", hex);
+  let palette: Vec<String> = scheme.colors.iter().map(|c| quote(c.hex())).collect();
+
+  format!(
+    "-- Generated by wallflow\n\
+     local colors = {{\n  background = {},\n  foreground = {},\n  cursor = {},\n  dark = {},\n  palette = {{ {} }},\n}}\n\
+     for i, color in ipairs(colors.palette) do\n  vim.g[\"terminal_color_\" .. (i - 1)] = color\nend\n\
+     vim.g.wallflow_colors = colors\n\
+     vim.api.nvim_exec_autocmds(\"User\", {{ pattern = \"WallflowColors\", modeline = false }})\n",
+    quote(scheme.background.hex()),
+    quote(scheme.foreground.hex()),
+    quote(scheme.cursor.hex()),
+    scheme.is_dark,
+    palette.join(", ")
+  )
+}
+
+/// Keys that source the Lua file without leaving the current mode
+fn neovim_keys(lua_path: &Path) -> String {
+  let path = serde_json::to_string(&lua_path.to_string_lossy()).unwrap_or_default();
+  // `<` would start a key code in --remote-send
+  format!("<Cmd>lua dofile({})<CR>", path.replace('<', "<lt>"))
+}
+
+/// Server sockets Neovim creates by default (`$XDG_RUNTIME_DIR/nvim.<pid>.0`)
+fn find_nvim_servers() -> Vec<PathBuf> {
+  let Some(runtime_dir) = dirs::runtime_dir().or_else(|| std::env::var_os("TMPDIR").map(PathBuf::from)) else {
+    return Vec::new();
+  };
+  let Ok(entries) = std::fs::read_dir(runtime_dir) else {
+    return Vec::new();
+  };
+
+  entries
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("nvim.")))
+    .collect()
+}
+
+fn run(mut cmd: Command) -> Result<()> {
+  let output = cmd.stdin(Stdio::null()).output().context("Failed to execute command")?;
+  if !output.status.success() {
+    bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::colors::Rgb;
+
+  #[test]
+  fn test_parse_targets() {
+    assert_eq!(NotifyTarget::parse("kitty"), Some(NotifyTarget::Kitty { socket: None }));
+    assert_eq!(
+      NotifyTarget::parse("kitty:unix:/tmp/kitty"),
+      Some(NotifyTarget::Kitty {
+        socket: Some("unix:/tmp/kitty".to_string())
+      })
+    );
+    assert_eq!(
+      NotifyTarget::parse("Neovim:/run/user/1000/nvim.42.0"),
+      Some(NotifyTarget::Neovim {
+        server: Some("/run/user/1000/nvim.42.0".to_string())
+      })
+    );
+    assert_eq!(NotifyTarget::parse("nvim:"), Some(NotifyTarget::Neovim { server: None }));
+    assert_eq!(NotifyTarget::parse("alacritty"), None);
+  }
+
+  #[test]
+  fn test_is_notified() {
+    let entries = vec!["kitty:unix:/tmp/kitty".to_string(), "alacritty".to_string()];
+    assert!(is_notified(&entries, "kitty"));
+    assert!(!is_notified(&entries, "nvim"));
+    assert!(!is_notified(&entries, "alacritty"));
+  }
+
+  #[test]
+  fn test_neovim_lua() {
+    let colors = (0..16).map(|i| Rgb::from_u8(i * 10, 0, 0)).collect();
+    let scheme = ColorScheme::new(
+      "/walls/a.jpg".to_string(),
+      true,
+      Rgb::from_u8(0, 0, 0),
+      Rgb::from_u8(255, 255, 255),
+      Rgb::from_u8(255, 0, 0),
+      colors,
+    );
+    let lua = neovim_lua(&scheme);
+
+    assert!(lua.contains("background = \"#000000\""));
+    assert!(lua.contains("dark = true"));
+    assert!(lua.contains("palette = { \"#000000\", \"#0A0000\""));
+    assert!(lua.contains("pattern = \"WallflowColors\""));
+  }
+
+  #[test]
+  fn test_neovim_keys() {
+    assert_eq!(
+      neovim_keys(Path::new("/home/me/.cache/colors-nvim.lua")),
+      "<Cmd>lua dofile(\"/home/me/.cache/colors-nvim.lua\")<CR>"
+    );
+    assert!(neovim_keys(Path::new("/tmp/<odd>/c.lua")).contains("/tmp/<lt>odd>/c.lua"));
+  }
+}
@@ -0,0 +1,94 @@
+//! GNOME accent color integration
+//!
+//! GNOME 47 added `org.gnome.desktop.interface accent-color`, which takes one of
+//! nine named colors. The wallpaper's dominant color is bucketed by hue exactly
+//! like the macOS integration does, then mapped onto GNOME's palette.
+
+use super::accent::HueBucket;
+use crate::colors::Rgb;
+use std::process::Command;
+use tracing::{debug, info};
+
+/// GNOME's named accent colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GnomeAccent {
+  Blue,
+  Teal,
+  Green,
+  Yellow,
+  Orange,
+  Red,
+  Pink,
+  Purple,
+  Slate,
+}
+
+impl GnomeAccent {
+  /// Value for the `accent-color` key
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Blue => "blue",
+      Self::Teal => "teal",
+      Self::Green => "green",
+      Self::Yellow => "yellow",
+      Self::Orange => "orange",
+      Self::Red => "red",
+      Self::Pink => "pink",
+      Self::Purple => "purple",
+      Self::Slate => "slate",
+    }
+  }
+
+  /// Suggest an accent color based on dominant color RGB values
+  pub fn from_dominant_color(r: u8, g: u8, b: u8) -> Self {
+    match HueBucket::from_rgb(r, g, b) {
+      HueBucket::Neutral => Self::Slate,
+      HueBucket::Red => Self::Red,
+      HueBucket::Orange => Self::Orange,
+      HueBucket::Yellow => Self::Yellow,
+      HueBucket::Green => Self::Green,
+      HueBucket::Teal => Self::Teal,
+      HueBucket::Blue => Self::Blue,
+      HueBucket::Purple => Self::Purple,
+      HueBucket::Pink => Self::Pink,
+    }
+  }
+}
+
+/// Set GNOME's accent color from the wallpaper's dominant color. Does nothing outside GNOME.
+pub fn sync_gnome_accent(dominant: Rgb) {
+  let is_gnome = std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.to_lowercase().contains("gnome"));
+  if !is_gnome || which::which("gsettings").is_err() {
+    debug!("Not on GNOME, skipping accent color sync");
+    return;
+  }
+
+  let to_u8 = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0) as u8;
+  let accent = GnomeAccent::from_dominant_color(to_u8(dominant.r), to_u8(dominant.g), to_u8(dominant.b));
+
+  match Command::new("gsettings")
+    .args(["set", "org.gnome.desktop.interface", "accent-color", accent.name()])
+    .output()
+  {
+    Ok(output) if output.status.success() => info!("GNOME accent color set to {}", accent.name()),
+    // Older GNOME releases don't have the key
+    Ok(output) => debug!(
+      "Failed to set GNOME accent color (needs GNOME 47+): {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    ),
+    Err(e) => debug!("Failed to run gsettings: {}", e),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_accent_from_dominant_color() {
+    assert_eq!(GnomeAccent::from_dominant_color(255, 50, 50), GnomeAccent::Red);
+    assert_eq!(GnomeAccent::from_dominant_color(0, 180, 180), GnomeAccent::Teal);
+    assert_eq!(GnomeAccent::from_dominant_color(50, 100, 255), GnomeAccent::Blue);
+    assert_eq!(GnomeAccent::from_dominant_color(128, 128, 128), GnomeAccent::Slate);
+  }
+}
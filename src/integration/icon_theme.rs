@@ -0,0 +1,127 @@
+//! Icon theme switching
+//!
+//! Switches between a light and a dark icon theme to match the color scheme
+//! extracted from the wallpaper. GNOME (and other GTK desktops) are updated via
+//! `gsettings`, KDE Plasma via `kdeglobals`.
+
+use crate::config::IconThemesConfig;
+use std::process::Command;
+use tracing::{debug, info, warn};
+
+/// Desktops with a known way to change the icon theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IconDesktop {
+  Gnome,
+  Kde,
+}
+
+/// Switch to the configured light or dark icon theme
+pub fn sync_icon_theme(is_dark: bool, themes: &IconThemesConfig) {
+  let Some(theme) = theme_for(is_dark, themes) else {
+    debug!(
+      "No {} icon theme configured, skipping icon theme sync",
+      if is_dark { "dark" } else { "light" }
+    );
+    return;
+  };
+
+  let Some(desktop) = detect_desktop() else {
+    debug!("No supported desktop for icon theme sync");
+    return;
+  };
+
+  let applied = match desktop {
+    IconDesktop::Gnome => set_gnome_icon_theme(theme),
+    IconDesktop::Kde => set_kde_icon_theme(theme),
+  };
+
+  if applied {
+    info!("Icon theme set to {}", theme);
+  }
+}
+
+fn theme_for(is_dark: bool, themes: &IconThemesConfig) -> Option<&str> {
+  let theme = if is_dark { &themes.dark } else { &themes.light };
+  theme.as_deref().filter(|name| !name.is_empty())
+}
+
+fn detect_desktop() -> Option<IconDesktop> {
+  let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+  desktop_from_env(&desktop).or_else(|| which::which("gsettings").is_ok().then_some(IconDesktop::Gnome))
+}
+
+/// Map `XDG_CURRENT_DESKTOP` (e.g. "ubuntu:GNOME", "KDE") to a desktop
+fn desktop_from_env(desktop: &str) -> Option<IconDesktop> {
+  let desktop = desktop.to_lowercase();
+  if desktop.contains("kde") {
+    Some(IconDesktop::Kde)
+  } else if desktop.contains("gnome") || desktop.contains("unity") || desktop.contains("budgie") {
+    Some(IconDesktop::Gnome)
+  } else {
+    None
+  }
+}
+
+fn set_gnome_icon_theme(theme: &str) -> bool {
+  run("gsettings", &["set", "org.gnome.desktop.interface", "icon-theme", theme])
+}
+
+fn set_kde_icon_theme(theme: &str) -> bool {
+  let Some(kwriteconfig) = ["kwriteconfig6", "kwriteconfig5"].into_iter().find(|cmd| which::which(cmd).is_ok()) else {
+    warn!("kwriteconfig not found, cannot change KDE icon theme");
+    return false;
+  };
+
+  if !run(kwriteconfig, &["--file", "kdeglobals", "--group", "Icons", "--key", "Theme", theme]) {
+    return false;
+  }
+
+  // Tell running apps to reload their icons
+  run(
+    "dbus-send",
+    &["--session", "--type=signal", "/KIconLoader", "org.kde.KIconLoader.iconChanged", "int32:0"],
+  );
+  true
+}
+
+fn run(program: &str, args: &[&str]) -> bool {
+  match Command::new(program).args(args).output() {
+    Ok(output) if output.status.success() => true,
+    Ok(output) => {
+      warn!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+      false
+    }
+    Err(e) => {
+      debug!("Failed to run {}: {}", program, e);
+      false
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_theme_for() {
+    let themes = IconThemesConfig {
+      light: Some("Papirus-Light".to_string()),
+      dark: Some("Papirus-Dark".to_string()),
+    };
+    assert_eq!(theme_for(true, &themes), Some("Papirus-Dark"));
+    assert_eq!(theme_for(false, &themes), Some("Papirus-Light"));
+
+    let only_dark = IconThemesConfig {
+      light: Some(String::new()),
+      dark: Some("Papirus-Dark".to_string()),
+    };
+    assert_eq!(theme_for(false, &only_dark), None);
+  }
+
+  #[test]
+  fn test_desktop_from_env() {
+    assert_eq!(desktop_from_env("ubuntu:GNOME"), Some(IconDesktop::Gnome));
+    assert_eq!(desktop_from_env("KDE"), Some(IconDesktop::Kde));
+    assert_eq!(desktop_from_env("Hyprland"), None);
+  }
+}
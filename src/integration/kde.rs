@@ -1,18 +1,93 @@
+//! KDE Plasma wallpaper integration
+//!
+//! Plasma is driven through its scripting interface on D-Bus
+//! (`org.kde.plasmashell /PlasmaShell evaluateScript`), which works on every
+//! Plasma version and can set the fill mode and target single screens or
+//! activities. `plasma-apply-wallpaperimage` is the fallback when the D-Bus
+//! call fails.
+
+use crate::display::Display;
+use crate::wallpaper::backends::traits::{MonitorSelection, WallpaperOptions, WallpaperScaling};
+use anyhow::{Context, Result, bail};
 use std::path::Path;
 use tokio::process::Command as AsyncCommand;
 use tracing::{debug, warn};
 
+/// Where and how Plasma should show the wallpaper
+#[derive(Debug, Clone, Default)]
+pub struct KdeWallpaperOptions {
+  pub scaling: WallpaperScaling,
+  /// Only the desktop on this screen (Plasma's 0-based screen number)
+  pub screen: Option<u32>,
+  /// Only desktops in this activity (activity id); all activities otherwise
+  pub activity: Option<String>,
+}
+
+impl KdeWallpaperOptions {
+  /// The scaling and monitor the wallpaper backend was given, on every activity
+  pub fn from_wallpaper_options(options: &WallpaperOptions) -> Self {
+    let displays = match options.monitor {
+      MonitorSelection::Named(_) => crate::display::get_displays().unwrap_or_default(),
+      _ => Vec::new(),
+    };
+    Self {
+      scaling: options.scaling.clone(),
+      screen: plasma_screen(&options.monitor, &displays),
+      activity: None,
+    }
+  }
+}
+
+/// Plasma's screen number for a monitor selection, `None` for every screen
+///
+/// Plasma numbers the primary screen 0. Named outputs are looked up in the
+/// detected displays, and a plain number is taken as the screen number.
+fn plasma_screen(monitor: &MonitorSelection, displays: &[Display]) -> Option<u32> {
+  match monitor {
+    MonitorSelection::All => None,
+    MonitorSelection::Primary => Some(0),
+    MonitorSelection::Named(name) => name.parse().ok().or_else(|| {
+      let index = displays.iter().position(|display| display.name.as_deref() == Some(name.as_str()));
+      if index.is_none() {
+        debug!("{} isn't a known display, setting the KDE wallpaper on every screen", name);
+      }
+      index.and_then(|index| u32::try_from(index).ok())
+    }),
+  }
+}
+
 /// Check if KDE Plasma is available by looking for plasma-apply-wallpaperimage
 fn is_kde_available() -> bool {
   which::which("plasma-apply-wallpaperimage").is_ok()
 }
 
-/// Set KDE Plasma wallpaper using plasma-apply-wallpaperimage
+/// Whether we're running inside a Plasma session, where plasmashell answers on D-Bus
+fn is_plasma_session() -> bool {
+  std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.to_lowercase().contains("kde"))
+}
+
+/// Set KDE Plasma wallpaper on every screen and activity
 ///
 /// This ensures that KDE applications inherit the wallpaper colors correctly.
 /// The function is called after the main wallpaper backend sets the wallpaper,
 /// so that both the compositor and KDE are in sync.
+#[allow(dead_code)]
 pub async fn set_kde_wallpaper(wallpaper_path: &Path) {
+  set_kde_wallpaper_with(wallpaper_path, &KdeWallpaperOptions::default()).await;
+}
+
+/// Set KDE Plasma wallpaper with a fill mode, optionally on one screen or activity
+pub async fn set_kde_wallpaper_with(wallpaper_path: &Path, options: &KdeWallpaperOptions) {
+  if is_plasma_session() && which::which("dbus-send").is_ok() {
+    match evaluate_script(&wallpaper_script(wallpaper_path, options)).await {
+      Ok(()) => {
+        debug!("KDE Plasma wallpaper set via D-Bus");
+        return;
+      }
+      Err(e) => debug!("Plasma scripting failed ({:#}), trying plasma-apply-wallpaperimage", e),
+    }
+  }
+
   if !is_kde_available() {
     debug!("plasma-apply-wallpaperimage not available, skipping KDE integration");
     return;
@@ -34,3 +109,105 @@ pub async fn set_kde_wallpaper(wallpaper_path: &Path) {
     }
   }
 }
+
+/// Run a Plasma desktop script through plasmashell
+async fn evaluate_script(script: &str) -> Result<()> {
+  let output = AsyncCommand::new("dbus-send")
+    .args([
+      "--session",
+      "--print-reply",
+      "--dest=org.kde.plasmashell",
+      "/PlasmaShell",
+      "org.kde.PlasmaShell.evaluateScript",
+    ])
+    .arg(format!("string:{}", script))
+    .output()
+    .await
+    .context("Failed to execute dbus-send")?;
+
+  if !output.status.success() {
+    bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+  }
+  Ok(())
+}
+
+/// Plasma's image wallpaper `FillMode` for a scaling mode
+fn fill_mode(scaling: &WallpaperScaling) -> u8 {
+  match scaling {
+    WallpaperScaling::Stretch => 0,
+    WallpaperScaling::Fit => 1,
+    WallpaperScaling::Fill => 2,
+    WallpaperScaling::Tile => 3,
+    WallpaperScaling::Center => 6,
+  }
+}
+
+/// Desktop script that points the matching desktops at the image
+fn wallpaper_script(wallpaper_path: &Path, options: &KdeWallpaperOptions) -> String {
+  // JSON string literals are valid JavaScript, which takes care of quoting
+  let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+
+  let image = quote(&format!("file://{}", wallpaper_path.display()));
+  let desktops = match &options.activity {
+    Some(activity) => format!("desktopsForActivity({})", quote(activity)),
+    None => "desktops()".to_string(),
+  };
+  let screen = options.screen.map_or(-1, i64::from);
+
+  format!(
+    "{desktops}.forEach(function (d) {{\
+      if ({screen} >= 0 && d.screen != {screen}) return;\
+      d.wallpaperPlugin = \"org.kde.image\";\
+      d.currentConfigGroup = [\"Wallpaper\", \"org.kde.image\", \"General\"];\
+      d.writeConfig(\"Image\", {image});\
+      d.writeConfig(\"FillMode\", {fill});\
+    }});",
+    fill = fill_mode(&options.scaling)
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_plasma_screen() {
+    let display = |name: &str| Display {
+      name: Some(name.to_string()),
+      resolution: crate::display::Resolution::new(1920, 1080),
+      primary: false,
+      position: None,
+    };
+    let displays = [display("DP-1"), display("HDMI-A-1")];
+
+    assert_eq!(plasma_screen(&MonitorSelection::All, &displays), None);
+    assert_eq!(plasma_screen(&MonitorSelection::Primary, &displays), Some(0));
+    assert_eq!(plasma_screen(&MonitorSelection::Named("HDMI-A-1".to_string()), &displays), Some(1));
+    assert_eq!(plasma_screen(&MonitorSelection::Named("2".to_string()), &displays), Some(2));
+    assert_eq!(plasma_screen(&MonitorSelection::Named("DP-9".to_string()), &displays), None);
+  }
+
+  #[test]
+  fn test_wallpaper_script_defaults() {
+    let script = wallpaper_script(Path::new("/walls/a \"b\".jpg"), &KdeWallpaperOptions::default());
+
+    assert!(script.starts_with("desktops().forEach"));
+    assert!(script.contains("if (-1 >= 0"));
+    assert!(script.contains(r#"d.writeConfig("Image", "file:///walls/a \"b\".jpg");"#));
+    assert!(script.contains(r#"d.writeConfig("FillMode", 2);"#));
+  }
+
+  #[test]
+  fn test_wallpaper_script_screen_and_activity() {
+    let options = KdeWallpaperOptions {
+      scaling: WallpaperScaling::Center,
+      screen: Some(1),
+      activity: Some("a1b2".to_string()),
+    };
+    let script = wallpaper_script(Path::new("/walls/a.jpg"), &options);
+
+    assert!(script.starts_with(r#"desktopsForActivity("a1b2").forEach"#));
+    assert!(script.contains("if (1 >= 0 && d.screen != 1) return;"));
+    assert!(script.contains(r#"d.writeConfig("FillMode", 6);"#));
+  }
+}
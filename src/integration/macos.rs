@@ -9,6 +9,7 @@
 //! and GTK/Qt applications based on wallpaper colors, macOS theming is
 //! limited to system-level settings with a fixed accent color palette.
 
+use super::accent::HueBucket;
 use std::path::Path;
 use tokio::process::Command as AsyncCommand;
 use tracing::{debug, info, warn};
@@ -64,40 +65,16 @@ impl AccentColor {
   /// Suggest an accent color based on dominant color RGB values
   /// This is a simple heuristic - not as sophisticated as pywal
   pub fn from_dominant_color(r: u8, g: u8, b: u8) -> Self {
-    // Convert to HSL-ish hue for color matching
-    let max = r.max(g).max(b) as f32;
-    let min = r.min(g).min(b) as f32;
-
-    // If grayscale or very desaturated, use multicolor
-    if max - min < 30.0 {
-      return Self::Multicolor;
-    }
-
-    let r = r as f32;
-    let g = g as f32;
-    let b = b as f32;
-
-    // Calculate approximate hue (0-360)
-    let hue = if max == r {
-      60.0 * (((g - b) / (max - min)) % 6.0)
-    } else if max == g {
-      60.0 * (((b - r) / (max - min)) + 2.0)
-    } else {
-      60.0 * (((r - g) / (max - min)) + 4.0)
-    };
-
-    let hue = if hue < 0.0 { hue + 360.0 } else { hue };
-
-    // Map hue to accent colors
-    match hue as u16 {
-      0..=15 | 346..=360 => Self::Red,
-      16..=45 => Self::Orange,
-      46..=70 => Self::Yellow,
-      71..=165 => Self::Green,
-      166..=260 => Self::Blue,
-      261..=290 => Self::Purple,
-      291..=345 => Self::Pink,
-      _ => Self::Blue,
+    // macOS has no teal, so it joins blue
+    match HueBucket::from_rgb(r, g, b) {
+      HueBucket::Neutral => Self::Multicolor,
+      HueBucket::Red => Self::Red,
+      HueBucket::Orange => Self::Orange,
+      HueBucket::Yellow => Self::Yellow,
+      HueBucket::Green => Self::Green,
+      HueBucket::Teal | HueBucket::Blue => Self::Blue,
+      HueBucket::Purple => Self::Purple,
+      HueBucket::Pink => Self::Pink,
     }
   }
 }
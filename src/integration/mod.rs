@@ -4,13 +4,27 @@
 //! and tools for enhanced wallpaper management:
 //!
 //! - **KDE Plasma**: Ensures KDE apps inherit wallpaper colors
+//! - **Icon themes**: Follows the wallpaper's light/dark scheme on GNOME and KDE
+//! - **GNOME**: Sets the accent color from the wallpaper
+//! - **Apps**: Pushes new colors to running kitty and Neovim instances
 //! - **macOS**: Controls system appearance and accent colors
 
+mod accent;
+mod apps;
+mod gnome;
+mod icon_theme;
 mod kde;
 
 #[cfg(target_os = "macos")]
 pub mod macos;
 
+pub use apps::{NotifyTarget, is_notified, notify_color_change};
+#[allow(unused_imports)]
+pub use gnome::{GnomeAccent, sync_gnome_accent};
+pub use icon_theme::sync_icon_theme;
+pub use kde::{KdeWallpaperOptions, set_kde_wallpaper_with};
+// For library users; wallflow passes its own options to set_kde_wallpaper_with
+#[allow(unused_imports)]
 pub use kde::set_kde_wallpaper;
 
 #[cfg(target_os = "macos")]
@@ -17,12 +17,30 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Custom sources implement [`WallpaperDownloader`] and are added to a
+//! [`DownloaderRegistry`], which downloads from them like any built-in source:
+//!
+//! ```no_run
+//! # use wallflow::{Config, DownloadOptions, DownloaderRegistry, WallpaperDownloader};
+//! # async fn example(my_source: Box<dyn WallpaperDownloader + Send + Sync>) -> anyhow::Result<()> {
+//! let mut registry = DownloaderRegistry::new();
+//! registry.register(my_source);
+//!
+//! let config = Config::load_or_default()?;
+//! let wallpaper = registry.download_from_source("my-source", &config, &[], &DownloadOptions::default()).await?;
+//! # Ok(())
+//! # }
+//! ```
 
 pub mod colors;
 pub mod config;
 pub mod display;
 pub mod downloaders;
+pub mod favorites;
+pub mod history;
 pub mod integration;
+pub mod paths;
 pub mod platform;
 pub mod templates;
 pub mod wallpaper;
@@ -30,7 +48,10 @@ pub mod wallpaper;
 // Re-export main types for convenience
 pub use colors::{ColorExtractor, ColorScheme, ExtractionOptions, Rgb};
 pub use config::Config;
-pub use downloaders::traits::Wallpaper;
-pub use downloaders::{DownloadOptions, download_from_source, list_sources};
+pub use downloaders::error::DownloaderError;
+pub use downloaders::registry::DownloaderRegistry;
+pub use downloaders::traits::{Metadata, Wallpaper, WallpaperDownloader};
+pub use downloaders::{DownloadOptions, download_batch, download_from_source, list_sources};
 pub use templates::TemplateEngine;
-pub use wallpaper::apply_wallpaper;
+pub use wallpaper::backends::traits::MonitorSelection;
+pub use wallpaper::{AppliedWallpaper, apply_wallpaper, apply_wallpaper_to_monitor};
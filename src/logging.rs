@@ -2,9 +2,10 @@
 //!
 //! This module provides comprehensive logging configuration that integrates
 //! with the YAML config file, supporting:
-//! - Multiple log levels (debug, info, warn, error)
+//! - Multiple log levels (debug, info, warn, error), or per-module directives
 //! - Optional file output with automatic directory creation
 //! - Timestamp formatting control
+//! - Text, JSON or logfmt output for log collectors
 //! - CLI verbose override capability
 //!
 //! Educational aspects:
@@ -13,10 +14,20 @@
 //! - Illustrates conditional compilation for different output modes
 
 use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::fmt::Write as _;
 use std::fs;
 use std::path::Path;
-use tracing_subscriber::EnvFilter;
-use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::FmtContext;
+use tracing_subscriber::fmt::format::{self, FormatEvent, FormatFields};
+use tracing_subscriber::fmt::writer::{BoxMakeWriter, MakeWriterExt};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
 use crate::config::{Config, LoggingConfig};
 
@@ -27,14 +38,16 @@ pub fn init_logging(config: &Config, verbose_override: bool) -> Result<()> {
 
   // Create environment filter
   let env_filter = create_env_filter(&effective_config.level)?;
+  let format = LogFormat::parse(&effective_config.format)?;
+  let rotation = parse_rotation(&effective_config.rotation)?;
 
   // Build the subscriber based on whether file logging is enabled
   match effective_config.file {
     Some(ref file_path) => {
-      init_with_file_logging(&effective_config, file_path, env_filter)?;
+      init_with_file_logging(&effective_config, format, rotation, file_path, env_filter)?;
     }
     None => {
-      init_console_only_logging(&effective_config, env_filter)?;
+      init_console_only_logging(&effective_config, format, env_filter)?;
     }
   }
 
@@ -60,6 +73,9 @@ fn resolve_logging_config(config: &LoggingConfig, verbose_override: bool) -> Eff
     level: if verbose_override { "debug".to_string() } else { config.level.clone() },
     file,
     timestamp: config.timestamp,
+    format: config.format.clone(),
+    rotation: config.rotation.clone(),
+    max_files: config.max_files,
     verbose_override,
   }
 }
@@ -72,42 +88,64 @@ struct EffectiveLoggingConfig {
   level: String,
   file: Option<String>,
   timestamp: bool,
+  format: String,
+  rotation: String,
+  max_files: usize,
   verbose_override: bool,
 }
 
-/// Initialize console-only logging
-fn init_console_only_logging(config: &EffectiveLoggingConfig, env_filter: EnvFilter) -> Result<()> {
-  // Use a simpler approach without conditional timestamp types
-  if config.timestamp {
-    tracing_subscriber::fmt()
-      .with_env_filter(env_filter)
-      .with_target(false)
-      .with_thread_ids(false)
-      .with_file(false)
-      .with_line_number(false)
-      .with_level(true)
-      .with_ansi(true)
-      .try_init()
-      .map_err(|e| anyhow::anyhow!("Failed to initialize console logging with timestamps: {}", e))?;
-  } else {
-    tracing_subscriber::fmt()
-      .with_env_filter(env_filter)
-      .with_target(false)
-      .with_thread_ids(false)
-      .with_file(false)
-      .with_line_number(false)
-      .with_level(true)
-      .with_ansi(true)
-      .without_time()
-      .try_init()
-      .map_err(|e| anyhow::anyhow!("Failed to initialize console logging without timestamps: {}", e))?;
+/// How each log event is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+  /// Human-readable lines (the default)
+  Text,
+  /// One JSON object per line
+  Json,
+  /// `key=value` pairs, one event per line
+  Logfmt,
+}
+
+impl LogFormat {
+  /// Parse a `logging.format` value
+  pub fn parse(format: &str) -> Result<Self> {
+    match format.to_lowercase().as_str() {
+      "text" => Ok(Self::Text),
+      "json" => Ok(Self::Json),
+      "logfmt" => Ok(Self::Logfmt),
+      _ => Err(anyhow::anyhow!("Invalid log format: '{}'. Valid formats: text, json, logfmt", format)),
+    }
   }
+}
 
-  Ok(())
+/// Initialize console-only logging
+fn init_console_only_logging(config: &EffectiveLoggingConfig, format: LogFormat, env_filter: EnvFilter) -> Result<()> {
+  tracing_subscriber::registry()
+    .with(env_filter)
+    .with(format_layer(config, format, BoxMakeWriter::new(std::io::stderr)))
+    .try_init()
+    .map_err(|e| anyhow::anyhow!("Failed to initialize console logging: {}", e))
 }
 
 /// Initialize logging with both console and file output
-fn init_with_file_logging(config: &EffectiveLoggingConfig, file_path: &str, env_filter: EnvFilter) -> Result<()> {
+fn init_with_file_logging(
+  config: &EffectiveLoggingConfig,
+  format: LogFormat,
+  rotation: Rotation,
+  file_path: &str,
+  env_filter: EnvFilter,
+) -> Result<()> {
+  let file_appender = open_log_file(config, rotation, file_path)?;
+
+  // A single layer writes every event to both console and file, in the same format
+  tracing_subscriber::registry()
+    .with(env_filter)
+    .with(format_layer(config, format, BoxMakeWriter::new(std::io::stderr.and(file_appender))))
+    .try_init()
+    .map_err(|e| anyhow::anyhow!("Failed to initialize dual logging: {}", e))
+}
+
+/// Open the log file, creating its directory and pruning old rotated files
+fn open_log_file(config: &EffectiveLoggingConfig, rotation: Rotation, file_path: &str) -> Result<RollingFileAppender> {
   // Expand environment variables in file path
   let expanded_path = shellexpand::full(file_path).context("Failed to expand environment variables in log file path")?;
   let log_path = Path::new(expanded_path.as_ref());
@@ -117,55 +155,125 @@ fn init_with_file_logging(config: &EffectiveLoggingConfig, file_path: &str, env_
     fs::create_dir_all(parent_dir).with_context(|| format!("Failed to create log directory: {}", parent_dir.display()))?;
   }
 
-  // Create file appender
-  let file_appender = tracing_appender::rolling::never(
-    log_path.parent().unwrap_or_else(|| Path::new(".")),
-    log_path.file_name().unwrap_or_else(|| std::ffi::OsStr::new("wallflow.log")),
-  );
-
-  // For dual output, we'll use the simpler approach with a single subscriber
-  // that logs to both console and file
-  if config.timestamp {
-    tracing_subscriber::fmt()
-      .with_env_filter(env_filter)
-      .with_writer(std::io::stderr.and(file_appender))
-      .with_target(false) // Console settings
-      .with_thread_ids(false)
-      .with_file(false)
-      .with_line_number(false)
-      .with_level(true)
-      .with_ansi(true) // ANSI colors work on console, ignored in files
-      .try_init()
-      .map_err(|e| anyhow::anyhow!("Failed to initialize dual logging with timestamps: {}", e))?;
-  } else {
-    tracing_subscriber::fmt()
-      .with_env_filter(env_filter)
-      .with_writer(std::io::stderr.and(file_appender))
-      .with_target(false)
-      .with_thread_ids(false)
-      .with_file(false)
-      .with_line_number(false)
-      .with_level(true)
-      .with_ansi(true)
-      .without_time()
-      .try_init()
-      .map_err(|e| anyhow::anyhow!("Failed to initialize dual logging without timestamps: {}", e))?;
+  // Create file appender. Rotated files are named `<file>.<date>`; building the
+  // appender deletes the oldest ones beyond `max_files`, and so does each rollover.
+  let file_name = log_path.file_name().and_then(|name| name.to_str()).unwrap_or("wallflow.log");
+  let mut builder = RollingFileAppender::builder().rotation(rotation.clone()).filename_prefix(file_name);
+  if rotation != Rotation::NEVER {
+    builder = builder.max_log_files(config.max_files);
   }
+  builder
+    .build(log_path.parent().unwrap_or_else(|| Path::new(".")))
+    .with_context(|| format!("Failed to open log file: {}", log_path.display()))
+}
 
-  Ok(())
+/// Build the formatting layer for the configured output format
+fn format_layer<S>(config: &EffectiveLoggingConfig, format: LogFormat, writer: BoxMakeWriter) -> Box<dyn Layer<S> + Send + Sync>
+where
+  S: Subscriber + for<'a> LookupSpan<'a>,
+{
+  let layer = tracing_subscriber::fmt::layer()
+    .with_writer(writer)
+    .with_target(false)
+    .with_thread_ids(false)
+    .with_file(false)
+    .with_line_number(false)
+    .with_level(true);
+
+  match (format, config.timestamp) {
+    // ANSI colors are meant for the console; the file gets them too
+    (LogFormat::Text, true) => layer.with_ansi(true).boxed(),
+    (LogFormat::Text, false) => layer.with_ansi(true).without_time().boxed(),
+    (LogFormat::Json, true) => layer.json().with_ansi(false).boxed(),
+    (LogFormat::Json, false) => layer.json().with_ansi(false).without_time().boxed(),
+    (LogFormat::Logfmt, timestamp) => layer.with_ansi(false).event_format(Logfmt { timestamp }).boxed(),
+  }
+}
+
+/// Formats events as logfmt: `ts=... level=info msg="..." key=value`
+struct Logfmt {
+  timestamp: bool,
+}
+
+impl<S, N> FormatEvent<S, N> for Logfmt
+where
+  S: Subscriber + for<'a> LookupSpan<'a>,
+  N: for<'a> FormatFields<'a> + 'static,
+{
+  fn format_event(&self, _ctx: &FmtContext<'_, S, N>, mut writer: format::Writer<'_>, event: &Event<'_>) -> std::fmt::Result {
+    let mut line = String::new();
+    if self.timestamp {
+      write!(line, "ts={} ", chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true))?;
+    }
+    write!(line, "level={}", event.metadata().level().as_str().to_lowercase())?;
+    event.record(&mut LogfmtFields(&mut line));
+    writeln!(writer, "{}", line)
+  }
+}
+
+/// Appends an event's fields to a logfmt line
+struct LogfmtFields<'a>(&'a mut String);
+
+impl LogfmtFields<'_> {
+  fn push(&mut self, field: &Field, value: &str) {
+    let key = if field.name() == "message" { "msg" } else { field.name() };
+    let _ = write!(self.0, " {}={}", key, logfmt_value(value));
+  }
+}
+
+impl Visit for LogfmtFields<'_> {
+  fn record_str(&mut self, field: &Field, value: &str) {
+    self.push(field, value);
+  }
+
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    self.push(field, &format!("{:?}", value));
+  }
+}
+
+/// Quote a logfmt value when it contains spaces, quotes or `=`
+fn logfmt_value(value: &str) -> Cow<'_, str> {
+  if !value.is_empty() && !value.chars().any(|c| c.is_whitespace() || c == '"' || c == '=' || c == '\\') {
+    return Cow::Borrowed(value);
+  }
+
+  let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\t', "\\t");
+  Cow::Owned(format!("\"{}\"", escaped))
+}
+
+/// Parse a `logging.rotation` value
+fn parse_rotation(rotation: &str) -> Result<Rotation> {
+  match rotation.to_lowercase().as_str() {
+    "never" => Ok(Rotation::NEVER),
+    "daily" => Ok(Rotation::DAILY),
+    "hourly" => Ok(Rotation::HOURLY),
+    _ => Err(anyhow::anyhow!(
+      "Invalid log rotation: '{}'. Valid values: never, daily, hourly",
+      rotation
+    )),
+  }
 }
 
 /// Create environment filter for the specified log level
+///
+/// `level` is either a bare level applied to all of wallflow, or a list of
+/// `EnvFilter` directives (`wallflow=info,wallflow::downloaders=warn`) used as is.
 fn create_env_filter(level: &str) -> Result<EnvFilter> {
-  // Parse the level string to ensure it's valid
-  let normalized_level = normalize_log_level(level)?;
-
-  // Create filter that applies to wallflow and its modules
-  let filter_directive = format!("wallflow={}", normalized_level);
+  let filter_directive = if is_directive_list(level) {
+    level.trim().to_string()
+  } else {
+    // Create filter that applies to wallflow and its modules
+    format!("wallflow={}", normalize_log_level(level)?)
+  };
 
   EnvFilter::try_new(&filter_directive).with_context(|| format!("Invalid log level configuration: {}", level))
 }
 
+/// Whether `level` is a directive list rather than a bare level name
+fn is_directive_list(level: &str) -> bool {
+  level.contains(['=', ',', ':'])
+}
+
 /// Normalize log level string to valid tracing levels
 fn normalize_log_level(level: &str) -> Result<&str> {
   match level.to_lowercase().as_str() {
@@ -229,6 +337,9 @@ mod tests {
       level: "info".to_string(),
       file: Some("/tmp/test.log".to_string()),
       timestamp: true,
+      format: "text".to_string(),
+      rotation: "never".to_string(),
+      max_files: 7,
     };
 
     // Test without verbose override
@@ -250,6 +361,9 @@ mod tests {
       level: "info".to_string(),
       file: Some("".to_string()),
       timestamp: true,
+      format: "text".to_string(),
+      rotation: "never".to_string(),
+      max_files: 7,
     };
     let effective = resolve_logging_config(&config, false);
     assert!(effective.file.is_none());
@@ -260,6 +374,9 @@ mod tests {
       level: "info".to_string(),
       file: Some("   ".to_string()),
       timestamp: true,
+      format: "text".to_string(),
+      rotation: "never".to_string(),
+      max_files: 7,
     };
     let effective = resolve_logging_config(&config, false);
     assert!(effective.file.is_none());
@@ -270,8 +387,112 @@ mod tests {
       level: "info".to_string(),
       file: Some("/tmp/test.log".to_string()),
       timestamp: true,
+      format: "text".to_string(),
+      rotation: "never".to_string(),
+      max_files: 7,
     };
     let effective = resolve_logging_config(&config, false);
     assert_eq!(effective.file, Some("/tmp/test.log".to_string()));
   }
+
+  #[test]
+  fn test_log_format_variants() {
+    for (value, expected) in [
+      ("text", LogFormat::Text),
+      ("json", LogFormat::Json),
+      ("logfmt", LogFormat::Logfmt),
+      ("JSON", LogFormat::Json),
+    ] {
+      let config: LoggingConfig = serde_yaml::from_str(&format!("format: {}", value)).unwrap();
+      assert_eq!(LogFormat::parse(&config.format).unwrap(), expected);
+    }
+
+    let config: LoggingConfig = serde_yaml::from_str("level: info").unwrap();
+    assert_eq!(LogFormat::parse(&config.format).unwrap(), LogFormat::Text);
+
+    assert!(LogFormat::parse("xml").is_err());
+  }
+
+  #[test]
+  fn test_logfmt_value() {
+    assert_eq!(logfmt_value("info"), "info");
+    assert_eq!(logfmt_value(""), "\"\"");
+    assert_eq!(logfmt_value("Wallpaper rotated"), "\"Wallpaper rotated\"");
+    assert_eq!(logfmt_value("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+  }
+
+  #[test]
+  fn test_parse_rotation() {
+    assert_eq!(parse_rotation("never").unwrap(), Rotation::NEVER);
+    assert_eq!(parse_rotation("Daily").unwrap(), Rotation::DAILY);
+    assert_eq!(parse_rotation("hourly").unwrap(), Rotation::HOURLY);
+    assert!(parse_rotation("weekly").is_err());
+  }
+
+  #[test]
+  fn test_rotated_logs_are_pruned() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path().join("logs");
+    std::fs::create_dir(&dir).unwrap();
+    for day in 1..=5 {
+      std::fs::write(dir.join(format!("wallflow.log.2025-01-0{}", day)), "old").unwrap();
+      std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    std::fs::write(dir.join("other.txt"), "keep").unwrap();
+
+    let log_files = || {
+      let mut names: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with("wallflow.log"))
+        .collect();
+      names.sort();
+      names
+    };
+    let logging = |rotation: &str| {
+      let config: LoggingConfig = serde_yaml::from_str(&format!(
+        "file: {}\nrotation: {}\nmax_files: 3",
+        dir.join("wallflow.log").display(),
+        rotation
+      ))
+      .unwrap();
+      resolve_logging_config(&config, false)
+    };
+
+    // Without rotation nothing is pruned
+    let config = logging("never");
+    open_log_file(&config, parse_rotation(&config.rotation).unwrap(), config.file.as_deref().unwrap()).unwrap();
+    assert_eq!(log_files().len(), 6);
+
+    let config = logging("daily");
+    open_log_file(&config, parse_rotation(&config.rotation).unwrap(), config.file.as_deref().unwrap()).unwrap();
+    let names = log_files();
+    assert_eq!(names.len(), 3);
+    assert!(!names.contains(&"wallflow.log.2025-01-01".to_string()));
+    assert!(dir.join("other.txt").exists());
+  }
+
+  #[test]
+  fn test_open_log_file_creates_directory() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let path = tmp.path().join("nested/logs/wallflow.log");
+    let config = resolve_logging_config(&LoggingConfig::default(), false);
+
+    open_log_file(&config, Rotation::NEVER, path.to_str().unwrap()).unwrap();
+    assert!(path.parent().unwrap().is_dir());
+  }
+
+  #[test]
+  fn test_create_env_filter() {
+    assert!(!is_directive_list("debug"));
+    assert!(is_directive_list("wallflow=info,wallflow::downloaders=warn"));
+    assert!(is_directive_list("wallflow::wallpaper"));
+
+    assert_eq!(create_env_filter("Warning").unwrap().to_string(), "wallflow=warn");
+    let filter = create_env_filter("wallflow=info,wallflow::downloaders=warn").unwrap();
+    assert!(filter.to_string().contains("wallflow::downloaders=warn"));
+
+    assert!(create_env_filter("loud").is_err());
+    assert!(create_env_filter("wallflow=loud").is_err());
+  }
 }
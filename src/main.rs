@@ -5,11 +5,16 @@ use tracing::info;
 mod colors;
 mod config;
 mod daemon;
+mod daemon_control;
 mod daemon_status;
 mod display;
+mod doctor;
 mod downloaders;
+mod favorites;
+mod history;
 mod integration;
 mod logging;
+mod paths;
 mod platform;
 mod templates;
 mod tui;
@@ -46,6 +51,14 @@ struct Cli {
   #[arg(long)]
   no_set: bool,
 
+  /// Pick (and download) a wallpaper, then print the backend and options that would set it
+  #[arg(long)]
+  dry_run: bool,
+
+  /// Download for and set on a single monitor (e.g. DP-1)
+  #[arg(long)]
+  monitor: Option<String>,
+
   /// Skip color extraction and template rendering
   #[arg(long)]
   no_theme: bool,
@@ -55,6 +68,8 @@ struct Cli {
 enum Commands {
   /// Set wallpaper from local collection
   Local,
+  /// Set a random wallpaper from your favorites
+  Favorites,
   /// Download and set wallpaper from Wallhaven (accepts search terms)
   Wallhaven {
     /// Search terms (e.g., "nature mountains")
@@ -64,9 +79,15 @@ enum Commands {
   /// Set random photo from Picsum
   Picsum,
   /// Download NASA Astronomy Picture of the Day
-  Apod,
+  Apod {
+    /// Fetch this day's picture instead of today's (YYYY-MM-DD, e.g. 2024-01-15)
+    #[arg(value_name = "DATE")]
+    date: Option<String>,
+  },
   /// Download Bing Photo of the Day
   Bing,
+  /// Download Wikimedia Commons Picture of the Day
+  Wikimedia,
   /// Download wallpaper from Reddit (accepts subreddit name)
   Reddit {
     /// Subreddit name (e.g., "earthporn", "wallpapers+cityporn")
@@ -81,6 +102,29 @@ enum Commands {
     #[arg(trailing_var_arg = true)]
     query: Vec<String>,
   },
+  /// Download photo from Pexels (accepts search terms)
+  Pexels {
+    /// Search terms (e.g., "forest", "city night")
+    #[arg(trailing_var_arg = true)]
+    query: Vec<String>,
+  },
+  /// Download image from Pixabay (accepts search terms)
+  Pixabay {
+    /// Search terms (e.g., "mountains", "beach")
+    #[arg(trailing_var_arg = true)]
+    query: Vec<String>,
+  },
+  /// Download photo from Flickr (accepts search tags)
+  Flickr {
+    /// Search tags (e.g., "mountains", "lake")
+    #[arg(trailing_var_arg = true)]
+    query: Vec<String>,
+  },
+  /// Download from an image URL or JSON endpoint (defaults to sources.url.endpoint)
+  Url {
+    /// Image URL or JSON endpoint
+    url: Option<String>,
+  },
   /// Run as background daemon with automatic rotation
   Daemon {
     #[command(subcommand)]
@@ -99,12 +143,27 @@ enum Commands {
     #[arg(long)]
     background: Option<f32>,
 
-    /// Output format: json, shell, css
-    #[arg(long, default_value = "json")]
-    format: String,
+    /// Number of dominant colors to cluster into (default: from config)
+    #[arg(long)]
+    count: Option<usize>,
+
+    /// Generate a dark scheme regardless of the image
+    #[arg(long, conflicts_with = "light")]
+    dark: bool,
+
+    /// Generate a light scheme regardless of the image
+    #[arg(long)]
+    light: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ColorFormat::Json)]
+    format: ColorFormat,
   },
   /// Render color templates from a scheme or image
   Templates {
+    #[command(subcommand)]
+    templates_command: Option<TemplateCommands>,
+
     /// Path to the image (extracts colors first)
     #[arg(long)]
     image: Option<std::path::PathBuf>,
@@ -118,10 +177,24 @@ enum Commands {
     /// Path to the image file
     image: std::path::PathBuf,
   },
+  /// Show recently applied wallpapers
+  History {
+    #[command(subcommand)]
+    history_command: Option<HistoryCommands>,
+
+    /// Number of entries to show
+    #[arg(short, long, default_value_t = 20)]
+    limit: usize,
+  },
   /// Show current configuration
-  Config,
+  Config {
+    #[command(subcommand)]
+    config_command: Option<ConfigCommands>,
+  },
   /// Show usage examples and setup guide
   Examples,
+  /// Check the whole setup: platform, backends, config, downloads and network (exits non-zero on failures)
+  Doctor,
   /// Show platform information and available backends
   PlatformInfo,
   /// List all available wallpaper backends
@@ -135,6 +208,12 @@ enum Commands {
     /// Only check for updates, don't install
     #[arg(short, long)]
     check: bool,
+    /// Skip verifying the download against the release's checksum
+    #[arg(long)]
+    insecure: bool,
+    /// Install without asking for confirmation
+    #[arg(short, long)]
+    yes: bool,
   },
 }
 
@@ -159,6 +238,19 @@ enum DaemonCommands {
   /// Reload daemon configuration (sends SIGHUP)
   Reload,
 
+  /// Switch to a new wallpaper now
+  Next,
+
+  /// Go back to the previous wallpaper
+  #[command(alias = "prev")]
+  Previous,
+
+  /// Pause automatic rotation
+  Pause,
+
+  /// Resume automatic rotation
+  Resume,
+
   /// Install daemon to run at system startup
   Install,
 
@@ -166,9 +258,84 @@ enum DaemonCommands {
   Uninstall,
 }
 
+#[derive(Subcommand)]
+enum TemplateCommands {
+  /// List installed template bundles
+  List,
+
+  /// Render templates for an image without setting it as wallpaper
+  Render {
+    /// Path to the image file
+    image: std::path::PathBuf,
+
+    /// Print rendered templates to stdout instead of writing files
+    #[arg(long)]
+    dry_run: bool,
+  },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+  /// Check the configuration for mistakes (exits non-zero on errors)
+  Validate {
+    /// Config file to check (default: --config or the default path)
+    #[arg(long)]
+    file: Option<std::path::PathBuf>,
+  },
+
+  /// Write a default config file
+  Init {
+    /// Overwrite an existing config file
+    #[arg(long)]
+    force: bool,
+  },
+
+  /// Change one setting, e.g. `wallflow config set timer.interval 15`
+  Set {
+    /// Dotted key path (e.g. "sources.default")
+    key: String,
+
+    /// New value, parsed as YAML (e.g. "15", "true", "[fade, wipe]")
+    value: String,
+  },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommands {
+  /// Delete all history entries
+  Clear,
+}
+
+/// Output formats of `wallflow colors --format`
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ColorFormat {
+  Json,
+  /// Shell variable assignments
+  Shell,
+  /// CSS custom properties
+  Css,
+  Kitty,
+  Alacritty,
+  /// Terminal escape sequences that recolor the running terminal
+  Sequences,
+}
+
 fn main() -> Result<()> {
   let cli = Cli::parse();
 
+  // Config subcommands work on the file itself, so a broken config can still be checked and fixed
+  if let Commands::Config {
+    config_command: Some(config_command),
+  } = &cli.command
+  {
+    return handle_config_command(config_command, cli.config.as_ref());
+  }
+
+  // The doctor loads the config itself so it can report a broken one
+  if let Commands::Doctor = &cli.command {
+    return tokio::runtime::Runtime::new()?.block_on(doctor::run(cli.config.as_deref()));
+  }
+
   // Load configuration first (needed for logging setup)
   let mut config = if let Some(config_path) = &cli.config {
     Config::load(config_path)?
@@ -176,6 +343,9 @@ fn main() -> Result<()> {
     Config::load_or_default()?
   };
 
+  // Environment overrides sit between the config file and CLI flags
+  config.apply_env_overrides()?;
+
   // Expand environment variables in paths
   config.expand_paths()?;
 
@@ -185,21 +355,35 @@ fn main() -> Result<()> {
   // Log system information and configuration details
   logging::log_system_info(&config);
 
+  // Earlier versions kept history and other state in ~/.local/share/mksg/wallflow
+  paths::migrate_legacy_files();
+
+  // The daemon watches this file for changes. Made absolute because daemonizing changes the working directory.
+  let config_path = cli
+    .config
+    .clone()
+    .or_else(Config::existing_default_path)
+    .map(|path| std::path::absolute(&path).unwrap_or(path));
+
   // Handle daemon commands that don't need tokio runtime BEFORE creating it
   // (daemonize fork + new runtime doesn't work from within an existing runtime)
   if let Commands::Daemon { daemon_command } = &cli.command {
     match daemon_command {
       DaemonCommands::Stop => return daemon::stop_daemon(),
       DaemonCommands::Reload => return daemon::reload_daemon(),
+      DaemonCommands::Next => return daemon::control_daemon(daemon_control::ControlCommand::Next),
+      DaemonCommands::Previous => return daemon::control_daemon(daemon_control::ControlCommand::Previous),
+      DaemonCommands::Pause => return daemon::control_daemon(daemon_control::ControlCommand::Pause),
+      DaemonCommands::Resume => return daemon::control_daemon(daemon_control::ControlCommand::Resume),
       DaemonCommands::Status => return daemon::status_daemon(),
       DaemonCommands::Install => return daemon::install_daemon(),
       DaemonCommands::Uninstall => return daemon::uninstall_daemon(),
-      DaemonCommands::Start { foreground: false } => return daemon::run_background(config),
+      DaemonCommands::Start { foreground: false } => return daemon::run_background(config, config_path),
       DaemonCommands::Restart => {
         // Stop if running, then start in background
         let _ = daemon::stop_daemon(); // Ignore error if not running
         std::thread::sleep(std::time::Duration::from_secs(1));
-        return daemon::run_background(config);
+        return daemon::run_background(config, config_path);
       }
       DaemonCommands::Start { foreground: true } => {
         // Fall through to async runtime for foreground mode
@@ -209,20 +393,27 @@ fn main() -> Result<()> {
 
   // Create tokio runtime for all other commands
   let rt = tokio::runtime::Runtime::new()?;
-  rt.block_on(async_main(cli, config))
+  rt.block_on(async_main(cli, config, config_path))
 }
 
-async fn async_main(cli: Cli, config: Config) -> Result<()> {
+async fn async_main(cli: Cli, config: Config, config_path: Option<std::path::PathBuf>) -> Result<()> {
   // Build download options from CLI flags
   let download_opts = downloaders::DownloadOptions {
     output_dir: cli.output.clone(),
     no_set: cli.no_set,
+    dry_run: cli.dry_run,
+    monitor: cli.monitor.clone(),
+    show_progress: true,
+    orientation: None,
   };
 
   // Execute command
   match cli.command {
     Commands::Local => {
-      wallpaper::set_local(&config).await?;
+      wallpaper::set_local(&config, &download_opts).await?;
+    }
+    Commands::Favorites => {
+      wallpaper::set_favorites(&config, &download_opts).await?;
     }
     Commands::Wallhaven { query } => {
       wallpaper::set_from_source(&config, "wallhaven", &query, &download_opts).await?;
@@ -230,12 +421,16 @@ async fn async_main(cli: Cli, config: Config) -> Result<()> {
     Commands::Picsum => {
       wallpaper::set_from_source(&config, "picsum", &[], &download_opts).await?;
     }
-    Commands::Apod => {
-      wallpaper::set_from_source(&config, "apod", &[], &download_opts).await?;
+    Commands::Apod { date } => {
+      let query: Vec<String> = date.into_iter().collect();
+      wallpaper::set_from_source(&config, "apod", &query, &download_opts).await?;
     }
     Commands::Bing => {
       wallpaper::set_from_source(&config, "bing", &[], &download_opts).await?;
     }
+    Commands::Wikimedia => {
+      wallpaper::set_from_source(&config, "wikimedia", &[], &download_opts).await?;
+    }
     Commands::Reddit { query } => {
       wallpaper::set_from_source(&config, "reddit", &query, &download_opts).await?;
     }
@@ -245,32 +440,67 @@ async fn async_main(cli: Cli, config: Config) -> Result<()> {
     Commands::Unsplash { query } => {
       wallpaper::set_from_source(&config, "unsplash", &query, &download_opts).await?;
     }
+    Commands::Pexels { query } => {
+      wallpaper::set_from_source(&config, "pexels", &query, &download_opts).await?;
+    }
+    Commands::Pixabay { query } => {
+      wallpaper::set_from_source(&config, "pixabay", &query, &download_opts).await?;
+    }
+    Commands::Flickr { query } => {
+      wallpaper::set_from_source(&config, "flickr", &query, &download_opts).await?;
+    }
+    Commands::Url { url } => {
+      let query: Vec<String> = url.into_iter().collect();
+      wallpaper::set_from_source(&config, "url", &query, &download_opts).await?;
+    }
     Commands::Colors {
       image,
       contrast,
       background,
+      count,
+      dark,
+      light,
       format,
     } => {
-      handle_colors(&config, &image, contrast, background, &format)?;
-    }
-    Commands::Templates { image, scheme } => {
-      handle_templates(&config, image.as_deref(), scheme.as_deref()).await?;
+      let prefers_dark = if dark {
+        Some(true)
+      } else if light {
+        Some(false)
+      } else {
+        None
+      };
+      handle_colors(&config, &image, contrast, background, count, prefers_dark, format)?;
     }
+    Commands::Templates {
+      templates_command,
+      image,
+      scheme,
+    } => match templates_command {
+      Some(TemplateCommands::List) => handle_templates_list().await?,
+      Some(TemplateCommands::Render { image, dry_run: true }) => handle_templates_dry_run(&config, &image).await?,
+      Some(TemplateCommands::Render { image, dry_run: false }) => handle_templates(&config, Some(&image), None).await?,
+      None => handle_templates(&config, image.as_deref(), scheme.as_deref()).await?,
+    },
     Commands::Apply { image } => {
-      handle_apply(&config, &image).await?;
+      handle_apply(&config, &image, download_opts.dry_run).await?;
     }
     Commands::Daemon { daemon_command } => {
       // Most daemon commands are handled in main() before runtime creation
       // Only foreground mode reaches here
       if let DaemonCommands::Start { foreground: true } = daemon_command {
-        daemon::run_foreground(config).await?;
+        daemon::run_foreground(config, config_path).await?;
       } else {
         unreachable!("Non-foreground daemon commands should be handled before async runtime");
       }
     }
-    Commands::Config => {
-      show_config(&config)?;
+    Commands::History { history_command, limit } => {
+      handle_history(history_command, limit)?;
     }
+    Commands::Config { config_command } => match config_command {
+      None => show_config(&config)?,
+      Some(_) => unreachable!("config subcommands are handled before loading the config"),
+    },
+    Commands::Doctor => unreachable!("doctor is handled before loading the config"),
     Commands::Examples => {
       println!("🌊 wallflow Usage Examples");
       println!();
@@ -284,6 +514,10 @@ async fn async_main(cli: Cli, config: Config) -> Result<()> {
       println!("  wallflow daemon status             # Show daemon status");
       println!("  wallflow daemon restart            # Restart daemon");
       println!("  wallflow daemon reload             # Reload configuration");
+      println!("  wallflow daemon next               # Switch wallpaper now");
+      println!("  wallflow daemon previous           # Go back to the last wallpaper");
+      println!("  wallflow daemon pause              # Freeze rotation (e.g. for a presentation)");
+      println!("  wallflow daemon resume             # Continue rotating");
       println!();
       println!("  # Auto-start at system boot/login");
       println!("  wallflow daemon install            # Install startup service");
@@ -293,17 +527,38 @@ async fn async_main(cli: Cli, config: Config) -> Result<()> {
       println!("  wallflow wallhaven nature mountains");
       println!("  wallflow reddit earthporn");
       println!("  wallflow unsplash architecture");
+      println!("  wallflow pexels forest");
+      println!("  wallflow pixabay mountains");
+      println!("  wallflow flickr mountains lake");
+      println!("  wallflow url https://example.com/wallpaper.jpg");
       println!("  wallflow bing");
+      println!("  wallflow wikimedia");
       println!("  wallflow earthview");
       println!("  wallflow apod");
+      println!("  wallflow apod 2024-01-15");
       println!();
       println!("  # Color extraction and theming");
       println!("  wallflow colors ~/wallpaper.jpg              # Extract 16-color JSON scheme");
       println!("  wallflow colors ~/wallpaper.jpg --format css # Export as CSS custom properties");
       println!("  wallflow templates --image ~/wallpaper.jpg   # Render templates for all apps");
+      println!("  wallflow templates list                      # Show installed templates");
+      println!("  wallflow templates render ~/wallpaper.jpg --dry-run # Preview rendered templates");
       println!("  wallflow apply ~/wallpaper.jpg               # Full pipeline: set + extract + render");
       println!();
+      println!("  # Wallpaper history");
+      println!("  wallflow history                   # Last 20 wallpapers");
+      println!("  wallflow history --limit 50        # Show more entries");
+      println!("  wallflow history clear             # Forget all entries");
+      println!();
+      println!("  # Configuration");
+      println!("  wallflow config                    # Show current settings");
+      println!("  wallflow config validate           # Check config for mistakes");
+      println!("  wallflow config init               # Write a default config file");
+      println!("  wallflow config set timer.interval 15");
+      println!();
       println!("  # Check platform and backends");
+      println!("  wallflow doctor                    # Diagnose the whole setup");
+      println!("  wallflow --dry-run local           # Show which backend would set which file");
       println!("  wallflow platform-info");
       println!("  wallflow list-backends");
       println!("  wallflow list-sources");
@@ -334,30 +589,50 @@ async fn async_main(cli: Cli, config: Config) -> Result<()> {
       info!("🎨 Launching TUI wallpaper browser");
       tui::run_with_default_terminal(config).await?;
     }
-    Commands::Update { check } => {
-      handle_update(check).await?;
+    Commands::Update { check, insecure, yes } => {
+      handle_update(check, insecure, yes).await?;
     }
   }
 
   Ok(())
 }
 
-fn handle_colors(config: &Config, image: &std::path::Path, contrast: Option<f32>, background: Option<f32>, format: &str) -> Result<()> {
-  let options = colors::ExtractionOptions {
-    contrast_ratio: contrast.unwrap_or(config.colors.contrast_ratio),
-    background_intensity: background.unwrap_or(config.colors.background_intensity),
-    prefers_dark: config.colors.prefer_dark.or_else(platform::detect_dark_mode),
-    ..Default::default()
-  };
+fn handle_colors(
+  config: &Config,
+  image: &std::path::Path,
+  contrast: Option<f32>,
+  background: Option<f32>,
+  count: Option<usize>,
+  prefers_dark: Option<bool>,
+  format: ColorFormat,
+) -> Result<()> {
+  use anyhow::Context;
+
+  // Check the cheap things before spending time on extraction
+  if !image.is_file() {
+    anyhow::bail!("Image not found: {}", image.display());
+  }
+
+  // Flags override the config before its values are checked and clamped
+  let mut colors_config = config.colors.clone();
+  colors_config.contrast_ratio = contrast.unwrap_or(colors_config.contrast_ratio);
+  colors_config.background_intensity = background.unwrap_or(colors_config.background_intensity);
+  colors_config.color_count = count.unwrap_or(colors_config.color_count);
+  colors_config.prefer_dark = prefers_dark.or(colors_config.prefer_dark);
+  let options = colors_config.extraction_options();
 
-  let extractor = colors::ColorExtractor::new();
-  let scheme = extractor.extract(image, &options)?;
+  let extractor = colors_config.extractor();
+  let scheme = extractor
+    .extract(image, &options)
+    .with_context(|| format!("Could not extract colors from {}", image.display()))?;
 
   match format {
-    "json" => println!("{}", scheme.to_json()?),
-    "shell" => println!("{}", scheme.to_shell_format()),
-    "css" => println!("{}", scheme.to_css_format()),
-    other => anyhow::bail!("Unknown format '{}'. Use json, shell, or css.", other),
+    ColorFormat::Json => println!("{}", scheme.to_json()?),
+    ColorFormat::Shell => println!("{}", scheme.to_shell_format()),
+    ColorFormat::Css => println!("{}", scheme.to_css_format()),
+    ColorFormat::Kitty => println!("{}", scheme.to_kitty_conf()),
+    ColorFormat::Alacritty => println!("{}", scheme.to_alacritty_toml()),
+    ColorFormat::Sequences => print!("{}", scheme.to_sequences()),
   }
 
   Ok(())
@@ -371,13 +646,7 @@ async fn handle_templates(config: &Config, image: Option<&std::path::Path>, sche
     let json = std::fs::read_to_string(path).context("Failed to read scheme file")?;
     colors::ColorScheme::from_json(&json).context("Failed to parse color scheme JSON")?
   } else if let Some(path) = image {
-    let options = colors::ExtractionOptions {
-      contrast_ratio: config.colors.contrast_ratio,
-      background_intensity: config.colors.background_intensity,
-      prefers_dark: config.colors.prefer_dark.or_else(platform::detect_dark_mode),
-      ..Default::default()
-    };
-    colors::ColorExtractor::new().extract(path, &options)?
+    config.colors.extractor().extract(path, &config.colors.extraction_options())?
   } else {
     anyhow::bail!("Provide --image or --scheme");
   };
@@ -397,62 +666,97 @@ async fn handle_templates(config: &Config, image: Option<&std::path::Path>, sche
   println!("Rendered {} templates to {}", rendered.len(), output_dir.display());
   for rt in &rendered {
     println!("  {}", rt.output_path);
+    for dest in &rt.installed {
+      println!("    -> {}", dest);
+    }
   }
 
   // Send reload signals
   if config.integration.reload_apps {
-    templates::TemplateEngine::notify_apps(&rendered);
+    templates::TemplateEngine::notify_apps(&rendered, &config.integration.notify_apps);
   }
+  integration::notify_color_change(&config.integration.notify_apps, &scheme, &output_dir);
 
   Ok(())
 }
 
-async fn handle_apply(config: &Config, image: &std::path::Path) -> Result<()> {
-  // 1. Set wallpaper
-  wallpaper::apply_wallpaper(image, config).await?;
-
-  // 2. Extract colors and render templates (if enabled)
-  if config.colors.enabled && config.colors.engine == "native" {
-    let options = colors::ExtractionOptions {
-      contrast_ratio: config.colors.contrast_ratio,
-      background_intensity: config.colors.background_intensity,
-      prefers_dark: config.colors.prefer_dark.or_else(platform::detect_dark_mode),
-      ..Default::default()
-    };
+async fn handle_templates_list() -> Result<()> {
+  let tpl_dir = templates::ensure_templates().await?;
+  let bundles = templates::TemplateEngine::list_bundles(&tpl_dir)?;
 
-    let extractor = colors::ColorExtractor::new();
-    match extractor.extract(image, &options) {
-      Ok(scheme) => {
-        let output_dir = templates::TemplateEngine::default_output_dir();
-
-        // Save scheme
-        std::fs::create_dir_all(&output_dir)?;
-        let scheme_file = output_dir.join("colors.json");
-        std::fs::write(&scheme_file, scheme.to_json()?)?;
-        info!("Color scheme saved to {}", scheme_file.display());
-
-        // Render templates if available
-        let tpl_dir = templates::templates_dir();
-        if tpl_dir.exists() {
-          let rendered = templates::TemplateEngine::render_all(&tpl_dir, &output_dir, &scheme)?;
-          if !rendered.is_empty() {
-            info!("Rendered {} templates", rendered.len());
-            if config.integration.reload_apps {
-              templates::TemplateEngine::notify_apps(&rendered);
-            }
-          }
-        }
-      }
-      Err(e) => {
-        tracing::warn!("Color extraction failed: {}", e);
-      }
-    }
+  if bundles.is_empty() {
+    println!("No templates found in {}", tpl_dir.display());
+    return Ok(());
+  }
+
+  println!("Templates in {}:", tpl_dir.display());
+  for (_, manifest) in &bundles {
+    println!("  {:<20} {:<12} -> {}", manifest.name, manifest.category, manifest.template.output_name);
+  }
+
+  Ok(())
+}
+
+async fn handle_templates_dry_run(config: &Config, image: &std::path::Path) -> Result<()> {
+  let scheme = config.colors.extractor().extract(image, &config.colors.extraction_options())?;
+
+  let tpl_dir = templates::ensure_templates().await?;
+  for (bundle, manifest) in templates::TemplateEngine::list_bundles(&tpl_dir)? {
+    let rendered = templates::TemplateEngine::render_file(bundle.join(&manifest.template.file), &scheme)?;
+    println!("==> {} <==", manifest.template.output_name);
+    println!("{}", rendered);
   }
 
   Ok(())
 }
 
-async fn handle_update(check_only: bool) -> Result<()> {
+async fn handle_apply(config: &Config, image: &std::path::Path, dry_run: bool) -> Result<()> {
+  if dry_run {
+    return wallpaper::dry_run(image, wallpaper::backends::traits::MonitorSelection::All, config);
+  }
+
+  // Setting the wallpaper also extracts its colors and renders templates (if enabled)
+  let applied = wallpaper::apply_wallpaper(image, config).await?;
+  if let Some(scheme) = &applied.scheme {
+    let output_dir = templates::TemplateEngine::default_output_dir();
+    info!(
+      "{} color scheme saved to {}",
+      if scheme.is_dark { "Dark" } else { "Light" },
+      output_dir.join("colors.json").display()
+    );
+  }
+
+  Ok(())
+}
+
+fn handle_history(command: Option<HistoryCommands>, limit: usize) -> Result<()> {
+  if let Some(HistoryCommands::Clear) = command {
+    history::clear()?;
+    println!("History cleared");
+    return Ok(());
+  }
+
+  let entries = history::recent(limit)?;
+  if entries.is_empty() {
+    println!("No wallpaper history yet");
+    return Ok(());
+  }
+
+  println!("🌊 wallflow History");
+  println!();
+  for entry in entries {
+    println!(
+      "  {}  {:<10} {}",
+      entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+      entry.source,
+      entry.path.display()
+    );
+  }
+
+  Ok(())
+}
+
+async fn handle_update(check_only: bool, insecure: bool, assume_yes: bool) -> Result<()> {
   // Check if self-update is possible
   if !updater::can_self_update() {
     println!("Self-update is disabled.");
@@ -468,11 +772,18 @@ async fn handle_update(check_only: bool) -> Result<()> {
       if check.update_available {
         println!("Update available: v{} -> v{}", check.current, check.latest);
 
+        let changelog = updater::changelog_summary(&check.body, 20);
+        if !changelog.is_empty() {
+          println!("\nWhat's new:\n{}", changelog);
+        }
+
         if check_only {
           println!("\nRun 'wallflow update' to install the update.");
+        } else if !assume_yes && !confirm(&format!("\nInstall v{}?", check.latest))? {
+          println!("Update cancelled.");
         } else {
           println!("\nDownloading and installing update...");
-          match updater::perform_update().await {
+          match updater::perform_update(insecure).await {
             Ok(version) => {
               println!("Downloaded v{}", version);
               updater::apply_update()?;
@@ -496,6 +807,100 @@ async fn handle_update(check_only: bool) -> Result<()> {
   Ok(())
 }
 
+/// Ask a yes/no question on the terminal; anything but "y" is a no.
+/// Without a terminal there's nobody to ask, so the answer is yes.
+fn confirm(question: &str) -> Result<bool> {
+  use std::io::{IsTerminal, Write};
+
+  if !std::io::stdin().is_terminal() {
+    return Ok(true);
+  }
+
+  print!("{} [y/N] ", question);
+  std::io::stdout().flush()?;
+  let mut answer = String::new();
+  std::io::stdin().read_line(&mut answer)?;
+  Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn handle_config_command(command: &ConfigCommands, config_path: Option<&std::path::PathBuf>) -> Result<()> {
+  match command {
+    ConfigCommands::Validate { file } => validate_config(file.as_ref().or(config_path)),
+    ConfigCommands::Init { force } => init_config(config_path, *force),
+    ConfigCommands::Set { key, value } => set_config(config_path, key, value),
+  }
+}
+
+fn init_config(path: Option<&std::path::PathBuf>, force: bool) -> Result<()> {
+  let path = path.cloned().unwrap_or_else(Config::default_path);
+
+  if path.exists() && !force {
+    println!("⚠️  {} already exists. Re-run with --force to overwrite it.", path.display());
+    return Ok(());
+  }
+  if path.exists() {
+    println!("⚠️  Overwriting {}", path.display());
+  }
+
+  Config::default().save(&path)?;
+  println!("✅ Wrote default config to {}", path.display());
+  Ok(())
+}
+
+fn set_config(path: Option<&std::path::PathBuf>, key: &str, value: &str) -> Result<()> {
+  let path = path.cloned().or_else(Config::existing_default_path).unwrap_or_else(Config::default_path);
+
+  // Start from defaults when there's no file yet
+  let mut config = if path.exists() { Config::load(&path)? } else { Config::default() };
+  config.set_field(key, value)?;
+  config.save(&path)?;
+
+  println!("✅ Set {} = {} in {}", key, value, path.display());
+  Ok(())
+}
+
+fn validate_config(path: Option<&std::path::PathBuf>) -> Result<()> {
+  use anyhow::Context;
+  use config::validate::{self, Severity};
+
+  let Some(path) = path.cloned().or_else(Config::existing_default_path) else {
+    println!("No config file at {}; wallflow uses built-in defaults.", Config::default_path().display());
+    return Ok(());
+  };
+
+  println!("🔍 Validating {}", path.display());
+  let contents = std::fs::read_to_string(&path).with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+  let mut config = match Config::load(&path) {
+    Ok(config) => config,
+    Err(e) => {
+      println!("  ❌ {:#}", e);
+      anyhow::bail!("Configuration is invalid");
+    }
+  };
+  config.expand_paths()?;
+
+  let issues = validate::validate(&config, &downloaders::list_sources());
+  for issue in &issues {
+    let icon = match issue.severity {
+      Severity::Error => "❌",
+      Severity::Warning => "⚠️ ",
+    };
+    let line = validate::locate(&contents, &issue.field)
+      .map(|n| format!(" (line {})", n))
+      .unwrap_or_default();
+    println!("  {} {}{}: {}", icon, issue.field, line, issue.message);
+  }
+
+  let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+  if errors > 0 {
+    anyhow::bail!("Configuration has {} error(s)", errors);
+  }
+
+  println!("✅ Configuration is valid");
+  Ok(())
+}
+
 fn show_config(config: &Config) -> Result<()> {
   println!("🌊 wallflow Configuration");
   println!();
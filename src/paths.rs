@@ -0,0 +1,147 @@
+//! Where wallflow keeps files that aren't configuration
+//!
+//! Persistent state (history, favorites, logs, the download index) goes to
+//! `$XDG_STATE_HOME/mksg/wallflow`, or `~/.local/share/mksg/wallflow` when that
+//! variable isn't set, which is where earlier versions kept everything.
+//!
+//! Files that only matter while the daemon runs (PID file, control socket, status)
+//! go to `$XDG_RUNTIME_DIR/mksg/wallflow` when it is set and to the state directory
+//! otherwise. The daemon and the commands talking to it must agree on this, so a
+//! daemon started by systemd and a CLI run from a terminal of the same session
+//! both see the runtime directory.
+//!
+//! Earlier versions kept all of this in `~/.local/share/mksg/wallflow`.
+//! [`migrate_legacy_files`] moves the state files from there, and a daemon
+//! started by an earlier version is still found through its old PID file.
+
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// State files earlier versions kept in the legacy directory
+const STATE_FILES: [&str; 5] = [
+  "history.jsonl",
+  "download_index.json",
+  "current_wallpaper",
+  "shuffle.json",
+  "favorites.json",
+];
+
+/// Directory for files kept between runs
+pub fn state_dir() -> Result<PathBuf> {
+  state_dir_from(std::env::var_os("XDG_STATE_HOME"), dirs::home_dir())
+}
+
+/// Directory for files that only live as long as the daemon
+pub fn runtime_dir() -> Result<PathBuf> {
+  match xdg_dir(std::env::var_os("XDG_RUNTIME_DIR")) {
+    Some(dir) => Ok(dir.join("mksg/wallflow")),
+    None => state_dir(),
+  }
+}
+
+fn state_dir_from(xdg_state_home: Option<OsString>, home: Option<PathBuf>) -> Result<PathBuf> {
+  match xdg_dir(xdg_state_home) {
+    Some(dir) => Ok(dir.join("mksg/wallflow")),
+    None => Ok(home.context("Could not find home directory")?.join(".local/share/mksg/wallflow")),
+  }
+}
+
+/// Where earlier versions kept state and daemon files
+fn legacy_dir() -> Option<PathBuf> {
+  dirs::home_dir().map(|home| home.join(".local/share/mksg/wallflow"))
+}
+
+/// `name` in the legacy directory, if an earlier version left it there
+pub fn legacy_file(name: &str) -> Option<PathBuf> {
+  legacy_dir().map(|dir| dir.join(name)).filter(|path| path.exists())
+}
+
+/// Move state files left by earlier versions into the state directory.
+/// Files that already exist there are kept, so this does nothing after the first run.
+pub fn migrate_legacy_files() {
+  if let (Some(legacy), Ok(state)) = (legacy_dir(), state_dir()) {
+    migrate_from(&legacy, &state);
+  }
+}
+
+fn migrate_from(legacy: &Path, state: &Path) {
+  if legacy == state {
+    return;
+  }
+
+  for name in STATE_FILES {
+    let (from, to) = (legacy.join(name), state.join(name));
+    if !from.is_file() || to.exists() {
+      continue;
+    }
+
+    // Renaming fails across filesystems, e.g. with XDG_STATE_HOME on another mount
+    let moved = std::fs::create_dir_all(state)
+      .and_then(|()| std::fs::rename(&from, &to).or_else(|_| std::fs::copy(&from, &to).and_then(|_| std::fs::remove_file(&from))));
+    match moved {
+      Ok(()) => info!("Moved {} to {}", from.display(), to.display()),
+      Err(e) => warn!("Failed to move {} to {}: {}", from.display(), to.display(), e),
+    }
+  }
+}
+
+/// An XDG base directory variable, ignored when empty or relative as the spec asks
+fn xdg_dir(value: Option<OsString>) -> Option<PathBuf> {
+  value.map(PathBuf::from).filter(|dir| dir.is_absolute())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_state_dir() {
+    let home = Some(PathBuf::from("/home/me"));
+
+    assert_eq!(
+      state_dir_from(None, home.clone()).unwrap(),
+      PathBuf::from("/home/me/.local/share/mksg/wallflow")
+    );
+    assert_eq!(
+      state_dir_from(Some("/var/state".into()), home.clone()).unwrap(),
+      PathBuf::from("/var/state/mksg/wallflow")
+    );
+    // Empty and relative values don't count
+    assert_eq!(
+      state_dir_from(Some("".into()), home.clone()).unwrap(),
+      PathBuf::from("/home/me/.local/share/mksg/wallflow")
+    );
+    assert_eq!(
+      state_dir_from(Some("state".into()), home).unwrap(),
+      PathBuf::from("/home/me/.local/share/mksg/wallflow")
+    );
+
+    assert!(state_dir_from(None, None).is_err());
+  }
+
+  #[test]
+  fn test_migrate_from() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let legacy = tmp.path().join("share");
+    let state = tmp.path().join("state");
+    std::fs::create_dir(&legacy).unwrap();
+    std::fs::write(legacy.join("history.jsonl"), "old history").unwrap();
+    std::fs::write(legacy.join("shuffle.json"), "old shuffle").unwrap();
+    std::fs::write(legacy.join("wallflow.pid"), "42").unwrap();
+
+    migrate_from(&legacy, &state);
+    assert_eq!(std::fs::read_to_string(state.join("history.jsonl")).unwrap(), "old history");
+    assert!(!legacy.join("history.jsonl").exists());
+    // Only state files move; a running daemon still owns its PID file
+    assert!(legacy.join("wallflow.pid").exists());
+    assert!(!state.join("wallflow.pid").exists());
+
+    // Newer state is never overwritten
+    std::fs::write(legacy.join("history.jsonl"), "stale").unwrap();
+    migrate_from(&legacy, &state);
+    assert_eq!(std::fs::read_to_string(state.join("history.jsonl")).unwrap(), "old history");
+    assert!(legacy.join("history.jsonl").exists());
+  }
+}
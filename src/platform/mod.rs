@@ -28,6 +28,7 @@ pub enum WaylandCompositor {
   Hyprland,
   Gnome,
   Kde,
+  Cosmic,
   Generic,
 }
 
@@ -109,6 +110,10 @@ fn detect_wayland_compositor() -> WaylandCompositor {
     if desktop.contains("kde") {
       return WaylandCompositor::Kde;
     }
+
+    if desktop.contains("cosmic") {
+      return WaylandCompositor::Cosmic;
+    }
   }
 
   // Check for compositor-specific environment variables
@@ -120,6 +125,11 @@ fn detect_wayland_compositor() -> WaylandCompositor {
     return WaylandCompositor::Hyprland;
   }
 
+  // COSMIC sessions export COSMIC_* variables (e.g. COSMIC_SESSION_SOCK)
+  if env::vars_os().any(|(key, _)| key.to_string_lossy().starts_with("COSMIC_")) {
+    return WaylandCompositor::Cosmic;
+  }
+
   WaylandCompositor::Generic
 }
 
@@ -159,7 +169,7 @@ pub fn check_platform_dependencies() -> PlatformStatus {
 /// Detect available wallpaper backends on Linux
 #[cfg(target_os = "linux")]
 fn detect_available_linux_backends() -> Vec<String> {
-  let backends = vec!["swww", "awww", "swaybg", "hyprpaper", "feh", "nitrogen", "xwallpaper"];
+  let backends = vec!["swww", "awww", "swaybg", "hyprpaper", "cosmic-bg", "feh", "nitrogen", "xwallpaper"];
 
   backends
     .into_iter()
@@ -286,6 +296,7 @@ impl std::fmt::Display for WaylandCompositor {
       WaylandCompositor::Hyprland => write!(f, "Hyprland"),
       WaylandCompositor::Gnome => write!(f, "GNOME"),
       WaylandCompositor::Kde => write!(f, "KDE"),
+      WaylandCompositor::Cosmic => write!(f, "COSMIC"),
       WaylandCompositor::Generic => write!(f, "Generic"),
     }
   }
@@ -1,15 +1,30 @@
 //! Template rendering engine
 //!
 //! Renders templates by replacing `{variable}` placeholders with color values.
+//! A single conditional, `{{#if dark}}...{{else}}...{{/if}}`, picks a branch
+//! based on whether the scheme is dark.
+//!
+//! Variables accept pipe-style filters applied left to right, e.g.
+//! `{color1|lighten:0.2|upper}`:
+//!
+//! - `upper`, `lower`: change case
+//! - `strip`: drop a leading `#`
+//! - `lighten:N`, `darken:N`: lighten or darken a hex color by N (0.0-1.0)
+//! - `rotate:N`: rotate a hex color's hue by N degrees
+//!
+//! Tokens with an unknown filter (or a color filter on a non-color value) are
+//! left untouched, like unknown variables.
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use tracing::debug;
 
-use crate::colors::ColorScheme;
+use crate::colors::{ColorScheme, Rgb};
 
+use super::install::install_rendered;
 use super::manifest::{ReloadConfig, TemplateManifest};
 
 /// Result of rendering a template bundle
@@ -18,6 +33,8 @@ pub struct RenderedTemplate {
   pub output_path: String,
   /// Reload config from the manifest, if any
   pub reload: Option<ReloadConfig>,
+  /// Destinations from the manifest's install section that now hold the output
+  pub installed: Vec<String>,
 }
 
 /// Template rendering engine
@@ -30,6 +47,7 @@ impl TemplateEngine {
   /// - `{background}`, `{foreground}`, `{cursor}`
   /// - `{color0}` through `{color15}`
   /// - `{color0.strip}`, `{color0.rgb}`, `{color0.rgba}`, etc.
+  /// - `{dark}` (`true`/`false`), which also drives `{{#if dark}}` blocks
   pub fn build_variables(scheme: &ColorScheme) -> HashMap<String, String> {
     let mut vars = HashMap::new();
 
@@ -39,6 +57,7 @@ impl TemplateEngine {
     vars.insert("background".to_string(), scheme.background.hex());
     vars.insert("foreground".to_string(), scheme.foreground.hex());
     vars.insert("cursor".to_string(), scheme.cursor.hex());
+    vars.insert("dark".to_string(), scheme.is_dark.to_string());
 
     // Alpha variants
     let alpha_decimal = scheme.alpha as f32 / 100.0;
@@ -88,9 +107,10 @@ impl TemplateEngine {
     vars
   }
 
-  /// Render a template string by replacing `{variable}` placeholders
+  /// Render a template string by resolving `{{#if dark}}` blocks, then
+  /// replacing `{variable}` placeholders
   pub fn render(template: &str, variables: &HashMap<String, String>) -> String {
-    let mut result = template.to_string();
+    let mut result = Self::render_filters(&Self::render_conditionals(template, variables), variables);
 
     for (key, value) in variables {
       result = result.replace(&format!("{{{}}}", key), value);
@@ -99,6 +119,93 @@ impl TemplateEngine {
     result
   }
 
+  /// Keep the matching branch of each `{{#if dark}}...{{else}}...{{/if}}` block.
+  ///
+  /// Blocks are left verbatim when unterminated or when `dark` isn't a known variable.
+  fn render_conditionals(template: &str, variables: &HashMap<String, String>) -> String {
+    const IF: &str = "{{#if dark}}";
+    const ELSE: &str = "{{else}}";
+    const END: &str = "{{/if}}";
+
+    let Some(is_dark) = variables.get("dark").map(|v| v == "true") else {
+      return template.to_string();
+    };
+
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(IF) {
+      let body_start = start + IF.len();
+      let Some(body_len) = rest[body_start..].find(END) else {
+        break;
+      };
+
+      let body = &rest[body_start..body_start + body_len];
+      let (then_branch, else_branch) = body.split_once(ELSE).unwrap_or((body, ""));
+
+      result.push_str(&rest[..start]);
+      result.push_str(if is_dark { then_branch } else { else_branch });
+      rest = &rest[body_start + body_len + END.len()..];
+    }
+
+    result.push_str(rest);
+    result
+  }
+
+  /// Replace `{variable|filter|...}` tokens whose variable and filters are all known
+  fn render_filters(template: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+      let Some(len) = rest[open + 1..].find(['{', '}']) else {
+        break;
+      };
+      let close = open + 1 + len;
+
+      // Nested brace: restart scanning from the inner one
+      if rest.as_bytes()[close] == b'{' {
+        result.push_str(&rest[..close]);
+        rest = &rest[close..];
+        continue;
+      }
+
+      let token = &rest[open + 1..close];
+      result.push_str(&rest[..open]);
+      match token
+        .split_once('|')
+        .and_then(|(name, filters)| Self::apply_filters(variables.get(name)?, filters))
+      {
+        Some(value) => result.push_str(&value),
+        None => result.push_str(&rest[open..=close]),
+      }
+      rest = &rest[close + 1..];
+    }
+
+    result.push_str(rest);
+    result
+  }
+
+  /// Apply `|`-separated filters to a value, or `None` if any filter doesn't apply
+  fn apply_filters(value: &str, filters: &str) -> Option<String> {
+    filters.split('|').try_fold(value.to_string(), |value, filter| {
+      let (name, arg) = match filter.split_once(':') {
+        Some((name, arg)) => (name, Some(arg.parse::<f32>().ok()?)),
+        None => (filter, None),
+      };
+
+      match (name, arg) {
+        ("upper", None) => Some(value.to_uppercase()),
+        ("lower", None) => Some(value.to_lowercase()),
+        ("strip", None) => Some(value.strip_prefix('#').unwrap_or(&value).to_string()),
+        ("lighten", Some(amount)) => Some(Rgb::from_hex(&value)?.lightened(amount).hex()),
+        ("darken", Some(amount)) => Some(Rgb::from_hex(&value)?.darkened(amount).hex()),
+        ("rotate", Some(degrees)) => Some(Rgb::from_hex(&value)?.hue_rotated(degrees).hex()),
+        _ => None,
+      }
+    })
+  }
+
   /// Render a template file and return the result
   pub fn render_file<P: AsRef<Path>>(template_path: P, scheme: &ColorScheme) -> Result<String> {
     let template = fs::read_to_string(template_path.as_ref()).context("Failed to read template file")?;
@@ -108,10 +215,14 @@ impl TemplateEngine {
   }
 
   /// Render a template bundle and write to output directory
+  #[allow(dead_code)]
   pub fn render_bundle<P: AsRef<Path>, Q: AsRef<Path>>(bundle_path: P, output_dir: Q, scheme: &ColorScheme) -> Result<RenderedTemplate> {
-    let bundle = bundle_path.as_ref();
-    let output = output_dir.as_ref();
+    Self::render_bundle_variant(bundle_path.as_ref(), output_dir.as_ref(), scheme, None)
+  }
 
+  /// Render a bundle, appending `-<variant>` to the output name when given
+  /// (e.g. `colors.conf` -> `colors-dark.conf`)
+  fn render_bundle_variant(bundle: &Path, output: &Path, scheme: &ColorScheme, variant: Option<&str>) -> Result<RenderedTemplate> {
     // Load manifest
     let manifest_path = bundle.join("manifest.json");
     let manifest = TemplateManifest::load(&manifest_path).context("Failed to load manifest.json")?;
@@ -123,19 +234,50 @@ impl TemplateEngine {
     // Write to output
     fs::create_dir_all(output).context("Failed to create output directory")?;
 
-    let output_path = output.join(&manifest.template.output_name);
+    let output_name = match variant {
+      Some(variant) => variant_file_name(&manifest.template.output_name, variant),
+      None => manifest.template.output_name.clone(),
+    };
+    let output_path = output.join(output_name);
     fs::write(&output_path, &rendered).context("Failed to write output file")?;
 
+    // Variants stay in the output dir; only the primary output is installed
+    let installed = match variant {
+      Some(_) => Vec::new(),
+      None => install_rendered(&output_path, &manifest.install),
+    };
+
     Ok(RenderedTemplate {
       output_path: output_path.to_string_lossy().to_string(),
       reload: manifest.reload,
+      installed: installed.iter().map(|p| p.to_string_lossy().to_string()).collect(),
     })
   }
 
   /// Render all template bundles in a directory
   pub fn render_all<P: AsRef<Path>, Q: AsRef<Path>>(templates_dir: P, output_dir: Q, scheme: &ColorScheme) -> Result<Vec<RenderedTemplate>> {
-    let templates = templates_dir.as_ref();
-    let output = output_dir.as_ref();
+    Self::render_all_variant(templates_dir.as_ref(), output_dir.as_ref(), scheme, None)
+  }
+
+  /// Render every bundle twice, into `<name>-dark.<ext>` and `<name>-light.<ext>`,
+  /// for apps that follow the system's dark mode toggle
+  pub fn render_dark_light<P: AsRef<Path>, Q: AsRef<Path>>(
+    templates_dir: P,
+    output_dir: Q,
+    dark: &ColorScheme,
+    light: &ColorScheme,
+  ) -> Result<Vec<RenderedTemplate>> {
+    let mut rendered = Self::render_all_variant(templates_dir.as_ref(), output_dir.as_ref(), dark, Some("dark"))?;
+    rendered.extend(Self::render_all_variant(
+      templates_dir.as_ref(),
+      output_dir.as_ref(),
+      light,
+      Some("light"),
+    )?);
+    Ok(rendered)
+  }
+
+  fn render_all_variant(templates: &Path, output: &Path, scheme: &ColorScheme, variant: Option<&str>) -> Result<Vec<RenderedTemplate>> {
     let mut rendered = Vec::new();
 
     // Find all .wallflowtemplate bundles
@@ -148,7 +290,7 @@ impl TemplateEngine {
       let path = entry.path();
 
       if path.is_dir() && path.extension().map(|e| e == "wallflowtemplate").unwrap_or(false) {
-        match Self::render_bundle(&path, output, scheme) {
+        match Self::render_bundle_variant(&path, output, scheme, variant) {
           Ok(result) => {
             rendered.push(result);
           }
@@ -162,13 +304,57 @@ impl TemplateEngine {
     Ok(rendered)
   }
 
-  /// Send reload signals to apps based on rendered template manifests
-  pub fn notify_apps(rendered: &[RenderedTemplate]) {
+  /// Template bundles in a directory with their manifests, sorted by name
+  ///
+  /// Bundles whose manifest can't be read are skipped with a warning.
+  pub fn list_bundles<P: AsRef<Path>>(templates_dir: P) -> Result<Vec<(PathBuf, TemplateManifest)>> {
+    let templates = templates_dir.as_ref();
+    let mut bundles = Vec::new();
+
+    if !templates.exists() {
+      return Ok(bundles);
+    }
+
+    for entry in fs::read_dir(templates)? {
+      let path = entry?.path();
+
+      if path.is_dir() && path.extension().map(|e| e == "wallflowtemplate").unwrap_or(false) {
+        match TemplateManifest::load(path.join("manifest.json")) {
+          Ok(manifest) => bundles.push((path, manifest)),
+          Err(e) => eprintln!("Warning: Failed to load manifest for {:?}: {}", path.file_name(), e),
+        }
+      }
+    }
+
+    bundles.sort_by_cached_key(|(_, manifest)| manifest.name.to_lowercase());
+    Ok(bundles)
+  }
+
+  /// Tell apps to reload based on rendered template manifests
+  ///
+  /// A manifest's reload command wins; kitty is recolored over remote control
+  /// when it allows it. Otherwise, or when those fail, the process is signalled.
+  /// Apps in `notify_apps` (`integration.notify_apps`) are skipped, since
+  /// `notify_color_change` already pushes colors to them.
+  pub fn notify_apps(rendered: &[RenderedTemplate], notify_apps: &[String]) {
     // Small delay to ensure template files are fully flushed before signalling
     std::thread::sleep(std::time::Duration::from_millis(50));
 
     for rt in rendered {
       if let Some(ref reload) = rt.reload {
+        if crate::integration::is_notified(notify_apps, &reload.process_name) {
+          debug!("{} is in integration.notify_apps, not reloading it here", reload.process_name);
+          continue;
+        }
+        if let Some(command) = reload_command(reload, &rt.output_path)
+          && run_quietly(&command)
+        {
+          continue;
+        }
+
+        if reload.signal.is_empty() || reload.process_name.is_empty() {
+          continue;
+        }
         let signal_arg = format!("-{}", reload.signal);
         let _ = std::process::Command::new("pkill")
           .args([&signal_arg, &reload.process_name])
@@ -199,6 +385,56 @@ impl TemplateEngine {
   }
 }
 
+/// Native reload command for an app, with `{output}` filled in
+fn reload_command(reload: &ReloadConfig, output_path: &str) -> Option<Vec<String>> {
+  if !reload.command.is_empty() {
+    return Some(reload.command.iter().map(|arg| arg.replace("{output}", output_path)).collect());
+  }
+
+  // Only works with allow_remote_control in kitty.conf; pkill covers the rest
+  if reload.process_name == "kitty" && which::which("kitty").is_ok() {
+    return Some(
+      ["kitty", "@", "set-colors", "--all", "--configured", output_path]
+        .map(String::from)
+        .to_vec(),
+    );
+  }
+
+  None
+}
+
+/// Run a command with its output discarded; true if it exited successfully
+fn run_quietly(command: &[String]) -> bool {
+  let Some((program, args)) = command.split_first() else {
+    return false;
+  };
+
+  let status = std::process::Command::new(program)
+    .args(args)
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::null())
+    .status();
+  match status {
+    Ok(status) if status.success() => true,
+    Ok(status) => {
+      debug!("Reload command {:?} failed with {}", command, status);
+      false
+    }
+    Err(e) => {
+      debug!("Failed to run reload command {:?}: {}", command, e);
+      false
+    }
+  }
+}
+
+/// Insert `-<variant>` before the extension: `colors.conf` -> `colors-dark.conf`
+fn variant_file_name(name: &str, variant: &str) -> String {
+  match name.rsplit_once('.') {
+    Some((stem, ext)) if !stem.is_empty() => format!("{}-{}.{}", stem, variant, ext),
+    _ => format!("{}-{}", name, variant),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -240,6 +476,99 @@ mod tests {
     assert!(rendered.contains("#E5E5E5")); // 0.9 * 255 = ~229 = 0xE5
   }
 
+  #[test]
+  fn test_render_dark_branch() {
+    let vars = TemplateEngine::build_variables(&test_scheme());
+    let template = "mode={{#if dark}}dark bg={background}{{else}}light fg={foreground}{{/if}};";
+
+    assert_eq!(TemplateEngine::render(template, &vars), "mode=dark bg=#191919;");
+  }
+
+  #[test]
+  fn test_render_light_branch() {
+    let mut scheme = test_scheme();
+    scheme.is_dark = false;
+    let vars = TemplateEngine::build_variables(&scheme);
+    let template = "{{#if dark}}a{{else}}b {foreground}{{/if}}\n{{#if dark}}only dark{{/if}}end";
+
+    assert_eq!(TemplateEngine::render(template, &vars), "b #E5E5E5\nend");
+  }
+
+  #[test]
+  fn test_render_conditional_left_verbatim() {
+    let vars = TemplateEngine::build_variables(&test_scheme());
+
+    let unterminated = "{{#if dark}}never closed";
+    assert_eq!(TemplateEngine::render(unterminated, &vars), unterminated);
+
+    let unknown = "{{#if light}}x{{/if}}";
+    assert_eq!(TemplateEngine::render(unknown, &vars), unknown);
+
+    let without_dark = "{{#if dark}}x{{/if}}";
+    assert_eq!(TemplateEngine::render(without_dark, &HashMap::new()), without_dark);
+  }
+
+  #[test]
+  fn test_render_filters() {
+    let mut vars = HashMap::new();
+    vars.insert("color1".to_string(), "#80FF00".to_string());
+    vars.insert("name".to_string(), "Wallflow".to_string());
+
+    let render = |template: &str| TemplateEngine::render(template, &vars);
+
+    assert_eq!(render("{color1|lower}"), "#80ff00");
+    assert_eq!(render("{name|upper}"), "WALLFLOW");
+    assert_eq!(render("{color1|strip|lower}"), "80ff00");
+    assert_eq!(render("{color1|darken:0.5}"), "#407F00");
+    assert_eq!(render("{color1|lighten:1.0|strip}"), "FFFFFF");
+    assert_eq!(render("bg={color1|rotate:120}, {color1}"), "bg=#007FFF, #80FF00");
+  }
+
+  #[test]
+  fn test_render_filters_left_verbatim() {
+    let mut vars = HashMap::new();
+    vars.insert("color1".to_string(), "#80FF00".to_string());
+    vars.insert("alpha".to_string(), "100".to_string());
+
+    for template in [
+      "{color1|bogus}",
+      "{color1|lighten:abc}",
+      "{alpha|lighten:0.2}",
+      "{missing|upper}",
+      "{{color1|upper",
+    ] {
+      assert_eq!(TemplateEngine::render(template, &vars), template);
+    }
+    assert_eq!(TemplateEngine::render("{{color1|upper}}", &vars), "{#80FF00}");
+  }
+
+  #[test]
+  fn test_variant_file_name() {
+    assert_eq!(variant_file_name("colors.conf", "dark"), "colors-dark.conf");
+    assert_eq!(variant_file_name("theme.tar.json", "light"), "theme.tar-light.json");
+    assert_eq!(variant_file_name("colors", "dark"), "colors-dark");
+    assert_eq!(variant_file_name(".Xresources", "dark"), ".Xresources-dark");
+  }
+
+  #[test]
+  fn test_reload_command() {
+    let reload = ReloadConfig {
+      signal: "USR1".to_string(),
+      process_name: "foot".to_string(),
+      command: vec!["footclient".to_string(), "--theme={output}".to_string()],
+    };
+    assert_eq!(
+      reload_command(&reload, "/tmp/colors-foot.ini"),
+      Some(vec!["footclient".to_string(), "--theme=/tmp/colors-foot.ini".to_string()])
+    );
+
+    let signal_only = ReloadConfig {
+      command: Vec::new(),
+      ..reload
+    };
+    assert_eq!(reload_command(&signal_only, "/tmp/colors-foot.ini"), None);
+  }
+
   #[test]
   fn test_render_preserves_unknown() {
     let vars = HashMap::new();
@@ -0,0 +1,215 @@
+//! Template installation
+//!
+//! Links or copies a rendered template into the destinations listed in the
+//! bundle's `install` section, so apps pick it up from their own config dirs.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use tracing::{debug, info, warn};
+
+use super::manifest::InstallConfig;
+
+/// Suffix for regular files moved aside before wallflow first installs over them
+const BACKUP_SUFFIX: &str = "wallflow-backup";
+
+/// Install a rendered file into every destination of the manifest
+///
+/// Failures are logged per destination and don't stop the others.
+/// Returns the destinations that now point at (or contain) the rendered output.
+pub fn install_rendered(rendered: &Path, install: &InstallConfig) -> Vec<PathBuf> {
+  let mut installed = Vec::new();
+
+  for destination in &install.destinations {
+    let dest = match shellexpand::full(destination) {
+      Ok(expanded) => PathBuf::from(expanded.as_ref()),
+      Err(e) => {
+        warn!("Skipping template destination {}: {}", destination, e);
+        continue;
+      }
+    };
+
+    match install_one(rendered, &dest, install) {
+      Ok(()) => installed.push(dest),
+      Err(e) => warn!("Failed to install {} to {}: {:#}", rendered.display(), dest.display(), e),
+    }
+  }
+
+  installed
+}
+
+fn install_one(rendered: &Path, dest: &Path, install: &InstallConfig) -> Result<()> {
+  if let Some(parent) = dest.parent()
+    && !parent.as_os_str().is_empty()
+    && !parent.exists()
+  {
+    if !install.create_directories {
+      bail!("{} does not exist and createDirectories is off", parent.display());
+    }
+    fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+  }
+
+  match install.method.as_str() {
+    "symlink" | "" => install_symlink(rendered, dest),
+    "copy" => install_copy(rendered, dest),
+    other => bail!("Unknown install method '{}'. Use symlink or copy.", other),
+  }
+}
+
+fn install_symlink(rendered: &Path, dest: &Path) -> Result<()> {
+  match fs::symlink_metadata(dest) {
+    Ok(meta) if meta.file_type().is_symlink() => {
+      if fs::read_link(dest).is_ok_and(|target| target == rendered) {
+        debug!("{} already links to {}", dest.display(), rendered.display());
+        return Ok(());
+      }
+      fs::remove_file(dest).context("Failed to replace existing symlink")?;
+    }
+    Ok(meta) if meta.is_dir() => bail!("{} is a directory", dest.display()),
+    Ok(_) => {
+      // Never clobber a file the user wrote by hand
+      let backup = backup_path(dest);
+      fs::rename(dest, &backup).context("Failed to back up existing file")?;
+      info!("Moved existing {} to {}", dest.display(), backup.display());
+    }
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+    Err(e) => return Err(e).context("Failed to inspect destination"),
+  }
+
+  symlink(rendered, dest).context("Failed to create symlink")?;
+  info!("Linked {} -> {}", dest.display(), rendered.display());
+  Ok(())
+}
+
+fn install_copy(rendered: &Path, dest: &Path) -> Result<()> {
+  // Copying through a symlink would overwrite whatever it points at
+  if fs::symlink_metadata(dest).is_ok_and(|meta| meta.file_type().is_symlink()) {
+    fs::remove_file(dest).context("Failed to remove existing symlink")?;
+  } else if dest.is_dir() {
+    bail!("{} is a directory", dest.display());
+  } else if dest.is_file() {
+    // Later installs replace wallflow's own earlier copy, so only the first one is kept
+    let backup = backup_path(dest);
+    if !backup.exists() {
+      fs::copy(dest, &backup).context("Failed to back up existing file")?;
+      info!("Backed up existing {} to {}", dest.display(), backup.display());
+    }
+  }
+
+  fs::copy(rendered, dest).context("Failed to copy rendered template")?;
+  info!("Copied {} to {}", rendered.display(), dest.display());
+  Ok(())
+}
+
+fn backup_path(dest: &Path) -> PathBuf {
+  let mut name = dest.file_name().unwrap_or_default().to_os_string();
+  name.push(".");
+  name.push(BACKUP_SUFFIX);
+  dest.with_file_name(name)
+}
+
+#[cfg(unix)]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+  std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+  std::os::windows::fs::symlink_file(original, link)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  fn install_config(method: &str, destinations: Vec<String>) -> InstallConfig {
+    InstallConfig {
+      method: method.to_string(),
+      create_directories: true,
+      destinations,
+    }
+  }
+
+  #[test]
+  fn test_symlink_backs_up_existing_file() {
+    let dir = TempDir::new().unwrap();
+    let rendered = dir.path().join("colors.conf");
+    fs::write(&rendered, "rendered").unwrap();
+
+    let dest = dir.path().join("app/colors.conf");
+    fs::create_dir_all(dest.parent().unwrap()).unwrap();
+    fs::write(&dest, "hand written").unwrap();
+
+    let config = install_config("symlink", vec![dest.to_string_lossy().to_string()]);
+    assert_eq!(install_rendered(&rendered, &config), vec![dest.clone()]);
+
+    assert_eq!(fs::read_link(&dest).unwrap(), rendered);
+    assert_eq!(fs::read_to_string(backup_path(&dest)).unwrap(), "hand written");
+
+    // Installing again leaves the link alone
+    assert_eq!(install_rendered(&rendered, &config), vec![dest.clone()]);
+    assert_eq!(fs::read_link(&dest).unwrap(), rendered);
+  }
+
+  #[test]
+  fn test_copy_replaces_symlink() {
+    let dir = TempDir::new().unwrap();
+    let rendered = dir.path().join("colors.conf");
+    fs::write(&rendered, "rendered").unwrap();
+
+    let elsewhere = dir.path().join("elsewhere.conf");
+    fs::write(&elsewhere, "untouched").unwrap();
+
+    let dest = dir.path().join("app/colors.conf");
+    fs::create_dir_all(dest.parent().unwrap()).unwrap();
+    symlink(&elsewhere, &dest).unwrap();
+
+    let config = install_config("copy", vec![dest.to_string_lossy().to_string()]);
+    install_rendered(&rendered, &config);
+
+    assert!(!fs::symlink_metadata(&dest).unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_to_string(&dest).unwrap(), "rendered");
+    assert_eq!(fs::read_to_string(&elsewhere).unwrap(), "untouched");
+  }
+
+  #[test]
+  fn test_copy_backs_up_existing_file() {
+    let dir = TempDir::new().unwrap();
+    let rendered = dir.path().join("colors.conf");
+    fs::write(&rendered, "rendered").unwrap();
+
+    let dest = dir.path().join("app/colors.conf");
+    fs::create_dir_all(dest.parent().unwrap()).unwrap();
+    fs::write(&dest, "hand written").unwrap();
+
+    let config = install_config("copy", vec![dest.to_string_lossy().to_string()]);
+    assert_eq!(install_rendered(&rendered, &config), vec![dest.clone()]);
+    assert_eq!(fs::read_to_string(&dest).unwrap(), "rendered");
+    assert_eq!(fs::read_to_string(backup_path(&dest)).unwrap(), "hand written");
+
+    // The next install replaces the copy, not the backup
+    fs::write(&rendered, "rendered again").unwrap();
+    install_rendered(&rendered, &config);
+    assert_eq!(fs::read_to_string(&dest).unwrap(), "rendered again");
+    assert_eq!(fs::read_to_string(backup_path(&dest)).unwrap(), "hand written");
+  }
+
+  #[test]
+  fn test_missing_parent_without_create_directories() {
+    let dir = TempDir::new().unwrap();
+    let rendered = dir.path().join("colors.conf");
+    fs::write(&rendered, "rendered").unwrap();
+
+    let dest = dir.path().join("missing/colors.conf");
+    let config = InstallConfig {
+      create_directories: false,
+      ..install_config("symlink", vec![dest.to_string_lossy().to_string()])
+    };
+
+    assert!(install_rendered(&rendered, &config).is_empty());
+    assert!(!dest.exists());
+  }
+}
@@ -53,10 +53,17 @@ pub struct TemplateManifest {
 #[serde(rename_all = "camelCase")]
 pub struct ReloadConfig {
   /// Signal name (e.g., "USR1", "USR2")
+  #[serde(default)]
   pub signal: String,
 
   /// Process name to signal (e.g., "kitty", "ghostty")
+  #[serde(default)]
   pub process_name: String,
+
+  /// Command that reloads the app, run instead of sending the signal.
+  /// `{output}` in an argument is replaced with the rendered file's path.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub command: Vec<String>,
 }
 
 /// App detection configuration
@@ -171,5 +178,17 @@ mod tests {
     assert_eq!(manifest.name, "Kitty");
     assert_eq!(manifest.detection.bundle_ids, vec!["net.kovidgoyal.kitty"]);
     assert_eq!(manifest.template.output_name, "colors-kitty.conf");
+    assert!(manifest.reload.is_none());
+  }
+
+  #[test]
+  fn test_parse_reload() {
+    let signal: ReloadConfig = serde_json::from_str(r#"{"signal": "USR1", "processName": "ghostty"}"#).unwrap();
+    assert_eq!(signal.process_name, "ghostty");
+    assert!(signal.command.is_empty());
+
+    let command: ReloadConfig = serde_json::from_str(r#"{"command": ["swaync-client", "--reload-css"]}"#).unwrap();
+    assert_eq!(command.command, vec!["swaync-client", "--reload-css"]);
+    assert!(command.signal.is_empty());
   }
 }
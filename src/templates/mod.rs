@@ -8,6 +8,7 @@
 
 mod download;
 mod engine;
+mod install;
 mod manifest;
 
 pub use download::{ensure_templates, templates_dir};
@@ -7,12 +7,20 @@
 //! - Event-driven state updates
 //! - Resource management for large collections
 
+use crate::colors::Rgb;
 use crate::daemon_status::{DaemonStatus, DaemonStatusManager};
+use crate::downloaders::filesystem::FilesystemHelper;
+use crate::downloaders::traits::Metadata;
 use anyhow::Result;
 use image::DynamicImage;
+use ratatui::layout::{Position, Rect};
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, info, warn};
 
 /// Main application state
@@ -26,15 +34,45 @@ pub struct App {
   /// Available wallpapers
   pub wallpapers: Vec<WallpaperItem>,
 
-  /// Currently selected wallpaper index
+  /// Indices into `wallpapers` that match the search query, in display order
+  filtered: Vec<usize>,
+
+  /// Currently selected position in the filtered view
   pub selected: usize,
 
+  /// Search text; wallpapers whose name contains it (ignoring case) are shown
+  pub search_query: String,
+
+  /// Whether keystrokes are going to the search bar
+  pub search_active: bool,
+
+  /// Order of the wallpaper list
+  pub sort_mode: SortMode,
+
+  /// Starred wallpapers, persisted to the favorites file
+  pub favorites: BTreeSet<PathBuf>,
+
+  /// Only show starred wallpapers
+  pub favorites_only: bool,
+
   /// Current view mode
   pub view_mode: ViewMode,
 
   /// Status messages for user feedback
   pub status_message: Option<String>,
 
+  /// When a transient status message should disappear
+  status_expires: Option<Instant>,
+
+  /// Pending delete waiting for a yes/no answer
+  pub confirm_delete: Option<DeletePrompt>,
+
+  /// Source picker / query prompt for downloading a new wallpaper
+  pub download_prompt: Option<DownloadPrompt>,
+
+  /// Download running in the background
+  pending_download: Option<PendingDownload>,
+
   /// Loading state
   pub is_loading: bool,
 
@@ -67,6 +105,48 @@ pub struct App {
 
   /// Channel to send image load requests
   image_tx: mpsc::Sender<(usize, DynamicImage)>,
+
+  /// Gallery thumbnails, keyed by path so they survive list refreshes
+  gallery_thumbnails: HashMap<PathBuf, StatefulProtocol>,
+
+  /// Gallery thumbnails currently being loaded (async)
+  gallery_loading: HashSet<PathBuf>,
+
+  /// Channel to receive downscaled gallery thumbnails
+  gallery_rx: mpsc::Receiver<(PathBuf, DynamicImage)>,
+
+  /// Channel to send gallery thumbnail loads
+  gallery_tx: mpsc::Sender<(PathBuf, DynamicImage)>,
+
+  /// Extracted palettes by path; `None` when extraction failed, so it isn't retried
+  palettes: HashMap<PathBuf, Option<Vec<Rgb>>>,
+
+  /// Palettes currently being extracted (async)
+  palette_loading: HashSet<PathBuf>,
+
+  /// Channel to receive extracted palettes
+  palette_rx: mpsc::Receiver<(PathBuf, Option<Vec<Rgb>>)>,
+
+  /// Channel to send palette extractions
+  palette_tx: mpsc::Sender<(PathBuf, Option<Vec<Rgb>>)>,
+
+  /// Rows of the wallpaper list at the last render, for mapping mouse clicks
+  pub list_area: Rect,
+
+  /// First list row shown at the last render; kept across frames so the list doesn't jump
+  pub list_offset: usize,
+
+  /// Position and time of the last click on the list, for spotting double-clicks
+  last_click: Option<(usize, Instant)>,
+
+  /// Gallery grid columns, updated by the renderer as the terminal resizes
+  pub gallery_columns: usize,
+
+  /// First grid row shown in the gallery
+  pub gallery_offset: usize,
+
+  /// Positions in the filtered view shown by the gallery at the last render
+  pub gallery_visible: Range<usize>,
 }
 
 /// Wallpaper item with metadata
@@ -81,6 +161,9 @@ pub struct WallpaperItem {
   /// File size in bytes
   pub size: Option<u64>,
 
+  /// Last modification time
+  pub modified: Option<SystemTime>,
+
   /// Image dimensions (width, height)
   pub dimensions: Option<(u32, u32)>,
 
@@ -89,6 +172,9 @@ pub struct WallpaperItem {
 
   /// Whether this wallpaper is currently set as desktop background
   pub is_current: bool,
+
+  /// Source details saved alongside downloads
+  pub metadata: Option<Metadata>,
 }
 
 /// TUI view modes
@@ -100,10 +186,117 @@ pub enum ViewMode {
   /// Preview selected wallpaper with details
   Preview,
 
+  /// Browse wallpapers as a grid of thumbnails
+  Gallery,
+
   /// Help screen with keybindings
   Help,
 }
 
+/// A wallpaper the user asked to delete, awaiting confirmation
+#[derive(Debug, Clone)]
+pub struct DeletePrompt {
+  pub path: PathBuf,
+  pub name: String,
+  /// Deleting the wallpaper that's on screen right now deserves a louder warning
+  pub is_current: bool,
+}
+
+/// Source picker for downloading from within the TUI
+#[derive(Debug, Clone)]
+pub struct DownloadPrompt {
+  /// Registered download sources
+  pub sources: Vec<String>,
+
+  /// Highlighted source
+  pub selected: usize,
+
+  /// Search terms being typed, once a source has been picked
+  pub query: Option<String>,
+}
+
+impl DownloadPrompt {
+  /// The highlighted source
+  pub fn source(&self) -> &str {
+    &self.sources[self.selected]
+  }
+}
+
+/// A download started from the TUI
+struct PendingDownload {
+  source: String,
+  started: Instant,
+  result: oneshot::Receiver<Result<PathBuf>>,
+}
+
+/// Frames of the download spinner, advanced every 100ms
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Two clicks on the same row within this window count as a double-click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// How long transient status messages stay up
+const STATUS_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Orders the wallpaper list can be sorted in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortMode {
+  /// Alphabetical by file name
+  #[default]
+  Name,
+
+  /// Most recently modified first
+  Newest,
+
+  /// Biggest files first
+  Largest,
+
+  /// Most pixels (width × height) first
+  Resolution,
+}
+
+impl SortMode {
+  /// The mode after this one, wrapping around
+  pub fn next(self) -> Self {
+    match self {
+      SortMode::Name => SortMode::Newest,
+      SortMode::Newest => SortMode::Largest,
+      SortMode::Largest => SortMode::Resolution,
+      SortMode::Resolution => SortMode::Name,
+    }
+  }
+
+  /// Short label for the list title
+  pub fn label(self) -> &'static str {
+    match self {
+      SortMode::Name => "name",
+      SortMode::Newest => "newest",
+      SortMode::Largest => "largest",
+      SortMode::Resolution => "resolution",
+    }
+  }
+
+  /// Compare two wallpapers; ties (and missing values) fall back to the name
+  fn compare(self, a: &WallpaperItem, b: &WallpaperItem) -> Ordering {
+    let pixels = |w: &WallpaperItem| w.dimensions.map(|(width, height)| u64::from(width) * u64::from(height));
+
+    let ordering = match self {
+      SortMode::Name => Ordering::Equal,
+      SortMode::Newest => b.modified.cmp(&a.modified),
+      SortMode::Largest => b.size.cmp(&a.size),
+      SortMode::Resolution => pixels(b).cmp(&pixels(a)),
+    };
+
+    ordering.then_with(|| a.name.cmp(&b.name))
+  }
+}
+
+/// Longest side of gallery thumbnails; cells are small, so there's no point decoding more
+const GALLERY_THUMBNAIL_SIZE: u32 = 320;
+
+/// Cached gallery thumbnails beyond this are dropped, keeping only the visible ones
+const GALLERY_CACHE_LIMIT: usize = 128;
+
 #[allow(dead_code)]
 impl App {
   /// Create a new application instance
@@ -126,14 +319,31 @@ impl App {
 
     // Create channel for async image loading
     let (image_tx, image_rx) = mpsc::channel(4);
+    let (gallery_tx, gallery_rx) = mpsc::channel(16);
+    let (palette_tx, palette_rx) = mpsc::channel(4);
+
+    let favorites = crate::favorites::load().unwrap_or_else(|e| {
+      warn!("Failed to load favorites: {:#}", e);
+      BTreeSet::new()
+    });
 
     let mut app = Self {
       should_quit: false,
       config,
       wallpapers: Vec::new(),
+      filtered: Vec::new(),
       selected: 0,
+      search_query: String::new(),
+      search_active: false,
+      sort_mode: SortMode::default(),
+      favorites,
+      favorites_only: false,
       view_mode: ViewMode::Browse,
       status_message: Some("Loading wallpapers...".to_string()),
+      status_expires: None,
+      confirm_delete: None,
+      download_prompt: None,
+      pending_download: None,
       is_loading: true,
       error_message: None,
       open_editor: false,
@@ -145,6 +355,20 @@ impl App {
       thumbnail_loading_for: None,
       image_rx,
       image_tx,
+      gallery_thumbnails: HashMap::new(),
+      gallery_loading: HashSet::new(),
+      gallery_rx,
+      gallery_tx,
+      palettes: HashMap::new(),
+      palette_loading: HashSet::new(),
+      palette_rx,
+      palette_tx,
+      list_area: Rect::default(),
+      list_offset: 0,
+      last_click: None,
+      gallery_columns: 1,
+      gallery_offset: 0,
+      gallery_visible: 0..0,
     };
 
     // Load wallpapers in background
@@ -178,44 +402,29 @@ impl App {
       return Ok(());
     }
 
-    let mut wallpapers = Vec::new();
-    self.collect_wallpapers(
+    let paths = crate::wallpaper::collect_wallpapers(
       wallpaper_dir,
-      &self.config.sources.local.formats,
-      &mut wallpapers,
-      self.config.sources.local.recursive,
+      &self.config.sources.local.collected_formats(),
+      self.config.sources.local.search_depth(),
     )?;
+    crate::wallpaper::warn_undecodable(paths.iter().map(PathBuf::as_path));
+    let mut wallpapers = paths.iter().map(|path| self.create_wallpaper_item(path)).collect::<Result<Vec<_>>>()?;
 
-    // Sort wallpapers by name for consistent ordering
-    wallpapers.sort_by(|a, b| a.name.cmp(&b.name));
-
-    self.wallpapers = wallpapers;
-    self.selected = 0; // Reset selection
-
-    debug!("📁 Loaded {} wallpapers", self.wallpapers.len());
-    Ok(())
-  }
-
-  /// Recursively collect wallpaper files
-  fn collect_wallpapers(&self, dir: &Path, formats: &[String], wallpapers: &mut Vec<WallpaperItem>, recursive: bool) -> Result<()> {
-    let entries = std::fs::read_dir(dir).map_err(|e| anyhow::anyhow!("Failed to read directory {}: {}", dir.display(), e))?;
-
-    for entry in entries {
-      let entry = entry?;
-      let path = entry.path();
+    let sort_mode = self.sort_mode;
+    wallpapers.sort_by(|a, b| sort_mode.compare(a, b));
 
-      if path.is_file() {
-        if let Some(extension) = path.extension().and_then(|ext| ext.to_str())
-          && formats.iter().any(|fmt| fmt.eq_ignore_ascii_case(extension))
-        {
-          let wallpaper_item = self.create_wallpaper_item(&path)?;
-          wallpapers.push(wallpaper_item);
-        }
-      } else if path.is_dir() && recursive {
-        self.collect_wallpapers(&path, formats, wallpapers, recursive)?;
+    if let Some(current) = crate::wallpaper::get_current_wallpaper().await {
+      for item in &mut wallpapers {
+        item.is_current = crate::wallpaper::is_same_wallpaper(&item.path, &current);
       }
     }
 
+    self.wallpapers = wallpapers;
+    self.thumbnail_loaded_for = None;
+    self.thumbnail_loading_for = None;
+    self.select_path(None); // Reset selection
+
+    debug!("📁 Loaded {} wallpapers", self.wallpapers.len());
     Ok(())
   }
 
@@ -223,7 +432,9 @@ impl App {
   fn create_wallpaper_item(&self, path: &Path) -> Result<WallpaperItem> {
     let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("Unknown").to_string();
 
-    let size = std::fs::metadata(path).ok().map(|m| m.len());
+    let metadata = std::fs::metadata(path).ok();
+    let size = metadata.as_ref().map(|m| m.len());
+    let modified = metadata.and_then(|m| m.modified().ok());
 
     let dimensions = imagesize::size(path).ok().map(|s| (s.width as u32, s.height as u32));
 
@@ -233,17 +444,165 @@ impl App {
       path: path.to_path_buf(),
       name,
       size,
+      modified,
       dimensions,
       format,
-      is_current: false, // TODO: Detect current wallpaper
+      is_current: false, // Set in refresh_wallpapers once the current wallpaper is known
+      metadata: FilesystemHelper::read_sidecar(path),
     })
   }
 
+  /// Number of wallpapers matching the search query
+  pub fn visible_count(&self) -> usize {
+    self.filtered.len()
+  }
+
+  /// Wallpapers matching the search query, in display order
+  pub fn visible_wallpapers(&self) -> impl Iterator<Item = &WallpaperItem> {
+    self.filtered.iter().map(|&index| &self.wallpapers[index])
+  }
+
+  /// Wallpaper at a position in the filtered view
+  pub fn visible_wallpaper(&self, position: usize) -> Option<&WallpaperItem> {
+    self.filtered.get(position).map(|&index| &self.wallpapers[index])
+  }
+
+  /// Index into `wallpapers` of the current selection
+  fn selected_index(&self) -> Option<usize> {
+    self.filtered.get(self.selected).copied()
+  }
+
+  /// Switch to the next sort mode, keeping the selected wallpaper selected
+  pub fn cycle_sort(&mut self) {
+    self.sort_mode = self.sort_mode.next();
+
+    let selected = self.selected_wallpaper().map(|w| w.path.clone());
+    let sort_mode = self.sort_mode;
+    self.wallpapers.sort_by(|a, b| sort_mode.compare(a, b));
+
+    // Indices moved, so index-keyed thumbnail state no longer applies
+    self.thumbnail_loaded_for = None;
+    self.thumbnail_loading_for = None;
+    self.select_path(selected.as_deref());
+
+    self.status_message = Some(format!("Sorted by {}", self.sort_mode.label()));
+  }
+
+  /// Whether `path` is starred
+  pub fn is_favorite(&self, path: &Path) -> bool {
+    self.favorites.contains(path)
+  }
+
+  /// Star or unstar the selected wallpaper and save the favorites file
+  pub fn toggle_favorite(&mut self) {
+    let Some(wallpaper) = self.selected_wallpaper() else {
+      return;
+    };
+    let (path, name) = (wallpaper.path.clone(), wallpaper.name.clone());
+
+    let starred = if self.favorites.remove(&path) {
+      false
+    } else {
+      self.favorites.insert(path);
+      true
+    };
+
+    if let Err(e) = crate::favorites::save(&self.favorites) {
+      let error = format!("❌ Failed to save favorites: {:#}", e);
+      warn!("{}", error);
+      self.set_transient_status(error);
+      return;
+    }
+
+    if self.favorites_only {
+      // Unstarring hides the wallpaper; stay at the same position like a delete does
+      let position = self.selected;
+      self.apply_filter();
+      self.selected = position.min(self.filtered.len().saturating_sub(1));
+      self.request_thumbnail();
+    }
+
+    let action = if starred { "★ Starred" } else { "Unstarred" };
+    self.set_transient_status(format!("{}: {}", action, name));
+  }
+
+  /// Switch between showing every wallpaper and only the starred ones
+  pub fn toggle_favorites_only(&mut self) {
+    self.favorites_only = !self.favorites_only;
+    self.apply_filter();
+
+    let message = if self.favorites_only {
+      format!("Showing {} favorites", self.visible_count())
+    } else {
+      "Showing all wallpapers".to_string()
+    };
+    self.set_transient_status(message);
+  }
+
+  /// Rebuild the filtered view from the search query, keeping the selection when it still matches
+  pub fn apply_filter(&mut self) {
+    let previous = self.selected_wallpaper().map(|w| w.path.clone());
+    self.select_path(previous.as_deref());
+  }
+
+  /// Rebuild the filtered view and select `path` in it, or the first entry if it isn't shown
+  fn select_path(&mut self, path: Option<&Path>) {
+    let query = self.search_query.to_lowercase();
+
+    self.filtered = self
+      .wallpapers
+      .iter()
+      .enumerate()
+      .filter(|(_, wallpaper)| !self.favorites_only || self.favorites.contains(&wallpaper.path))
+      .filter(|(_, wallpaper)| query.is_empty() || wallpaper.name.to_lowercase().contains(&query))
+      .map(|(index, _)| index)
+      .collect();
+
+    self.selected = path
+      .and_then(|path| self.filtered.iter().position(|&index| self.wallpapers[index].path == path))
+      .unwrap_or(0);
+    self.gallery_offset = 0;
+    self.list_offset = 0;
+    self.request_thumbnail();
+  }
+
+  /// Open the search bar
+  pub fn start_search(&mut self) {
+    self.search_active = true;
+  }
+
+  /// Type a character into the search bar
+  pub fn push_search_char(&mut self, c: char) {
+    self.search_query.push(c);
+    self.apply_filter();
+  }
+
+  /// Delete the last character of the search query
+  pub fn pop_search_char(&mut self) {
+    if self.search_query.pop().is_some() {
+      self.apply_filter();
+    }
+  }
+
+  /// Close the search bar, keeping the filter
+  pub fn finish_search(&mut self) {
+    self.search_active = false;
+  }
+
+  /// Close the search bar and show every wallpaper again
+  pub fn clear_search(&mut self) {
+    self.search_active = false;
+    if !self.search_query.is_empty() {
+      self.search_query.clear();
+      self.apply_filter();
+    }
+  }
+
   /// Move selection up
   pub fn select_previous(&mut self) {
-    if !self.wallpapers.is_empty() {
+    if !self.filtered.is_empty() {
       self.selected = if self.selected == 0 {
-        self.wallpapers.len() - 1
+        self.filtered.len() - 1
       } else {
         self.selected - 1
       };
@@ -253,55 +612,187 @@ impl App {
 
   /// Move selection down
   pub fn select_next(&mut self) {
-    if !self.wallpapers.is_empty() {
-      self.selected = (self.selected + 1) % self.wallpapers.len();
+    if !self.filtered.is_empty() {
+      self.selected = (self.selected + 1) % self.filtered.len();
+      self.request_thumbnail();
+    }
+  }
+
+  /// Jump to the first wallpaper
+  pub fn select_first(&mut self) {
+    self.selected = 0;
+    self.request_thumbnail();
+  }
+
+  /// Jump to the last wallpaper
+  pub fn select_last(&mut self) {
+    if !self.filtered.is_empty() {
+      self.selected = self.filtered.len() - 1;
+      self.request_thumbnail();
+    }
+  }
+
+  /// Position in the filtered view of the list row at a terminal cell, if there is one
+  pub fn list_position_at(&self, column: u16, row: u16) -> Option<usize> {
+    if !self.list_area.contains(Position::new(column, row)) {
+      return None;
+    }
+    let position = self.list_offset + usize::from(row - self.list_area.y);
+    (position < self.filtered.len()).then_some(position)
+  }
+
+  /// Select the list row under a click; a second click on the same row applies it
+  pub async fn click_list(&mut self, column: u16, row: u16) -> Result<()> {
+    let Some(position) = self.list_position_at(column, row) else {
+      return Ok(());
+    };
+
+    let now = Instant::now();
+    let double_click = self
+      .last_click
+      .is_some_and(|(last, at)| last == position && now.duration_since(at) <= DOUBLE_CLICK);
+
+    self.selected = position;
+    self.request_thumbnail();
+
+    if double_click {
+      self.last_click = None;
+      self.apply_selected_wallpaper().await?;
+    } else {
+      self.last_click = Some((position, now));
+    }
+    Ok(())
+  }
+
+  /// Move selection one gallery row down, staying in the same column
+  pub fn select_next_row(&mut self) {
+    let target = self.selected + self.gallery_columns.max(1);
+    if target < self.filtered.len() {
+      self.selected = target;
+      self.request_thumbnail();
+    }
+  }
+
+  /// Move selection one gallery row up, staying in the same column
+  pub fn select_previous_row(&mut self) {
+    if let Some(target) = self.selected.checked_sub(self.gallery_columns.max(1)) {
+      self.selected = target;
       self.request_thumbnail();
     }
   }
 
   /// Request thumbnail load for current selection (non-blocking)
   pub fn request_thumbnail(&mut self) {
+    let Some(index) = self.selected_index() else {
+      return;
+    };
+
     // Skip if we already have this thumbnail or it's already loading
-    if self.thumbnail_loaded_for == Some(self.selected) {
+    if self.thumbnail_loaded_for == Some(index) {
       return;
     }
-    if self.thumbnail_loading_for == Some(self.selected) {
+    if self.thumbnail_loading_for == Some(index) {
       return;
     }
     if self.image_picker.is_none() {
       return;
     }
 
-    let Some(wallpaper) = self.wallpapers.get(self.selected) else {
-      return;
-    };
+    let wallpaper = &self.wallpapers[index];
 
     // Clear old thumbnail immediately so "Loading..." shows
     self.thumbnail_state = None;
     self.thumbnail_loaded_for = None;
 
-    let index = self.selected;
     let path = wallpaper.path.clone();
     let tx = self.image_tx.clone();
 
     self.thumbnail_loading_for = Some(index);
 
     // Spawn background task to load image
-    tokio::spawn(async move {
-      let load_result = tokio::task::spawn_blocking(move || image::ImageReader::open(&path).ok().and_then(|r| r.decode().ok())).await;
+    spawn_image_load(path, None, move |img| async move {
+      let _ = tx.send((index, img)).await;
+    });
+  }
+
+  /// Request thumbnails for the gallery cells visible at the last render (non-blocking)
+  pub fn prefetch_gallery(&mut self) {
+    if self.view_mode != ViewMode::Gallery || self.image_picker.is_none() {
+      return;
+    }
+
+    let visible = self.gallery_visible.start.min(self.filtered.len())..self.gallery_visible.end.min(self.filtered.len());
+
+    if self.gallery_thumbnails.len() > GALLERY_CACHE_LIMIT {
+      let visible: HashSet<&PathBuf> = self.filtered[visible.clone()].iter().map(|&index| &self.wallpapers[index].path).collect();
+      self.gallery_thumbnails.retain(|path, _| visible.contains(path));
+    }
 
-      if let Ok(Some(img)) = load_result {
-        let _ = tx.send((index, img)).await;
+    for &index in &self.filtered[visible] {
+      let wallpaper = &self.wallpapers[index];
+      if self.gallery_thumbnails.contains_key(&wallpaper.path) || !self.gallery_loading.insert(wallpaper.path.clone()) {
+        continue;
       }
+
+      let path = wallpaper.path.clone();
+      let tx = self.gallery_tx.clone();
+      spawn_image_load(path.clone(), Some(GALLERY_THUMBNAIL_SIZE), move |img| async move {
+        let _ = tx.send((path, img)).await;
+      });
+    }
+  }
+
+  /// Extract the selected wallpaper's palette unless it's cached or already underway (non-blocking)
+  pub fn request_palette(&mut self) {
+    let Some(path) = self.selected_wallpaper().map(|w| w.path.clone()) else {
+      return;
+    };
+    if self.palettes.contains_key(&path) || !self.palette_loading.insert(path.clone()) {
+      return;
+    }
+
+    let extractor = self.config.colors.extractor();
+    let options = self.config.colors.extraction_options();
+    let tx = self.palette_tx.clone();
+
+    tokio::spawn(async move {
+      let extract_path = path.clone();
+      let colors = tokio::task::spawn_blocking(move || match extractor.extract(&extract_path, &options) {
+        Ok(scheme) => Some(scheme.colors),
+        Err(e) => {
+          debug!("No palette for {}: {:#}", extract_path.display(), e);
+          None
+        }
+      })
+      .await
+      .ok()
+      .flatten();
+
+      let _ = tx.send((path, colors)).await;
     });
   }
 
+  /// Extracted palette for a wallpaper, if it's ready and extraction succeeded
+  pub fn palette(&self, path: &Path) -> Option<&[Rgb]> {
+    self.palettes.get(path)?.as_deref()
+  }
+
+  /// Whether a palette is still being extracted for `path`
+  pub fn is_palette_loading(&self, path: &Path) -> bool {
+    self.palette_loading.contains(path)
+  }
+
+  /// Gallery thumbnail for a wallpaper, if it has been loaded
+  pub fn gallery_thumbnail(&mut self, path: &Path) -> Option<&mut StatefulProtocol> {
+    self.gallery_thumbnails.get_mut(path)
+  }
+
   /// Poll for loaded images and update state (call from render loop)
   pub fn poll_thumbnail(&mut self) {
     // Check if an image was loaded
     while let Ok((index, dyn_img)) = self.image_rx.try_recv() {
       // Only use if it's still the selected wallpaper
-      if index == self.selected
+      if Some(index) == self.selected_index()
         && let Some(picker) = &mut self.image_picker
       {
         self.thumbnail_state = Some(picker.new_resize_protocol(dyn_img));
@@ -313,6 +804,18 @@ impl App {
         self.thumbnail_loading_for = None;
       }
     }
+
+    while let Ok((path, colors)) = self.palette_rx.try_recv() {
+      self.palette_loading.remove(&path);
+      self.palettes.insert(path, colors);
+    }
+
+    while let Ok((path, dyn_img)) = self.gallery_rx.try_recv() {
+      self.gallery_loading.remove(&path);
+      if let Some(picker) = &mut self.image_picker {
+        self.gallery_thumbnails.insert(path, picker.new_resize_protocol(dyn_img));
+      }
+    }
   }
 
   /// Check if a thumbnail is currently loading
@@ -332,7 +835,7 @@ impl App {
 
   /// Get the currently selected wallpaper
   pub fn selected_wallpaper(&self) -> Option<&WallpaperItem> {
-    self.wallpapers.get(self.selected)
+    self.selected_index().map(|index| &self.wallpapers[index])
   }
 
   /// Set the selected wallpaper as desktop background
@@ -343,8 +846,8 @@ impl App {
 
       // Use the wallpaper module to apply the wallpaper
       match crate::wallpaper::apply_wallpaper(&wallpaper.path, &self.config).await {
-        Ok(()) => {
-          self.status_message = Some(format!("✅ Applied: {}", wallpaper.name));
+        Ok(applied) => {
+          self.status_message = Some(format!("✅ Applied with {}: {}", applied.backend, wallpaper.name));
 
           // Mark this wallpaper as current and unmark others
           for item in &mut self.wallpapers {
@@ -361,6 +864,236 @@ impl App {
     Ok(())
   }
 
+  /// Ask for confirmation before deleting the selected wallpaper
+  pub fn request_delete(&mut self) {
+    if let Some(wallpaper) = self.selected_wallpaper() {
+      self.confirm_delete = Some(DeletePrompt {
+        path: wallpaper.path.clone(),
+        name: wallpaper.name.clone(),
+        is_current: wallpaper.is_current,
+      });
+    }
+  }
+
+  /// Dismiss the delete prompt without deleting anything
+  pub fn cancel_delete(&mut self) {
+    self.confirm_delete = None;
+  }
+
+  /// Delete the wallpaper from the pending prompt
+  ///
+  /// The file goes to the system trash when there is one, and is removed
+  /// outright otherwise. Its metadata sidecar goes with it.
+  pub fn confirm_delete(&mut self) {
+    let Some(prompt) = self.confirm_delete.take() else {
+      return;
+    };
+
+    let result = match trash::delete(&prompt.path) {
+      Ok(()) => Ok("Moved to trash"),
+      Err(e) => {
+        debug!("Trash unavailable for {} ({}), deleting instead", prompt.path.display(), e);
+        std::fs::remove_file(&prompt.path).map(|()| "Deleted")
+      }
+    };
+
+    match result {
+      Ok(action) => {
+        let sidecar = FilesystemHelper::sidecar_path(&prompt.path);
+        if sidecar.exists() && trash::delete(&sidecar).is_err() {
+          let _ = std::fs::remove_file(&sidecar);
+        }
+
+        info!("{} {}", action, prompt.path.display());
+        self.remove_wallpaper(&prompt.path);
+        self.set_transient_status(format!("🗑️  {}: {}", action, prompt.name));
+      }
+      Err(e) => {
+        let error = format!("❌ Failed to delete {}: {}", prompt.name, e);
+        warn!("{}", error);
+        self.set_transient_status(error);
+      }
+    }
+  }
+
+  /// Open the source picker for a new download
+  pub fn open_download_prompt(&mut self) {
+    if let Some(pending) = &self.pending_download {
+      self.set_transient_status(format!("Already downloading from {}", pending.source));
+      return;
+    }
+
+    let sources = crate::downloaders::list_sources();
+    if sources.is_empty() {
+      self.error_message = Some("No download sources are available".to_string());
+      return;
+    }
+
+    self.download_prompt = Some(DownloadPrompt {
+      sources,
+      selected: 0,
+      query: None,
+    });
+  }
+
+  /// Move the source picker highlight down, wrapping around
+  pub fn download_prompt_next(&mut self) {
+    if let Some(prompt) = &mut self.download_prompt {
+      prompt.selected = (prompt.selected + 1) % prompt.sources.len();
+    }
+  }
+
+  /// Move the source picker highlight up, wrapping around
+  pub fn download_prompt_previous(&mut self) {
+    if let Some(prompt) = &mut self.download_prompt {
+      prompt.selected = prompt.selected.checked_sub(1).unwrap_or(prompt.sources.len() - 1);
+    }
+  }
+
+  /// Pick the highlighted source and ask for search terms
+  pub fn choose_download_source(&mut self) {
+    if let Some(prompt) = &mut self.download_prompt {
+      prompt.query = Some(String::new());
+    }
+  }
+
+  /// Type a character into the download query
+  pub fn push_download_char(&mut self, c: char) {
+    if let Some(query) = self.download_prompt.as_mut().and_then(|p| p.query.as_mut()) {
+      query.push(c);
+    }
+  }
+
+  /// Delete the last character of the download query
+  pub fn pop_download_char(&mut self) {
+    if let Some(query) = self.download_prompt.as_mut().and_then(|p| p.query.as_mut()) {
+      query.pop();
+    }
+  }
+
+  /// Step back out of the download prompt: from the query to the picker, then closed
+  pub fn cancel_download_prompt(&mut self) {
+    match &mut self.download_prompt {
+      Some(prompt) if prompt.query.is_some() => prompt.query = None,
+      _ => self.download_prompt = None,
+    }
+  }
+
+  /// Start downloading from the chosen source in the background
+  pub fn start_download(&mut self) {
+    let Some(prompt) = self.download_prompt.take() else {
+      return;
+    };
+
+    let source = prompt.source().to_string();
+    let query: Vec<String> = prompt.query.unwrap_or_default().split_whitespace().map(str::to_string).collect();
+    let config = self.config.clone();
+    let (tx, result) = oneshot::channel();
+
+    info!("Downloading from {} (query: {:?})", source, query);
+    let task_source = source.clone();
+    tokio::spawn(async move {
+      let opts = crate::downloaders::DownloadOptions {
+        no_set: true,
+        ..Default::default()
+      };
+      let downloaded = crate::downloaders::download_from_source(&task_source, &config, &query, &opts)
+        .await
+        .map(|wallpaper| wallpaper.file_path)
+        .map_err(anyhow::Error::from);
+      let _ = tx.send(downloaded);
+    });
+
+    self.pending_download = Some(PendingDownload {
+      source,
+      started: Instant::now(),
+      result,
+    });
+  }
+
+  /// Spinner line for the running download, if there is one
+  pub fn download_status(&self) -> Option<String> {
+    let pending = self.pending_download.as_ref()?;
+    let frame = (pending.started.elapsed().as_millis() / 100) as usize % SPINNER_FRAMES.len();
+    Some(format!("{} Downloading from {}...", SPINNER_FRAMES[frame], pending.source))
+  }
+
+  /// Pick up a finished download: refresh the list and select the new file (call from render loop)
+  pub async fn poll_download(&mut self) -> Result<()> {
+    let Some(pending) = &mut self.pending_download else {
+      return Ok(());
+    };
+
+    let outcome = match pending.result.try_recv() {
+      Ok(outcome) => outcome,
+      Err(oneshot::error::TryRecvError::Empty) => return Ok(()),
+      Err(oneshot::error::TryRecvError::Closed) => Err(anyhow::anyhow!("download task stopped unexpectedly")),
+    };
+    let source = self.pending_download.take().map(|p| p.source).unwrap_or_default();
+
+    let path = match outcome {
+      Ok(path) => path,
+      Err(e) => {
+        let error = format!("Download from {} failed: {:#}", source, e);
+        warn!("{}", error);
+        self.error_message = Some(error);
+        return Ok(());
+      }
+    };
+
+    self.refresh_wallpapers().await?;
+    self.is_loading = false;
+
+    // Downloads can land outside the browsed folder; list them anyway
+    if !self.wallpapers.iter().any(|w| w.path == path) {
+      let item = self.create_wallpaper_item(&path)?;
+      self.wallpapers.push(item);
+      let sort_mode = self.sort_mode;
+      self.wallpapers.sort_by(|a, b| sort_mode.compare(a, b));
+    }
+
+    // Make sure the new file isn't hidden by a filter
+    self.search_query.clear();
+    self.favorites_only = false;
+    self.select_path(Some(&path));
+
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    self.set_transient_status(format!("⬇️  Downloaded from {}: {}", source, name));
+    Ok(())
+  }
+
+  /// Drop a wallpaper from the collection, keeping the selection at the same position
+  fn remove_wallpaper(&mut self, path: &Path) {
+    let Some(index) = self.wallpapers.iter().position(|w| w.path == path) else {
+      return;
+    };
+
+    let position = self.selected;
+    self.wallpapers.remove(index);
+    self.gallery_thumbnails.remove(path);
+    self.thumbnail_loaded_for = None;
+    self.thumbnail_loading_for = None;
+    self.thumbnail_state = None;
+
+    self.select_path(None);
+    self.selected = position.min(self.filtered.len().saturating_sub(1));
+    self.request_thumbnail();
+  }
+
+  /// Show a status message that clears itself after a few seconds
+  pub fn set_transient_status(&mut self, message: String) {
+    self.status_message = Some(message);
+    self.status_expires = Some(Instant::now() + STATUS_TIMEOUT);
+  }
+
+  /// Clear the transient status message once it has timed out (call from render loop)
+  pub fn expire_status(&mut self) {
+    if self.status_expires.is_some_and(|expires| Instant::now() >= expires) {
+      self.status_message = None;
+      self.status_expires = None;
+    }
+  }
+
   /// Switch view mode
   pub fn set_view_mode(&mut self, mode: ViewMode) {
     debug!("Switching to view mode: {:?}", mode);
@@ -399,12 +1132,36 @@ impl App {
   pub fn status_info(&self) -> String {
     match &self.daemon_status {
       Some(status) if status.is_stale() => "Daemon: Offline".to_string(),
+      Some(status) if status.paused => "Daemon: Paused".to_string(),
       Some(status) => format!("Daemon: {} remaining", status.time_remaining_formatted()),
       None => "Daemon: Unknown".to_string(),
     }
   }
 }
 
+/// Decode an image on the blocking pool, optionally downscaled, and hand it to `deliver`.
+/// Images that fail to open or decode are skipped.
+fn spawn_image_load<F, Fut>(path: PathBuf, max_size: Option<u32>, deliver: F)
+where
+  F: FnOnce(DynamicImage) -> Fut + Send + 'static,
+  Fut: std::future::Future<Output = ()> + Send,
+{
+  tokio::spawn(async move {
+    let load_result = tokio::task::spawn_blocking(move || {
+      let img = image::ImageReader::open(&path).ok().and_then(|r| r.decode().ok())?;
+      Some(match max_size {
+        Some(size) => img.thumbnail(size, size),
+        None => img,
+      })
+    })
+    .await;
+
+    if let Ok(Some(img)) = load_result {
+      deliver(img).await;
+    }
+  });
+}
+
 /// Helper function to format file size
 pub fn format_file_size(size: u64) -> String {
   const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
@@ -424,3 +1181,50 @@ pub fn format_file_size(size: u64) -> String {
     format!("{:.1} {}", value, UNITS[unit_index])
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  fn item(name: &str, size: u64, age_secs: u64, dimensions: (u32, u32)) -> WallpaperItem {
+    WallpaperItem {
+      path: PathBuf::from(name),
+      name: name.to_string(),
+      size: Some(size),
+      modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 - age_secs)),
+      dimensions: Some(dimensions),
+      format: None,
+      is_current: false,
+      metadata: None,
+    }
+  }
+
+  #[test]
+  fn test_sort_modes() {
+    let mut items = [
+      item("b.jpg", 300, 10, (1920, 1080)),
+      item("a.jpg", 100, 5, (3840, 2160)),
+      item("c.jpg", 200, 50, (2560, 1440)),
+    ];
+
+    let mut order = |mode: SortMode| {
+      items.sort_by(|a, b| mode.compare(a, b));
+      items.iter().map(|w| w.name.as_str()).collect::<Vec<_>>().join(",")
+    };
+
+    assert_eq!(order(SortMode::Name), "a.jpg,b.jpg,c.jpg");
+    assert_eq!(order(SortMode::Newest), "a.jpg,b.jpg,c.jpg");
+    assert_eq!(order(SortMode::Largest), "b.jpg,c.jpg,a.jpg");
+    assert_eq!(order(SortMode::Resolution), "a.jpg,c.jpg,b.jpg");
+  }
+
+  #[test]
+  fn test_sort_mode_cycles() {
+    let mut mode = SortMode::default();
+    for _ in 0..4 {
+      mode = mode.next();
+    }
+    assert_eq!(mode, SortMode::Name);
+  }
+}
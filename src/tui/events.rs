@@ -8,7 +8,7 @@
 //! - Error handling in interactive context
 
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use std::time::Duration;
 use tracing::debug;
 
@@ -35,8 +35,8 @@ pub async fn handle_events(app: &mut App) -> Result<bool> {
       Event::Key(key_event) => {
         return handle_key_event(app, key_event).await;
       }
-      Event::Mouse(_) => {
-        // Mouse events can be handled here for future enhancement
+      Event::Mouse(mouse_event) => {
+        handle_mouse_event(app, mouse_event).await?;
       }
       Event::Resize(_, _) => {
         // Terminal resize events - automatically handled by ratatui
@@ -51,6 +51,36 @@ pub async fn handle_events(app: &mut App) -> Result<bool> {
 
 /// Handle keyboard input events
 async fn handle_key_event(app: &mut App, key: KeyEvent) -> Result<bool> {
+  // A pending delete takes the next keystroke as its answer
+  if app.confirm_delete.is_some() {
+    match key.code {
+      KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_delete(),
+      KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.cancel_delete(),
+      _ => {}
+    }
+    return Ok(false);
+  }
+
+  // The download prompt owns the keyboard while it's open
+  if app.download_prompt.is_some() {
+    if key.modifiers == KeyModifiers::CONTROL && key.code == KeyCode::Char('c') {
+      app.quit();
+      return Ok(true);
+    }
+    handle_download_keys(app, &key);
+    return Ok(false);
+  }
+
+  // While searching, keystrokes go to the search bar
+  if app.search_active {
+    if key.modifiers == KeyModifiers::CONTROL && key.code == KeyCode::Char('c') {
+      app.quit();
+      return Ok(true);
+    }
+    handle_search_keys(app, &key);
+    return Ok(false);
+  }
+
   // Global keybindings (work in all modes)
   if handle_global_keys(app, &key).await? {
     return Ok(true); // Should quit
@@ -59,6 +89,7 @@ async fn handle_key_event(app: &mut App, key: KeyEvent) -> Result<bool> {
   // Mode-specific keybindings
   match app.view_mode {
     ViewMode::Browse => handle_browse_keys(app, &key).await?,
+    ViewMode::Gallery => handle_gallery_keys(app, &key).await?,
     ViewMode::Preview => handle_preview_keys(app, &key).await?,
     ViewMode::Help => handle_help_keys(app, &key).await?,
   }
@@ -66,6 +97,29 @@ async fn handle_key_event(app: &mut App, key: KeyEvent) -> Result<bool> {
   Ok(false)
 }
 
+/// Handle mouse input: clicks select list rows, the wheel moves the selection
+async fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Result<()> {
+  // Popups and the search bar expect the keyboard
+  if app.confirm_delete.is_some() || app.download_prompt.is_some() || app.search_active {
+    return Ok(());
+  }
+
+  let gallery = app.view_mode == ViewMode::Gallery;
+  match mouse.kind {
+    MouseEventKind::Down(MouseButton::Left) if app.view_mode == ViewMode::Browse => {
+      app.click_list(mouse.column, mouse.row).await?;
+    }
+    MouseEventKind::ScrollDown if gallery => app.select_next_row(),
+    MouseEventKind::ScrollUp if gallery => app.select_previous_row(),
+    // The wheel stops at the ends rather than wrapping around like j/k
+    MouseEventKind::ScrollDown if app.selected + 1 < app.visible_count() => app.select_next(),
+    MouseEventKind::ScrollUp if app.selected > 0 => app.select_previous(),
+    _ => {}
+  }
+
+  Ok(())
+}
+
 /// Handle global keybindings that work in all modes
 async fn handle_global_keys(app: &mut App, key: &KeyEvent) -> Result<bool> {
   match (key.modifiers, key.code) {
@@ -75,15 +129,17 @@ async fn handle_global_keys(app: &mut App, key: &KeyEvent) -> Result<bool> {
       return Ok(true);
     }
     (KeyModifiers::NONE, KeyCode::Char('q')) => {
-      if app.view_mode == ViewMode::Browse {
+      if matches!(app.view_mode, ViewMode::Browse | ViewMode::Gallery) {
         app.quit();
         return Ok(true);
       }
     }
     (KeyModifiers::NONE, KeyCode::Esc) => {
-      // Return to browse mode from any other mode
+      // Return to browse mode from any other mode, or drop the search filter
       if app.view_mode != ViewMode::Browse {
         app.set_view_mode(ViewMode::Browse);
+      } else {
+        app.clear_search();
       }
     }
 
@@ -111,11 +167,11 @@ async fn handle_browse_keys(app: &mut App, key: &KeyEvent) -> Result<()> {
     // Vim-style navigation
     KeyCode::Char('j') | KeyCode::Down => {
       app.select_next();
-      debug!("Selected wallpaper: {}/{}", app.selected + 1, app.wallpapers.len());
+      debug!("Selected wallpaper: {}/{}", app.selected + 1, app.visible_count());
     }
     KeyCode::Char('k') | KeyCode::Up => {
       app.select_previous();
-      debug!("Selected wallpaper: {}/{}", app.selected + 1, app.wallpapers.len());
+      debug!("Selected wallpaper: {}/{}", app.selected + 1, app.visible_count());
     }
 
     // Page navigation
@@ -134,16 +190,35 @@ async fn handle_browse_keys(app: &mut App, key: &KeyEvent) -> Result<()> {
 
     // First/last navigation
     KeyCode::Char('g') => {
-      app.selected = 0;
-      app.request_thumbnail();
+      app.select_first();
       debug!("Jumped to first wallpaper");
     }
     KeyCode::Char('G') => {
-      if !app.wallpapers.is_empty() {
-        app.selected = app.wallpapers.len() - 1;
-        app.request_thumbnail();
-        debug!("Jumped to last wallpaper");
-      }
+      app.select_last();
+      debug!("Jumped to last wallpaper");
+    }
+
+    // Search and sort
+    KeyCode::Char('/') => {
+      app.start_search();
+    }
+    KeyCode::Char('s') => {
+      app.cycle_sort();
+    }
+
+    KeyCode::Char('d') => {
+      app.request_delete();
+    }
+    KeyCode::Char('D') => {
+      app.open_download_prompt();
+    }
+
+    // Favorites
+    KeyCode::Char('f') => {
+      app.toggle_favorite();
+    }
+    KeyCode::Char('F') => {
+      app.toggle_favorites_only();
     }
 
     // Actions
@@ -153,6 +228,9 @@ async fn handle_browse_keys(app: &mut App, key: &KeyEvent) -> Result<()> {
     KeyCode::Char('p') => {
       app.set_view_mode(ViewMode::Preview);
     }
+    KeyCode::Char('v') => {
+      app.set_view_mode(ViewMode::Gallery);
+    }
     KeyCode::Char('e') => {
       // Signal to open editor (handled by main loop)
       app.open_editor = true;
@@ -173,6 +251,78 @@ async fn handle_browse_keys(app: &mut App, key: &KeyEvent) -> Result<()> {
   Ok(())
 }
 
+/// Handle typing in the search bar
+fn handle_search_keys(app: &mut App, key: &KeyEvent) {
+  match key.code {
+    KeyCode::Esc => app.clear_search(),
+    KeyCode::Enter => app.finish_search(),
+    KeyCode::Backspace => app.pop_search_char(),
+    KeyCode::Down => app.select_next(),
+    KeyCode::Up => app.select_previous(),
+    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => app.push_search_char(c),
+    _ => {}
+  }
+}
+
+/// Handle the download source picker and its query prompt
+fn handle_download_keys(app: &mut App, key: &KeyEvent) {
+  let typing = app.download_prompt.as_ref().is_some_and(|prompt| prompt.query.is_some());
+
+  match key.code {
+    KeyCode::Esc => app.cancel_download_prompt(),
+    KeyCode::Enter if typing => app.start_download(),
+    KeyCode::Enter => app.choose_download_source(),
+    KeyCode::Backspace if typing => app.pop_download_char(),
+    KeyCode::Char(c) if typing && !key.modifiers.contains(KeyModifiers::CONTROL) => app.push_download_char(c),
+    KeyCode::Char('j') | KeyCode::Down if !typing => app.download_prompt_next(),
+    KeyCode::Char('k') | KeyCode::Up if !typing => app.download_prompt_previous(),
+    _ => {}
+  }
+}
+
+/// Handle keybindings in gallery mode
+async fn handle_gallery_keys(app: &mut App, key: &KeyEvent) -> Result<()> {
+  match key.code {
+    // Grid navigation
+    KeyCode::Char('h') | KeyCode::Left => app.select_previous(),
+    KeyCode::Char('l') | KeyCode::Right => app.select_next(),
+    KeyCode::Char('j') | KeyCode::Down => app.select_next_row(),
+    KeyCode::Char('k') | KeyCode::Up => app.select_previous_row(),
+
+    KeyCode::Char('g') => app.select_first(),
+    KeyCode::Char('G') => app.select_last(),
+
+    // Actions
+    KeyCode::Enter | KeyCode::Char(' ') => {
+      app.apply_selected_wallpaper().await?;
+    }
+    KeyCode::Char('p') => {
+      app.set_view_mode(ViewMode::Preview);
+    }
+    KeyCode::Char('d') => {
+      app.request_delete();
+    }
+    KeyCode::Char('D') => {
+      app.open_download_prompt();
+    }
+    KeyCode::Char('f') => {
+      app.toggle_favorite();
+    }
+    KeyCode::Char('F') => {
+      app.toggle_favorites_only();
+    }
+
+    // Back to the list
+    KeyCode::Char('v') => {
+      app.set_view_mode(ViewMode::Browse);
+    }
+
+    _ => {}
+  }
+
+  Ok(())
+}
+
 /// Handle keybindings in preview mode
 async fn handle_preview_keys(app: &mut App, key: &KeyEvent) -> Result<()> {
   match key.code {
@@ -210,3 +360,23 @@ async fn handle_help_keys(_app: &mut App, key: &KeyEvent) -> Result<()> {
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn test_q_quits_from_gallery() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let mut config = crate::config::Config::default();
+    config.paths.local = dir.path().to_string_lossy().to_string();
+    let mut app = App::new(config).await.unwrap();
+
+    app.set_view_mode(ViewMode::Gallery);
+    let quit = handle_key_event(&mut app, KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE))
+      .await
+      .unwrap();
+    assert!(quit);
+    assert!(app.should_quit);
+  }
+}
@@ -59,10 +59,16 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
   loop {
     // Poll for completed thumbnail loads
     app.poll_thumbnail();
+    app.poll_download().await?;
+    app.expire_status();
 
     // Draw UI
     terminal.draw(|f| ui::draw(f, app))?;
 
+    // Load thumbnails for whatever the gallery is now showing
+    app.prefetch_gallery();
+    app.request_palette();
+
     // Handle events (with short timeout to allow polling)
     if events::handle_events(app).await? {
       break;
@@ -80,6 +86,7 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
 /// Initialize TUI with default terminal
 pub async fn run_with_default_terminal(config: crate::config::Config) -> Result<()> {
   let mut terminal = ratatui::init();
+  execute!(io::stdout(), EnableMouseCapture)?;
   let mut app = App::new(config).await?;
 
   loop {
@@ -90,6 +97,7 @@ pub async fn run_with_default_terminal(config: crate::config::Config) -> Result<
       app.open_editor = false;
 
       // Restore terminal for editor
+      execute!(io::stdout(), DisableMouseCapture)?;
       ratatui::restore();
 
       // Open editor
@@ -118,8 +126,10 @@ pub async fn run_with_default_terminal(config: crate::config::Config) -> Result<
 
       // Reinit terminal
       terminal = ratatui::init();
+      execute!(io::stdout(), EnableMouseCapture)?;
     } else {
       // Normal exit
+      execute!(io::stdout(), DisableMouseCapture)?;
       ratatui::restore();
       return result;
     }
@@ -16,7 +16,7 @@ use ratatui::{
 };
 use ratatui_image::StatefulImage;
 
-use super::app::{App, ViewMode, WallpaperItem, format_file_size};
+use super::app::{App, DeletePrompt, DownloadPrompt, ViewMode, WallpaperItem, format_file_size};
 
 /// Main UI drawing function
 pub fn draw(f: &mut Frame, app: &mut App) {
@@ -36,6 +36,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
   // Draw main content based on view mode
   match app.view_mode {
     ViewMode::Browse => draw_browse_mode(f, chunks[1], app),
+    ViewMode::Gallery => draw_gallery_mode(f, chunks[1], app),
     ViewMode::Preview => draw_preview_mode(f, chunks[1], app),
     ViewMode::Help => draw_help_mode(f, chunks[1], app),
   }
@@ -52,12 +53,22 @@ pub fn draw(f: &mut Frame, app: &mut App) {
   if let Some(ref error) = app.error_message {
     draw_error_popup(f, f.area(), error);
   }
+
+  if let Some(ref prompt) = app.download_prompt {
+    draw_download_prompt(f, f.area(), prompt);
+  }
+
+  // Draw delete confirmation on top of everything
+  if let Some(ref prompt) = app.confirm_delete {
+    draw_delete_prompt(f, f.area(), prompt);
+  }
 }
 
 /// Draw the title bar
 fn draw_title_bar(f: &mut Frame, area: Rect, app: &App) {
   let title = match app.view_mode {
     ViewMode::Browse => "🌊 wallflow - Browser",
+    ViewMode::Gallery => "🌊 wallflow - Gallery",
     ViewMode::Preview => "🌊 wallflow - Preview",
     ViewMode::Help => "🌊 wallflow - Help",
   };
@@ -80,18 +91,26 @@ fn draw_browse_mode(f: &mut Frame, area: Rect, app: &mut App) {
     ])
     .split(area);
 
-  // Draw wallpaper list
-  draw_wallpaper_list(f, chunks[0], app);
+  // Draw wallpaper list, with the search bar underneath while filtering
+  if app.search_active || !app.search_query.is_empty() {
+    let list_chunks = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints([Constraint::Min(3), Constraint::Length(3)])
+      .split(chunks[0]);
+    draw_wallpaper_list(f, list_chunks[0], app);
+    draw_search_bar(f, list_chunks[1], app);
+  } else {
+    draw_wallpaper_list(f, chunks[0], app);
+  }
 
   // Draw details panel with thumbnail
   draw_details_panel(f, chunks[1], app);
 }
 
 /// Draw wallpaper list widget
-fn draw_wallpaper_list(f: &mut Frame, area: Rect, app: &App) {
+fn draw_wallpaper_list(f: &mut Frame, area: Rect, app: &mut App) {
   let items: Vec<ListItem> = app
-    .wallpapers
-    .iter()
+    .visible_wallpapers()
     .enumerate()
     .map(|(i, wallpaper)| {
       let style = if i == app.selected {
@@ -102,7 +121,11 @@ fn draw_wallpaper_list(f: &mut Frame, area: Rect, app: &App) {
         Style::default()
       };
 
-      let mut content = vec![Span::from(wallpaper.name.clone())];
+      let mut content = Vec::new();
+      if app.is_favorite(&wallpaper.path) {
+        content.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
+      }
+      content.push(Span::from(wallpaper.name.clone()));
 
       // Add format indicator
       if let Some(ref format) = wallpaper.format {
@@ -118,39 +141,171 @@ fn draw_wallpaper_list(f: &mut Frame, area: Rect, app: &App) {
     })
     .collect();
 
-  let title = format!("Wallpapers ({}/{})", app.selected + 1, app.wallpapers.len());
-  let list = List::new(items)
-    .block(
-      Block::default()
-        .borders(Borders::ALL)
-        .title(title)
-        .title_style(Style::default().fg(Color::Cyan)),
+  let heading = if app.favorites_only { "★ Favorites" } else { "Wallpapers" };
+  let title = if app.search_query.is_empty() && !app.favorites_only {
+    format!(
+      "{} ({}/{}) · by {}",
+      heading,
+      app.selected + 1,
+      app.visible_count(),
+      app.sort_mode.label()
     )
+  } else {
+    format!(
+      "{} ({}/{} of {}) · by {}",
+      heading,
+      (app.selected + 1).min(app.visible_count()),
+      app.visible_count(),
+      app.wallpapers.len(),
+      app.sort_mode.label()
+    )
+  };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(title)
+    .title_style(Style::default().fg(Color::Cyan));
+  let inner = block.inner(area);
+  let list = List::new(items)
+    .block(block)
     .highlight_style(Style::default().bg(Color::Blue))
     .highlight_symbol("▶ ");
 
-  // Calculate list state for scrolling
-  let mut list_state = ListState::default();
+  // Calculate list state for scrolling, starting from last frame's offset
+  let mut list_state = ListState::default().with_offset(app.list_offset);
   list_state.select(Some(app.selected));
 
   f.render_stateful_widget(list, area, &mut list_state);
+
+  // Remember where the rows ended up so mouse clicks can be mapped back to them
+  app.list_area = inner;
+  app.list_offset = list_state.offset();
+}
+
+/// Draw the search input below the wallpaper list
+fn draw_search_bar(f: &mut Frame, area: Rect, app: &App) {
+  let (text, style) = if app.search_active {
+    (format!("/{}█", app.search_query), Style::default().fg(Color::Yellow))
+  } else {
+    (format!("/{}", app.search_query), Style::default().fg(Color::Gray))
+  };
+
+  let search = Paragraph::new(text).style(style).block(
+    Block::default()
+      .borders(Borders::ALL)
+      .title("Search [ESC to clear]")
+      .title_style(Style::default().fg(Color::Yellow)),
+  );
+
+  f.render_widget(search, area);
+}
+
+/// Gallery cell size in terminal columns and rows, borders included
+const GALLERY_CELL_WIDTH: u16 = 24;
+const GALLERY_CELL_HEIGHT: u16 = 9;
+
+/// Draw gallery mode UI - a wrapping grid of thumbnails (or names without image support)
+fn draw_gallery_mode(f: &mut Frame, area: Rect, app: &mut App) {
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(format!(
+      "{} ({}/{})",
+      if app.favorites_only { "★ Favorites" } else { "Gallery" },
+      app.selected + 1,
+      app.visible_count()
+    ))
+    .title_style(Style::default().fg(Color::Cyan));
+
+  let inner = block.inner(area);
+  f.render_widget(block, area);
+
+  let columns = (inner.width / GALLERY_CELL_WIDTH).max(1) as usize;
+  let visible_rows = (inner.height / GALLERY_CELL_HEIGHT).max(1) as usize;
+
+  // Scroll so the selected row stays on screen
+  let selected_row = app.selected / columns;
+  if selected_row < app.gallery_offset {
+    app.gallery_offset = selected_row;
+  } else if selected_row >= app.gallery_offset + visible_rows {
+    app.gallery_offset = selected_row + 1 - visible_rows;
+  }
+
+  let first = (app.gallery_offset * columns).min(app.visible_count());
+  let last = (first + visible_rows * columns).min(app.visible_count());
+  app.gallery_columns = columns;
+  app.gallery_visible = first..last;
+
+  let show_images = app.supports_images();
+
+  for index in first..last {
+    let position = index - first;
+    let cell = Rect {
+      x: inner.x + (position % columns) as u16 * GALLERY_CELL_WIDTH,
+      y: inner.y + (position / columns) as u16 * GALLERY_CELL_HEIGHT,
+      width: GALLERY_CELL_WIDTH.min(inner.width),
+      height: GALLERY_CELL_HEIGHT.min(inner.height),
+    };
+
+    let Some(wallpaper) = app.visible_wallpaper(index) else {
+      break;
+    };
+    let border_style = if index == app.selected {
+      Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)
+    } else if wallpaper.is_current {
+      Style::default().fg(Color::Green)
+    } else {
+      Style::default().fg(Color::DarkGray)
+    };
+
+    let name = wallpaper.name.clone();
+    let path = wallpaper.path.clone();
+    let title = if app.is_favorite(&path) { format!("★ {}", name) } else { name.clone() };
+    let cell_block = Block::default()
+      .borders(Borders::ALL)
+      .border_style(border_style)
+      .title(Span::styled(title, border_style));
+    let cell_inner = cell_block.inner(cell);
+    f.render_widget(cell_block, cell);
+
+    // Name grid until the thumbnail arrives, or for good without image support
+    match app.gallery_thumbnail(&path).filter(|_| show_images) {
+      Some(thumbnail) => f.render_stateful_widget(StatefulImage::new(None), cell_inner, thumbnail),
+      None => {
+        let label = Paragraph::new(name)
+          .style(Style::default().fg(Color::Gray))
+          .alignment(Alignment::Center)
+          .wrap(Wrap { trim: true });
+        f.render_widget(label, cell_inner);
+      }
+    }
+  }
 }
 
 /// Draw details panel for selected wallpaper
 fn draw_details_panel(f: &mut Frame, area: Rect, app: &mut App) {
-  // Split details panel into thumbnail, wallpaper details, and config
+  // The palette strip is dropped when there's nothing to show
+  let palette_height = match app.selected_wallpaper() {
+    Some(wallpaper) if app.palette(&wallpaper.path).is_some() || app.is_palette_loading(&wallpaper.path) => 4,
+    _ => 0,
+  };
+
+  // Split details panel into thumbnail, palette, wallpaper details, and config
   let chunks = Layout::default()
     .direction(Direction::Vertical)
     .constraints([
-      Constraint::Percentage(40), // Thumbnail preview
-      Constraint::Percentage(25), // Wallpaper details
-      Constraint::Percentage(35), // Config summary
+      Constraint::Percentage(40),         // Thumbnail preview
+      Constraint::Length(palette_height), // Color palette
+      Constraint::Percentage(25),         // Wallpaper details
+      Constraint::Min(0),                 // Config summary
     ])
     .split(area);
 
   // Thumbnail preview
   draw_thumbnail(f, chunks[0], app);
 
+  if palette_height > 0 {
+    draw_palette(f, chunks[1], app);
+  }
+
   // Wallpaper details
   let wallpaper_content = if let Some(wallpaper) = app.selected_wallpaper() {
     format_wallpaper_details(wallpaper)
@@ -167,7 +322,7 @@ fn draw_details_panel(f: &mut Frame, area: Rect, app: &mut App) {
     )
     .wrap(Wrap { trim: true });
 
-  f.render_widget(wallpaper_details, chunks[1]);
+  f.render_widget(wallpaper_details, chunks[2]);
 
   // Config summary
   let config_content = format_config_summary(app);
@@ -180,7 +335,44 @@ fn draw_details_panel(f: &mut Frame, area: Rect, app: &mut App) {
     )
     .wrap(Wrap { trim: true });
 
-  f.render_widget(config_panel, chunks[2]);
+  f.render_widget(config_panel, chunks[3]);
+}
+
+/// Draw the selected wallpaper's extracted colors as two rows of swatches
+fn draw_palette(f: &mut Frame, area: Rect, app: &App) {
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title("Palette")
+    .title_style(Style::default().fg(Color::Magenta));
+
+  let inner = block.inner(area);
+  f.render_widget(block, area);
+
+  let Some(colors) = app.selected_wallpaper().and_then(|w| app.palette(&w.path)) else {
+    let placeholder = Paragraph::new("⏳ Extracting...").style(Style::default().fg(Color::Yellow));
+    f.render_widget(placeholder, inner);
+    return;
+  };
+
+  // Normal colors on the first row, bright ones on the second, like a terminal theme
+  let per_row = colors.len().div_ceil(2).max(1);
+  let swatch_width = (inner.width as usize / per_row).max(1);
+  let lines: Vec<Line> = colors
+    .chunks(per_row)
+    .map(|row| {
+      Line::from(
+        row
+          .iter()
+          .map(|color| {
+            let bg = color.hex().parse::<Color>().unwrap_or(Color::Reset);
+            Span::styled(" ".repeat(swatch_width), Style::default().bg(bg))
+          })
+          .collect::<Vec<_>>(),
+      )
+    })
+    .collect();
+
+  f.render_widget(Paragraph::new(lines), inner);
 }
 
 /// Draw thumbnail preview
@@ -237,6 +429,21 @@ fn format_wallpaper_details(wallpaper: &WallpaperItem) -> String {
     details.push(format!("🎨 Format: {}", format));
   }
 
+  if let Some(ref metadata) = wallpaper.metadata {
+    if let Some(credit) = metadata.credit() {
+      details.push(format!("📷 {}", credit));
+    }
+    if let Some(ref location) = metadata.location {
+      details.push(format!("🌍 {}", location));
+    }
+    if let Some(ref date) = metadata.date {
+      details.push(format!("📅 {}", date));
+    }
+    if let Some(ref page_url) = metadata.page_url {
+      details.push(format!("🔗 {}", page_url));
+    }
+  }
+
   if wallpaper.is_current {
     details.push("✅ Currently active".to_string());
   }
@@ -303,12 +510,21 @@ fn draw_help_mode(f: &mut Frame, area: Rect, _app: &App) {
         J           Jump down 10 items\n\
         K           Jump up 10 items\n\
         g           Go to first item\n\
-        G           Go to last item\n\n\
+        G           Go to last item\n\
+        /           Search by name (ESC clears)\n\
+        s           Cycle sort: name, newest, largest, resolution\n\
+        h/j/k/l     Move around the gallery grid\n\
+        Mouse       Click selects, double-click applies, wheel scrolls\n\n\
         Actions:\n\
         ENTER, SPC  Apply selected wallpaper\n\
         p           Preview mode\n\
+        v           Toggle gallery (grid) view\n\
         e           Edit config in $EDITOR\n\
         r           Refresh wallpaper list\n\
+        d           Delete selected wallpaper (asks first)\n\
+        D           Download a new wallpaper from a source\n\
+        f           Star/unstar selected wallpaper\n\
+        F           Show only favorites (toggle)\n\
         c           Clear messages\n\n\
         Modes:\n\
         ?           Show this help\n\
@@ -346,15 +562,22 @@ fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
   let status = Paragraph::new(app.status_info()).style(Style::default().fg(Color::Gray));
   f.render_widget(status, chunks[0]);
 
-  // Message line
-  if let Some(ref message) = app.status_message {
+  // Message line, taken over by the spinner while a download runs
+  if let Some(progress) = app.download_status() {
+    let msg = Paragraph::new(progress).style(Style::default().fg(Color::Yellow));
+    f.render_widget(msg, chunks[1]);
+  } else if let Some(ref message) = app.status_message {
     let msg = Paragraph::new(message.as_str()).style(Style::default().fg(Color::Green));
     f.render_widget(msg, chunks[1]);
   }
 
   // Keybinding hints
   let hints = match app.view_mode {
-    ViewMode::Browse => "j/k: navigate | ENTER: apply | p: preview | e: edit config | ?: help | q: quit",
+    ViewMode::Browse if app.search_active => "type to filter | ↑/↓: navigate | ENTER: done | ESC: clear",
+    ViewMode::Browse => {
+      "j/k: navigate | ENTER: apply | /: search | s: sort | f: star | D: download | p: preview | v: gallery | e: edit config | ?: help | q: quit"
+    }
+    ViewMode::Gallery => "h/j/k/l: navigate | ENTER: apply | p: preview | v: list | ?: help | q: quit",
     ViewMode::Preview => "j/k: navigate | ENTER: apply | ESC: back",
     ViewMode::Help => "ESC: back",
   };
@@ -419,6 +642,82 @@ fn draw_error_popup(f: &mut Frame, area: Rect, error: &str) {
   f.render_widget(error_text, inner);
 }
 
+/// Draw the yes/no prompt for deleting a wallpaper
+fn draw_delete_prompt(f: &mut Frame, area: Rect, prompt: &DeletePrompt) {
+  let popup_area = centered_rect(50, 25, area);
+
+  f.render_widget(Clear, popup_area);
+
+  let border = if prompt.is_current { Color::Red } else { Color::Yellow };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title("Delete wallpaper")
+    .title_style(Style::default().fg(border))
+    .border_style(Style::default().fg(border));
+
+  let inner = block.inner(popup_area);
+  f.render_widget(block, popup_area);
+
+  let mut lines = vec![Line::from(format!("Delete {}?", prompt.name)), Line::from("")];
+  if prompt.is_current {
+    lines.push(Line::styled(
+      "⚠️  This is your current wallpaper!",
+      Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+    ));
+    lines.push(Line::from(""));
+  }
+  lines.push(Line::styled("y: delete    n/ESC: cancel", Style::default().fg(Color::DarkGray)));
+
+  let text = Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true });
+  f.render_widget(text, inner);
+}
+
+/// Draw the download source picker, or the query prompt once a source is picked
+fn draw_download_prompt(f: &mut Frame, area: Rect, prompt: &DownloadPrompt) {
+  let popup_area = centered_rect(40, 50, area);
+
+  f.render_widget(Clear, popup_area);
+
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title("Download wallpaper")
+    .title_style(Style::default().fg(Color::Cyan))
+    .border_style(Style::default().fg(Color::Cyan));
+
+  let inner = block.inner(popup_area);
+  f.render_widget(block, popup_area);
+
+  match &prompt.query {
+    None => {
+      let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+      let items: Vec<ListItem> = prompt.sources.iter().map(|source| ListItem::new(source.as_str())).collect();
+      let list = List::new(items).highlight_style(Style::default().bg(Color::Blue)).highlight_symbol("▶ ");
+      let mut list_state = ListState::default();
+      list_state.select(Some(prompt.selected));
+      f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+      let hint = Paragraph::new("j/k: choose | ENTER: next | ESC: cancel").style(Style::default().fg(Color::DarkGray));
+      f.render_widget(hint, chunks[1]);
+    }
+    Some(query) => {
+      let lines = vec![
+        Line::from(format!("Source: {}", prompt.source())),
+        Line::from(""),
+        Line::from("Search terms (optional):"),
+        Line::styled(format!("{}█", query), Style::default().fg(Color::Yellow)),
+        Line::from(""),
+        Line::styled("ENTER: download | ESC: back", Style::default().fg(Color::DarkGray)),
+      ];
+      let text = Paragraph::new(lines).wrap(Wrap { trim: true });
+      f.render_widget(text, inner);
+    }
+  }
+}
+
 /// Helper function to create a centered rect
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
   let popup_layout = Layout::default()
@@ -2,11 +2,13 @@
 //!
 //! Downloads and installs updates from GitHub releases.
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::Write;
-use tracing::info;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
 const REPO_OWNER: &str = "MKSG-MugunthKumar";
 const REPO_NAME: &str = "wallflow";
@@ -14,6 +16,9 @@ const REPO_NAME: &str = "wallflow";
 #[derive(Deserialize)]
 struct GitHubRelease {
   tag_name: String,
+  /// Release notes (markdown); null when the release has none
+  #[serde(default)]
+  body: Option<String>,
   assets: Vec<GitHubAsset>,
 }
 
@@ -28,6 +33,8 @@ pub struct UpdateCheck {
   pub current: String,
   pub latest: String,
   pub update_available: bool,
+  /// Release notes of the latest version (markdown)
+  pub body: String,
 }
 
 /// Check if a new version is available on GitHub
@@ -67,11 +74,35 @@ pub async fn check_for_updates() -> Result<UpdateCheck> {
     current: current_version.to_string(),
     latest: latest_version,
     update_available,
+    body: release.body.unwrap_or_default(),
   })
 }
 
+/// Release notes cut down for the terminal
+///
+/// Stops at the installation instructions our release notes end with, drops
+/// blank lines and keeps at most `max_lines` lines.
+pub fn changelog_summary(body: &str, max_lines: usize) -> String {
+  let lines: Vec<&str> = body
+    .lines()
+    .map(str::trim_end)
+    .take_while(|line| !line.trim_start_matches('#').trim().eq_ignore_ascii_case("installation"))
+    .filter(|line| !line.trim().is_empty())
+    .collect();
+
+  let mut summary = lines.iter().take(max_lines).copied().collect::<Vec<_>>().join("\n");
+  if lines.len() > max_lines {
+    summary.push_str(&format!("\n… and {} more", lines.len() - max_lines));
+  }
+  summary
+}
+
 /// Perform the self-update process
-pub async fn perform_update() -> Result<String> {
+///
+/// The download is checked against the release's `<asset>.sha256` before it is
+/// written, and a mismatch aborts the update. Releases without a checksum are
+/// installed with a warning. `insecure` skips the check altogether.
+pub async fn perform_update(insecure: bool) -> Result<String> {
   info!("Starting self-update process...");
 
   let url = format!("https://api.github.com/repos/{}/{}/releases/latest", REPO_OWNER, REPO_NAME);
@@ -106,6 +137,23 @@ pub async fn perform_update() -> Result<String> {
     println!("Downloaded {} bytes", size);
   }
 
+  let checksum_name = format!("{}.sha256", asset.name);
+  match release.assets.iter().find(|a| a.name == checksum_name) {
+    _ if insecure => warn!("--insecure given, installing without checksum verification"),
+    Some(checksum_asset) => {
+      let checksum_file = client
+        .get(&checksum_asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+      verify_checksum(&binary_data, &checksum_file)?;
+      println!("Checksum verified");
+    }
+    None => warn!("Release has no {}, installing without checksum verification", checksum_name),
+  }
+
   // Get current executable path
   let current_exe = std::env::current_exe()?;
   let temp_new = current_exe.with_extension("new");
@@ -125,20 +173,8 @@ pub async fn perform_update() -> Result<String> {
   }
 
   // Create update script that will run after we exit
-  let script_path = current_exe.with_extension("update.sh");
-  let script_content = format!(
-    r#"#!/bin/bash
-sleep 1
-mv "{current}" "{current}.bak"
-mv "{new}" "{current}"
-chmod +x "{current}"
-rm "{current}.bak" 2>/dev/null
-rm -- "$0"
-echo "Update complete! Run 'wallflow --version' to verify."
-"#,
-    current = current_exe.display(),
-    new = temp_new.display()
-  );
+  let script_path = update_script_path(&current_exe);
+  let script_content = update_script(&current_exe, &temp_new, cfg!(windows));
 
   let mut script_file = fs::File::create(&script_path)?;
   script_file.write_all(script_content.as_bytes())?;
@@ -156,6 +192,66 @@ echo "Update complete! Run 'wallflow --version' to verify."
   Ok(release.tag_name.trim_start_matches('v').to_string())
 }
 
+/// Compare the sha256 of `data` with a `sha256sum`-style checksum file
+fn verify_checksum(data: &[u8], checksum_file: &str) -> Result<()> {
+  let expected = checksum_file
+    .split_whitespace()
+    .next()
+    .ok_or_else(|| anyhow!("Checksum file is empty"))?
+    .to_lowercase();
+  let actual = format!("{:x}", Sha256::digest(data));
+
+  if actual != expected {
+    bail!(
+      "Checksum mismatch: expected {}, got {}. The download may be corrupted or tampered with.",
+      expected,
+      actual
+    );
+  }
+  Ok(())
+}
+
+/// Where the update script lives, next to the executable
+fn update_script_path(exe_path: &Path) -> PathBuf {
+  exe_path.with_extension(if cfg!(windows) { "update.bat" } else { "update.sh" })
+}
+
+/// Script that swaps the new binary in once this process has exited
+///
+/// Windows can't overwrite a running exe, so the batch version waits, moves
+/// the old exe aside, moves the new one in and relaunches it.
+fn update_script(current: &Path, new: &Path, windows: bool) -> String {
+  if windows {
+    format!(
+      r#"@echo off
+timeout /t 2 /nobreak > nul
+move /y "{current}" "{current}.bak" > nul
+move /y "{new}" "{current}" > nul
+del "{current}.bak" 2> nul
+echo Update complete!
+"{current}" --version
+(goto) 2> nul & del "%~f0"
+"#,
+      current = current.display(),
+      new = new.display()
+    )
+  } else {
+    format!(
+      r#"#!/bin/bash
+sleep 1
+mv "{current}" "{current}.bak"
+mv "{new}" "{current}"
+chmod +x "{current}"
+rm "{current}.bak" 2>/dev/null
+rm -- "$0"
+echo "Update complete! Run 'wallflow --version' to verify."
+"#,
+      current = current.display(),
+      new = new.display()
+    )
+  }
+}
+
 /// Get the expected asset name for the current platform
 fn get_asset_name() -> String {
   #[cfg(target_os = "linux")]
@@ -212,7 +308,7 @@ pub fn can_self_update() -> bool {
 /// Apply the update by running the update script and exiting
 pub fn apply_update() -> Result<()> {
   let exe_path = std::env::current_exe()?;
-  let script_path = exe_path.with_extension("update.sh");
+  let script_path = update_script_path(&exe_path);
 
   if !script_path.exists() {
     return Err(anyhow!("Update script not found. Run 'wallflow update' first."));
@@ -222,7 +318,11 @@ pub fn apply_update() -> Result<()> {
   println!("Applying update...");
 
   // Launch the update script in the background
-  std::process::Command::new("sh").arg(&script_path).spawn()?;
+  if cfg!(windows) {
+    std::process::Command::new("cmd").arg("/C").arg(&script_path).spawn()?;
+  } else {
+    std::process::Command::new("sh").arg(&script_path).spawn()?;
+  }
 
   // Exit current process so the script can replace the binary
   std::process::exit(0);
@@ -238,6 +338,50 @@ mod tests {
     println!("Can self-update: {}", can_update);
   }
 
+  #[test]
+  fn test_verify_checksum() {
+    let digest = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+    assert!(verify_checksum(b"hello world", &format!("{}  wallflow-x86_64-unknown-linux-gnu\n", digest)).is_ok());
+    assert!(verify_checksum(b"hello world", &digest.to_uppercase()).is_ok());
+    assert!(verify_checksum(b"hello world!", digest).is_err());
+    assert!(verify_checksum(b"hello world", "").is_err());
+  }
+
+  #[test]
+  fn test_update_script_unix() {
+    let script = update_script(Path::new("/home/me/bin/wallflow"), Path::new("/home/me/bin/wallflow.new"), false);
+
+    assert!(script.starts_with("#!/bin/bash\n"));
+    assert!(script.contains(r#"mv "/home/me/bin/wallflow.new" "/home/me/bin/wallflow""#));
+    assert!(script.contains(r#"chmod +x "/home/me/bin/wallflow""#));
+    assert!(script.contains(r#"rm -- "$0""#));
+  }
+
+  #[test]
+  fn test_update_script_windows() {
+    let script = update_script(Path::new(r"C:\Tools\wallflow.exe"), Path::new(r"C:\Tools\wallflow.new"), true);
+
+    assert!(script.starts_with("@echo off\n"));
+    assert!(script.contains("timeout /t"));
+    assert!(script.contains(r#"move /y "C:\Tools\wallflow.exe" "C:\Tools\wallflow.exe.bak""#));
+    assert!(script.contains(r#"move /y "C:\Tools\wallflow.new" "C:\Tools\wallflow.exe""#));
+    assert!(script.contains(r#""C:\Tools\wallflow.exe" --version"#));
+    assert!(!script.contains("#!/bin/bash"));
+    assert!(script.contains(r#"del "%~f0""#));
+  }
+
+  #[test]
+  fn test_changelog_summary() {
+    let body = "Changes since v0.5.1:\n\n- Add favorites\n- Fix KDE\n- Add COSMIC\n\n## Installation\n\nDownload the binary";
+
+    assert_eq!(
+      changelog_summary(body, 10),
+      "Changes since v0.5.1:\n- Add favorites\n- Fix KDE\n- Add COSMIC"
+    );
+    assert_eq!(changelog_summary(body, 2), "Changes since v0.5.1:\n- Add favorites\n… and 2 more");
+    assert_eq!(changelog_summary("", 10), "");
+  }
+
   #[test]
   fn test_get_asset_name() {
     let name = get_asset_name();
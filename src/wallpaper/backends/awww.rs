@@ -1,6 +1,6 @@
 //! awww backend - refactored from existing wallpaper.rs implementation
 
-use super::traits::{WallpaperBackend, WallpaperOptions};
+use super::traits::{MonitorSelection, WallpaperBackend, WallpaperOptions};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
@@ -40,6 +40,11 @@ impl AwwwBackend {
       cmd.args(["--transition-duration", &duration.to_string()]);
     }
 
+    // Limit to a single output if one was requested
+    if let MonitorSelection::Named(output) = &options.monitor {
+      cmd.args(["--outputs", output]);
+    }
+
     cmd
   }
 }
@@ -99,8 +104,17 @@ impl WallpaperBackend for AwwwBackend {
   }
 
   async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
-    // awww doesn't support getting current wallpaper
-    Ok(None)
+    let output = AsyncCommand::new("awww")
+      .arg("query")
+      .output()
+      .await
+      .context("Failed to execute awww query")?;
+
+    if !output.status.success() {
+      return Ok(None);
+    }
+
+    Ok(super::linux::parse_swww_query(&String::from_utf8_lossy(&output.stdout)))
   }
 
   fn is_available(&self) -> bool {
@@ -111,6 +125,9 @@ impl WallpaperBackend for AwwwBackend {
     75 // High priority if available, but not highest
   }
 
+  fn sets_per_output(&self) -> bool {
+    true
+  }
   fn name(&self) -> &'static str {
     "awww"
   }
@@ -0,0 +1,159 @@
+//! COSMIC desktop backend
+//!
+//! cosmic-bg has no command for changing the wallpaper; it watches its
+//! cosmic-config entries instead. Writing those entries is what COSMIC Settings
+//! does too, and cosmic-bg picks up the change on its own.
+
+use super::traits::{MonitorSelection, WallpaperBackend, WallpaperOptions, WallpaperScaling};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Backend that updates cosmic-bg's config
+pub struct CosmicBackend;
+
+impl CosmicBackend {
+  pub fn new() -> Self {
+    Self
+  }
+
+  /// Directory holding cosmic-bg's config entries
+  fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cosmic/com.system76.CosmicBackground/v1"))
+  }
+
+  /// Detect if running under COSMIC
+  fn is_cosmic_desktop() -> bool {
+    std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.to_lowercase().contains("cosmic"))
+  }
+
+  fn write_entry(dir: &Path, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let write = |key: &str, value: &str| fs::write(dir.join(key), value).with_context(|| format!("Failed to write cosmic-bg entry '{}'", key));
+
+    match &options.monitor {
+      MonitorSelection::Named(output) => {
+        write(&format!("output.{}", output), &background_entry(output, image_path, &options.scaling))?;
+
+        let mut outputs = fs::read_to_string(dir.join("backgrounds"))
+          .map(|list| parse_string_list(&list))
+          .unwrap_or_default();
+        if !outputs.contains(output) {
+          outputs.push(output.clone());
+        }
+        write("backgrounds", &format!("{:?}", outputs))?;
+        write("same-on-all", "false")
+      }
+      _ => {
+        write("all", &background_entry("all", image_path, &options.scaling))?;
+        write("same-on-all", "true")
+      }
+    }
+  }
+}
+
+#[async_trait]
+impl WallpaperBackend for CosmicBackend {
+  async fn set_wallpaper(&self, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
+    let dir = Self::config_dir().context("Could not determine config directory")?;
+    let image_path = image_path.canonicalize().unwrap_or_else(|_| image_path.to_path_buf());
+
+    Self::write_entry(&dir, &image_path, options)?;
+    debug!("COSMIC wallpaper set via {}", dir.display());
+    Ok(())
+  }
+
+  async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
+    let Some(dir) = Self::config_dir() else {
+      return Ok(None);
+    };
+    Ok(fs::read_to_string(dir.join("all")).ok().and_then(|entry| parse_source_path(&entry)))
+  }
+
+  fn is_available(&self) -> bool {
+    Self::is_cosmic_desktop() && which::which("cosmic-bg").is_ok()
+  }
+
+  fn priority(&self) -> u32 {
+    80 // Native to the desktop, ahead of awww/swww
+  }
+
+  fn sets_per_output(&self) -> bool {
+    true
+  }
+  fn name(&self) -> &'static str {
+    "cosmic-bg"
+  }
+
+  fn supported_transitions(&self) -> Vec<String> {
+    vec![]
+  }
+}
+
+impl Default for CosmicBackend {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A cosmic-bg `Entry` in RON. Debug formatting of a `str` is a valid RON string.
+fn background_entry(output: &str, image_path: &Path, scaling: &WallpaperScaling) -> String {
+  let scaling_mode = match scaling {
+    WallpaperScaling::Fill => "Zoom",
+    WallpaperScaling::Stretch => "Stretch",
+    // cosmic-bg has no centre or tile mode; fit on a black background is the closest
+    WallpaperScaling::Fit | WallpaperScaling::Center | WallpaperScaling::Tile => "Fit((0.0, 0.0, 0.0))",
+  };
+
+  format!(
+    "(\n    output: {:?},\n    source: Path({:?}),\n    filter_by_theme: false,\n    rotation_frequency: 300,\n    filter_method: Lanczos,\n    scaling_mode: {},\n    sampling_method: Alphanumeric,\n)",
+    output,
+    image_path.to_string_lossy(),
+    scaling_mode
+  )
+}
+
+/// Image path from the `source: Path("...")` field of an entry
+fn parse_source_path(entry: &str) -> Option<PathBuf> {
+  let start = entry.find("Path(\"")? + "Path(\"".len();
+  let end = start + entry[start..].find("\")")?;
+  Some(PathBuf::from(entry[start..end].replace("\\\"", "\"").replace("\\\\", "\\")))
+}
+
+/// Output names from a RON list like `["DP-1", "HDMI-A-1"]`
+fn parse_string_list(list: &str) -> Vec<String> {
+  list.split('"').skip(1).step_by(2).map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  #[test]
+  fn test_entry_round_trip() {
+    let entry = background_entry("all", Path::new("/walls/a \"b\".jpg"), &WallpaperScaling::Fill);
+    assert!(entry.contains("scaling_mode: Zoom,"));
+    assert_eq!(parse_source_path(&entry), Some(PathBuf::from("/walls/a \"b\".jpg")));
+  }
+
+  #[test]
+  fn test_named_output_is_added_to_backgrounds() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("backgrounds"), "[\"HDMI-A-1\"]").unwrap();
+
+    let options = WallpaperOptions {
+      monitor: MonitorSelection::Named("DP-1".to_string()),
+      ..Default::default()
+    };
+    CosmicBackend::write_entry(dir.path(), Path::new("/walls/a.jpg"), &options).unwrap();
+
+    let backgrounds = fs::read_to_string(dir.path().join("backgrounds")).unwrap();
+    assert_eq!(parse_string_list(&backgrounds), vec!["HDMI-A-1", "DP-1"]);
+    assert_eq!(fs::read_to_string(dir.path().join("same-on-all")).unwrap(), "false");
+    assert!(fs::read_to_string(dir.path().join("output.DP-1")).unwrap().contains("output: \"DP-1\""));
+  }
+}
@@ -15,15 +15,23 @@ impl GnomeBackend {
 
   /// Detect if running under a GNOME-based desktop
   fn is_gnome_desktop() -> bool {
-    std::env::var("XDG_CURRENT_DESKTOP")
-      .map(|d| {
-        let d = d.to_lowercase();
-        d.contains("gnome") || d.contains("unity") || d.contains("budgie")
-      })
-      .unwrap_or(false)
+    std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| is_gnome_based(&desktop))
   }
 }
 
+/// Whether an `XDG_CURRENT_DESKTOP` value (e.g. "ubuntu:GNOME") names a desktop that reads org.gnome.desktop.background
+fn is_gnome_based(desktop: &str) -> bool {
+  let desktop = desktop.to_lowercase();
+  desktop.contains("gnome") || desktop.contains("unity") || desktop.contains("budgie")
+}
+
+/// Turn `gsettings get ... picture-uri` output (`'file:///home/me/wall.jpg'`) into a path
+fn parse_picture_uri(stdout: &str) -> Option<PathBuf> {
+  let uri = stdout.trim().trim_matches('\'');
+  let path = uri.strip_prefix("file://").unwrap_or(uri);
+  (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
 #[async_trait]
 impl WallpaperBackend for GnomeBackend {
   async fn set_wallpaper(&self, image_path: &Path, _options: &WallpaperOptions) -> Result<()> {
@@ -68,10 +76,7 @@ impl WallpaperBackend for GnomeBackend {
       .context("Failed to get current wallpaper")?;
 
     if output.status.success() {
-      let uri = String::from_utf8_lossy(&output.stdout).trim().to_string();
-      // Strip quotes and file:// prefix
-      let path = uri.trim_matches('\'').strip_prefix("file://").unwrap_or(&uri);
-      Ok(Some(PathBuf::from(path)))
+      Ok(parse_picture_uri(&String::from_utf8_lossy(&output.stdout)))
     } else {
       Ok(None)
     }
@@ -99,3 +104,25 @@ impl Default for GnomeBackend {
     Self::new()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_gnome_based() {
+    assert!(is_gnome_based("ubuntu:GNOME"));
+    assert!(is_gnome_based("Budgie:GNOME"));
+    assert!(!is_gnome_based("KDE"));
+    assert!(!is_gnome_based("sway"));
+  }
+
+  #[test]
+  fn test_parse_picture_uri() {
+    assert_eq!(
+      parse_picture_uri("'file:///home/me/wall.jpg'\n"),
+      Some(PathBuf::from("/home/me/wall.jpg"))
+    );
+    assert_eq!(parse_picture_uri("''\n"), None);
+  }
+}
@@ -1,11 +1,133 @@
 //! Linux-specific wallpaper backends for X11 and Wayland
 
-use super::traits::{WallpaperBackend, WallpaperOptions, WallpaperScaling};
+use super::traits::{MonitorSelection, WallpaperBackend, WallpaperOptions, WallpaperScaling};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use tokio::process::Command as AsyncCommand;
-use tracing::debug;
+use tracing::{debug, warn};
+
+/// swww backend for wlroots-based Wayland compositors
+#[derive(Default)]
+pub struct SwwwBackend;
+
+impl SwwwBackend {
+  pub fn new() -> Self {
+    Self
+  }
+
+  /// Convert wallpaper options to swww command arguments
+  fn build_swww_command(&self, image_path: &Path, options: &WallpaperOptions) -> AsyncCommand {
+    let mut cmd = AsyncCommand::new("swww");
+    cmd.args(["img", &image_path.to_string_lossy()]);
+
+    let transition = options.transition.as_deref().unwrap_or("random");
+    cmd.args(["--transition-type", transition]);
+
+    if let Some(fps) = options.fps {
+      cmd.args(["--transition-fps", &fps.to_string()]);
+    }
+    cmd.args(["--transition-step", "90"]);
+
+    if let Some(duration) = options.duration {
+      cmd.args(["--transition-duration", &duration.to_string()]);
+    }
+
+    // Primary is resolved to a named output before reaching the backend
+    if let MonitorSelection::Named(output) = &options.monitor {
+      cmd.args(["--outputs", output]);
+    }
+
+    cmd
+  }
+}
+
+#[async_trait]
+impl WallpaperBackend for SwwwBackend {
+  async fn set_wallpaper(&self, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
+    let mut cmd = self.build_swww_command(image_path, options);
+
+    debug!("swww command: {:?}", cmd.as_std());
+
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::piped());
+
+    if options.fire_and_forget {
+      let child = cmd.spawn().context("Failed to spawn swww")?;
+      debug!("swww spawned (fire-and-forget mode), pid: {:?}", child.id());
+      return Ok(());
+    }
+
+    let output = cmd.output().await.context("Failed to execute swww")?;
+
+    if output.status.success() {
+      debug!("✅ swww wallpaper set successfully");
+      Ok(())
+    } else {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      warn!("swww failed (exit {}): {}", output.status, stderr);
+      Err(anyhow::anyhow!("swww failed: {}", stderr))
+    }
+  }
+
+  async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
+    let output = AsyncCommand::new("swww")
+      .arg("query")
+      .output()
+      .await
+      .context("Failed to execute swww query")?;
+
+    if !output.status.success() {
+      return Ok(None);
+    }
+
+    Ok(parse_swww_query(&String::from_utf8_lossy(&output.stdout)))
+  }
+
+  fn is_available(&self) -> bool {
+    which::which("swww").is_ok()
+  }
+
+  fn priority(&self) -> u32 {
+    74
+  }
+  fn sets_per_output(&self) -> bool {
+    true
+  }
+  fn name(&self) -> &'static str {
+    "swww"
+  }
+  fn supported_transitions(&self) -> Vec<String> {
+    ["simple", "fade", "wipe", "grow", "outer", "wave", "random"]
+      .iter()
+      .map(|t| t.to_string())
+      .collect()
+  }
+
+  fn validate(&self) -> Result<()> {
+    if !self.is_available() {
+      return Err(anyhow::anyhow!("swww is not available. Install from: https://github.com/LGFae/swww"));
+    }
+
+    // `swww query` fails when swww-daemon isn't running
+    let output = std::process::Command::new("swww")
+      .arg("query")
+      .stdin(Stdio::null())
+      .output()
+      .context("Failed to execute swww query")?;
+
+    if !output.status.success() {
+      return Err(anyhow::anyhow!(
+        "swww-daemon is not running: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+      ));
+    }
+
+    Ok(())
+  }
+}
 
 /// swaybg backend for Sway
 #[derive(Default)]
@@ -75,7 +197,7 @@ impl HyprpaperBackend {
 
 #[async_trait]
 impl WallpaperBackend for HyprpaperBackend {
-  async fn set_wallpaper(&self, image_path: &Path, _options: &WallpaperOptions) -> Result<()> {
+  async fn set_wallpaper(&self, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
     // hyprpaper requires preloading and then setting
     let mut preload_cmd = AsyncCommand::new("hyprctl");
     preload_cmd.args(["hyprpaper", "preload", &image_path.to_string_lossy()]);
@@ -87,9 +209,14 @@ impl WallpaperBackend for HyprpaperBackend {
       return Err(anyhow::anyhow!("hyprpaper preload failed: {}", stderr));
     }
 
-    // Set wallpaper on all monitors
+    // An empty monitor name means all monitors
+    let monitor = match &options.monitor {
+      MonitorSelection::Named(output) => output.as_str(),
+      _ => "",
+    };
+
     let mut set_cmd = AsyncCommand::new("hyprctl");
-    set_cmd.args(["hyprpaper", "wallpaper", &format!(",{}", image_path.display())]);
+    set_cmd.args(["hyprpaper", "wallpaper", &format!("{},{}", monitor, image_path.display())]);
 
     let output = set_cmd.output().await.context("Failed to set wallpaper with hyprpaper")?;
 
@@ -103,7 +230,37 @@ impl WallpaperBackend for HyprpaperBackend {
   }
 
   async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
-    Ok(None)
+    // listactive shows what each monitor displays; older hyprpaper only has listloaded
+    let output = AsyncCommand::new("hyprctl")
+      .args(["hyprpaper", "listactive"])
+      .output()
+      .await
+      .context("Failed to query hyprpaper")?;
+
+    if output.status.success()
+      && let Some(path) = parse_hyprpaper_listactive(&String::from_utf8_lossy(&output.stdout))
+    {
+      return Ok(Some(path));
+    }
+
+    let output = AsyncCommand::new("hyprctl")
+      .args(["hyprpaper", "listloaded"])
+      .output()
+      .await
+      .context("Failed to query hyprpaper")?;
+
+    if !output.status.success() {
+      return Ok(None);
+    }
+
+    // The most recently preloaded image is the one we set last
+    Ok(
+      String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .rfind(|line| line.starts_with('/'))
+        .map(PathBuf::from),
+    )
   }
 
   fn is_available(&self) -> bool {
@@ -113,6 +270,9 @@ impl WallpaperBackend for HyprpaperBackend {
   fn priority(&self) -> u32 {
     90
   }
+  fn sets_per_output(&self) -> bool {
+    true
+  }
   fn name(&self) -> &'static str {
     "hyprpaper"
   }
@@ -129,11 +289,8 @@ impl FehBackend {
   pub fn new() -> Self {
     Self
   }
-}
 
-#[async_trait]
-impl WallpaperBackend for FehBackend {
-  async fn set_wallpaper(&self, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
+  fn build_feh_command(&self, image_path: &Path, options: &WallpaperOptions) -> AsyncCommand {
     let mut cmd = AsyncCommand::new("feh");
 
     let bg_option = match options.scaling {
@@ -146,6 +303,20 @@ impl WallpaperBackend for FehBackend {
 
     cmd.args([bg_option, &image_path.to_string_lossy()]);
 
+    // Treat the whole X screen as one, instead of repeating the image on every monitor
+    if options.span {
+      cmd.arg("--no-xinerama");
+    }
+
+    cmd
+  }
+}
+
+#[async_trait]
+impl WallpaperBackend for FehBackend {
+  async fn set_wallpaper(&self, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
+    let mut cmd = self.build_feh_command(image_path, options);
+
     let output = cmd.output().await.context("Failed to execute feh")?;
 
     if output.status.success() {
@@ -158,7 +329,14 @@ impl WallpaperBackend for FehBackend {
   }
 
   async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
-    Ok(None) // feh doesn't provide current wallpaper info
+    // feh records its last command in ~/.fehbg so the background can be restored
+    let Some(fehbg) = dirs::home_dir().map(|home| home.join(".fehbg")) else {
+      return Ok(None);
+    };
+    match tokio::fs::read_to_string(&fehbg).await {
+      Ok(contents) => Ok(parse_fehbg(&contents)),
+      Err(_) => Ok(None),
+    }
   }
 
   fn is_available(&self) -> bool {
@@ -168,6 +346,9 @@ impl WallpaperBackend for FehBackend {
   fn priority(&self) -> u32 {
     50
   }
+  fn spans_outputs(&self) -> bool {
+    true
+  }
   fn name(&self) -> &'static str {
     "feh"
   }
@@ -199,7 +380,8 @@ impl WallpaperBackend for NitrogenBackend {
       WallpaperScaling::Tile => "--set-tiled",
     };
 
-    cmd.args([mode, &image_path.to_string_lossy()]);
+    // --save writes bg-saved.cfg, which is also how the current wallpaper is read back
+    cmd.args([mode, "--save", &image_path.to_string_lossy()]);
 
     let output = cmd.output().await.context("Failed to execute nitrogen")?;
 
@@ -213,7 +395,13 @@ impl WallpaperBackend for NitrogenBackend {
   }
 
   async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
-    Ok(None)
+    let Some(saved) = dirs::config_dir().map(|dir| dir.join("nitrogen/bg-saved.cfg")) else {
+      return Ok(None);
+    };
+    match tokio::fs::read_to_string(&saved).await {
+      Ok(contents) => Ok(parse_nitrogen_saved(&contents)),
+      Err(_) => Ok(None),
+    }
   }
 
   fn is_available(&self) -> bool {
@@ -285,3 +473,276 @@ impl WallpaperBackend for XwallpaperBackend {
     vec![]
   }
 }
+
+/// mpvpaper backend for video wallpapers on Wayland
+///
+/// mpvpaper keeps running for as long as the video plays, so it's started in the
+/// background after stopping any earlier instance. Transitions don't apply.
+#[derive(Default)]
+pub struct MpvpaperBackend;
+
+impl MpvpaperBackend {
+  pub fn new() -> Self {
+    Self
+  }
+
+  /// Stop the mpvpaper playing on `monitor`, so a still wallpaper underneath becomes visible again
+  ///
+  /// For a single output, only the instance started for it and one playing on every
+  /// output are stopped; videos on other outputs keep playing.
+  pub async fn stop_running(monitor: &MonitorSelection) {
+    if which::which("pkill").is_err() {
+      return;
+    }
+
+    let monitor = match monitor {
+      MonitorSelection::Primary => crate::display::get_displays()
+        .ok()
+        .and_then(|displays| displays.into_iter().find(|display| display.primary)?.name)
+        .map_or(MonitorSelection::All, MonitorSelection::Named),
+      monitor => monitor.clone(),
+    };
+
+    match AsyncCommand::new("pkill").args(Self::pkill_args(&monitor)).status().await {
+      Ok(status) if status.success() => debug!("Stopped running mpvpaper ({:?})", monitor),
+      Ok(_) => {} // Nothing was running
+      Err(e) => debug!("Failed to run pkill: {}", e),
+    }
+  }
+
+  /// pkill arguments matching the mpvpaper instances `build_mpvpaper_command` started for `monitor`
+  fn pkill_args(monitor: &MonitorSelection) -> Vec<String> {
+    match monitor {
+      MonitorSelection::Named(output) => vec!["-f".to_string(), format!("^mpvpaper -o no-audio --loop ({}|\\*) ", regex::escape(output))],
+      _ => vec!["-x".to_string(), "mpvpaper".to_string()],
+    }
+  }
+
+  /// Loop the video silently on the selected output, or on every output
+  fn build_mpvpaper_command(&self, video_path: &Path, options: &WallpaperOptions) -> AsyncCommand {
+    let output = match &options.monitor {
+      MonitorSelection::Named(output) => output.as_str(),
+      _ => "*",
+    };
+
+    let mut cmd = AsyncCommand::new("mpvpaper");
+    cmd.args(["-o", "no-audio --loop", output, &video_path.to_string_lossy()]);
+    cmd
+  }
+}
+
+#[async_trait]
+impl WallpaperBackend for MpvpaperBackend {
+  async fn set_wallpaper(&self, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
+    Self::stop_running(&options.monitor).await;
+
+    let mut cmd = self.build_mpvpaper_command(image_path, options);
+    debug!("mpvpaper command: {:?}", cmd.as_std());
+
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::null());
+
+    let child = cmd.spawn().context("Failed to spawn mpvpaper")?;
+    debug!("mpvpaper started, pid: {:?}", child.id());
+    Ok(())
+  }
+
+  async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
+    Ok(None)
+  }
+
+  fn is_available(&self) -> bool {
+    which::which("mpvpaper").is_ok()
+  }
+
+  fn priority(&self) -> u32 {
+    50
+  }
+  fn sets_per_output(&self) -> bool {
+    true
+  }
+  fn name(&self) -> &'static str {
+    "mpvpaper"
+  }
+  fn supported_transitions(&self) -> Vec<String> {
+    vec![]
+  }
+  fn plays_video(&self) -> bool {
+    true
+  }
+}
+
+/// Extract the displayed image from `swww query` output, which looks like
+/// `DP-1: 2560x1440, scale: 1, currently displaying: image: /path/to/wall.jpg`.
+/// Returns the first output's image. awww shares the same output format.
+pub(super) fn parse_swww_query(stdout: &str) -> Option<PathBuf> {
+  stdout
+    .lines()
+    .find_map(|line| line.split_once("image: ").map(|(_, path)| path.trim()))
+    .filter(|path| !path.is_empty())
+    .map(PathBuf::from)
+}
+
+/// Extract the first wallpaper from `hyprctl hyprpaper listactive` (`DP-1 = /path/to/wall.jpg`)
+fn parse_hyprpaper_listactive(stdout: &str) -> Option<PathBuf> {
+  stdout
+    .lines()
+    .find_map(|line| line.split_once(" = ").map(|(_, path)| path.trim()))
+    .filter(|path| !path.is_empty())
+    .map(PathBuf::from)
+}
+
+/// First image in a `~/.fehbg` script, e.g. `feh --no-fehbg --bg-fill '/home/me/a.jpg' `.
+/// feh single-quotes paths and writes a literal quote as `'\''`.
+fn parse_fehbg(contents: &str) -> Option<PathBuf> {
+  let command = contents.lines().find(|line| line.trim_start().starts_with("feh "))?;
+  let start = command.find('\'')? + 1;
+
+  let mut path = String::new();
+  let mut rest = &command[start..];
+  loop {
+    let end = rest.find('\'')?;
+    path.push_str(&rest[..end]);
+    match rest[end..].strip_prefix("'\\''") {
+      Some(after) => {
+        path.push('\'');
+        rest = after;
+      }
+      None => break,
+    }
+  }
+
+  (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// The first `file=` entry in nitrogen's `bg-saved.cfg`
+fn parse_nitrogen_saved(contents: &str) -> Option<PathBuf> {
+  contents
+    .lines()
+    .find_map(|line| line.trim().strip_prefix("file="))
+    .filter(|path| !path.is_empty())
+    .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_fehbg() {
+    let fehbg = "#!/bin/sh\nfeh --no-fehbg --bg-fill '/home/me/Wallpapers/it'\\''s.jpg' '/home/me/b.png' \n";
+    assert_eq!(parse_fehbg(fehbg), Some(PathBuf::from("/home/me/Wallpapers/it's.jpg")));
+    assert_eq!(parse_fehbg("#!/bin/sh\n"), None);
+  }
+
+  #[test]
+  fn test_parse_nitrogen_saved() {
+    let saved = "[xin_-1]\nfile=/home/me/Wallpapers/a.jpg\nmode=5\nbgcolor=#000000\n";
+    assert_eq!(parse_nitrogen_saved(saved), Some(PathBuf::from("/home/me/Wallpapers/a.jpg")));
+    assert_eq!(parse_nitrogen_saved("[xin_-1]\nmode=5\n"), None);
+  }
+
+  #[test]
+  fn test_feh_command_args() {
+    let args = |options: &WallpaperOptions| -> Vec<String> {
+      let cmd = FehBackend::new().build_feh_command(Path::new("/tmp/wall.jpg"), options);
+      cmd.as_std().get_args().map(|a| a.to_string_lossy().into_owned()).collect()
+    };
+
+    assert_eq!(args(&WallpaperOptions::default()), vec!["--bg-fill", "/tmp/wall.jpg"]);
+    let span = WallpaperOptions {
+      span: true,
+      ..Default::default()
+    };
+    assert_eq!(args(&span), vec!["--bg-fill", "/tmp/wall.jpg", "--no-xinerama"]);
+  }
+
+  #[test]
+  fn test_swww_command_args() {
+    let options = WallpaperOptions {
+      transition: Some("wave".to_string()),
+      fps: Some(60),
+      duration: None,
+      ..Default::default()
+    };
+
+    let cmd = SwwwBackend::new().build_swww_command(Path::new("/tmp/wall.jpg"), &options);
+    let args: Vec<_> = cmd.as_std().get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+
+    assert_eq!(
+      args,
+      vec![
+        "img",
+        "/tmp/wall.jpg",
+        "--transition-type",
+        "wave",
+        "--transition-fps",
+        "60",
+        "--transition-step",
+        "90"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_swww_command_outputs() {
+    let options = WallpaperOptions {
+      monitor: MonitorSelection::Named("DP-1".to_string()),
+      ..Default::default()
+    };
+
+    let cmd = SwwwBackend::new().build_swww_command(Path::new("/tmp/wall.jpg"), &options);
+    let args: Vec<_> = cmd.as_std().get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+
+    assert!(args.ends_with(&["--outputs".to_string(), "DP-1".to_string()]));
+  }
+
+  #[test]
+  fn test_parse_swww_query() {
+    let stdout = "DP-1: 2560x1440, scale: 1, currently displaying: image: /home/me/Pictures/a b.jpg
+HDMI-A-1: 1920x1080, scale: 1, currently displaying: image: /home/me/Pictures/c.png
+";
+    assert_eq!(parse_swww_query(stdout), Some(PathBuf::from("/home/me/Pictures/a b.jpg")));
+    assert_eq!(parse_swww_query("DP-1: 2560x1440, scale: 1, currently displaying: color: 000000"), None);
+  }
+
+  #[test]
+  fn test_parse_hyprpaper_listactive() {
+    assert_eq!(
+      parse_hyprpaper_listactive("DP-1 = /home/me/wall.jpg\n"),
+      Some(PathBuf::from("/home/me/wall.jpg"))
+    );
+    assert_eq!(parse_hyprpaper_listactive("no wallpapers active\n"), None);
+  }
+
+  #[test]
+  fn test_mpvpaper_command_args() {
+    let backend = MpvpaperBackend::new();
+    let args = |options: &WallpaperOptions| -> Vec<String> {
+      let cmd = backend.build_mpvpaper_command(Path::new("/tmp/loop.mp4"), options);
+      cmd.as_std().get_args().map(|a| a.to_string_lossy().into_owned()).collect()
+    };
+
+    assert_eq!(args(&WallpaperOptions::default()), vec!["-o", "no-audio --loop", "*", "/tmp/loop.mp4"]);
+
+    let named = WallpaperOptions {
+      monitor: MonitorSelection::Named("DP-1".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(args(&named), vec!["-o", "no-audio --loop", "DP-1", "/tmp/loop.mp4"]);
+  }
+
+  #[test]
+  fn test_mpvpaper_pkill_args() {
+    assert_eq!(MpvpaperBackend::pkill_args(&MonitorSelection::All), vec!["-x", "mpvpaper"]);
+
+    let args = MpvpaperBackend::pkill_args(&MonitorSelection::Named("DP-1".to_string()));
+    assert_eq!(args[0], "-f");
+    let pattern = regex::Regex::new(&args[1]).unwrap();
+    assert!(pattern.is_match("mpvpaper -o no-audio --loop DP-1 /tmp/loop.mp4"));
+    assert!(pattern.is_match("mpvpaper -o no-audio --loop * /tmp/loop.mp4"));
+    assert!(!pattern.is_match("mpvpaper -o no-audio --loop DP-10 /tmp/loop.mp4"));
+    assert!(!pattern.is_match("mpvpaper -o no-audio --loop HDMI-A-1 /tmp/DP-1 loop.mp4"));
+  }
+}
@@ -51,8 +51,8 @@ impl WallpaperBackend for MacOSWallpaperBackend {
       MonitorSelection::Primary => {
         cmd.args(["--screen", "main"]);
       }
-      MonitorSelection::Specific(index) => {
-        cmd.args(["--screen", &index.to_string()]);
+      MonitorSelection::Named(name) => {
+        cmd.args(["--screen", name]);
       }
     }
 
@@ -93,6 +93,9 @@ impl WallpaperBackend for MacOSWallpaperBackend {
     100 // Highest priority - best UX
   }
 
+  fn sets_per_output(&self) -> bool {
+    true
+  }
   fn name(&self) -> &'static str {
     "macos-wallpaper"
   }
@@ -261,7 +264,7 @@ impl WallpaperBackend for SwiftNativeBackend {
     let screen = match &options.monitor {
       MonitorSelection::All => "all".to_string(),
       MonitorSelection::Primary => "main".to_string(),
-      MonitorSelection::Specific(index) => index.to_string(),
+      MonitorSelection::Named(name) => name.clone(),
     };
 
     let output = AsyncCommand::new(&helper_path)
@@ -294,6 +297,9 @@ impl WallpaperBackend for SwiftNativeBackend {
     80 // Good priority - native but requires compilation
   }
 
+  fn sets_per_output(&self) -> bool {
+    true
+  }
   fn name(&self) -> &'static str {
     "swift-native"
   }
@@ -18,6 +18,9 @@ pub mod awww;
 #[cfg(target_os = "linux")]
 pub mod gnome;
 
+#[cfg(target_os = "linux")]
+pub mod cosmic;
+
 pub use registry::BackendRegistry;
 pub use traits::WallpaperBackend;
 
@@ -104,6 +104,10 @@ impl BackendRegistry {
 
         use crate::wallpaper::backends::AwwwBackend;
         self.register_backend(Arc::new(AwwwBackend::new()));
+        self.register_backend(Arc::new(SwwwBackend::new()));
+
+        // Only used for video files; still images always go to the backends above
+        self.register_backend(Arc::new(MpvpaperBackend::new()));
 
         match compositor {
           crate::platform::WaylandCompositor::Sway => {
@@ -116,17 +120,26 @@ impl BackendRegistry {
             debug!("GNOME detected — registering gsettings backend");
             self.register_backend(Arc::new(super::gnome::GnomeBackend::new()));
           }
+          crate::platform::WaylandCompositor::Cosmic => {
+            debug!("COSMIC detected — registering cosmic-bg backend, with swaybg as a fallback");
+            self.register_backend(Arc::new(super::cosmic::CosmicBackend::new()));
+            self.register_backend(Arc::new(SwaybgBackend::new()));
+          }
           crate::platform::WaylandCompositor::Kde => {
-            debug!("KDE detected — only awww backend is supported for Wayland/KDE");
+            debug!("KDE detected — only awww/swww backends are supported for Wayland/KDE");
           }
           crate::platform::WaylandCompositor::Generic => {
-            debug!("Generic Wayland compositor — only awww backend is supported");
+            // Budgie and other GNOME-based desktops don't say "gnome"; the backend checks for them itself
+            debug!("Generic Wayland compositor — awww/swww, plus gsettings on GNOME-based desktops");
+            self.register_backend(Arc::new(super::gnome::GnomeBackend::new()));
           }
         }
       }
 
       crate::platform::LinuxDisplayServer::X11 => {
-        // X11 backends in priority order
+        // X11 backends in priority order. On GNOME the desktop draws over the root
+        // window, so gsettings (only available there) outranks the others.
+        self.register_backend(Arc::new(super::gnome::GnomeBackend::new()));
         self.register_backend(Arc::new(FehBackend::new()));
         self.register_backend(Arc::new(NitrogenBackend::new()));
         self.register_backend(Arc::new(XwallpaperBackend::new()));
@@ -158,6 +171,8 @@ impl BackendRegistry {
   fn register_windows_backends(&mut self) {
     use super::windows::*;
 
+    // IDesktopWallpaper first; its validate() fails on Windows 7, leaving SPI
+    self.register_backend(Arc::new(WindowsDesktopWallpaperBackend::new()));
     self.register_backend(Arc::new(WindowsSystemParametersBackend::new()));
   }
 
@@ -186,7 +201,7 @@ impl BackendRegistry {
     }
 
     // Backends are registered in priority order, but let's sort to be sure
-    let mut sorted_backends: Vec<_> = self.backends.iter().collect();
+    let mut sorted_backends: Vec<_> = self.backends.iter().filter(|backend| !backend.plays_video()).collect();
     sorted_backends.sort_by_key(|backend| std::cmp::Reverse(backend.priority()));
 
     for backend in &sorted_backends {
@@ -202,6 +217,16 @@ impl BackendRegistry {
     Err(anyhow!("No working wallpaper backends found"))
   }
 
+  /// Get the backend for video wallpapers, if one is installed
+  pub fn get_video_backend(&self) -> Result<Arc<dyn WallpaperBackend + Send + Sync>> {
+    self
+      .backends
+      .iter()
+      .find(|backend| backend.plays_video() && backend.is_available())
+      .cloned()
+      .ok_or_else(|| anyhow!("Video wallpapers need mpvpaper, which is only supported on Wayland"))
+  }
+
   /// List all registered backends
   pub fn list_backends(&self) -> Vec<String> {
     let mut backends: Vec<_> = self
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Request parameters for downloading a wallpaper
 #[derive(Debug, Clone)]
@@ -23,6 +24,8 @@ pub struct WallpaperOptions {
   /// If true, spawn the wallpaper setter without waiting for completion.
   /// Useful for daemon mode to avoid blocking during transitions.
   pub fire_and_forget: bool,
+  /// Stretch the image across all monitors as one screen (only for backends that `spans_outputs`)
+  pub span: bool,
 }
 
 /// How to scale/position the wallpaper
@@ -37,6 +40,24 @@ pub enum WallpaperScaling {
   Tile,    // Tile image across screen
 }
 
+impl FromStr for WallpaperScaling {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s.to_lowercase().as_str() {
+      "fill" => Ok(Self::Fill),
+      "fit" => Ok(Self::Fit),
+      "stretch" => Ok(Self::Stretch),
+      "center" => Ok(Self::Center),
+      "tile" => Ok(Self::Tile),
+      other => Err(anyhow::anyhow!(
+        "Unknown scaling mode '{}'. Expected one of: fill, fit, stretch, center, tile",
+        other
+      )),
+    }
+  }
+}
+
 /// Which monitor(s) to apply wallpaper to
 #[derive(Debug, Clone, Default)]
 #[allow(dead_code)]
@@ -44,7 +65,7 @@ pub enum MonitorSelection {
   Primary, // Primary monitor only
   #[default]
   All, // All monitors
-  Specific(u32), // Specific monitor by index
+  Named(String), // Specific output by name (e.g. "DP-1"), or screen index on macOS and Windows
 }
 
 /// Trait for wallpaper backend implementations
@@ -69,6 +90,22 @@ pub trait WallpaperBackend {
   /// List of transition effects this backend supports
   fn supported_transitions(&self) -> Vec<String>;
 
+  /// Whether this backend plays video files rather than still images
+  fn plays_video(&self) -> bool {
+    false
+  }
+
+  /// Whether `MonitorSelection::Named` sets only that output. Backends that put the
+  /// image on every monitor regardless can't show a different piece on each.
+  fn sets_per_output(&self) -> bool {
+    false
+  }
+
+  /// Whether the backend can stretch one image across all monitors (`WallpaperOptions::span`)
+  fn spans_outputs(&self) -> bool {
+    false
+  }
+
   /// Validate that the backend is properly configured
   fn validate(&self) -> Result<()> {
     if !self.is_available() {
@@ -87,6 +124,20 @@ impl Default for WallpaperOptions {
       scaling: WallpaperScaling::Fill,
       monitor: MonitorSelection::All,
       fire_and_forget: false,
+      span: false,
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_scaling_from_str() {
+    assert!(matches!("fill".parse::<WallpaperScaling>(), Ok(WallpaperScaling::Fill)));
+    assert!(matches!("Center".parse::<WallpaperScaling>(), Ok(WallpaperScaling::Center)));
+    assert!(matches!("tile".parse::<WallpaperScaling>(), Ok(WallpaperScaling::Tile)));
+    assert!("zoom".parse::<WallpaperScaling>().is_err());
+  }
+}
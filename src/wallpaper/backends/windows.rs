@@ -1,12 +1,279 @@
-//! Windows wallpaper backends (stub for future implementation)
+//! Windows wallpaper backends
+//!
+//! Both backends drive Win32 through PowerShell's `Add-Type`, so no extra crates
+//! are needed. `IDesktopWallpaper` (Windows 8+) can give each monitor its own
+//! image; `SystemParametersInfo` is the fallback that sets one image everywhere.
+//! Paths and monitor IDs are passed through environment variables rather than
+//! spliced into the script, so quoting never gets in the way.
 
-#[cfg(target_os = "windows")]
+use super::traits::{MonitorSelection, WallpaperBackend, WallpaperOptions, WallpaperScaling};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use tokio::process::Command as AsyncCommand;
+use tracing::debug;
+
+/// C# binding for the IDesktopWallpaper COM interface. Only the methods up to
+/// SetPosition are declared; COM only needs the vtable prefix to line up.
+const DESKTOP_WALLPAPER_TYPE: &str = r#"
+Add-Type -TypeDefinition @"
+using System;
+using System.Runtime.InteropServices;
+
+[ComImport, Guid("B92B56A9-8B55-4E14-9A89-0199BBB6F93B"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown)]
+public interface IDesktopWallpaper {
+  void SetWallpaper([MarshalAs(UnmanagedType.LPWStr)] string monitorID, [MarshalAs(UnmanagedType.LPWStr)] string wallpaper);
+  [return: MarshalAs(UnmanagedType.LPWStr)] string GetWallpaper([MarshalAs(UnmanagedType.LPWStr)] string monitorID);
+  [return: MarshalAs(UnmanagedType.LPWStr)] string GetMonitorDevicePathAt(uint monitorIndex);
+  uint GetMonitorDevicePathCount();
+  void GetMonitorRECT([MarshalAs(UnmanagedType.LPWStr)] string monitorID, out WallflowRect displayRect);
+  void SetBackgroundColor(uint color);
+  uint GetBackgroundColor();
+  void SetPosition(int position);
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct WallflowRect { public int Left, Top, Right, Bottom; }
+
+[ComImport, Guid("C2CF3110-460E-4fc1-B9D0-8A1C0C9CC4BD")]
+public class DesktopWallpaperClass {}
+
+public static class WallflowDesktopWallpaper {
+  static IDesktopWallpaper Get() { return (IDesktopWallpaper)new DesktopWallpaperClass(); }
+
+  // PowerShell passes $null to a string parameter as "", so an empty monitor means all of them
+  public static void Set(string monitor, string path, int position) {
+    IDesktopWallpaper wallpaper = Get();
+    wallpaper.SetPosition(position);
+    wallpaper.SetWallpaper(String.IsNullOrEmpty(monitor) ? null : monitor, path);
+  }
+
+  public static string Current(string monitor) { return Get().GetWallpaper(monitor); }
+
+  public static string[] Monitors() {
+    IDesktopWallpaper wallpaper = Get();
+    uint count = wallpaper.GetMonitorDevicePathCount();
+    string[] monitors = new string[count];
+    for (uint i = 0; i < count; i++) { monitors[i] = wallpaper.GetMonitorDevicePathAt(i); }
+    return monitors;
+  }
+}
+"@
+"#;
+
+/// Run a PowerShell script with extra environment variables and return its stdout
+async fn run_powershell(script: &str, env: &[(&str, &str)]) -> Result<String> {
+  let mut cmd = AsyncCommand::new("powershell");
+  cmd.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+  for (key, value) in env {
+    cmd.env(key, value);
+  }
+
+  let output = cmd.output().await.context("Failed to execute powershell")?;
+  if !output.status.success() {
+    bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Device paths of the connected monitors, in IDesktopWallpaper's order
+pub fn list_monitors() -> Result<Vec<String>> {
+  let script = format!("{}[WallflowDesktopWallpaper]::Monitors()", DESKTOP_WALLPAPER_TYPE);
+  let output = std::process::Command::new("powershell")
+    .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+    .output()
+    .context("Failed to execute powershell")?;
+
+  if !output.status.success() {
+    bail!("IDesktopWallpaper unavailable: {}", String::from_utf8_lossy(&output.stderr).trim());
+  }
+  Ok(parse_monitor_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// One monitor device path per line
+fn parse_monitor_list(stdout: &str) -> Vec<String> {
+  stdout
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .map(str::to_string)
+    .collect()
+}
+
+/// Monitor argument for IDesktopWallpaper: empty means every monitor.
+/// IDesktopWallpaper has no notion of a primary monitor, so that's the first one.
+fn monitor_argument(monitor: &MonitorSelection) -> String {
+  match monitor {
+    MonitorSelection::All => String::new(),
+    MonitorSelection::Primary => "0".to_string(),
+    MonitorSelection::Named(name) => name.clone(),
+  }
+}
+
+/// IDesktopWallpaper's `DESKTOP_WALLPAPER_POSITION` for a scaling mode
+fn position(scaling: &WallpaperScaling) -> u8 {
+  match scaling {
+    WallpaperScaling::Center => 0,
+    WallpaperScaling::Tile => 1,
+    WallpaperScaling::Stretch => 2,
+    WallpaperScaling::Fit => 3,
+    WallpaperScaling::Fill => 4,
+  }
+}
+
+/// Per-monitor wallpapers through the IDesktopWallpaper COM interface (Windows 8+)
+#[derive(Default)]
+pub struct WindowsDesktopWallpaperBackend;
+
+impl WindowsDesktopWallpaperBackend {
+  pub fn new() -> Self {
+    Self
+  }
+}
+
+#[async_trait]
+impl WallpaperBackend for WindowsDesktopWallpaperBackend {
+  async fn set_wallpaper(&self, image_path: &Path, options: &WallpaperOptions) -> Result<()> {
+    // A numeric monitor is an index into the device path list
+    let script = format!(
+      r#"{}
+$monitor = $env:WALLFLOW_MONITOR
+if ($monitor -match '^\d+$') {{ $monitor = [WallflowDesktopWallpaper]::Monitors()[[int]$monitor] }}
+[WallflowDesktopWallpaper]::Set($monitor, $env:WALLFLOW_WALLPAPER, [int]$env:WALLFLOW_POSITION)"#,
+      DESKTOP_WALLPAPER_TYPE
+    );
+
+    let image_path = image_path.canonicalize().unwrap_or_else(|_| image_path.to_path_buf());
+    let monitor = monitor_argument(&options.monitor);
+    let position = position(&options.scaling).to_string();
+    run_powershell(
+      &script,
+      &[
+        ("WALLFLOW_WALLPAPER", &image_path.to_string_lossy()),
+        ("WALLFLOW_MONITOR", &monitor),
+        ("WALLFLOW_POSITION", &position),
+      ],
+    )
+    .await
+    .context("IDesktopWallpaper failed")?;
+
+    debug!("✅ Windows wallpaper set via IDesktopWallpaper (monitor: {:?})", options.monitor);
+    Ok(())
+  }
+
+  async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
+    let script = format!(
+      "{}[WallflowDesktopWallpaper]::Current([WallflowDesktopWallpaper]::Monitors()[0])",
+      DESKTOP_WALLPAPER_TYPE
+    );
+    let path = run_powershell(&script, &[]).await?;
+    let path = path.trim();
+    Ok((!path.is_empty()).then(|| PathBuf::from(path)))
+  }
+
+  fn is_available(&self) -> bool {
+    which::which("powershell").is_ok()
+  }
+
+  fn priority(&self) -> u32 {
+    60
+  }
+  fn sets_per_output(&self) -> bool {
+    true
+  }
+  fn name(&self) -> &'static str {
+    "windows-desktop-wallpaper"
+  }
+  fn supported_transitions(&self) -> Vec<String> {
+    vec![]
+  }
+
+  fn validate(&self) -> Result<()> {
+    if !self.is_available() {
+      bail!("PowerShell is not available");
+    }
+    // Fails on Windows 7, which lacks the COM class
+    list_monitors().map(|_| ())
+  }
+}
+
+/// Single wallpaper for every monitor through SystemParametersInfo (works on any Windows)
 #[derive(Default)]
 pub struct WindowsSystemParametersBackend;
 
-#[cfg(target_os = "windows")]
 impl WindowsSystemParametersBackend {
   pub fn new() -> Self {
     Self
   }
 }
+
+#[async_trait]
+impl WallpaperBackend for WindowsSystemParametersBackend {
+  async fn set_wallpaper(&self, image_path: &Path, _options: &WallpaperOptions) -> Result<()> {
+    // SPI_SETDESKWALLPAPER = 0x14, SPIF_UPDATEINIFILE | SPIF_SENDCHANGE = 0x3
+    let script = r#"
+Add-Type -TypeDefinition @"
+using System.Runtime.InteropServices;
+public static class WallflowSpi {
+  [DllImport("user32.dll", CharSet = CharSet.Unicode, SetLastError = true)]
+  public static extern bool SystemParametersInfo(uint action, uint param, string path, uint flags);
+}
+"@
+if (-not [WallflowSpi]::SystemParametersInfo(0x14, 0, $env:WALLFLOW_WALLPAPER, 0x3)) { exit 1 }"#;
+
+    let image_path = image_path.canonicalize().unwrap_or_else(|_| image_path.to_path_buf());
+    run_powershell(script, &[("WALLFLOW_WALLPAPER", &image_path.to_string_lossy())])
+      .await
+      .context("SystemParametersInfo failed")?;
+
+    debug!("✅ Windows wallpaper set via SystemParametersInfo");
+    Ok(())
+  }
+
+  async fn get_current_wallpaper(&self) -> Result<Option<PathBuf>> {
+    let path = run_powershell("(Get-ItemProperty 'HKCU:\\Control Panel\\Desktop').WallPaper", &[]).await?;
+    let path = path.trim();
+    Ok((!path.is_empty()).then(|| PathBuf::from(path)))
+  }
+
+  fn is_available(&self) -> bool {
+    which::which("powershell").is_ok()
+  }
+
+  fn priority(&self) -> u32 {
+    50
+  }
+  fn name(&self) -> &'static str {
+    "windows-spi"
+  }
+  fn supported_transitions(&self) -> Vec<String> {
+    vec![]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_monitor_list() {
+    let stdout = "\\\\?\\DISPLAY#DEL40F4#5&1a2b3c&0&UID4353#{e6f07b5f-ee97-4a90-b076-33f57bf4eaa7}\r\n\r\n\\\\?\\DISPLAY#GSM5B7F#5&1a2b3c&0&UID4357#{e6f07b5f-ee97-4a90-b076-33f57bf4eaa7}\r\n";
+    let monitors = parse_monitor_list(stdout);
+    assert_eq!(monitors.len(), 2);
+    assert!(monitors[1].starts_with("\\\\?\\DISPLAY#GSM5B7F"));
+  }
+
+  #[test]
+  fn test_monitor_argument() {
+    assert_eq!(monitor_argument(&MonitorSelection::All), "");
+    assert_eq!(monitor_argument(&MonitorSelection::Primary), "0");
+    assert_eq!(monitor_argument(&MonitorSelection::Named("1".to_string())), "1");
+  }
+
+  #[test]
+  fn test_position() {
+    assert_eq!(position(&WallpaperScaling::Fill), 4);
+    assert_eq!(position(&WallpaperScaling::Center), 0);
+    assert_eq!(position(&WallpaperScaling::Tile), 1);
+  }
+}
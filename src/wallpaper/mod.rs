@@ -1,30 +1,254 @@
 //! Wallpaper management module with cross-platform backend support
 
 pub mod backends;
+mod post_process;
+mod shuffle;
+mod span;
 
+use crate::colors::ColorScheme;
 use crate::config::Config;
 use crate::integration;
 use anyhow::{Context, Result};
 use backends::{
   BackendRegistry,
-  traits::{MonitorSelection, WallpaperOptions, WallpaperScaling},
+  traits::{MonitorSelection, WallpaperBackend, WallpaperOptions, WallpaperScaling},
 };
 use rand::seq::SliceRandom;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
-use tracing::{debug, info};
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, info, warn};
+
+/// Video extensions collected with `sources.local.include_video`
+pub const VIDEO_FORMATS: [&str; 3] = ["mp4", "webm", "gif"];
+
+/// Whether a file is one of the supported video formats
+pub fn is_video(path: &Path) -> bool {
+  has_extension(path, &VIDEO_FORMATS)
+}
+
+/// Whether a file is played as a video. Gifs are only animated with
+/// `sources.local.include_video`; otherwise they are set like any other image.
+fn plays_as_video(path: &Path, config: &Config) -> bool {
+  is_video(path) && (config.sources.local.include_video || !has_extension(path, &["gif"]))
+}
+
+/// Whether wallflow itself can decode the image (for colors, previews and size checks)
+pub fn can_decode(path: &Path) -> bool {
+  image::ImageFormat::from_path(path).is_ok_and(|format| format.reading_enabled())
+}
+
+/// Warn about collected files wallflow can't decode, once per extension per run.
+/// The backend may still display them, but colors and TUI previews won't work.
+pub fn warn_undecodable<'a>(paths: impl IntoIterator<Item = &'a Path>) {
+  static WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+  let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+  for path in paths {
+    if is_video(path) || can_decode(path) {
+      continue;
+    }
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+      *counts.entry(extension.to_lowercase()).or_default() += 1;
+    }
+  }
+
+  let mut warned = WARNED.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+  for (extension, count) in counts {
+    if warned.insert(extension.clone()) {
+      warn!(
+        "wallflow can't decode .{} files ({} found): they can still be set if the backend supports them, but get no colors or previews",
+        extension, count
+      );
+    }
+  }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+  path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
+
+/// What `apply_wallpaper` did
+#[derive(Debug, Clone)]
+pub struct AppliedWallpaper {
+  /// Canonical path of the wallpaper that was set
+  pub path: PathBuf,
+  /// Name of the backend that set it
+  pub backend: String,
+  /// Colors extracted from it, when theming is enabled and extraction worked
+  pub scheme: Option<ColorScheme>,
+}
 
 /// Set wallpaper using the best available backend
-pub async fn apply_wallpaper(wallpaper_path: &Path, config: &Config) -> Result<()> {
-  apply_wallpaper_with_options(wallpaper_path, config, false).await
+pub async fn apply_wallpaper(wallpaper_path: &Path, config: &Config) -> Result<AppliedWallpaper> {
+  apply_wallpaper_with_options(wallpaper_path, config, false, MonitorSelection::All).await
 }
 
 /// Set wallpaper with fire-and-forget option (for daemon mode)
-pub async fn apply_wallpaper_daemon(wallpaper_path: &Path, config: &Config) -> Result<()> {
-  apply_wallpaper_with_options(wallpaper_path, config, true).await
+pub async fn apply_wallpaper_daemon(wallpaper_path: &Path, config: &Config) -> Result<AppliedWallpaper> {
+  apply_wallpaper_with_options(wallpaper_path, config, true, MonitorSelection::All).await
+}
+
+/// Set wallpaper on a single monitor (or all of them)
+///
+/// Named monitors are checked against the outputs reported by the display
+/// module. Only backends that support per-output wallpapers (awww, swww,
+/// hyprpaper, macOS) honour the selection; others apply to every monitor.
+pub async fn apply_wallpaper_to_monitor(wallpaper_path: &Path, monitor: MonitorSelection, config: &Config) -> Result<AppliedWallpaper> {
+  let monitor = resolve_monitor(monitor)?;
+  apply_wallpaper_with_options(wallpaper_path, config, false, monitor).await
+}
+
+/// Print how `wallpaper_path` would be applied, without setting it (`--dry-run`)
+///
+/// The backend and options are picked exactly as `apply_wallpaper` would pick
+/// them, but nothing is set, recorded in history or themed.
+pub fn dry_run(wallpaper_path: &Path, monitor: MonitorSelection, config: &Config) -> Result<()> {
+  let monitor = resolve_monitor(monitor)?;
+  let registry = BackendRegistry::new();
+  let video = plays_as_video(wallpaper_path, config);
+  let backend = select_backend(&registry, video)?;
+  let options = build_wallpaper_options(config, false, monitor);
+
+  let layout = crate::display::get_displays().ok().and_then(|displays| span::layout(&displays));
+  let spanning = span_mode(backend.as_ref(), config, video, &options).map(|mode| describe_span(mode, backend.name(), layout.as_ref()));
+  // Spanned pieces are sized to their monitors already, so only the effects are left
+  let fit = config.transition.fit_to_display && spanning.is_none();
+  let processing = if video { None } else { describe_processing(wallpaper_path, fit, config) };
+
+  println!(
+    "{}",
+    describe_apply(wallpaper_path, backend.as_ref(), &options, processing.as_deref(), spanning.as_deref())
+  );
+  Ok(())
+}
+
+/// What `transition.fit_to_display` (when `fit`) and `transition.post_process` would do to the wallpaper
+fn describe_processing(wallpaper_path: &Path, fit: bool, config: &Config) -> Option<String> {
+  let mut steps = Vec::new();
+  if fit {
+    steps.push("fit to the display".to_string());
+  }
+  if let Some(blur) = config.transition.post_process.blur.filter(|blur| *blur > 0.0) {
+    steps.push(format!("blur {}", blur));
+  }
+  if let Some(dim) = config.transition.post_process.dim.filter(|dim| *dim > 0.0) {
+    steps.push(format!("dim {}%", (dim.min(1.0) * 100.0).round()));
+  }
+
+  if steps.is_empty() {
+    None
+  } else if !can_decode(wallpaper_path) {
+    Some("none (can't decode the image, the original is set)".to_string())
+  } else {
+    Some(format!("{} (the backend gets a processed copy)", steps.join(", ")))
+  }
+}
+
+/// How `transition.span_monitors` would spread the wallpaper, listing each monitor's piece
+fn describe_span(mode: SpanMode, backend: &str, layout: Option<&((u32, u32), Vec<span::Region>)>) -> String {
+  let Some(((width, height), regions)) = layout else {
+    return "no (needs two or more named displays)".to_string();
+  };
+
+  match mode {
+    SpanMode::Pieces => std::iter::once(format!("one {}x{} canvas, a piece per monitor", width, height))
+      .chain(regions.iter().map(|region| {
+        format!(
+          "    {}: {}x{}+{}+{} scaled to {}x{}",
+          region.output, region.width, region.height, region.x, region.y, region.pixels.0, region.pixels.1
+        )
+      }))
+      .collect::<Vec<_>>()
+      .join("\n"),
+    SpanMode::Canvas => format!("one {}x{} image stretched across all monitors", width, height),
+    SpanMode::Unsupported => format!("no ({} shows the same image on every monitor)", backend),
+  }
+}
+
+fn describe_apply(
+  wallpaper_path: &Path,
+  backend: &dyn WallpaperBackend,
+  options: &WallpaperOptions,
+  processing: Option<&str>,
+  spanning: Option<&str>,
+) -> String {
+  let transition = match &options.transition {
+    _ if backend.supported_transitions().is_empty() => format!("none ({} has no transitions)", backend.name()),
+    Some(transition) => format!(
+      "{} ({}s at {} fps)",
+      transition,
+      options.duration.unwrap_or_default(),
+      options.fps.unwrap_or_default()
+    ),
+    None => "none".to_string(),
+  };
+  let monitor = match &options.monitor {
+    MonitorSelection::All => "all",
+    MonitorSelection::Primary => "primary",
+    MonitorSelection::Named(name) => name,
+  };
+
+  let mut lines = vec![
+    "🔍 Dry run, nothing was changed".to_string(),
+    format!("  Wallpaper:  {}", wallpaper_path.display()),
+    format!("  Backend:    {}", backend.name()),
+    format!("  Transition: {}", transition),
+    format!("  Scaling:    {}", format!("{:?}", options.scaling).to_lowercase()),
+    format!("  Monitor:    {}", monitor),
+  ];
+  if let Some(spanning) = spanning {
+    lines.push(format!("  Spanning:   {}", spanning));
+  }
+  if let Some(processing) = processing {
+    lines.push(format!("  Processing: {}", processing));
+  }
+  lines.join("\n")
+}
+
+/// The backend that plays videos, or the best one for still images
+fn select_backend(registry: &BackendRegistry, video: bool) -> Result<std::sync::Arc<dyn WallpaperBackend + Send + Sync>> {
+  if video {
+    registry.get_video_backend()
+  } else {
+    registry.get_best_backend().context("No wallpaper backends available")
+  }
+}
+
+/// Turn `Primary` into a concrete output name and make sure named outputs exist
+fn resolve_monitor(monitor: MonitorSelection) -> Result<MonitorSelection> {
+  let outputs = match crate::display::list_outputs() {
+    Ok(outputs) => outputs,
+    Err(e) => {
+      // Without an output list there's nothing to check against; let the backend decide
+      debug!("Could not list outputs ({}), using monitor selection as-is", e);
+      return Ok(monitor);
+    }
+  };
+
+  match monitor {
+    MonitorSelection::All => Ok(MonitorSelection::All),
+    MonitorSelection::Primary => Ok(outputs.into_iter().next().map(MonitorSelection::Named).unwrap_or(MonitorSelection::All)),
+    MonitorSelection::Named(name) => {
+      if outputs.contains(&name) {
+        Ok(MonitorSelection::Named(name))
+      } else {
+        Err(anyhow::anyhow!("Monitor '{}' not found. Available outputs: {}", name, outputs.join(", ")))
+      }
+    }
+  }
 }
 
 /// Internal function that handles both CLI and daemon modes
-async fn apply_wallpaper_with_options(wallpaper_path: &Path, config: &Config, fire_and_forget: bool) -> Result<()> {
+async fn apply_wallpaper_with_options(
+  wallpaper_path: &Path,
+  config: &Config,
+  fire_and_forget: bool,
+  monitor: MonitorSelection,
+) -> Result<AppliedWallpaper> {
   debug!(
     "apply_wallpaper_with_options: path={}, fire_and_forget={}",
     wallpaper_path.display(),
@@ -35,57 +259,363 @@ async fn apply_wallpaper_with_options(wallpaper_path: &Path, config: &Config, fi
 
   debug!("Available backends: {:?}", registry.available_backend_names());
 
-  let backend = registry.get_best_backend().context("No wallpaper backends available")?;
+  let video = plays_as_video(wallpaper_path, config);
+  let backend = select_backend(&registry, video)?;
+  let options = build_wallpaper_options(config, fire_and_forget, monitor);
+  let display_path = show_wallpaper(wallpaper_path, config, backend.as_ref(), video, &options).await?;
+
+  let path = wallpaper_path.canonicalize().unwrap_or_else(|_| wallpaper_path.to_path_buf());
+  record_applied_wallpaper(&path);
+
+  if config.history.enabled {
+    let entry = crate::history::HistoryEntry::new(wallpaper_path, &wallpaper_source(wallpaper_path, config));
+    if let Err(e) = crate::history::record(&entry, config.history.max_entries) {
+      warn!("Failed to record wallpaper history: {}", e);
+    }
+  }
+
+  // Set KDE Plasma wallpaper if available (ensures KDE apps inherit colors)
+  if !video {
+    integration::set_kde_wallpaper_with(&display_path, &integration::KdeWallpaperOptions::from_wallpaper_options(&options)).await;
+  }
+
+  // Color theming pipeline. Gifs decode to their first frame; other videos have no still to sample.
+  let mut scheme = None;
+  if config.colors.enabled {
+    if !video || has_extension(wallpaper_path, &["gif"]) {
+      scheme = apply_color_theme(wallpaper_path, config).await;
+    } else {
+      debug!("Skipping color extraction for video {}", wallpaper_path.display());
+    }
+  }
+
+  info!("✅ Wallpaper {} applied successfully using {}", wallpaper_path.display(), backend.name());
+
+  Ok(AppliedWallpaper {
+    path,
+    backend: backend.name().to_string(),
+    scheme,
+  })
+}
+
+/// Put a wallpaper on one output without recording it or theming from it.
+/// `per_monitor` uses this for every output but the primary one.
+async fn show_on_output(wallpaper_path: &Path, config: &Config, fire_and_forget: bool, output: &str) -> Result<()> {
+  let registry = BackendRegistry::new();
+  let video = plays_as_video(wallpaper_path, config);
+  let backend = select_backend(&registry, video)?;
+  let options = build_wallpaper_options(config, fire_and_forget, MonitorSelection::Named(output.to_string()));
+  show_wallpaper(wallpaper_path, config, backend.as_ref(), video, &options).await?;
+  Ok(())
+}
+
+/// Hand the wallpaper to `backend`, spanned or post-processed as configured,
+/// and return the path the backend got
+async fn show_wallpaper(
+  wallpaper_path: &Path,
+  config: &Config,
+  backend: &(dyn WallpaperBackend + Send + Sync),
+  video: bool,
+  options: &WallpaperOptions,
+) -> Result<PathBuf> {
+  // A video left playing would cover the new still wallpaper
+  #[cfg(target_os = "linux")]
+  if !video && config.sources.local.include_video {
+    backends::linux::MpvpaperBackend::stop_running(&options.monitor).await;
+  }
 
-  let options = build_wallpaper_options(config, fire_and_forget);
+  let spanned = match span_mode(backend, config, video, options) {
+    Some(SpanMode::Unsupported) => {
+      warn!(
+        "{} shows the same image on every monitor, ignoring transition.span_monitors",
+        backend.name()
+      );
+      None
+    }
+    Some(mode) => spanned(wallpaper_path, config, mode).await,
+    None => None,
+  };
+
+  // The backend gets the fitted/blurred/dimmed copy; history and colors stay with the original
+  let display_path = match &spanned {
+    Some(Spanned::Canvas(canvas)) => canvas.clone(),
+    Some(Spanned::Pieces(_)) => wallpaper_path.to_path_buf(),
+    None if !video && (config.transition.fit_to_display || config.transition.post_process.is_enabled()) => {
+      post_processed(wallpaper_path, &options.monitor, config).await
+    }
+    None => wallpaper_path.to_path_buf(),
+  };
 
   debug!(
-    "Applying wallpaper with {}, options: transition={:?}, scaling={:?}, fire_and_forget={}",
+    "Applying wallpaper with {}, options: transition={:?}, scaling={:?}, monitor={:?}, fire_and_forget={}",
     backend.name(),
     options.transition,
     options.scaling,
+    options.monitor,
     options.fire_and_forget
   );
 
-  debug!("Calling {}.set_wallpaper({})", backend.name(), wallpaper_path.display());
+  match &spanned {
+    Some(Spanned::Pieces(pieces)) => {
+      for (output, piece) in pieces {
+        debug!("Calling {}.set_wallpaper({}) on {}", backend.name(), piece.display(), output);
 
-  backend
-    .set_wallpaper(wallpaper_path, &options)
-    .await
-    .context("Failed to apply wallpaper")?;
+        let options = WallpaperOptions {
+          monitor: MonitorSelection::Named(output.clone()),
+          ..options.clone()
+        };
+        backend
+          .set_wallpaper(piece, &options)
+          .await
+          .with_context(|| format!("Failed to apply wallpaper to {}", output))?;
+      }
+    }
+    Some(Spanned::Canvas(canvas)) => {
+      debug!("Calling {}.set_wallpaper({}) across all monitors", backend.name(), canvas.display());
 
-  // Set KDE Plasma wallpaper if available (ensures KDE apps inherit colors)
-  integration::set_kde_wallpaper(wallpaper_path).await;
+      let options = WallpaperOptions {
+        span: true,
+        ..options.clone()
+      };
+      backend.set_wallpaper(canvas, &options).await.context("Failed to apply wallpaper")?;
+    }
+    None => {
+      debug!("Calling {}.set_wallpaper({})", backend.name(), display_path.display());
 
-  // Color theming pipeline
-  if config.colors.enabled {
-    apply_color_theme(wallpaper_path, config);
+      backend.set_wallpaper(&display_path, options).await.context("Failed to apply wallpaper")?;
+    }
   }
 
-  info!("✅ Wallpaper {} applied successfully using {}", wallpaper_path.display(), backend.name());
+  Ok(display_path)
+}
 
-  Ok(())
+/// How `transition.span_monitors` is carried out with a backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpanMode {
+  /// Set each monitor's piece on its output
+  Pieces,
+  /// Set one image covering the whole layout, which the backend stretches across the monitors
+  Canvas,
+  /// The backend shows one image on every monitor, so spanning is skipped
+  Unsupported,
+}
+
+/// A wallpaper prepared for `transition.span_monitors`
+enum Spanned {
+  /// Output names with their piece
+  Pieces(Vec<(String, PathBuf)>),
+  Canvas(PathBuf),
+}
+
+/// How to span the wallpaper with `backend`, or `None` when it isn't spanned
+fn span_mode(backend: &dyn WallpaperBackend, config: &Config, video: bool, options: &WallpaperOptions) -> Option<SpanMode> {
+  if video || !config.transition.span_monitors || !matches!(options.monitor, MonitorSelection::All) {
+    return None;
+  }
+
+  Some(if backend.sets_per_output() {
+    SpanMode::Pieces
+  } else if backend.spans_outputs() {
+    SpanMode::Canvas
+  } else {
+    SpanMode::Unsupported
+  })
+}
+
+/// Lay the wallpaper out across the monitors for `transition.span_monitors`.
+/// Returns `None`, so a single wallpaper is set instead, with fewer than two
+/// monitors or when the images can't be made.
+async fn spanned(wallpaper_path: &Path, config: &Config, mode: SpanMode) -> Option<Spanned> {
+  if !can_decode(wallpaper_path) {
+    debug!("Can't span {}, setting it as-is", wallpaper_path.display());
+    return None;
+  }
+
+  let original = wallpaper_path.to_path_buf();
+  let effects = config.transition.post_process.clone();
+  let result = tokio::task::spawn_blocking(move || {
+    let displays = crate::display::get_displays()?;
+    let Some((canvas, regions)) = span::layout(&displays) else {
+      debug!("Spanning needs two or more named displays, found {}", displays.len());
+      return Ok(None);
+    };
+    Ok(Some(if mode == SpanMode::Canvas {
+      Spanned::Canvas(post_process::process(&original, Some(canvas), &effects)?)
+    } else {
+      let pieces = post_process::span(&original, canvas, &regions, &effects)?;
+      Spanned::Pieces(regions.into_iter().map(|region| region.output).zip(pieces).collect())
+    }))
+  })
+  .await
+  .map_err(anyhow::Error::from)
+  .and_then(|result| result);
+
+  result.unwrap_or_else(|e: anyhow::Error| {
+    warn!("Spanning failed, setting one wallpaper on every monitor: {:#}", e);
+    None
+  })
+}
+
+/// Apply `transition.fit_to_display` and `transition.post_process` to a copy of
+/// the wallpaper, falling back to the original when that fails
+async fn post_processed(wallpaper_path: &Path, monitor: &MonitorSelection, config: &Config) -> PathBuf {
+  if !can_decode(wallpaper_path) {
+    debug!("Can't post-process {}, setting it as-is", wallpaper_path.display());
+    return wallpaper_path.to_path_buf();
+  }
+
+  let original = wallpaper_path.to_path_buf();
+  let output = match monitor {
+    MonitorSelection::Named(name) => Some(name.clone()),
+    _ => None,
+  };
+  let fit_to_display = config.transition.fit_to_display;
+  let effects = config.transition.post_process.clone();
+  let result = tokio::task::spawn_blocking(move || {
+    // Physical pixels, so the compositor has nothing left to scale
+    let fit = match &output {
+      _ if !fit_to_display => None,
+      Some(output) => {
+        let resolution = crate::display::get_display_resolution(output)?;
+        Some((resolution.width, resolution.height))
+      }
+      // The same image goes on every monitor, so it can only be fitted when they match
+      None => {
+        let displays = crate::display::get_displays().unwrap_or_default();
+        let fit = shared_resolution(&displays);
+        if fit.is_none() {
+          info!("Monitors differ in resolution or couldn't be detected, not fitting the wallpaper to one of them");
+        }
+        fit
+      }
+    };
+    post_process::process(&original, fit, &effects)
+  })
+  .await
+  .map_err(anyhow::Error::from)
+  .and_then(|result| result);
+
+  match result {
+    Ok(processed) => {
+      debug!("Post-processed {} into {}", wallpaper_path.display(), processed.display());
+      processed
+    }
+    Err(e) => {
+      warn!("Post-processing failed, setting the original wallpaper: {:#}", e);
+      wallpaper_path.to_path_buf()
+    }
+  }
+}
+
+/// Resolution every display has, in physical pixels, or `None` when they differ
+fn shared_resolution(displays: &[crate::display::Display]) -> Option<(u32, u32)> {
+  let mut sizes = displays.iter().map(|display| (display.resolution.width, display.resolution.height));
+  let first = sizes.next()?;
+  sizes.all(|size| size == first).then_some(first)
+}
+
+/// File holding the path of the most recently applied wallpaper
+fn applied_wallpaper_file() -> Option<PathBuf> {
+  crate::paths::state_dir().ok().map(|dir| dir.join("current_wallpaper"))
+}
+
+/// Remember which wallpaper was applied so it can be protected from cleanup
+fn record_applied_wallpaper(path: &Path) {
+  let Some(file) = applied_wallpaper_file() else {
+    return;
+  };
+
+  let result = file
+    .parent()
+    .map(std::fs::create_dir_all)
+    .unwrap_or(Ok(()))
+    .and_then(|_| std::fs::write(&file, path.to_string_lossy().as_bytes()));
+
+  if let Err(e) = result {
+    debug!("Failed to record applied wallpaper: {}", e);
+  }
+}
+
+/// Work out where a wallpaper came from for the history log.
+/// Downloads are named `<source>_<timestamp>`, so the file name prefix identifies them.
+fn wallpaper_source(wallpaper_path: &Path, config: &Config) -> String {
+  let file_name = wallpaper_path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+  if let Some(source) = crate::downloaders::list_sources()
+    .into_iter()
+    .find(|source| file_name.starts_with(&format!("{}_", source)))
+  {
+    return source;
+  }
+
+  if wallpaper_path.starts_with(&config.paths.local) {
+    "local".to_string()
+  } else {
+    "file".to_string()
+  }
+}
+
+/// Path of the wallpaper wallflow applied most recently, if known
+pub fn last_applied_wallpaper() -> Option<PathBuf> {
+  let contents = std::fs::read_to_string(applied_wallpaper_file()?).ok()?;
+  let path = contents.trim();
+  (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// The wallpaper currently on screen. Asks the best backend first and falls
+/// back to the last wallpaper wallflow applied for backends that can't tell.
+pub async fn get_current_wallpaper() -> Option<PathBuf> {
+  if let Ok(backend) = BackendRegistry::new().get_best_backend() {
+    match backend.get_current_wallpaper().await {
+      // Backends only know about the post-processed copy
+      Ok(Some(path)) if post_process::is_processed(&path) => return last_applied_wallpaper(),
+      Ok(Some(path)) => return Some(path),
+      Ok(None) => debug!("{} can't report the current wallpaper", backend.name()),
+      Err(e) => debug!("Failed to query current wallpaper from {}: {}", backend.name(), e),
+    }
+  }
+
+  last_applied_wallpaper()
+}
+
+/// Whether two paths refer to the same wallpaper file
+pub fn is_same_wallpaper(a: &Path, b: &Path) -> bool {
+  if a == b {
+    return true;
+  }
+
+  // Only resolve symlinks/relative paths when the file names could match
+  a.file_name() == b.file_name() && matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a == b)
 }
 
 /// Apply color theme after wallpaper is set.
-/// Runs native k-means++ extraction and renders templates.
-fn apply_color_theme(wallpaper_path: &Path, config: &Config) {
+/// Runs native k-means++ extraction and renders templates, returning the extracted scheme.
+async fn apply_color_theme(wallpaper_path: &Path, config: &Config) -> Option<ColorScheme> {
   match config.colors.engine.as_str() {
     "native" => {
-      let options = crate::colors::ExtractionOptions {
-        contrast_ratio: config.colors.contrast_ratio,
-        background_intensity: config.colors.background_intensity,
-        prefers_dark: config.colors.prefer_dark.or_else(crate::platform::detect_dark_mode),
-        ..Default::default()
-      };
+      let options = config.colors.extraction_options();
+      let extractor = config.colors.extractor();
+      // Cluster once; the scheme, its dark/light variants and the accent all come from the same colors
+      let colors = image::open(wallpaper_path)
+        .context("Failed to open image")
+        .and_then(|image| extractor.weighted_colors(&image, &options));
+      match colors {
+        Ok(colors) => {
+          let scheme = extractor.scheme_from_colors(wallpaper_path.to_string_lossy().to_string(), &colors, &options);
+          if config.integration.icon_theme_sync {
+            integration::sync_icon_theme(scheme.is_dark, &config.integration.icon_themes);
+          }
+          if config.integration.gnome.accent_sync {
+            match extractor.accent_from_colors(&colors) {
+              Some(accent) => integration::sync_gnome_accent(accent),
+              None => tracing::warn!("Failed to pick an accent color"),
+            }
+          }
 
-      let extractor = crate::colors::ColorExtractor::new();
-      match extractor.extract(wallpaper_path, &options) {
-        Ok(scheme) => {
           let output_dir = crate::templates::TemplateEngine::default_output_dir();
           if let Err(e) = std::fs::create_dir_all(&output_dir) {
             tracing::warn!("Failed to create output dir: {}", e);
-            return;
+            return Some(scheme);
           }
 
           // Save color scheme JSON
@@ -101,35 +631,68 @@ fn apply_color_theme(wallpaper_path: &Path, config: &Config) {
             Err(e) => tracing::warn!("Failed to serialize color scheme: {}", e),
           }
 
-          // Render templates if available
-          let tpl_dir = crate::templates::templates_dir();
-          if tpl_dir.exists() {
-            match crate::templates::TemplateEngine::render_all(&tpl_dir, &output_dir, &scheme) {
+          // Render templates, fetching them on first use
+          match crate::templates::ensure_templates().await {
+            Ok(tpl_dir) => match crate::templates::TemplateEngine::render_all(&tpl_dir, &output_dir, &scheme) {
               Ok(rendered) => {
                 if !rendered.is_empty() {
                   debug!("Rendered {} templates", rendered.len());
                   if config.integration.reload_apps {
-                    crate::templates::TemplateEngine::notify_apps(&rendered);
+                    crate::templates::TemplateEngine::notify_apps(&rendered, &config.integration.notify_apps);
                   }
                 }
               }
               Err(e) => tracing::warn!("Failed to render templates: {}", e),
-            }
+            },
+            Err(e) => tracing::warn!("Templates unavailable, skipping: {}", e),
           }
+          integration::notify_color_change(&config.integration.notify_apps, &scheme, &output_dir);
+
+          if config.colors.dark_and_light
+            && let Err(e) = write_dark_light_variants(&extractor, wallpaper_path, &colors, &options, &output_dir)
+          {
+            tracing::warn!("Failed to write dark/light color variants: {}", e);
+          }
+          Some(scheme)
         }
         Err(e) => {
           tracing::warn!("Color extraction failed: {}", e);
+          None
         }
       }
     }
     other => {
       tracing::warn!("Unknown colors engine '{}', skipping", other);
+      None
     }
   }
 }
 
+/// Write `colors-dark.json`/`colors-light.json` and the matching template variants,
+/// so apps that switch with the system theme can pick either one
+fn write_dark_light_variants(
+  extractor: &crate::colors::ColorExtractor,
+  wallpaper_path: &Path,
+  colors: &[(crate::colors::Rgb, usize)],
+  options: &crate::colors::ExtractionOptions,
+  output_dir: &Path,
+) -> Result<()> {
+  let (dark, light) = extractor.both_from_colors(wallpaper_path.to_string_lossy().to_string(), colors, options);
+
+  std::fs::write(output_dir.join("colors-dark.json"), dark.to_json()?).context("Failed to write colors-dark.json")?;
+  std::fs::write(output_dir.join("colors-light.json"), light.to_json()?).context("Failed to write colors-light.json")?;
+
+  let tpl_dir = crate::templates::templates_dir();
+  if tpl_dir.exists() {
+    let rendered = crate::templates::TemplateEngine::render_dark_light(&tpl_dir, output_dir, &dark, &light)?;
+    debug!("Rendered {} dark/light template variants", rendered.len());
+  }
+
+  Ok(())
+}
+
 /// Build wallpaper options from configuration
-fn build_wallpaper_options(config: &Config, fire_and_forget: bool) -> WallpaperOptions {
+fn build_wallpaper_options(config: &Config, fire_and_forget: bool, monitor: MonitorSelection) -> WallpaperOptions {
   let transition = match &config.transition.transition_type {
     crate::config::TransitionType::Single(t) => Some(t.clone()),
     crate::config::TransitionType::Multiple(types) => {
@@ -143,9 +706,13 @@ fn build_wallpaper_options(config: &Config, fire_and_forget: bool) -> WallpaperO
     transition,
     duration: Some(config.transition.duration),
     fps: Some(config.transition.fps),
-    scaling: WallpaperScaling::Fill, // Default for now, could be configurable
-    monitor: MonitorSelection::All,
+    scaling: config.transition.scaling.parse().unwrap_or_else(|e| {
+      warn!("{}, using fill", e);
+      WallpaperScaling::Fill
+    }),
+    monitor,
     fire_and_forget,
+    span: false,
   }
 }
 
@@ -178,6 +745,17 @@ pub fn platform_info() -> Result<String> {
     info.push_str("No working backends found\n");
   }
 
+  #[cfg(target_os = "windows")]
+  match backends::windows::list_monitors() {
+    Ok(monitors) => {
+      info.push_str("Monitors (use the index or device path with --monitor):\n");
+      for (index, monitor) in monitors.iter().enumerate() {
+        info.push_str(&format!("  {}: {}\n", index, monitor));
+      }
+    }
+    Err(e) => info.push_str(&format!("Monitors: unavailable ({:#})\n", e)),
+  }
+
   Ok(info)
 }
 
@@ -185,83 +763,273 @@ pub fn platform_info() -> Result<String> {
 /// The `query` parameter contains additional arguments (e.g., search terms, subreddit names)
 pub async fn set_from_source(config: &Config, source: &str, query: &[String], opts: &crate::downloaders::DownloadOptions) -> Result<()> {
   info!("Downloading wallpaper from {}", source);
-  let wallpaper = crate::downloaders::download_from_source(source, config, query, opts).await?;
+  let wallpaper = download_or_reuse(config, source, query, opts).await?;
   debug!("Downloaded: {:?}", wallpaper);
 
+  if let Some(credit) = wallpaper.metadata.as_ref().and_then(|m| m.credit()) {
+    info!("📷 {}", credit);
+  }
+
+  if opts.dry_run {
+    let monitor = opts.monitor.clone().map(MonitorSelection::Named).unwrap_or(MonitorSelection::All);
+    return dry_run(&wallpaper.file_path, monitor, config);
+  }
+
   if opts.no_set {
     // Just print the path for the caller to use
     println!("{}", wallpaper.file_path.display());
+  } else if let Some(monitor) = &opts.monitor {
+    apply_wallpaper_to_monitor(&wallpaper.file_path, MonitorSelection::Named(monitor.clone()), config).await?;
   } else {
     apply_wallpaper(&wallpaper.file_path, config).await?;
   }
 
+  match crate::downloaders::filesystem::cleanup_downloads(config) {
+    Ok(0) => {}
+    Ok(removed) => debug!("Removed {} old downloads", removed),
+    Err(e) => warn!("Failed to clean up old downloads: {}", e),
+  }
+
   Ok(())
 }
 
 /// Download and set wallpaper from source (daemon mode - fire and forget)
 pub async fn set_from_source_daemon(config: &Config, source: &str, query: &[String], opts: &crate::downloaders::DownloadOptions) -> Result<()> {
   info!("Downloading wallpaper from {}", source);
-  let wallpaper = crate::downloaders::download_from_source(source, config, query, opts).await?;
+  let wallpaper = download_or_reuse(config, source, query, opts).await?;
   debug!("Downloaded: {:?}", wallpaper);
 
+  if let Some(credit) = wallpaper.metadata.as_ref().and_then(|m| m.credit()) {
+    info!("📷 {}", credit);
+  }
+
   if opts.no_set {
     println!("{}", wallpaper.file_path.display());
   } else {
     apply_wallpaper_daemon(&wallpaper.file_path, config).await?;
   }
 
+  match crate::downloaders::filesystem::cleanup_downloads(config) {
+    Ok(0) => {}
+    Ok(removed) => debug!("Removed {} old downloads", removed),
+    Err(e) => warn!("Failed to clean up old downloads: {}", e),
+  }
+
   Ok(())
 }
 
+/// Download from `source`, or with `advanced.offline_fallback` set and the source
+/// unreachable, pick one of the earlier downloads instead
+async fn download_or_reuse(
+  config: &Config,
+  source: &str,
+  query: &[String],
+  opts: &crate::downloaders::DownloadOptions,
+) -> Result<crate::downloaders::traits::Wallpaper> {
+  use crate::downloaders::{error::DownloaderError, filesystem::FilesystemHelper, traits::Wallpaper};
+
+  let error = match crate::downloaders::download_from_source(source, config, query, opts).await {
+    Ok(wallpaper) => return Ok(wallpaper),
+    Err(error @ DownloaderError::Network(_)) if config.advanced.offline_fallback => error,
+    Err(error) => return Err(error.into()),
+  };
+
+  let downloads_dir = Path::new(&config.paths.downloads);
+  let downloads = if downloads_dir.is_dir() {
+    collect_wallpapers(downloads_dir, &config.sources.local.formats, 0)?
+  } else {
+    Vec::new()
+  };
+  let Some(file_path) = downloads.choose(&mut rand::thread_rng()).cloned() else {
+    return Err(anyhow::Error::from(error).context("Offline fallback found no earlier downloads to reuse"));
+  };
+
+  warn!(
+    "Offline fallback: {} is unreachable ({}), reusing earlier download {}",
+    source,
+    error,
+    file_path.display()
+  );
+
+  let downloaded_at = std::fs::metadata(&file_path)
+    .and_then(|metadata| metadata.modified())
+    .map(chrono::DateTime::<chrono::Utc>::from)
+    .unwrap_or_else(|_| chrono::Utc::now());
+
+  Ok(Wallpaper {
+    metadata: FilesystemHelper::read_sidecar(&file_path),
+    file_path,
+    source: source.to_string(),
+    downloaded_at,
+  })
+}
+
 /// Set wallpaper from local collection
-pub async fn set_local(config: &Config) -> Result<()> {
-  let wallpaper_path = select_local_wallpaper(config)?;
+pub async fn set_local(config: &Config, opts: &crate::downloaders::DownloadOptions) -> Result<()> {
+  if opts.dry_run {
+    return dry_run_local(config);
+  }
+  if config.transition.per_monitor {
+    return set_local_per_monitor(config, false).await;
+  }
+
+  let wallpaper_path = select_local_wallpaper(config, true)?;
   apply_wallpaper(&wallpaper_path, config).await?;
   Ok(())
 }
 
 /// Set wallpaper from local collection (daemon mode - fire and forget)
 pub async fn set_local_daemon(config: &Config) -> Result<()> {
-  let wallpaper_path = select_local_wallpaper(config)?;
+  if config.transition.per_monitor {
+    return set_local_per_monitor(config, true).await;
+  }
+
+  let wallpaper_path = select_local_wallpaper(config, true)?;
   apply_wallpaper_daemon(&wallpaper_path, config).await?;
   Ok(())
 }
 
-/// Select random wallpaper from local collection
-fn select_local_wallpaper(config: &Config) -> Result<PathBuf> {
-  let wallpaper_dir = Path::new(&config.paths.local);
+/// Set a random starred wallpaper
+pub async fn set_favorites(config: &Config, opts: &crate::downloaders::DownloadOptions) -> Result<()> {
+  let wallpaper_path = select_favorite_wallpaper()?;
+  if opts.dry_run {
+    return dry_run(&wallpaper_path, MonitorSelection::All, config);
+  }
+  apply_wallpaper(&wallpaper_path, config).await?;
+  Ok(())
+}
+
+/// Set a random starred wallpaper (daemon mode - fire and forget)
+pub async fn set_favorites_daemon(config: &Config) -> Result<()> {
+  let wallpaper_path = select_favorite_wallpaper()?;
+  apply_wallpaper_daemon(&wallpaper_path, config).await?;
+  Ok(())
+}
+
+/// Pick a random favorite, skipping ones that have since been deleted
+fn select_favorite_wallpaper() -> Result<PathBuf> {
+  let favorites = crate::favorites::existing()?;
+  favorites
+    .choose(&mut rand::thread_rng())
+    .cloned()
+    .ok_or_else(|| anyhow::anyhow!("No favorite wallpapers yet. Star some with 'f' in the TUI."))
+}
+
+/// Give each connected output its own random wallpaper from the local collection
+async fn set_local_per_monitor(config: &Config, fire_and_forget: bool) -> Result<()> {
+  let outputs = crate::display::list_outputs().unwrap_or_default();
+
+  if outputs.len() < 2 {
+    debug!("per_monitor enabled but {} output(s) detected, using one wallpaper", outputs.len());
+    let wallpaper_path = select_local_wallpaper(config, true)?;
+    apply_wallpaper_with_options(&wallpaper_path, config, fire_and_forget, MonitorSelection::All).await?;
+    return Ok(());
+  }
+
+  let wallpapers = collect_local_wallpapers(config)?;
+  let mut rng = rand::thread_rng();
+  let picks: Vec<&PathBuf> = wallpapers.choose_multiple(&mut rng, outputs.len()).collect();
+
+  // Only the primary output's wallpaper goes through history and theming, so
+  // colors and templates are updated once. With fewer wallpapers than outputs, picks are reused.
+  for (index, output) in outputs.iter().enumerate().skip(1) {
+    show_on_output(picks[index % picks.len()], config, fire_and_forget, output).await?;
+  }
+  apply_wallpaper_with_options(picks[0], config, fire_and_forget, MonitorSelection::Named(outputs[0].clone())).await?;
+
+  Ok(())
+}
+
+/// `set_local` for `--dry-run`: the same picks, with the shuffle state left alone
+fn dry_run_local(config: &Config) -> Result<()> {
+  let outputs = if config.transition.per_monitor {
+    crate::display::list_outputs().unwrap_or_default()
+  } else {
+    Vec::new()
+  };
+
+  if outputs.len() < 2 {
+    return dry_run(&select_local_wallpaper(config, false)?, MonitorSelection::All, config);
+  }
+
+  let wallpapers = collect_local_wallpapers(config)?;
+  let picks: Vec<&PathBuf> = wallpapers.choose_multiple(&mut rand::thread_rng(), outputs.len()).collect();
+  for (index, output) in outputs.iter().enumerate() {
+    dry_run(picks[index % picks.len()], MonitorSelection::Named(output.clone()), config)?;
+  }
+  Ok(())
+}
+
+/// Select random wallpaper from local collection, avoiding recent ones in shuffle mode.
+/// Without `remember` the pick isn't saved to the shuffle state.
+fn select_local_wallpaper(config: &Config, remember: bool) -> Result<PathBuf> {
+  let wallpapers = collect_local_wallpapers(config)?;
+  let mut rng = rand::thread_rng();
+
+  if !config.sources.local.shuffle {
+    let wallpaper = wallpapers
+      .choose(&mut rng)
+      .ok_or_else(|| anyhow::anyhow!("Failed to select random wallpaper"))?;
+    return Ok(wallpaper.clone());
+  }
+
+  let mut state = shuffle::ShuffleState::load();
+  let wallpaper = state
+    .pick(&wallpapers, &mut rng)
+    .ok_or_else(|| anyhow::anyhow!("Failed to select random wallpaper"))?
+    .clone();
+
+  if remember && let Err(e) = state.save() {
+    warn!("Failed to save shuffle state: {}", e);
+  }
+
+  Ok(wallpaper)
+}
+
+/// Collect all image files in the local collection, failing if there are none
+fn collect_local_wallpapers(config: &Config) -> Result<Vec<PathBuf>> {
+  let wallpaper_dir = config.sources.local.directory(&config.paths.local)?;
+  let wallpaper_dir = wallpaper_dir.as_path();
 
   if !wallpaper_dir.exists() {
     return Err(anyhow::anyhow!("Local wallpaper directory does not exist: {}", wallpaper_dir.display()));
   }
 
   // Collect all image files
-  let mut wallpapers = Vec::new();
-  collect_wallpapers(
+  let wallpapers = collect_wallpapers(
     wallpaper_dir,
-    &config.sources.local.formats,
-    &mut wallpapers,
-    config.sources.local.recursive,
+    &config.sources.local.collected_formats(),
+    config.sources.local.search_depth(),
   )?;
 
   if wallpapers.is_empty() {
     return Err(anyhow::anyhow!("No wallpapers found in: {}", wallpaper_dir.display()));
   }
+  warn_undecodable(wallpapers.iter().map(PathBuf::as_path));
 
-  // Select random wallpaper
-  let wallpaper = wallpapers
-    .choose(&mut rand::thread_rng())
-    .ok_or_else(|| anyhow::anyhow!("Failed to select random wallpaper"))?;
-
-  Ok(wallpaper.clone())
+  Ok(wallpapers)
 }
 
-/// Recursively collect wallpaper files
-fn collect_wallpapers(dir: &Path, formats: &[String], wallpapers: &mut Vec<PathBuf>, recursive: bool) -> Result<()> {
+/// Collect wallpaper files in `dir` and up to `max_depth` levels of subfolders
+///
+/// Symlinked folders are followed, but each folder is only visited once, so links
+/// pointing back up the tree can't loop. Subfolders that can't be read are skipped;
+/// only an unreadable `dir` is an error. Formats are file extensions, matched
+/// case-insensitively.
+pub fn collect_wallpapers(dir: &Path, formats: &[String], max_depth: usize) -> Result<Vec<PathBuf>> {
   let entries = std::fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+  let mut visited = HashSet::new();
+  if let Ok(canonical) = dir.canonicalize() {
+    visited.insert(canonical);
+  }
 
-  for entry in entries {
-    let entry = entry?;
+  let mut wallpapers = Vec::new();
+  collect_entries(entries, formats, &mut wallpapers, max_depth, &mut visited);
+  Ok(wallpapers)
+}
+
+fn collect_entries(entries: std::fs::ReadDir, formats: &[String], wallpapers: &mut Vec<PathBuf>, depth_left: usize, visited: &mut HashSet<PathBuf>) {
+  for entry in entries.flatten() {
     let path = entry.path();
 
     if path.is_file() {
@@ -270,10 +1038,215 @@ fn collect_wallpapers(dir: &Path, formats: &[String], wallpapers: &mut Vec<PathB
       {
         wallpapers.push(path);
       }
-    } else if path.is_dir() && recursive {
-      collect_wallpapers(&path, formats, wallpapers, recursive)?;
+    } else if path.is_dir() && depth_left > 0 {
+      match path.canonicalize().map(|canonical| visited.insert(canonical)) {
+        Ok(true) => {}
+        Ok(false) => {
+          debug!("Skipping {}: already visited (symlink loop?)", path.display());
+          continue;
+        }
+        Err(e) => {
+          debug!("Skipping {}: {}", path.display(), e);
+          continue;
+        }
+      }
+
+      match std::fs::read_dir(&path) {
+        Ok(entries) => collect_entries(entries, formats, wallpapers, depth_left - 1, visited),
+        Err(e) => debug!("Skipping unreadable directory {}: {}", path.display(), e),
+      }
     }
   }
+}
 
-  Ok(())
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_can_decode() {
+    for name in ["a.jpg", "a.PNG", "a.webp", "a.bmp", "a.tiff", "a.gif"] {
+      assert!(can_decode(Path::new(name)), "{}", name);
+    }
+    assert!(!can_decode(Path::new("a.heic")));
+    assert!(!can_decode(Path::new("a.mp4")));
+
+    // Everything collected by default gets colors and previews, except the formats
+    // warn_undecodable reports
+    for format in &Config::default().sources.local.formats {
+      let undecodable = ["avif", "heic", "heif"].contains(&format.as_str());
+      assert_eq!(can_decode(Path::new(&format!("a.{}", format))), !undecodable, "{}", format);
+    }
+  }
+
+  #[test]
+  fn test_gif_plays_as_video_only_when_enabled() {
+    let mut config = Config::default();
+    assert!(!plays_as_video(Path::new("a.gif"), &config));
+    assert!(plays_as_video(Path::new("a.mp4"), &config));
+    assert!(!plays_as_video(Path::new("a.png"), &config));
+
+    config.sources.local.include_video = true;
+    assert!(plays_as_video(Path::new("a.gif"), &config));
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn test_shared_resolution() {
+    use crate::display::{Display, Resolution};
+    let display = |width, height| Display {
+      name: None,
+      resolution: Resolution::new(width, height),
+      primary: false,
+      position: None,
+    };
+
+    assert_eq!(shared_resolution(&[display(2560, 1440), display(2560, 1440)]), Some((2560, 1440)));
+    assert_eq!(shared_resolution(&[display(2560, 1440), display(1920, 1080)]), None);
+    assert_eq!(shared_resolution(&[]), None);
+  }
+
+  #[test]
+  fn test_describe_apply() {
+    let mut config = Config::default();
+    config.transition.transition_type = crate::config::TransitionType::Single("wipe".to_string());
+    let options = build_wallpaper_options(&config, false, MonitorSelection::Named("DP-1".to_string()));
+    let path = Path::new("/walls/a.jpg");
+
+    let report = describe_apply(path, &backends::linux::SwwwBackend::new(), &options, None, None);
+    assert!(report.contains("Wallpaper:  /walls/a.jpg"), "{}", report);
+    assert!(report.contains("Backend:    swww"), "{}", report);
+    assert!(report.contains("Transition: wipe (5s at 30 fps)"), "{}", report);
+    assert!(report.contains("Scaling:    fill"), "{}", report);
+    assert!(report.contains("Monitor:    DP-1"), "{}", report);
+
+    // Transitions are only listed when the backend can show them
+    let report = describe_apply(path, &backends::linux::FehBackend::new(), &options, None, None);
+    assert!(report.contains("Transition: none (feh has no transitions)"), "{}", report);
+    assert!(!report.contains("Processing"), "{}", report);
+
+    // The backend is handed a processed copy, not the original
+    config.transition.post_process.dim = Some(0.3);
+    assert_eq!(
+      describe_processing(path, false, &config).as_deref(),
+      Some("dim 30% (the backend gets a processed copy)")
+    );
+    let report = describe_apply(
+      path,
+      &backends::linux::FehBackend::new(),
+      &options,
+      describe_processing(path, false, &config).as_deref(),
+      None,
+    );
+    assert!(report.contains("Processing: dim 30% (the backend gets a processed copy)"), "{}", report);
+    assert!(
+      describe_processing(Path::new("/walls/a.heic"), false, &config)
+        .unwrap()
+        .starts_with("none")
+    );
+    assert_eq!(
+      describe_processing(path, true, &config).as_deref(),
+      Some("fit to the display, dim 30% (the backend gets a processed copy)")
+    );
+  }
+
+  #[test]
+  fn test_span_mode_follows_backend() {
+    let mut config = Config::default();
+    let all = build_wallpaper_options(&config, false, MonitorSelection::All);
+    let swww = backends::linux::SwwwBackend::new();
+    let feh = backends::linux::FehBackend::new();
+    let swaybg = backends::linux::SwaybgBackend::new();
+
+    assert_eq!(span_mode(&swww, &config, false, &all), None);
+
+    config.transition.span_monitors = true;
+    assert_eq!(span_mode(&swww, &config, false, &all), Some(SpanMode::Pieces));
+    assert_eq!(span_mode(&feh, &config, false, &all), Some(SpanMode::Canvas));
+    assert_eq!(span_mode(&swaybg, &config, false, &all), Some(SpanMode::Unsupported));
+    // Videos and a single monitor are never spanned
+    assert_eq!(span_mode(&swww, &config, true, &all), None);
+    let named = build_wallpaper_options(&config, false, MonitorSelection::Named("DP-1".to_string()));
+    assert_eq!(span_mode(&swww, &config, false, &named), None);
+  }
+
+  #[test]
+  fn test_describe_span() {
+    let region = |output: &str, x| span::Region {
+      output: output.to_string(),
+      x,
+      y: 0,
+      width: 1920,
+      height: 1080,
+      pixels: (1920, 1080),
+    };
+    let layout = ((3840, 1080), vec![region("DP-1", 0), region("DP-2", 1920)]);
+
+    assert_eq!(
+      describe_span(SpanMode::Pieces, "swww", Some(&layout)),
+      "one 3840x1080 canvas, a piece per monitor\n    DP-1: 1920x1080+0+0 scaled to 1920x1080\n    DP-2: 1920x1080+1920+0 scaled to 1920x1080"
+    );
+    assert_eq!(
+      describe_span(SpanMode::Canvas, "feh", Some(&layout)),
+      "one 3840x1080 image stretched across all monitors"
+    );
+    assert_eq!(
+      describe_span(SpanMode::Unsupported, "swaybg", Some(&layout)),
+      "no (swaybg shows the same image on every monitor)"
+    );
+    assert!(describe_span(SpanMode::Pieces, "swww", None).starts_with("no"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_collect_wallpapers_survives_symlink_loops() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let nested = dir.path().join("a/b");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(dir.path().join("top.jpg"), b"").unwrap();
+    std::fs::write(nested.join("deep.png"), b"").unwrap();
+    std::fs::write(nested.join("notes.txt"), b"").unwrap();
+    std::os::unix::fs::symlink(dir.path(), nested.join("loop")).unwrap();
+
+    let formats = vec!["jpg".to_string(), "png".to_string()];
+    let mut wallpapers = collect_wallpapers(dir.path(), &formats, 16).unwrap();
+    wallpapers.sort();
+    assert_eq!(wallpapers, vec![dir.path().join("a/b/deep.png"), dir.path().join("top.jpg")]);
+
+    // The depth limit stops before a/b
+    let wallpapers = collect_wallpapers(dir.path(), &formats, 1).unwrap();
+    assert_eq!(wallpapers, vec![dir.path().join("top.jpg")]);
+  }
+
+  #[test]
+  fn test_collect_wallpapers_matches_formats_and_depth() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("nature/forest")).unwrap();
+    for file in [
+      "beach.JPG",
+      "notes.md",
+      "nature/lake.png",
+      "nature/forest/pines.webp",
+      "nature/forest/pines.xcf",
+    ] {
+      std::fs::write(dir.path().join(file), b"").unwrap();
+    }
+    let formats = vec!["jpg".to_string(), "png".to_string(), "webp".to_string()];
+
+    let flat = collect_wallpapers(dir.path(), &formats, 0).unwrap();
+    assert_eq!(flat, vec![dir.path().join("beach.JPG")]);
+
+    let mut all = collect_wallpapers(dir.path(), &formats, 16).unwrap();
+    all.sort();
+    assert_eq!(
+      all,
+      vec![
+        dir.path().join("beach.JPG"),
+        dir.path().join("nature/forest/pines.webp"),
+        dir.path().join("nature/lake.png"),
+      ]
+    );
+
+    assert!(collect_wallpapers(&dir.path().join("missing"), &formats, 16).is_err());
+  }
 }
@@ -0,0 +1,262 @@
+//! Prepare wallpapers before they are set: crop them to the display
+//! (`transition.fit_to_display`) or cut them up across several
+//! (`transition.span_monitors`), then blur and dim them (`transition.post_process`)
+//!
+//! Processed copies are cached in `~/.cache/mksg/wallflow/processed`, named after
+//! a hash of the original's contents plus the settings used, so setting the same
+//! wallpaper again reuses its copy. Only the most recently used copies are kept.
+
+use super::span::Region;
+use crate::config::PostProcessConfig;
+use anyhow::{Context, Result};
+use image::DynamicImage;
+use image::imageops::FilterType;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::debug;
+
+/// Processed copies kept in the cache, enough for a different one on each monitor
+const CACHE_SIZE: usize = 8;
+
+/// Directory holding processed copies of wallpapers
+fn processed_dir() -> PathBuf {
+  crate::templates::TemplateEngine::default_output_dir().join("processed")
+}
+
+/// Whether `path` is a processed copy rather than a wallpaper of the collection
+pub fn is_processed(path: &Path) -> bool {
+  path.starts_with(processed_dir())
+}
+
+/// Write a copy of `wallpaper_path` cropped to `fit` (width, height) and with
+/// `effects` applied, and return its path
+pub fn process(wallpaper_path: &Path, fit: Option<(u32, u32)>, effects: &PostProcessConfig) -> Result<PathBuf> {
+  process_into(wallpaper_path, fit, effects, &processed_dir())
+}
+
+fn process_into(wallpaper_path: &Path, fit: Option<(u32, u32)>, effects: &PostProcessConfig, dir: &Path) -> Result<PathBuf> {
+  let bytes = std::fs::read(wallpaper_path).with_context(|| format!("Failed to read {}", wallpaper_path.display()))?;
+  let hash = format!("{:x}", Sha256::digest(&bytes));
+  let output = dir.join(format!("{}.jpg", cache_key(&hash, fit, effects)));
+
+  if reuse(&output) {
+    return Ok(output);
+  }
+
+  let image = image::load_from_memory(&bytes).with_context(|| format!("Failed to decode {}", wallpaper_path.display()))?;
+  save(&apply(image, fit, effects), &output, dir)?;
+
+  evict_old_copies(dir, CACHE_SIZE);
+  Ok(output)
+}
+
+/// Cut `wallpaper_path` into one piece per region of a `canvas` laid out by
+/// `span::layout`, apply `effects` to each, and return the pieces in region order
+pub fn span(wallpaper_path: &Path, canvas: (u32, u32), regions: &[Region], effects: &PostProcessConfig) -> Result<Vec<PathBuf>> {
+  span_into(wallpaper_path, canvas, regions, effects, &processed_dir())
+}
+
+fn span_into(wallpaper_path: &Path, canvas: (u32, u32), regions: &[Region], effects: &PostProcessConfig, dir: &Path) -> Result<Vec<PathBuf>> {
+  let bytes = std::fs::read(wallpaper_path).with_context(|| format!("Failed to read {}", wallpaper_path.display()))?;
+  let hash = format!("{:x}", Sha256::digest(&bytes));
+
+  let pieces: Vec<PathBuf> = regions
+    .iter()
+    .map(|region| {
+      let key = cache_key(&hash, Some(region.pixels), effects);
+      dir.join(format!(
+        "{}-span{}x{}-{}x{}+{}+{}.jpg",
+        key, canvas.0, canvas.1, region.width, region.height, region.x, region.y
+      ))
+    })
+    .collect();
+
+  let missing: Vec<(&Region, &PathBuf)> = regions.iter().zip(&pieces).filter(|(_, output)| !reuse(output)).collect();
+  if !missing.is_empty() {
+    let image = image::load_from_memory(&bytes).with_context(|| format!("Failed to decode {}", wallpaper_path.display()))?;
+    let spanned = image.resize_to_fill(canvas.0, canvas.1, FilterType::Lanczos3);
+    for (region, output) in missing {
+      let piece = spanned.crop_imm(region.x, region.y, region.width, region.height);
+      save(&apply(piece, Some(region.pixels), effects), output, dir)?;
+    }
+  }
+
+  evict_old_copies(dir, CACHE_SIZE.max(regions.len()));
+  Ok(pieces)
+}
+
+/// Whether a cached copy exists; it's marked as used so it isn't evicted next
+fn reuse(output: &Path) -> bool {
+  if !output.is_file() {
+    return false;
+  }
+
+  debug!("Reusing processed copy {}", output.display());
+  if let Err(e) = std::fs::File::options()
+    .append(true)
+    .open(output)
+    .and_then(|file| file.set_modified(SystemTime::now()))
+  {
+    debug!("Failed to touch {}: {}", output.display(), e);
+  }
+  true
+}
+
+fn save(image: &DynamicImage, output: &Path, dir: &Path) -> Result<()> {
+  std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+  DynamicImage::ImageRgb8(image.to_rgb8())
+    .save(output)
+    .with_context(|| format!("Failed to write {}", output.display()))
+}
+
+/// File name for a processed copy: content hash, then every setting that changes the result
+fn cache_key(hash: &str, fit: Option<(u32, u32)>, effects: &PostProcessConfig) -> String {
+  let mut key = hash[..hash.len().min(16)].to_string();
+  if let Some((width, height)) = fit {
+    key.push_str(&format!("-{}x{}", width, height));
+  }
+  if let Some(blur) = effects.blur.filter(|blur| *blur > 0.0) {
+    key.push_str(&format!("-blur{}", blur));
+  }
+  if let Some(dim) = effects.dim.filter(|dim| *dim > 0.0) {
+    key.push_str(&format!("-dim{}", dim));
+  }
+  key
+}
+
+fn apply(mut image: DynamicImage, fit: Option<(u32, u32)>, effects: &PostProcessConfig) -> DynamicImage {
+  if let Some((width, height)) = fit
+    && (image.width(), image.height()) != (width, height)
+  {
+    // Scales to cover the display, then crops the overflow evenly from both sides
+    image = image.resize_to_fill(width, height, FilterType::Lanczos3);
+  }
+  if let Some(sigma) = effects.blur.filter(|blur| *blur > 0.0) {
+    image = image.blur(sigma);
+  }
+  if let Some(dim) = effects.dim.filter(|dim| *dim > 0.0) {
+    // Scale every channel, so 0.3 leaves 70% of each pixel's brightness
+    let factor = 1.0 - dim.min(1.0);
+    let mut rgb = image.to_rgb8();
+    for channel in rgb.iter_mut() {
+      *channel = (f32::from(*channel) * factor).round() as u8;
+    }
+    image = DynamicImage::ImageRgb8(rgb);
+  }
+  image
+}
+
+/// Delete all but the `keep` most recently used copies
+fn evict_old_copies(dir: &Path, keep: usize) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+  let mut copies: Vec<(SystemTime, PathBuf)> = entries
+    .flatten()
+    .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+    .collect();
+  copies.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+  for (_, path) in copies.into_iter().skip(keep) {
+    if let Err(e) = std::fs::remove_file(&path) {
+      debug!("Failed to remove old processed wallpaper {}: {}", path.display(), e);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn brightness(path: &Path) -> u64 {
+    let image = image::open(path).unwrap().to_luma8();
+    image.pixels().map(|pixel| u64::from(pixel.0[0])).sum::<u64>() / u64::from(image.width() * image.height())
+  }
+
+  #[test]
+  fn test_process_dims_a_copy() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let original = tmp.path().join("sunset.png");
+    image::RgbImage::from_pixel(16, 16, image::Rgb([200, 180, 160])).save(&original).unwrap();
+    let dir = tmp.path().join("processed");
+
+    let effects = PostProcessConfig {
+      blur: Some(2.0),
+      dim: Some(0.5),
+    };
+    let processed = process_into(&original, None, &effects, &dir).unwrap();
+
+    assert!(processed.starts_with(&dir));
+    // Half as bright, not a fixed amount darker
+    assert!(brightness(&processed).abs_diff(183 / 2) <= 2, "{}", brightness(&processed));
+    // The original is untouched
+    assert_eq!(brightness(&original), 183);
+  }
+
+  #[test]
+  fn test_process_fits_and_caches() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path().join("processed");
+    // Left half black, right half white
+    let original = tmp.path().join("wide.png");
+    image::RgbImage::from_fn(40, 20, |x, _| if x < 20 { image::Rgb([0, 0, 0]) } else { image::Rgb([255, 255, 255]) })
+      .save(&original)
+      .unwrap();
+
+    let none = PostProcessConfig::default();
+    let fitted = process_into(&original, Some((10, 10)), &none, &dir).unwrap();
+    let image = image::open(&fitted).unwrap();
+    assert_eq!((image.width(), image.height()), (10, 10));
+    // Cropped from the middle, so both halves survive
+    assert!(brightness(&fitted) > 60 && brightness(&fitted) < 200);
+
+    // Same image and settings hit the cache, other settings get their own copy
+    assert_eq!(process_into(&original, Some((10, 10)), &none, &dir).unwrap(), fitted);
+    assert_ne!(process_into(&original, Some((20, 10)), &none, &dir).unwrap(), fitted);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+    evict_old_copies(&dir, 1);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+  }
+
+  #[test]
+  fn test_span_cuts_one_piece_per_region() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path().join("processed");
+    // Left half black, right half white, twice as wide as it is tall
+    let original = tmp.path().join("panorama.png");
+    image::RgbImage::from_fn(80, 40, |x, _| if x < 40 { image::Rgb([0, 0, 0]) } else { image::Rgb([255, 255, 255]) })
+      .save(&original)
+      .unwrap();
+
+    let region = |output: &str, x, pixels| Region {
+      output: output.to_string(),
+      x,
+      y: 0,
+      width: 20,
+      height: 20,
+      pixels,
+    };
+    let regions = [region("DP-1", 0, (20, 20)), region("DP-2", 20, (40, 40))];
+    let pieces = span_into(&original, (40, 20), &regions, &PostProcessConfig::default(), &dir).unwrap();
+
+    assert_eq!(pieces.len(), 2);
+    assert!(brightness(&pieces[0]) < 20);
+    assert!(brightness(&pieces[1]) > 235);
+    // Each piece is scaled to its monitor's physical size
+    let right = image::open(&pieces[1]).unwrap();
+    assert_eq!((right.width(), right.height()), (40, 40));
+  }
+
+  #[test]
+  fn test_cache_key() {
+    let hash = "0123456789abcdef0123456789abcdef";
+    let effects = PostProcessConfig {
+      blur: Some(8.0),
+      dim: Some(0.3),
+    };
+    assert_eq!(cache_key(hash, None, &PostProcessConfig::default()), "0123456789abcdef");
+    assert_eq!(cache_key(hash, Some((2560, 1440)), &effects), "0123456789abcdef-2560x1440-blur8-dim0.3");
+  }
+}
@@ -0,0 +1,177 @@
+//! Shuffle mode for the local collection
+//!
+//! Works like a shuffle bag: every wallpaper is shown once before any comes back.
+//! The wallpapers shown in the current cycle are kept in a small ring buffer
+//! persisted to `shuffle.json` in the state directory (see [`crate::paths`]).
+//! Collections larger than the buffer skip only the last 100 picks.
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// How many shown wallpapers are remembered, and so the largest collection cycled through in full
+const SHUFFLE_MEMORY: usize = 100;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ShuffleState {
+  /// Recently shown wallpapers, oldest first
+  recent: VecDeque<PathBuf>,
+}
+
+impl ShuffleState {
+  /// Load the persisted state, starting fresh if there is none or it can't be read
+  pub fn load() -> Self {
+    let Ok(file) = state_file() else {
+      return Self::default();
+    };
+
+    std::fs::read_to_string(&file)
+      .ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_default()
+  }
+
+  /// Persist the state for the next pick
+  pub fn save(&self) -> Result<()> {
+    let file = state_file()?;
+    if let Some(parent) = file.parent() {
+      std::fs::create_dir_all(parent).context("Failed to create shuffle state directory")?;
+    }
+
+    let json = serde_json::to_string(self).context("Failed to serialize shuffle state")?;
+    std::fs::write(&file, json).with_context(|| format!("Failed to write shuffle state: {}", file.display()))
+  }
+
+  /// Pick a wallpaper that hasn't been shown recently and remember it
+  pub fn pick<'a, R: Rng>(&mut self, wallpapers: &'a [PathBuf], rng: &mut R) -> Option<&'a PathBuf> {
+    // With a single wallpaper there's nothing to avoid
+    if wallpapers.len() < 2 {
+      return wallpapers.first();
+    }
+
+    let shown: HashSet<&Path> = self.recent.iter().map(PathBuf::as_path).collect();
+    let mut candidates: Vec<&PathBuf> = wallpapers.iter().filter(|path| !shown.contains(path.as_path())).collect();
+
+    if candidates.is_empty() {
+      // Every wallpaper was shown, so a new cycle starts. Its first pick skips the
+      // last one, so nothing comes back twice in a row and the order isn't fixed.
+      let last = self.recent.pop_back();
+      self.recent.clear();
+      candidates = wallpapers.iter().filter(|path| Some(*path) != last.as_ref()).collect();
+      debug!("Shuffle showed all {} wallpapers, starting a new cycle", wallpapers.len());
+    } else {
+      debug!("Shuffle avoiding {} already shown of {} wallpapers", shown.len(), wallpapers.len());
+    }
+
+    let choice = *candidates.choose(rng)?;
+
+    self.recent.push_back(choice.clone());
+    while self.recent.len() > SHUFFLE_MEMORY {
+      self.recent.pop_front();
+    }
+
+    Some(choice)
+  }
+}
+
+fn state_file() -> Result<PathBuf> {
+  Ok(crate::paths::state_dir()?.join("shuffle.json"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn wallpapers(count: usize) -> Vec<PathBuf> {
+    (0..count).map(|i| PathBuf::from(format!("/walls/{}.jpg", i))).collect()
+  }
+
+  #[test]
+  fn test_every_wallpaper_shown_once_per_cycle() {
+    let walls = wallpapers(10);
+    let mut state = ShuffleState::default();
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..5 {
+      let cycle: HashSet<_> = (0..walls.len()).map(|_| state.pick(&walls, &mut rng).unwrap().clone()).collect();
+      assert_eq!(cycle.len(), walls.len());
+    }
+  }
+
+  #[test]
+  fn test_large_collection_avoids_the_last_picks() {
+    let walls = wallpapers(SHUFFLE_MEMORY + 20);
+    let mut state = ShuffleState::default();
+    let mut rng = rand::thread_rng();
+
+    let shown: Vec<_> = (0..SHUFFLE_MEMORY * 3).map(|_| state.pick(&walls, &mut rng).unwrap().clone()).collect();
+    for window in shown.windows(SHUFFLE_MEMORY + 1) {
+      let distinct: HashSet<_> = window.iter().collect();
+      assert_eq!(distinct.len(), SHUFFLE_MEMORY + 1);
+    }
+  }
+
+  #[test]
+  fn test_no_immediate_repeat() {
+    let walls = wallpapers(3);
+    let mut state = ShuffleState::default();
+    let mut rng = rand::thread_rng();
+
+    let mut last = state.pick(&walls, &mut rng).unwrap().clone();
+    for _ in 0..30 {
+      let next = state.pick(&walls, &mut rng).unwrap().clone();
+      assert_ne!(next, last);
+      last = next;
+    }
+  }
+
+  #[test]
+  fn test_small_collection_is_not_a_fixed_cycle() {
+    let walls = wallpapers(3);
+    let mut state = ShuffleState::default();
+    let mut rng = rand::thread_rng();
+
+    // What follows the first wallpaper should vary between the other two
+    let shown: Vec<_> = (0..200).map(|_| state.pick(&walls, &mut rng).unwrap().clone()).collect();
+    let followers: HashSet<_> = shown.windows(2).filter(|pair| pair[0] == walls[0]).map(|pair| &pair[1]).collect();
+    assert_eq!(followers.len(), 2);
+  }
+
+  #[test]
+  fn test_two_wallpapers_alternate() {
+    let walls = wallpapers(2);
+    let mut state = ShuffleState::default();
+    let mut rng = rand::thread_rng();
+
+    let first = state.pick(&walls, &mut rng).unwrap().clone();
+    let second = state.pick(&walls, &mut rng).unwrap().clone();
+    assert_ne!(first, second);
+    assert_eq!(state.pick(&walls, &mut rng), Some(&first));
+  }
+
+  #[test]
+  fn test_memory_is_bounded() {
+    let walls = wallpapers(SHUFFLE_MEMORY * 2);
+    let mut state = ShuffleState::default();
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..SHUFFLE_MEMORY + 10 {
+      state.pick(&walls, &mut rng);
+    }
+    assert_eq!(state.recent.len(), SHUFFLE_MEMORY);
+  }
+
+  #[test]
+  fn test_single_wallpaper() {
+    let walls = wallpapers(1);
+    let mut state = ShuffleState::default();
+    let mut rng = rand::thread_rng();
+
+    assert_eq!(state.pick(&walls, &mut rng), walls.first());
+    assert_eq!(state.pick(&walls, &mut rng), walls.first());
+  }
+}
@@ -0,0 +1,150 @@
+//! Spreading one wallpaper across every monitor (`transition.span_monitors`)
+//!
+//! The monitors are arranged on a single canvas the image is scaled to cover,
+//! and each monitor gets the part of the canvas it occupies.
+
+use crate::display::Display;
+
+/// The part of the spanned image one monitor shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+  /// Output name the piece is set on
+  pub output: String,
+  /// Area of the canvas, in layout pixels
+  pub x: u32,
+  pub y: u32,
+  pub width: u32,
+  pub height: u32,
+  /// The monitor's physical resolution, which the piece is scaled to
+  pub pixels: (u32, u32),
+}
+
+/// Arrange the displays on one canvas and return its size with each display's region
+///
+/// Displays go where the compositor reports them when every one has a position,
+/// and side by side in detection order otherwise. Returns `None` for fewer than
+/// two displays, or when one has no output name to set its piece on.
+pub fn layout(displays: &[Display]) -> Option<((u32, u32), Vec<Region>)> {
+  if displays.len() < 2 {
+    return None;
+  }
+
+  let outputs: Vec<String> = displays.iter().map(|display| display.name.clone()).collect::<Option<_>>()?;
+  // Positions are in logical pixels on scaled Wayland outputs, so sizes must be too
+  let sizes: Vec<(u32, u32)> = displays.iter().map(layout_size).collect();
+  let positions: Vec<(i64, i64)> = if displays.iter().all(|display| display.position.is_some()) {
+    displays
+      .iter()
+      .map(|display| display.position.map(|(x, y)| (i64::from(x), i64::from(y))).unwrap_or_default())
+      .collect()
+  } else {
+    let mut x = 0;
+    sizes
+      .iter()
+      .map(|(width, _)| {
+        let position = (x, 0);
+        x += i64::from(*width);
+        position
+      })
+      .collect()
+  };
+
+  let left = positions.iter().map(|(x, _)| *x).min()?;
+  let top = positions.iter().map(|(_, y)| *y).min()?;
+  let right = positions.iter().zip(&sizes).map(|((x, _), (width, _))| x + i64::from(*width)).max()?;
+  let bottom = positions.iter().zip(&sizes).map(|((_, y), (_, height))| y + i64::from(*height)).max()?;
+  let canvas = (u32::try_from(right - left).ok()?, u32::try_from(bottom - top).ok()?);
+
+  let regions = outputs
+    .into_iter()
+    .zip(displays)
+    .zip(positions.into_iter().zip(sizes))
+    .map(|((output, display), ((x, y), (width, height)))| {
+      Some(Region {
+        output,
+        x: u32::try_from(x - left).ok()?,
+        y: u32::try_from(y - top).ok()?,
+        width,
+        height,
+        pixels: (display.resolution.width, display.resolution.height),
+      })
+    })
+    .collect::<Option<_>>()?;
+
+  Some((canvas, regions))
+}
+
+/// Size a display takes up in the layout
+fn layout_size(display: &Display) -> (u32, u32) {
+  let logical = display.resolution.logical();
+  (logical.width.max(1), logical.height.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::display::Resolution;
+
+  fn display(name: &str, resolution: Resolution, position: Option<(i32, i32)>) -> Display {
+    Display {
+      name: Some(name.to_string()),
+      resolution,
+      primary: false,
+      position,
+    }
+  }
+
+  #[test]
+  fn test_layout_uses_positions() {
+    // A scaled 4K panel left of a 1080p one that sits lower
+    let displays = [
+      display("HDMI-A-1", Resolution::new(1920, 1080), Some((2560, 360))),
+      display("DP-1", Resolution::new(3840, 2160).with_scale(1.5), Some((0, 0))),
+    ];
+
+    let (canvas, regions) = layout(&displays).unwrap();
+    assert_eq!(canvas, (4480, 1440));
+    assert_eq!(
+      regions,
+      vec![
+        Region {
+          output: "HDMI-A-1".to_string(),
+          x: 2560,
+          y: 360,
+          width: 1920,
+          height: 1080,
+          pixels: (1920, 1080),
+        },
+        Region {
+          output: "DP-1".to_string(),
+          x: 0,
+          y: 0,
+          width: 2560,
+          height: 1440,
+          pixels: (3840, 2160),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_layout_side_by_side_without_positions() {
+    let displays = [
+      display("DP-1", Resolution::new(1920, 1080), None),
+      display("DP-2", Resolution::new(1080, 1920), Some((1920, 0))),
+    ];
+
+    let (canvas, regions) = layout(&displays).unwrap();
+    assert_eq!(canvas, (3000, 1920));
+    assert_eq!((regions[1].x, regions[1].y), (1920, 0));
+  }
+
+  #[test]
+  fn test_layout_needs_two_named_displays() {
+    assert!(layout(&[display("DP-1", Resolution::new(1920, 1080), None)]).is_none());
+
+    let mut unnamed = display("DP-2", Resolution::new(1920, 1080), None);
+    unnamed.name = None;
+    assert!(layout(&[display("DP-1", Resolution::new(1920, 1080), None), unnamed]).is_none());
+  }
+}